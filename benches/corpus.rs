@@ -0,0 +1,113 @@
+//! Benchmarks of documents of different shapes and sizes, the agreed-upon baselines for changes
+//! motivated by performance. Run them with `cargo bench --bench corpus` and compare against a
+//! baseline saved from the main branch (`-- --save-baseline main` / `-- --baseline main`).
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use gura::parse;
+use std::{fs, hint::black_box, path::Path};
+
+/// Real world document with every kind of value
+const FULL: &str = include_str!("../tests/full/tests-files/full.ura");
+
+/// Generates a document with `sections` objects like the ones of service configurations.
+fn sections_document(sections: usize) -> String {
+    (0..sections)
+        .map(|index| {
+            format!(
+                "# Service {0}\nservice_{0}:\n    name: \"service-{0}\"\n    enabled: true\n    port: {1}\n    ratio: 0.{0}\n    database:\n        host: \"db-{0}.local\"\n        pool: [1, 2, 4]\n\n",
+                index,
+                8000 + index
+            )
+        })
+        .collect()
+}
+
+/// Generates a document with `pairs` long strings of every kind.
+fn strings_document(pairs: usize) -> String {
+    let value = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(20);
+    (0..pairs)
+        .map(|index| {
+            format!(
+                "basic_{0}: \"{1}\\t{1}\"\nliteral_{0}: '{1}'\nmultiline_{0}: \"\"\"\n{1}\n{1}\"\"\"\n",
+                index, value
+            )
+        })
+        .collect()
+}
+
+/// Generates a document with `arrays` arrays of numbers, strings and nested arrays.
+fn arrays_document(arrays: usize) -> String {
+    let numbers = (0..100)
+        .map(|number| number.to_string())
+        .collect::<Vec<_>>();
+    (0..arrays)
+        .map(|index| {
+            format!(
+                "numbers_{0}: [{1}]\nstrings_{0}: [\"a\", \"b\", \"c\", \"d\"]\nnested_{0}: [\n    [1, 2],\n    [3, [4, 5]],\n    [\"six\", 7.0]\n]\n",
+                index,
+                numbers.join(", ")
+            )
+        })
+        .collect()
+}
+
+/// Writes a tree of `files` files imported by the returned document, every one of them
+/// importing the next one.
+fn write_import_tree(dir: &Path, files: usize) -> String {
+    for index in 0..files {
+        let import = if index + 1 < files {
+            format!("import \"file_{}.ura\"\n", index + 1)
+        } else {
+            String::new()
+        };
+        let content = format!(
+            "{}{}",
+            import,
+            sections_document(5).replace("service_", &format!("file_{}_service_", index))
+        );
+        fs::write(dir.join(format!("file_{}.ura", index)), content).unwrap();
+    }
+    format!(
+        "import \"{}\"\nname: \"root\"",
+        dir.join("file_0.ura").display()
+    )
+}
+
+fn sizes_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("size");
+    let documents = [
+        ("small", FULL.to_string()),
+        ("medium", sections_document(50)),
+        ("large", sections_document(500)),
+    ];
+    for (name, document) in documents.iter() {
+        group.throughput(Throughput::Bytes(document.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            document,
+            |b, document| b.iter(|| parse(black_box(document)).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+fn shapes_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("shape");
+    let dir = tempfile::tempdir().unwrap();
+    let documents = [
+        ("imports", write_import_tree(dir.path(), 20)),
+        ("strings", strings_document(50)),
+        ("arrays", arrays_document(50)),
+    ];
+    // Throughputs are not comparable, as imported files are not part of the documents
+    for (name, document) in documents.iter() {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            document,
+            |b, document| b.iter(|| parse(black_box(document)).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, sizes_benchmark, shapes_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+//! Micro-benchmarks of the texts that stress `keyword`, which is tried at every char of strings
+//! to find their closing quotes.
+use criterion::{criterion_group, criterion_main, Criterion};
+use gura::parse;
+use std::hint::black_box;
+
+/// Generates a document with `pairs` strings of `length` chars of every kind.
+fn strings_document(pairs: usize, length: usize) -> String {
+    let value = "lorem ipsum ".repeat(length / 12);
+    (0..pairs)
+        .map(|index| {
+            format!(
+                "basic_{0}: \"{1}\"\nliteral_{0}: '{1}'\nmultiline_{0}: \"\"\"\n{1}\n{1}\"\"\"\n",
+                index, value
+            )
+        })
+        .collect()
+}
+
+fn keyword_benchmark(c: &mut Criterion) {
+    let short_strings = strings_document(200, 24);
+    let long_strings = strings_document(20, 2400);
+    let keywords: String = (0..500)
+        .map(|index| {
+            format!(
+                "enabled_{0}: true\ndisabled_{0}: false\nnothing_{0}: null\n",
+                index
+            )
+        })
+        .collect();
+
+    c.bench_function("short strings", |b| {
+        b.iter(|| parse(black_box(&short_strings)).unwrap())
+    });
+    c.bench_function("long strings", |b| {
+        b.iter(|| parse(black_box(&long_strings)).unwrap())
+    });
+    c.bench_function("keywords", |b| {
+        b.iter(|| parse(black_box(&keywords)).unwrap())
+    });
+}
+
+criterion_group!(benches, keyword_benchmark);
+criterion_main!(benches);
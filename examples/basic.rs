@@ -21,10 +21,11 @@ hosts: [
 
     // Debug and Display
     // println!("{:#?}", parsed);
-    // println!("{}", parsed);
+    // println!("{}", parsed); // Compact, single line
+    // println!("{:#}", parsed); // Indented Gura document
 
     // Access a specific field
-    println!("Title -> {}", parsed["title"]);
+    println!("Title -> {}", parsed["title"].raw_display());
 
     // You can check if object contains a key
     if parsed.contains_key("an_object") {
@@ -35,7 +36,7 @@ hosts: [
     println!("\nHosts:");
     if let GuraType::Array(hosts) = &parsed["hosts"] {
         for host in hosts.iter() {
-            println!("Host -> {}", *host);
+            println!("Host -> {}", host.raw_display());
         }
     }
 
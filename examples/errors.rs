@@ -11,7 +11,7 @@ some_invalid: $non_existent_var
     // Checks parsing result
     match parse(gura_string) {
         Ok(parsed) => {
-            println!("Title -> {}", parsed["title"]);
+            println!("Title -> {}", parsed["title"].raw_display());
         }
         Err(e) => {
             println!("Error: {}", e); // Error implements fmt::Display
@@ -28,6 +28,7 @@ some_invalid: $non_existent_var
                 Error::DuplicatedImportError => {
                     println!("The same Gura file was imported more than once!")
                 }
+                Error::InternalError => println!("The parser has a bug!"),
             }
         }
     }
@@ -1,4 +1,5 @@
 use gura::{parse, GuraType};
+use std::convert::TryFrom;
 
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -8,6 +9,28 @@ struct TangoSinger {
     year_of_birth: u16,
 }
 
+impl TryFrom<&GuraType> for TangoSinger {
+    type Error = String;
+
+    fn try_from(value: &GuraType) -> Result<Self, Self::Error> {
+        // Discards object key
+        let (_singer_key, singer_props) = value.iter()?.next().ok_or("Singer without data")?;
+
+        Ok(TangoSinger {
+            name: singer_props["name"]
+                .expect_str()
+                .map_err(|e| e.to_string())?
+                .to_string(),
+            surname: singer_props["surname"]
+                .expect_str()
+                .map_err(|e| e.to_string())?
+                .to_string(),
+            year_of_birth: u16::try_from(&singer_props["year_of_birth"])
+                .map_err(|e| e.to_string())?,
+        })
+    }
+}
+
 fn main() {
     // NOTE: Serde-Gura implementation is finished and it is the recommended way to use Gura in Rust.
     // You can find it at https://github.com/gura-conf/serde-gura/
@@ -29,37 +52,12 @@ tango_singers: [
     // Parse: transforms a Gura string into a dictionary
     let parsed = parse(gura_string).unwrap();
 
-	// Lets make an array of singers
-    if let GuraType::Array(tango_singers) = &parsed["tango_singers"] {
-        let mut tango_singers_structs: Vec<TangoSinger> =
-            Vec::with_capacity(tango_singers.capacity());
-
-        // Iterate over structure
-        for tango_singer in tango_singers {
-            // Discards object key
-            if let GuraType::Object(key_values) = tango_singer {
-                let (_singer_key, singer_props) = key_values.iter().next().unwrap();
-
-                // Inside the for loop
-                let year_of_birth: u16 = match singer_props["year_of_birth"] {
-                    GuraType::Integer(value) => value as u16,
-                    GuraType::BigInteger(value) => value as u16,
-                    _ => panic!("Gura text is not a valid array of tango singers!"),
-                };
-
-                let my_struct = TangoSinger {
-                    name: singer_props["name"].to_string(),
-                    surname: singer_props["surname"].to_string(),
-                    year_of_birth,
-                };
-
-                tango_singers_structs.push(my_struct);
-            } else {
-                panic!("Gura text is not a valid array of tango singers!")
-            }
+    // Lets make an array of singers
+    match parsed["tango_singers"].to_vec_of::<TangoSinger>() {
+        Ok(tango_singers_structs) => {
+            println!("Tango singers:");
+            println!("{:#?}", tango_singers_structs);
         }
-
-        println!("Tango singers:");
-        println!("{:#?}", tango_singers_structs);
+        Err(e) => panic!("Gura text is not a valid array of tango singers: {}", e),
     }
 }
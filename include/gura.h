@@ -0,0 +1,182 @@
+#ifndef GURA_H
+#define GURA_H
+
+/* Generated with cbindgen from src/ffi.rs, do not edit it manually */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Version of the layout of `GuraScalarPlugin` and `GuraScalar`
+#define GURA_PLUGIN_ABI_VERSION 1
+
+// Kind of `GuraScalar` with a null value
+#define GURA_SCALAR_KIND_NULL 0
+
+// Kind of `GuraScalar` with a `bool_value`
+#define GURA_SCALAR_KIND_BOOL 1
+
+// Kind of `GuraScalar` with an `int_value`
+#define GURA_SCALAR_KIND_INTEGER 2
+
+// Kind of `GuraScalar` with a `float_value`
+#define GURA_SCALAR_KIND_FLOAT 3
+
+// Kind of `GuraScalar` with a `string`
+#define GURA_SCALAR_KIND_STRING 4
+
+// Kinds of errors, `GURA_ERROR_KIND_NONE` when no error happened
+typedef enum GuraErrorKind {
+  GURA_ERROR_KIND_NONE = 0,
+  GURA_ERROR_KIND_PARSE_ERROR,
+  GURA_ERROR_KIND_VARIABLE_NOT_DEFINED_ERROR,
+  GURA_ERROR_KIND_INVALID_INDENTATION_ERROR,
+  GURA_ERROR_KIND_DUPLICATED_VARIABLE_ERROR,
+  GURA_ERROR_KIND_DUPLICATED_KEY_ERROR,
+  GURA_ERROR_KIND_FILE_NOT_FOUND_ERROR,
+  GURA_ERROR_KIND_DUPLICATED_IMPORT_ERROR,
+  GURA_ERROR_KIND_INTERNAL_ERROR,
+  // The text is not valid UTF-8 or a pointer is NULL
+  GURA_ERROR_KIND_INVALID_ARGUMENT,
+} GuraErrorKind;
+
+// Parsed Gura document, opaque for C
+typedef struct GuraDocument GuraDocument;
+
+// Error filled by `gura_parse`. `message` must be released with `gura_error_free`
+typedef struct GuraErrorInfo {
+  enum GuraErrorKind kind;
+  // Line of the error (one based), 0 if unknown
+  uintptr_t line;
+  // Position of the error as a byte offset
+  uintptr_t byte_pos;
+  // Message of the error, NULL if no error happened
+  char *message;
+} GuraErrorInfo;
+
+// Set of custom scalars, opaque for C
+typedef struct GuraExtensions GuraExtensions;
+
+// Value filled by the recognizer of a plugin. Only the field of its `kind` is read
+typedef struct GuraScalar {
+  // Type of the value, one of the `GURA_SCALAR_KIND_*` constants. It is an integer rather
+  // than an enum as it is written by plugins, so unknown values can be rejected
+  uint32_t kind;
+  bool bool_value;
+  int64_t int_value;
+  double float_value;
+  // NUL terminated UTF-8 string, owned by the plugin (it is copied by the parser). When the
+  // text is rejected, it can be set to the reason
+  const char *string;
+} GuraScalar;
+
+// Converts the text of a custom scalar (NUL terminated UTF-8) into a value. Returns false if
+// the text is not valid
+typedef bool (*GuraScalarRecognizer)(const char *text, void *user_data, struct GuraScalar *result);
+
+// Custom scalar implemented by a plugin
+typedef struct GuraScalarPlugin {
+  // Must be `GURA_PLUGIN_ABI_VERSION`
+  uint32_t abi_version;
+  // Tag of the custom scalar, without the `@`. It is copied when the plugin is registered
+  const char *tag;
+  GuraScalarRecognizer recognize;
+  // Passed to every call of `recognize` and `release`
+  void *user_data;
+  // Called with `user_data` when the plugin is not used anymore. Can be NULL
+  void (*release)(void *user_data);
+} GuraScalarPlugin;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Parses a NUL terminated UTF-8 Gura text. Returns NULL if the text is not valid, filling
+// `error` (if it is not NULL) with the reason.
+//
+// # Safety
+//
+// `text` must be NULL or a valid NUL terminated string, and `error` must be NULL or point to
+// a writable `GuraErrorInfo`.
+struct GuraDocument *gura_parse(const char *text, struct GuraErrorInfo *error);
+
+// Like `gura_parse` but also parsing the custom scalars of `extensions` (ignored if it is
+// NULL).
+//
+// # Safety
+//
+// The same as `gura_parse`, and `extensions` must be NULL or a set returned by
+// `gura_extensions_new` not released yet.
+struct GuraDocument *gura_parse_with_extensions(const char *text,
+                                                const struct GuraExtensions *extensions,
+                                                struct GuraErrorInfo *error);
+
+// Creates an empty set of custom scalars, which must be released with
+// `gura_extensions_free`.
+struct GuraExtensions *gura_extensions_new(void);
+
+// Registers a plugin, replacing the previous one with the same tag. Returns
+// `GURA_ERROR_KIND_INVALID_ARGUMENT` if a pointer is NULL, the tag is not valid UTF-8 or the
+// plugin was built for another ABI version. Rejected plugins are released, except the ones of
+// other ABI versions: their layout is unknown, so only `abi_version` is read.
+//
+// Recognizers can be called from any thread, so they must be thread safe.
+//
+// # Safety
+//
+// `extensions` must be NULL or a set returned by `gura_extensions_new` not released yet, and
+// `plugin` must be NULL or point to a plugin whose pointers are valid until it is released.
+enum GuraErrorKind gura_extensions_register(struct GuraExtensions *extensions,
+                                            const struct GuraScalarPlugin *plugin);
+
+// Releases a set of custom scalars. Documents parsed with it are still valid and plugins are
+// released once no parser uses them. NULL is ignored.
+//
+// # Safety
+//
+// `extensions` must be NULL or a set returned by `gura_extensions_new` not released yet.
+void gura_extensions_free(struct GuraExtensions *extensions);
+
+// Dumps a document into a Gura text. Returns NULL if `document` is NULL.
+//
+// # Safety
+//
+// `document` must be NULL or a document returned by `gura_parse` not released yet.
+char *gura_dump(const struct GuraDocument *document);
+
+// Gets the value located at a path (keys separated by dots) as a Gura text (e.g. strings
+// are quoted). Returns NULL if the path does not exist or an argument is not valid.
+//
+// # Safety
+//
+// `document` must be NULL or a document returned by `gura_parse` not released yet, and
+// `path` must be NULL or a valid NUL terminated string.
+char *gura_get(const struct GuraDocument *document, const char *path);
+
+// Releases a document returned by `gura_parse`. NULL is ignored.
+//
+// # Safety
+//
+// `document` must be NULL or a document returned by `gura_parse` not released yet.
+void gura_free(struct GuraDocument *document);
+
+// Releases a string returned by this library. NULL is ignored.
+//
+// # Safety
+//
+// `text` must be NULL or a string returned by this library not released yet.
+void gura_string_free(char *text);
+
+// Releases the message of an error, setting it to NULL. NULL is ignored.
+//
+// # Safety
+//
+// `error` must be NULL or point to an error filled by `gura_parse`.
+void gura_error_free(struct GuraErrorInfo *error);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* GURA_H */
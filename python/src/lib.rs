@@ -0,0 +1,7 @@
+//! Python extension module, see `gura::python` in the parent crate.
+use pyo3::prelude::*;
+
+#[pymodule]
+fn gura(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    gura_parser::python::register(module)
+}
@@ -0,0 +1,203 @@
+//! Arena allocated representation of Gura values, for services that handle many documents per
+//! second: all the strings, arrays and objects of a document are allocated in a
+//! [bumpalo](https://docs.rs/bumpalo) arena and freed at once when it is reset or dropped,
+//! instead of node by node.
+//!
+//! Requires the `arena` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use gura::{arena::Bump, parse};
+//!
+//! let mut arena = Bump::new();
+//! for port in [8080, 8081] {
+//!     let parsed = parse(&format!("server:\n    port: {}", port)).unwrap();
+//!     let server = parsed.deep_clone_into(&arena)["server"];
+//!     assert_eq!(server["port"], port as isize);
+//!
+//!     // Frees every value at once, reusing the memory for the next document
+//!     arena.reset();
+//! }
+//! ```
+use crate::{
+    parser::{GuraType, Kind},
+    path::{Path, Segment},
+};
+pub use bumpalo::Bump;
+use std::{fmt, ops::Index};
+
+/// A Gura value whose strings, arrays and objects are stored in an arena, so it is freed along
+/// with it. It is immutable and `Copy`: modifications must be done on a `GuraType` (see
+/// `to_gura_type`). Create it with `GuraType::deep_clone_into`.
+///
+/// Objects keep their pairs in the order they were defined, and keys are looked up by
+/// traversing them, which is fast for the small objects of configuration files.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArenaGuraType<'a> {
+    Null,
+    Bool(bool),
+    String(&'a str),
+    Integer(isize),
+    BigInteger(i128),
+    Float(f64),
+    Array(&'a [ArenaGuraType<'a>]),
+    Object(&'a [(&'a str, ArenaGuraType<'a>)]),
+    /// Custom scalar with its tag and value (see `GuraType::Extension`)
+    Extension(&'a str, &'a ArenaGuraType<'a>),
+}
+
+impl<'a> ArenaGuraType<'a> {
+    /// Copies a value into an arena (see `GuraType::deep_clone_into`). Internal variants
+    /// (e.g. `GuraType::Comment`) are converted to `Null`.
+    pub(crate) fn new(value: &GuraType, arena: &'a Bump) -> Self {
+        match value {
+            GuraType::Bool(value) => ArenaGuraType::Bool(*value),
+            GuraType::String(value) => ArenaGuraType::String(arena.alloc_str(value)),
+            GuraType::Integer(value) => ArenaGuraType::Integer(*value),
+            GuraType::BigInteger(value) => ArenaGuraType::BigInteger(*value),
+            GuraType::Float(value) => ArenaGuraType::Float(*value),
+            GuraType::Array(values) => ArenaGuraType::Array(arena.alloc_slice_fill_iter(
+                values.iter().map(|value| ArenaGuraType::new(value, arena)),
+            )),
+            GuraType::Object(values) => {
+                ArenaGuraType::Object(arena.alloc_slice_fill_iter(values.iter().map(
+                    |(key, value)| (&*arena.alloc_str(key), ArenaGuraType::new(value, arena)),
+                )))
+            }
+            GuraType::Extension(tag, value) => ArenaGuraType::Extension(
+                arena.alloc_str(tag),
+                arena.alloc(ArenaGuraType::new(value, arena)),
+            ),
+            _ => ArenaGuraType::Null,
+        }
+    }
+
+    /// Gets the value of a key. Returns `None` if the value is not an object or the key does
+    /// not exist.
+    pub fn get(&self, key: &str) -> Option<ArenaGuraType<'a>> {
+        match self {
+            ArenaGuraType::Object(values) => values
+                .iter()
+                .find(|(other, _)| *other == key)
+                .map(|(_, value)| *value),
+            _ => None,
+        }
+    }
+
+    /// Like `get` but following a path of keys separated by dots, as in `GuraType::get_path`.
+    pub fn get_path(&self, path: &str) -> Option<ArenaGuraType<'a>> {
+        let path = path.parse::<Path>().ok()?;
+        path.segments()
+            .iter()
+            .try_fold(*self, |current, segment| match (current, segment) {
+                (ArenaGuraType::Array(values), _) => {
+                    segment.index().and_then(|index| values.get(index).copied())
+                }
+                (_, Segment::Key(key)) => current.get(key),
+                _ => None,
+            })
+    }
+
+    /// Gets the kind of the value.
+    pub fn kind(&self) -> Kind {
+        match self {
+            ArenaGuraType::Null => Kind::Null,
+            ArenaGuraType::Bool(_) => Kind::Bool,
+            ArenaGuraType::String(_) => Kind::String,
+            ArenaGuraType::Integer(_) => Kind::Integer,
+            ArenaGuraType::BigInteger(_) => Kind::BigInteger,
+            ArenaGuraType::Float(_) => Kind::Float,
+            ArenaGuraType::Array(_) => Kind::Array,
+            ArenaGuraType::Object(_) => Kind::Object,
+            ArenaGuraType::Extension(..) => Kind::Extension,
+        }
+    }
+
+    /// Converts the value back into a `GuraType`, deep cloning it out of the arena.
+    pub fn to_gura_type(&self) -> GuraType {
+        match self {
+            ArenaGuraType::Null => GuraType::Null,
+            ArenaGuraType::Bool(value) => GuraType::Bool(*value),
+            ArenaGuraType::String(value) => GuraType::String(value.to_string()),
+            ArenaGuraType::Integer(value) => GuraType::Integer(*value),
+            ArenaGuraType::BigInteger(value) => GuraType::BigInteger(*value),
+            ArenaGuraType::Float(value) => GuraType::Float(*value),
+            ArenaGuraType::Array(values) => {
+                GuraType::Array(values.iter().map(ArenaGuraType::to_gura_type).collect())
+            }
+            ArenaGuraType::Object(values) => GuraType::Object(
+                values
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_gura_type()))
+                    .collect(),
+            ),
+            ArenaGuraType::Extension(tag, value) => {
+                GuraType::Extension(tag.to_string(), Box::new(value.to_gura_type()))
+            }
+        }
+    }
+}
+
+impl<'a, T> Index<T> for ArenaGuraType<'a>
+where
+    T: AsRef<str>,
+{
+    type Output = ArenaGuraType<'a>;
+
+    fn index(&self, index: T) -> &ArenaGuraType<'a> {
+        match self {
+            ArenaGuraType::Object(values) => values
+                .iter()
+                .find(|(key, _)| *key == index.as_ref())
+                .map(|(_, value)| value)
+                .expect("The key does not exist in the Gura object"),
+            _ => panic!("Using index in an non object type. Check if the Gura object contains the key first"),
+        }
+    }
+}
+
+impl PartialEq<GuraType> for ArenaGuraType<'_> {
+    fn eq(&self, other: &GuraType) -> bool {
+        match (self, other) {
+            (ArenaGuraType::Array(values), GuraType::Array(others)) => {
+                values.len() == others.len() && values.iter().zip(others).all(|(a, b)| a == b)
+            }
+            (ArenaGuraType::Object(values), GuraType::Object(others)) => {
+                values.len() == others.len()
+                    && values.iter().all(
+                        |(key, value)| matches!(others.get(*key), Some(other) if value == other),
+                    )
+            }
+            (ArenaGuraType::String(value), GuraType::String(other)) => *value == other,
+            (ArenaGuraType::Null, GuraType::Null) => true,
+            (ArenaGuraType::Bool(value), GuraType::Bool(other)) => value == other,
+            (ArenaGuraType::Integer(value), GuraType::Integer(other)) => value == other,
+            (ArenaGuraType::BigInteger(value), GuraType::BigInteger(other)) => value == other,
+            (ArenaGuraType::Float(value), GuraType::Float(other)) => value == other,
+            (ArenaGuraType::Extension(tag, value), GuraType::Extension(other_tag, other)) => {
+                tag == other_tag && **value == **other
+            }
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq<isize> for ArenaGuraType<'_> {
+    fn eq(&self, other: &isize) -> bool {
+        matches!(self, ArenaGuraType::Integer(value) if value == other)
+    }
+}
+
+impl PartialEq<&str> for ArenaGuraType<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        matches!(self, ArenaGuraType::String(value) if value == other)
+    }
+}
+
+/// Same output as `GuraType`'s `Display`
+impl fmt::Display for ArenaGuraType<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_gura_type(), f)
+    }
+}
@@ -0,0 +1,823 @@
+//! Command line tool to query and edit Gura files (requires the `cli` feature).
+//!
+//! ```text
+//! gura check config.ura
+//! gura check --watch configs/
+//! gura ci 'configs/**/*.ura' app.ura
+//! gura get config.ura services.nginx.port
+//! gura set config.ura services.nginx.port 8081
+//! gura diff old.ura new.ura --json
+//! gura lint config.ura --config .guralint.ura
+//! gura env config.ura
+//! gura complete config.ura services.ng
+//! source <(gura completions bash)
+//! gura lint config.ura --format json
+//! gura merge-driver base.ura ours.ura theirs.ura
+//! ```
+//!
+//! To merge Gura files key by key in Git, register the merge driver in the Git configuration
+//! and assign it to the files in `.gitattributes`:
+//!
+//! ```text
+//! git config merge.gura.name "Gura three-way merge"
+//! git config merge.gura.driver "gura merge-driver %O %A %B --conflict-comments"
+//! echo "*.ura merge=gura" >> .gitattributes
+//! ```
+//!
+//! `gura ci` checks whole repositories at once, and can run as a hook of pre-commit
+//! frameworks with the names of the staged files (e.g. a `system` hook of
+//! [pre-commit](https://pre-commit.com) with `entry: gura ci` and `files: \.ura$`).
+use gura::{
+    ci::{check_paths, CheckOptions, FileReport, Report},
+    diff::{diff, Change},
+    dump,
+    editor::EditableDocument,
+    errors::{GuraError, MergeError, ReadError},
+    import_graph,
+    lint::{lint, LintConfig},
+    merge::{merge_three_way_in, MergeConflict, MergeOptions},
+    parse, parse_file, parse_spanned_in,
+    parser::ParserOptions,
+    path::completions,
+    span::{Span, SpannedDocument, VariableSource, VariableUsage},
+    GuraType,
+};
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    process, thread,
+    time::{Duration, SystemTime},
+};
+
+const USAGE: &str = "Usage:
+    gura check <file>               Checks that a file is a valid Gura document, exiting with 1
+                                    otherwise
+    gura check --watch <dir>        Checks the Gura files of a directory and checks them again
+                                    every time they or the files they import change
+    gura ci [--config <file>] <glob>...
+                                    Checks that the files matched by globs (or the .ura files of
+                                    directories) are valid, free of lint warnings and without
+                                    trailing whitespace, exiting with 1 otherwise
+    gura get <file> <path>          Prints the value located at a path (keys separated by dots)
+    gura set <file> <path> <value>  Sets the value located at a path keeping the format of the file
+    gura diff <old> <new> [--json]  Prints the differences between two files, exiting with 1 if any
+    gura lint <file> [--config <file>]
+                                    Prints the warnings of a file as <file>:<line>:<column>, exiting
+                                    with 1 if any. Rules are read from .guralint.ura by default
+    gura env <file>                 Prints a file with the value and origin of every variable used
+                                    at the end of its line
+    gura merge-driver <base> <ours> <theirs> [--conflict-comments]
+                                    Merges the changes of two versions of a file key by key, writing
+                                    the result to <ours>. Conflicts are printed (and written as
+                                    comments before their keys with --conflict-comments), exiting
+                                    with 1 if any
+    gura complete <file> <path>     Prints the paths of the file that complete a partial path, one
+                                    per line (e.g. for wrapper scripts or fzf)
+    gura completions <shell>        Prints the completion script of a shell (bash, zsh or fish),
+                                    which completes the paths of get and set
+
+Every command but completions accepts --format json to print its result (or its error) as JSON";
+
+/// Completion script of Bash
+const BASH_COMPLETIONS: &str = r#"_gura() {
+    local cur=${COMP_WORDS[COMP_CWORD]} command=${COMP_WORDS[1]}
+    case $COMP_CWORD in
+        1) COMPREPLY=($(compgen -W "check ci get set diff lint env merge-driver complete completions help" -- "$cur")) ;;
+        2) case $command in
+               completions) COMPREPLY=($(compgen -W "bash zsh fish" -- "$cur")) ;;
+               *) COMPREPLY=($(compgen -f -- "$cur")) ;;
+           esac ;;
+        3) case $command in
+               get|set|complete) COMPREPLY=($(gura complete "${COMP_WORDS[2]}" "$cur" 2>/dev/null)) ;;
+               diff) COMPREPLY=($(compgen -f -- "$cur")) ;;
+               lint) COMPREPLY=($(compgen -W "--config" -- "$cur")) ;;
+           esac ;;
+        4) [ "$command" = lint ] && COMPREPLY=($(compgen -f -- "$cur")) ;;
+    esac
+}
+complete -o default -F _gura gura"#;
+
+/// Completion script of Zsh
+const ZSH_COMPLETIONS: &str = r#"#compdef gura
+_gura() {
+    case $CURRENT in
+        2) compadd check ci get set diff lint env merge-driver complete completions help ;;
+        3) case $words[2] in
+               completions) compadd bash zsh fish ;;
+               *) _files ;;
+           esac ;;
+        4) case $words[2] in
+               get|set|complete) compadd -- ${(f)"$(gura complete $words[3] $words[4] 2>/dev/null)"} ;;
+               diff) _files ;;
+               lint) compadd -- --config ;;
+           esac ;;
+        5) [[ $words[2] == lint ]] && _files ;;
+    esac
+}
+compdef _gura gura"#;
+
+/// Completion script of Fish
+const FISH_COMPLETIONS: &str = r#"function __gura_args_count
+    test (count (commandline -opc)) -eq $argv[1]
+end
+complete -c gura -f
+complete -c gura -n __fish_use_subcommand -a "check ci get set diff lint env merge-driver complete completions help"
+complete -c gura -n "__fish_seen_subcommand_from completions" -a "bash zsh fish"
+complete -c gura -n "not __fish_seen_subcommand_from completions; and __gura_args_count 2" -F
+complete -c gura -n "__fish_seen_subcommand_from get set complete; and __gura_args_count 3" -a "(gura complete (commandline -opc)[3] (commandline -ct) 2>/dev/null)"
+complete -c gura -n "__fish_seen_subcommand_from diff; and __gura_args_count 3" -F
+complete -c gura -n "__fish_seen_subcommand_from lint; and __gura_args_count 3" -a --config
+complete -c gura -n "__fish_seen_subcommand_from lint; and __gura_args_count 4" -F"#;
+
+/// Lint configuration used when none is given, if it exists
+const DEFAULT_LINT_CONFIG: &str = ".guralint.ura";
+
+/// Time between the scans of the watched directories
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Exit code of the errors of the arguments
+const USAGE_EXIT_CODE: i32 = 2;
+
+/// Exit code of `diff` when the files are different, `lint` and `ci` when problems are found
+/// and `merge-driver` when there are conflicts
+const FINDINGS_EXIT_CODE: i32 = 1;
+
+/// Format of the results of the commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// Output of a successful command, printed to the standard output before exiting with `code`
+#[derive(Debug)]
+struct Output {
+    code: i32,
+    text: String,
+}
+
+impl From<String> for Output {
+    fn from(text: String) -> Self {
+        Output { code: 0, text }
+    }
+}
+
+/// Error of a command, printed to the standard error before exiting with `code`. With
+/// `--format json`, `details` is printed to the standard output instead, as the value of an
+/// `error` key.
+#[derive(Debug)]
+struct CliError {
+    code: i32,
+    message: String,
+    /// Object with the `code` of the error (e.g. `PathNotFoundError`) and its `message`,
+    /// along with the location of parsing errors
+    details: serde_json::Value,
+}
+
+impl CliError {
+    fn new(kind: &str, message: impl fmt::Display) -> Self {
+        let message = message.to_string();
+        CliError {
+            code: 1,
+            details: json!({ "code": kind, "message": message }),
+            message,
+        }
+    }
+
+    fn usage() -> Self {
+        CliError {
+            code: USAGE_EXIT_CODE,
+            message: USAGE.to_string(),
+            details: json!({ "code": "UsageError", "message": "Invalid arguments, run gura help to see the usage" }),
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(output) => {
+            if !output.text.is_empty() {
+                println!("{}", output.text);
+            }
+            process::exit(output.code);
+        }
+        Err(error) => {
+            if args.windows(2).any(|pair| pair == ["--format", "json"]) {
+                println!("{}", json!({ "error": error.details }));
+            } else {
+                eprintln!("{}", error.message);
+            }
+            process::exit(error.code);
+        }
+    }
+}
+
+/// Runs the command of the arguments, returning what must be printed.
+fn run(args: &[String]) -> Result<Output, CliError> {
+    let mut args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let format = match args.iter().position(|arg| *arg == "--format") {
+        Some(index) => {
+            let format = match args.get(index + 1) {
+                Some(&"text") => Format::Text,
+                Some(&"json") => Format::Json,
+                _ => return Err(CliError::usage()),
+            };
+            args.drain(index..index + 2);
+            format
+        }
+        None => Format::Text,
+    };
+
+    match args.as_slice() {
+        ["check", file] => check(file, format),
+        ["check", "--watch", dir] => watch(dir, format),
+        ["ci", "--config", config, globs @ ..] if !globs.is_empty() => {
+            ci(globs, Some(config), format)
+        }
+        ["ci", globs @ ..] if !globs.is_empty() => ci(globs, None, format),
+        ["get", file, path] => get(file, path, format).map(Output::from),
+        ["set", file, path, value] => set(file, path, value, format).map(Output::from),
+        ["diff", old, new] => diff_files(old, new, format),
+        ["diff", old, new, "--json"] => diff_files(old, new, Format::Json),
+        ["lint", file] => lint_file(file, None, format),
+        ["lint", file, "--config", config] => lint_file(file, Some(config), format),
+        ["env", file] => env_file(file, format).map(Output::from),
+        ["merge-driver", base, ours, theirs] => merge_driver(base, ours, theirs, false, format),
+        ["merge-driver", base, ours, theirs, "--conflict-comments"] => {
+            merge_driver(base, ours, theirs, true, format)
+        }
+        ["complete", file, path] => complete(file, path, format).map(Output::from),
+        ["completions", "bash"] => Ok(Output::from(BASH_COMPLETIONS.to_string())),
+        ["completions", "zsh"] => Ok(Output::from(ZSH_COMPLETIONS.to_string())),
+        ["completions", "fish"] => Ok(Output::from(FISH_COMPLETIONS.to_string())),
+        ["help" | "--help" | "-h"] => Ok(Output::from(USAGE.to_string())),
+        _ => Err(CliError::usage()),
+    }
+}
+
+/// Checks that a file is a valid Gura document. Nothing is printed if it is, and in JSON the
+/// result is an object with the `file` and its `errors` (see `error_json`).
+fn check(file: &str, format: Format) -> Result<Output, CliError> {
+    let error = parse_document(file, &read(file)?, &ParserOptions::default()).err();
+    match (format, error) {
+        (Format::Text, None) => Ok(Output::from(String::new())),
+        (Format::Text, Some(error)) => Err(parse_error(file, &error)),
+        (Format::Json, error) => {
+            let code = if error.is_some() {
+                FINDINGS_EXIT_CODE
+            } else {
+                0
+            };
+            let errors: Vec<_> = error.iter().map(|error| error_json(file, error)).collect();
+            let text = json!({ "file": file, "errors": errors }).to_string();
+            Ok(Output { code, text })
+        }
+    }
+}
+
+/// Checked file of a watched directory
+struct WatchedFile {
+    /// Canonical paths of the file and the ones it imports, directly or not
+    dependencies: Vec<PathBuf>,
+}
+
+/// Modification time and size of a file, `None` if it does not exist
+type Stamp = Option<(SystemTime, u64)>;
+
+/// Checks the Gura files of `dir` and its subdirectories (see `gura_files`) until the process
+/// is killed, printing the result of every file (as in `check`, or `<file>: ok`) and printing
+/// it again every time the file or any file it imports changes. Removed files are reported as
+/// `<file>: removed`. In JSON every result is printed as a line with an object (see `check`),
+/// with a `removed` key for removed files.
+fn watch(dir: &str, format: Format) -> Result<Output, CliError> {
+    let mut watched: BTreeMap<PathBuf, WatchedFile> = BTreeMap::new();
+    let mut stamps: HashMap<PathBuf, Stamp> = HashMap::new();
+    loop {
+        let files = gura_files(Path::new(dir)).map_err(|error| {
+            CliError::new("IoError", format!("Could not read {}: {}", dir, error))
+        })?;
+
+        // Files whose content changed since the last scan
+        let changed: Vec<PathBuf> = stamps
+            .iter_mut()
+            .filter_map(|(path, stamp)| {
+                let current = stamp_of(path);
+                (current != *stamp).then(|| {
+                    *stamp = current;
+                    path.clone()
+                })
+            })
+            .collect();
+
+        watched.retain(|file, _| {
+            let exists = files.contains(file);
+            if !exists {
+                let file = file.to_string_lossy();
+                match format {
+                    Format::Text => println!("{}: removed", file),
+                    Format::Json => println!("{}", json!({ "file": file, "removed": true })),
+                }
+            }
+            exists
+        });
+        for file in files {
+            let is_affected = watched.get(&file).is_none_or(|watched_file| {
+                watched_file
+                    .dependencies
+                    .iter()
+                    .any(|dependency| changed.contains(dependency))
+            });
+            if is_affected {
+                // Imports of invalid files are the ones of their last valid version
+                let dependencies = check_watched(&file, format).unwrap_or_else(|| {
+                    watched.get(&file).map_or_else(
+                        || vec![canonical(&file)],
+                        |watched_file| watched_file.dependencies.clone(),
+                    )
+                });
+                for dependency in dependencies.iter() {
+                    stamps
+                        .entry(dependency.clone())
+                        .or_insert_with(|| stamp_of(dependency));
+                }
+                watched.insert(file, WatchedFile { dependencies });
+            }
+        }
+        thread::sleep(WATCH_INTERVAL);
+    }
+}
+
+/// Checks a file of a watched directory, printing the result. Returns the canonical paths of
+/// the file and the ones it imports, `None` if they can not be known (e.g. an imported file is
+/// not valid).
+fn check_watched(file: &Path, format: Format) -> Option<Vec<PathBuf>> {
+    let name = file.to_string_lossy();
+    let options = ParserOptions::default();
+    let error = parse_file(file, &options).err();
+    match (format, &error) {
+        (Format::Text, None) => println!("{}: ok", name),
+        (Format::Text, Some(ReadError::Parse(error))) => {
+            println!("{}", parse_error(&name, error).message)
+        }
+        (Format::Text, Some(error)) => println!("{}: {}", name, error),
+        (Format::Json, error) => {
+            let errors: Vec<_> = error
+                .iter()
+                .map(|error| read_error_json(&name, error))
+                .collect();
+            println!("{}", json!({ "file": name, "errors": errors }));
+        }
+    }
+
+    let graph = import_graph(file).ok()?;
+    Some(
+        graph
+            .files
+            .iter()
+            .map(|imported| canonical(&imported.path))
+            .collect(),
+    )
+}
+
+/// Gets the Gura files (with the `.ura` extension) of a directory and its subdirectories,
+/// sorted by path. Hidden directories (e.g. `.git`) and symbolic links to directories are
+/// skipped, so link cycles end.
+fn gura_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        let is_link = fs::symlink_metadata(&path).is_ok_and(|metadata| metadata.is_symlink());
+        if path.is_dir() {
+            if !is_hidden && !is_link {
+                files.extend(gura_files(&path)?);
+            }
+        } else if path.extension().is_some_and(|extension| extension == "ura") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn stamp_of(path: &Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Prints the value located at `path`. Strings are printed without quotes and objects as
+/// Gura documents. In JSON the result is an object with the `path` and its `value`.
+fn get(file: &str, path: &str, format: Format) -> Result<String, CliError> {
+    let document = parse_document(file, &read(file)?, &ParserOptions::default())
+        .map_err(|error| parse_error(file, &error))?;
+    let value = document.value.get_path(path).ok_or_else(|| {
+        CliError::new(
+            "PathNotFoundError",
+            format!("The path \"{}\" does not exist", path),
+        )
+    })?;
+    Ok(match (format, value) {
+        (Format::Json, _) => json!({ "path": path, "value": value }).to_string(),
+        (Format::Text, GuraType::Object(_)) => dump(value),
+        (Format::Text, _) => value.raw_display().to_string(),
+    })
+}
+
+/// Prints the paths that complete `path`, one per line or as a JSON array of strings.
+fn complete(file: &str, path: &str, format: Format) -> Result<String, CliError> {
+    let document = parse_document(file, &read(file)?, &ParserOptions::default())
+        .map_err(|error| parse_error(file, &error))?;
+    let paths: Vec<String> = completions(&document.value, path)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    Ok(match format {
+        Format::Text => paths.join("\n"),
+        Format::Json => json!(paths).to_string(),
+    })
+}
+
+/// Sets the value located at `path`. Values that are not valid Gura values (e.g. unquoted
+/// words) are set as strings. Nothing is printed, and in JSON the result is an object with the
+/// `file`, the `path` and the `value` set.
+fn set(file: &str, path: &str, value: &str, format: Format) -> Result<String, CliError> {
+    let mut document = EditableDocument::parse_in(&read(file)?, import_dir(file))
+        .map_err(|error| parse_error(file, &error))?;
+    let value = parse_value(value);
+    document
+        .set_value(path, &value)
+        .map_err(|error| CliError::new("EditError", error))?;
+    fs::write(file, document.text()).map_err(|error| {
+        CliError::new("IoError", format!("Could not write {}: {}", file, error))
+    })?;
+    Ok(match format {
+        Format::Text => String::new(),
+        Format::Json => json!({ "file": file, "path": path, "value": value }).to_string(),
+    })
+}
+
+/// Prints the differences between two files, one per line or as a JSON array of objects with
+/// `change` (`added`, `removed` or `modified`) and `path` keys, along with `value` or `old` and
+/// `new` keys.
+fn diff_files(old: &str, new: &str, format: Format) -> Result<Output, CliError> {
+    let options = ParserOptions::default();
+    let old_document =
+        parse_document(old, &read(old)?, &options).map_err(|error| parse_error(old, &error))?;
+    let new_document =
+        parse_document(new, &read(new)?, &options).map_err(|error| parse_error(new, &error))?;
+    let changes = diff(&old_document.value, &new_document.value);
+
+    let text = if format == Format::Json {
+        let changes: Vec<serde_json::Value> = changes.iter().map(change_to_json).collect();
+        serde_json::Value::Array(changes).to_string()
+    } else {
+        changes
+            .iter()
+            .map(Change::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let code = if changes.is_empty() {
+        0
+    } else {
+        FINDINGS_EXIT_CODE
+    };
+    Ok(Output { code, text })
+}
+
+fn change_to_json(change: &Change) -> serde_json::Value {
+    match change {
+        Change::Added { path, value } => {
+            json!({ "change": "added", "path": path.to_string(), "value": value })
+        }
+        Change::Removed { path, value } => {
+            json!({ "change": "removed", "path": path.to_string(), "value": value })
+        }
+        Change::Modified { path, old, new } => {
+            json!({ "change": "modified", "path": path.to_string(), "old": old, "new": new })
+        }
+    }
+}
+
+/// Merges `ours` and `theirs`, two versions of `base`, writing the result to `ours` as a Git
+/// merge driver does. Conflicts are printed one per line, or as a JSON array of objects with
+/// the `path` and the `base`, `ours` and `theirs` values (null for missing keys).
+fn merge_driver(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    conflict_comments: bool,
+    format: Format,
+) -> Result<Output, CliError> {
+    let (base_text, ours_text, theirs_text) = (read(base)?, read(ours)?, read(theirs)?);
+    let options = MergeOptions::default().conflict_comments(conflict_comments);
+    let merged = merge_three_way_in(
+        &base_text,
+        &ours_text,
+        &theirs_text,
+        Some(import_dir(ours)),
+        &options,
+    )
+    .map_err(|error| match error {
+        MergeError::Base(error) => parse_error(base, &error),
+        MergeError::Ours(error) => parse_error(ours, &error),
+        MergeError::Theirs(error) => parse_error(theirs, &error),
+    })?;
+    fs::write(ours, &merged.text).map_err(|error| {
+        CliError::new("IoError", format!("Could not write {}: {}", ours, error))
+    })?;
+
+    let text = match format {
+        Format::Text => merged
+            .conflicts
+            .iter()
+            .map(|conflict| format!("CONFLICT {}", conflict))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Format::Json => {
+            let conflicts: Vec<_> = merged.conflicts.iter().map(conflict_json).collect();
+            serde_json::Value::Array(conflicts).to_string()
+        }
+    };
+    let code = if merged.is_clean() {
+        0
+    } else {
+        FINDINGS_EXIT_CODE
+    };
+    Ok(Output { code, text })
+}
+
+fn conflict_json(conflict: &MergeConflict) -> serde_json::Value {
+    json!({
+        "path": conflict.path.to_string(),
+        "base": conflict.base,
+        "ours": conflict.ours,
+        "theirs": conflict.theirs,
+    })
+}
+
+/// Checks the files matched by globs, printing the report (see `Report`). In JSON the result
+/// is an object with the `files`, each one with its `errors` (as in `check`), its lint
+/// `warnings` (as in `lint`) and its `layout` issues (with their `message` and `line`), and
+/// the `unmatched` globs.
+fn ci(globs: &[&str], config: Option<&str>, format: Format) -> Result<Output, CliError> {
+    let options = CheckOptions::default().lint(Some(lint_config(config)?));
+    let report = check_paths(globs, &options);
+    let code = if report.is_ok() {
+        0
+    } else {
+        FINDINGS_EXIT_CODE
+    };
+    let text = match format {
+        Format::Text => report.to_string(),
+        Format::Json => report_json(&report).to_string(),
+    };
+    Ok(Output { code, text })
+}
+
+fn report_json(report: &Report) -> serde_json::Value {
+    let files: Vec<serde_json::Value> = report.files.iter().map(file_report_json).collect();
+    json!({ "files": files, "unmatched": report.unmatched })
+}
+
+fn file_report_json(report: &FileReport) -> serde_json::Value {
+    let name = report.path.to_string_lossy();
+    // Spans are only described for the files that could be read
+    let text = fs::read_to_string(&report.path).unwrap_or_default();
+    let errors: Vec<_> = report
+        .error
+        .iter()
+        .map(|error| read_error_json(&name, error))
+        .collect();
+    let warnings: Vec<_> = report
+        .warnings
+        .iter()
+        .map(|warning| {
+            json!({
+                "rule": warning.rule.name(),
+                "message": warning.msg,
+                "span": warning
+                    .span
+                    .as_ref()
+                    .filter(|span| span.byte_end <= text.len())
+                    .map(|span| span_json(&text, span)),
+            })
+        })
+        .collect();
+    let layout: Vec<_> = report
+        .layout
+        .iter()
+        .map(|issue| json!({ "message": issue.to_string(), "line": issue.line() }))
+        .collect();
+    json!({ "file": name, "errors": errors, "warnings": warnings, "layout": layout })
+}
+
+/// Prints the warnings of a file, one per line as `<file>:<line>:<column>: warning: <msg>
+/// [<rule>]` so editors can match them, or as a JSON array of objects with the `rule`, the
+/// `message` and the `span` (see `span_json`, null for keys defined in imported files).
+/// Without explicit configuration, rules are read from `.guralint.ura` if it exists in the
+/// working directory.
+fn lint_file(file: &str, config: Option<&str>, format: Format) -> Result<Output, CliError> {
+    let config = lint_config(config)?;
+    let text = read(file)?;
+    let document = parse_document(file, &text, &ParserOptions::default())
+        .map_err(|error| parse_error(file, &error))?;
+    let warnings = lint(&document, &config);
+
+    let text = if format == Format::Json {
+        let warnings: Vec<serde_json::Value> = warnings
+            .iter()
+            .map(|warning| {
+                json!({
+                    "rule": warning.rule.name(),
+                    "message": warning.msg,
+                    "span": warning.span.as_ref().map(|span| span_json(&text, span)),
+                })
+            })
+            .collect();
+        serde_json::Value::Array(warnings).to_string()
+    } else {
+        warnings
+            .iter()
+            .map(|warning| match &warning.span {
+                Some(span) => format!(
+                    "{}:{}:{}: warning: {}",
+                    file,
+                    span.line,
+                    column(&text, span),
+                    warning
+                ),
+                None => format!("{}: warning: {}", file, warning),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let code = if warnings.is_empty() {
+        0
+    } else {
+        FINDINGS_EXIT_CODE
+    };
+    Ok(Output { code, text })
+}
+
+/// Loads the lint configuration of a file, or the one of `.guralint.ura` if it exists and none
+/// is given.
+fn lint_config(file: Option<&str>) -> Result<LintConfig, CliError> {
+    match file {
+        Some(file) => load_lint_config(file),
+        None if fs::metadata(DEFAULT_LINT_CONFIG).is_ok() => load_lint_config(DEFAULT_LINT_CONFIG),
+        None => Ok(LintConfig::default()),
+    }
+}
+
+fn load_lint_config(file: &str) -> Result<LintConfig, CliError> {
+    let document = parse_document(file, &read(file)?, &ParserOptions::default())
+        .map_err(|error| parse_error(file, &error))?;
+    LintConfig::from_document(&document.value)
+        .map_err(|error| CliError::new("LintConfigError", format!("{}: {}", file, error)))
+}
+
+/// Gets the column (one based, in chars) where a span starts.
+fn column(text: &str, span: &Span) -> usize {
+    let line_start = text[..span.byte_start]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    text[line_start..span.byte_start].chars().count() + 1
+}
+
+/// Describes the location of a span as its `line`, its `column` and its `byte_start` and
+/// `byte_end` offsets.
+fn span_json(text: &str, span: &Span) -> serde_json::Value {
+    json!({
+        "line": span.line,
+        "column": column(text, span),
+        "byte_start": span.byte_start,
+        "byte_end": span.byte_end,
+    })
+}
+
+/// Prints the text of a file annotating every line that uses variables with a comment like
+/// `# $port = 8080 (line 1), $USER = "admin" (environment)`. In JSON the result is an array
+/// with the `name`, `value`, `span` and `source` (`document`, `imported` or `environment`) of
+/// every usage, along with the `definition` span of the ones defined in the document.
+fn env_file(file: &str, format: Format) -> Result<String, CliError> {
+    let text = read(file)?;
+    let document = parse_document(file, &text, &ParserOptions::default())
+        .map_err(|error| parse_error(file, &error))?;
+    let usages = document.variable_usages();
+    if format == Format::Json {
+        let usages: Vec<serde_json::Value> = usages
+            .iter()
+            .map(|usage| usage_json(&text, usage))
+            .collect();
+        return Ok(serde_json::Value::Array(usages).to_string());
+    }
+
+    let lines = text.lines().enumerate().map(|(index, line)| {
+        let annotations: Vec<String> = usages
+            .iter()
+            .filter(|usage| usage.span.line == index + 1)
+            .map(annotation)
+            .collect();
+        if annotations.is_empty() {
+            line.to_string()
+        } else {
+            format!("{} # {}", line, annotations.join(", "))
+        }
+    });
+    Ok(lines.collect::<Vec<_>>().join("\n"))
+}
+
+fn annotation(usage: &VariableUsage) -> String {
+    let source = match usage.source {
+        VariableSource::Document(Some(definition)) => format!("line {}", definition.line),
+        VariableSource::Document(None) => String::from("imported"),
+        VariableSource::Environment => String::from("environment"),
+    };
+    format!("${} = {} ({})", usage.name, usage.value, source)
+}
+
+fn usage_json(text: &str, usage: &VariableUsage) -> serde_json::Value {
+    let (source, definition) = match usage.source {
+        VariableSource::Document(Some(definition)) => ("document", Some(definition)),
+        VariableSource::Document(None) => ("imported", None),
+        VariableSource::Environment => ("environment", None),
+    };
+    json!({
+        "name": usage.name,
+        "value": usage.value,
+        "span": span_json(text, &usage.span),
+        "source": source,
+        "definition": definition.map(|span| span_json(text, &span)),
+    })
+}
+
+/// Parses a value given as argument.
+fn parse_value(value: &str) -> GuraType {
+    match parse(&format!("value: {}", value)) {
+        Ok(GuraType::Object(mut values)) => values
+            .shift_remove("value")
+            .unwrap_or_else(|| GuraType::String(value.to_string())),
+        _ => GuraType::String(value.to_string()),
+    }
+}
+
+fn read(file: &str) -> Result<String, CliError> {
+    fs::read_to_string(file)
+        .map_err(|error| CliError::new("IoError", format!("Could not read {}: {}", file, error)))
+}
+
+/// Gets the directory the imports of a file are relative to.
+fn import_dir(file: &str) -> &Path {
+    Path::new(file).parent().unwrap_or_else(|| Path::new(""))
+}
+
+/// Parses the text of a file in spanned mode, with its imports relative to the directory of the
+/// file.
+fn parse_document(
+    file: &str,
+    text: &str,
+    options: &ParserOptions,
+) -> Result<SpannedDocument, GuraError> {
+    parse_spanned_in(text, Some(import_dir(file)), options)
+}
+
+fn parse_error(file: &str, error: &GuraError) -> CliError {
+    CliError {
+        code: 1,
+        message: format!("{}:{}: {}", file, error.line, error),
+        details: error_json(file, error),
+    }
+}
+
+/// Describes a parsing error as its `code` (the kind of error, e.g. `DuplicatedKeyError`), its
+/// `message`, the `file`, `line` and `byte_offset` where it was found and the `suggestion` to
+/// fix it (null if there is none).
+fn error_json(file: &str, error: &GuraError) -> serde_json::Value {
+    json!({
+        "code": format!("{:?}", error.kind),
+        "message": error.msg,
+        "file": file,
+        "line": error.line,
+        "byte_offset": error.byte_pos,
+        "suggestion": error.suggestion,
+    })
+}
+
+/// Describes an error reading a file as `error_json` does, with the `IoError` code for the
+/// errors that are not parsing ones.
+fn read_error_json(file: &str, error: &ReadError) -> serde_json::Value {
+    match error {
+        ReadError::Parse(error) => error_json(file, error),
+        error => json!({ "code": "IoError", "message": error.to_string(), "file": file }),
+    }
+}
@@ -0,0 +1,103 @@
+//! Fluent construction of Gura documents. An alternative to the `object!` macro when the keys
+//! come from runtime data.
+use crate::parser::GuraType;
+use indexmap::IndexMap;
+
+/// Builds a `GuraType::Object` pair by pair. Keys are kept in insertion order and keys that
+/// are set again replace their previous value.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{builder::DocumentBuilder, dump};
+///
+/// let document = DocumentBuilder::new()
+///     .key("title").string("Gura")
+///     .object("server", |server| server.key("port").int(8080))
+///     .build();
+///
+/// assert_eq!(dump(&document), "title: \"Gura\"\nserver:\n    port: 8080");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentBuilder {
+    values: IndexMap<String, GuraType>,
+}
+
+impl DocumentBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        DocumentBuilder::default()
+    }
+
+    /// Starts a pair with `key`. Its value is set with the returned `KeyBuilder`.
+    pub fn key(self, key: impl Into<String>) -> KeyBuilder {
+        KeyBuilder {
+            builder: self,
+            key: key.into(),
+        }
+    }
+
+    /// Adds a pair whose value is an object built by `build`.
+    pub fn object<F>(self, key: impl Into<String>, build: F) -> Self
+    where
+        F: FnOnce(DocumentBuilder) -> DocumentBuilder,
+    {
+        let object = build(DocumentBuilder::new()).build();
+        self.key(key).value(object)
+    }
+
+    /// Adds a pair with a value of any type.
+    pub fn pair(mut self, key: impl Into<String>, value: GuraType) -> Self {
+        self.values.insert(key.into(), value);
+        self
+    }
+
+    /// Gets the built object.
+    pub fn build(self) -> GuraType {
+        GuraType::Object(self.values)
+    }
+}
+
+/// Sets the value of a pair started with `DocumentBuilder::key`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyBuilder {
+    builder: DocumentBuilder,
+    key: String,
+}
+
+impl KeyBuilder {
+    /// Sets a value of any type.
+    pub fn value(self, value: GuraType) -> DocumentBuilder {
+        self.builder.pair(self.key, value)
+    }
+
+    pub fn string(self, value: impl Into<String>) -> DocumentBuilder {
+        self.value(GuraType::String(value.into()))
+    }
+
+    pub fn int(self, value: isize) -> DocumentBuilder {
+        self.value(GuraType::Integer(value))
+    }
+
+    /// Sets an integer which does not fit in an `isize`.
+    pub fn big_int(self, value: i128) -> DocumentBuilder {
+        self.value(GuraType::BigInteger(value))
+    }
+
+    pub fn float(self, value: f64) -> DocumentBuilder {
+        self.value(GuraType::Float(value))
+    }
+
+    pub fn bool(self, value: bool) -> DocumentBuilder {
+        self.value(GuraType::Bool(value))
+    }
+
+    pub fn null(self) -> DocumentBuilder {
+        self.value(GuraType::Null)
+    }
+
+    /// Sets an array with the given elements.
+    pub fn array(self, values: impl IntoIterator<Item = GuraType>) -> DocumentBuilder {
+        self.value(GuraType::Array(values.into_iter().collect()))
+    }
+}
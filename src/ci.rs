@@ -0,0 +1,432 @@
+//! Checks of whole repositories, for pre-commit hooks and CI jobs: `check_paths` parses, lints
+//! and checks the layout of every Gura file matched by some globs, in parallel, and gathers the
+//! results in a single `Report` (also available as `gura ci <globs>` in the command line tool).
+//!
+//! Globs are relative to the working directory and support `*` and `?` inside a segment and
+//! `**` for any number of directories (e.g. `configs/**/*.ura`). Paths without wildcards select
+//! a file, or the `.ura` files of a directory and its subdirectories, so the names of the files
+//! staged in a commit (as given by pre-commit frameworks) can be passed directly. Hidden
+//! directories (e.g. `.git`) are only searched if a glob names them.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use gura::ci::{check_paths, CheckOptions};
+//!
+//! let report = check_paths(&["configs/**/*.ura", "app.ura"], &CheckOptions::default());
+//! if !report.is_ok() {
+//!     eprintln!("{}", report);
+//!     std::process::exit(1);
+//! }
+//! ```
+use crate::{
+    errors::ReadError,
+    lint::{lint, LintConfig, LintWarning},
+    parser::{parse_spanned_file, ParserOptions},
+};
+use std::{
+    collections::BTreeSet,
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+/// Options to customize `check_paths`.
+#[derive(Debug, Clone)]
+pub struct CheckOptions {
+    parser: ParserOptions,
+    lint: Option<LintConfig>,
+    layout: bool,
+    threads: Option<usize>,
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        CheckOptions {
+            parser: ParserOptions::default(),
+            lint: Some(LintConfig::default()),
+            layout: true,
+            threads: None,
+        }
+    }
+}
+
+impl CheckOptions {
+    /// Sets the options used to parse the files.
+    pub fn parser(mut self, options: ParserOptions) -> Self {
+        self.parser = options;
+        self
+    }
+
+    /// Sets the rules checked on the valid files, or disables linting with `None`. By default
+    /// the default rules of `LintConfig` are checked.
+    pub fn lint(mut self, config: Option<LintConfig>) -> Self {
+        self.lint = config;
+        self
+    }
+
+    /// Checks the layout of the text of the files (see `LayoutIssue`). Enabled by default.
+    pub fn layout(mut self, enabled: bool) -> Self {
+        self.layout = enabled;
+        self
+    }
+
+    /// Sets the number of files checked at the same time. By default it is the parallelism
+    /// available to the process (one at a time if it can not be known, e.g. on WASI).
+    pub fn threads(mut self, threads: Option<usize>) -> Self {
+        self.threads = threads;
+        self
+    }
+}
+
+/// Problem in the layout of the text of a file, which does not prevent parsing it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutIssue {
+    /// A line, with its number, ends with spaces or tabs
+    TrailingWhitespace(usize),
+    /// A line, with its number, ends with `\r\n` instead of `\n`
+    CarriageReturn(usize),
+    /// The text is not empty and does not end with a new line
+    MissingFinalNewline,
+}
+
+impl LayoutIssue {
+    /// Gets the line of the issue, `None` for the ones of the whole text.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            LayoutIssue::TrailingWhitespace(line) | LayoutIssue::CarriageReturn(line) => {
+                Some(*line)
+            }
+            LayoutIssue::MissingFinalNewline => None,
+        }
+    }
+}
+
+impl fmt::Display for LayoutIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutIssue::TrailingWhitespace(_) => f.write_str("Trailing whitespace"),
+            LayoutIssue::CarriageReturn(_) => f.write_str("Line ends with \\r\\n"),
+            LayoutIssue::MissingFinalNewline => f.write_str("Missing new line at the end"),
+        }
+    }
+}
+
+/// Gets the layout issues of a text, sorted by line.
+///
+/// # Examples
+///
+/// ```
+/// use gura::ci::{layout_issues, LayoutIssue};
+///
+/// assert_eq!(
+///     layout_issues("title: \"Gura\" \nport: 8080"),
+///     [LayoutIssue::TrailingWhitespace(1), LayoutIssue::MissingFinalNewline]
+/// );
+/// ```
+pub fn layout_issues(text: &str) -> Vec<LayoutIssue> {
+    let mut issues = Vec::new();
+    for (index, line) in text.split('\n').enumerate() {
+        let content = match line.strip_suffix('\r') {
+            Some(content) => {
+                issues.push(LayoutIssue::CarriageReturn(index + 1));
+                content
+            }
+            None => line,
+        };
+        if content.ends_with([' ', '\t']) {
+            issues.push(LayoutIssue::TrailingWhitespace(index + 1));
+        }
+    }
+    if !text.is_empty() && !text.ends_with('\n') {
+        issues.push(LayoutIssue::MissingFinalNewline);
+    }
+    issues
+}
+
+/// Result of the checks of a file
+#[derive(Debug)]
+pub struct FileReport {
+    /// Path of the file, as matched by the globs
+    pub path: PathBuf,
+    /// Error reading or parsing the file, if any. Invalid files are not linted
+    pub error: Option<ReadError>,
+    /// Warnings of the linter, sorted by position
+    pub warnings: Vec<LintWarning>,
+    /// Problems in the layout of the text, sorted by line
+    pub layout: Vec<LayoutIssue>,
+}
+
+impl FileReport {
+    /// Checks if nothing was found in the file.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none() && self.warnings.is_empty() && self.layout.is_empty()
+    }
+}
+
+/// Results of `check_paths`
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Results of every matched file, sorted by path
+    pub files: Vec<FileReport>,
+    /// Globs that did not match any file, which usually are mistakes in the configuration of
+    /// the job
+    pub unmatched: Vec<String>,
+}
+
+impl Report {
+    /// Checks if every glob matched some file and nothing was found in any of them.
+    pub fn is_ok(&self) -> bool {
+        self.unmatched.is_empty() && self.files.iter().all(FileReport::is_ok)
+    }
+
+    /// Gets the files that could not be read or parsed.
+    pub fn failed(&self) -> impl Iterator<Item = &FileReport> {
+        self.files.iter().filter(|file| file.error.is_some())
+    }
+}
+
+/// Writes a line for every problem, as `<file>:<line>: <severity>: <message>` (without line
+/// for the problems of whole files), followed by a summary.
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for glob in &self.unmatched {
+            writeln!(f, "{}: error: No file matches the glob", glob)?;
+        }
+        for file in &self.files {
+            let path = file.path.display();
+            match &file.error {
+                Some(ReadError::Parse(error)) => {
+                    writeln!(f, "{}:{}: error: {}", path, error.line, error)?
+                }
+                Some(error) => writeln!(f, "{}: error: {}", path, error)?,
+                None => (),
+            }
+            for warning in &file.warnings {
+                match &warning.span {
+                    Some(span) => writeln!(f, "{}:{}: warning: {}", path, span.line, warning)?,
+                    None => writeln!(f, "{}: warning: {}", path, warning)?,
+                }
+            }
+            for issue in &file.layout {
+                match issue.line() {
+                    Some(line) => writeln!(f, "{}:{}: layout: {}", path, line, issue)?,
+                    None => writeln!(f, "{}: layout: {}", path, issue)?,
+                }
+            }
+        }
+        let with_problems = self.files.iter().filter(|file| !file.is_ok()).count();
+        write!(
+            f,
+            "{} files checked, {} with problems",
+            self.files.len(),
+            with_problems
+        )
+    }
+}
+
+/// Parses, lints and checks the layout of the files matched by `globs` (see the module
+/// documentation for their syntax), checking several files at the same time. Files matched by
+/// more than one glob are checked once.
+///
+/// # Examples
+///
+/// ```
+/// use gura::ci::{check_paths, CheckOptions};
+/// use std::fs;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// fs::write(dir.path().join("valid.ura"), "port: 8080\n").unwrap();
+/// fs::write(dir.path().join("invalid.ura"), "port: 8080\nport: 8081\n").unwrap();
+///
+/// let glob = format!("{}/*.ura", dir.path().display());
+/// let report = check_paths(&[&glob], &CheckOptions::default());
+/// assert_eq!(report.files.len(), 2);
+/// assert!(!report.is_ok());
+/// assert_eq!(report.failed().count(), 1);
+/// ```
+pub fn check_paths(globs: &[&str], options: &CheckOptions) -> Report {
+    let mut paths = BTreeSet::new();
+    let mut unmatched = Vec::new();
+    for glob in globs {
+        let matched = expand_glob(glob);
+        if matched.is_empty() {
+            unmatched.push(glob.to_string());
+        }
+        paths.extend(matched);
+    }
+    let paths: Vec<PathBuf> = paths.into_iter().collect();
+
+    let threads = options
+        .threads
+        .or_else(|| thread::available_parallelism().ok().map(usize::from))
+        .unwrap_or(1)
+        .clamp(1, paths.len().max(1));
+    let files = if threads == 1 {
+        paths
+            .into_iter()
+            .map(|path| check_file(path, options))
+            .collect()
+    } else {
+        // Every thread takes the next unchecked file until there are none left
+        let next = AtomicUsize::new(0);
+        let results = Mutex::new(Vec::with_capacity(paths.len()));
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(path) = paths.get(index) else {
+                        break;
+                    };
+                    let report = check_file(path.clone(), options);
+                    results.lock().unwrap().push((index, report));
+                });
+            }
+        });
+        let mut results = results.into_inner().unwrap();
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, report)| report).collect()
+    };
+
+    Report { files, unmatched }
+}
+
+fn check_file(path: PathBuf, options: &CheckOptions) -> FileReport {
+    let mut report = FileReport {
+        path,
+        error: None,
+        warnings: Vec::new(),
+        layout: Vec::new(),
+    };
+    if options.layout {
+        match fs::read(&report.path) {
+            Ok(bytes) => report.layout = layout_issues(&String::from_utf8_lossy(&bytes)),
+            Err(error) => {
+                report.error = Some(ReadError::Io(error));
+                return report;
+            }
+        }
+    }
+    match parse_spanned_file(&report.path, &options.parser) {
+        Ok(document) => {
+            if let Some(config) = &options.lint {
+                report.warnings = lint(&document, config);
+            }
+        }
+        Err(error) => report.error = Some(error),
+    }
+    report
+}
+
+/// Gets the files matched by a glob, or the `.ura` files of a directory without wildcards.
+fn expand_glob(glob: &str) -> Vec<PathBuf> {
+    let glob = glob.strip_prefix("./").unwrap_or(glob);
+    let segments: Vec<&str> = glob.split('/').collect();
+
+    // Directory of the segments before the first one with wildcards
+    let literal = segments
+        .iter()
+        .take_while(|segment| !segment.contains(['*', '?']))
+        .count();
+    if literal == segments.len() {
+        let path = Path::new(glob);
+        return if path.is_file() {
+            vec![path.to_path_buf()]
+        } else if path.is_dir() {
+            let mut files = Vec::new();
+            walk(path, None, &mut files);
+            files.retain(|file| file.extension().is_some_and(|extension| extension == "ura"));
+            files
+        } else {
+            Vec::new()
+        };
+    }
+    let base = segments[..literal].join("/");
+    // Without `**`, files can only be as deep as the segments after the base
+    let depth = (!segments.contains(&"**")).then(|| segments.len() - literal - 1);
+    let mut files = Vec::new();
+    if base.is_empty() && glob.starts_with('/') {
+        walk(Path::new("/"), depth, &mut files);
+    } else if base.is_empty() {
+        for entry in fs::read_dir(".").into_iter().flatten().flatten() {
+            visit(PathBuf::from(entry.file_name()), depth, &mut files);
+        }
+    } else {
+        walk(Path::new(&base), depth, &mut files);
+    }
+
+    files.retain(|file| {
+        let file = file.to_string_lossy().replace('\\', "/");
+        let file_segments: Vec<&str> = file.split('/').collect();
+        matches_segments(&segments, &file_segments)
+    });
+    files
+}
+
+/// Collects the files of a directory and its subdirectories (up to `depth` levels below it, if
+/// any), skipping hidden directories and symbolic links to directories (which could make
+/// cycles).
+fn walk(dir: &Path, depth: Option<usize>, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        visit(entry.path(), depth, files);
+    }
+}
+
+fn visit(path: PathBuf, depth: Option<usize>, files: &mut Vec<PathBuf>) {
+    let is_hidden = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+    let is_link = fs::symlink_metadata(&path).is_ok_and(|metadata| metadata.is_symlink());
+    if path.is_dir() {
+        match depth {
+            Some(0) => (),
+            _ if is_hidden || is_link => (),
+            depth => walk(&path, depth.map(|depth| depth - 1), files),
+        }
+    } else {
+        files.push(path);
+    }
+}
+
+/// Matches the segments of a path with the ones of a glob, where `**` matches any number of
+/// segments.
+fn matches_segments(glob: &[&str], path: &[&str]) -> bool {
+    match glob.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => {
+            (0..=path.len()).any(|skipped| matches_segments(rest, &path[skipped..]))
+        }
+        Some((segment, rest)) => match path.split_first() {
+            Some((name, path_rest)) => {
+                matches_segment(segment.as_bytes(), name.as_bytes())
+                    && matches_segments(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Matches a name with a segment of a glob, where `*` matches any text and `?` any char.
+fn matches_segment(glob: &[u8], name: &[u8]) -> bool {
+    match glob.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => {
+            (0..=name.len()).any(|skipped| matches_segment(rest, &name[skipped..]))
+        }
+        Some((b'?', rest)) => {
+            // Skips a whole UTF-8 char
+            let len = name
+                .iter()
+                .skip(1)
+                .take_while(|byte| (**byte & 0xC0) == 0x80)
+                .count()
+                + 1;
+            !name.is_empty() && matches_segment(rest, &name[len.min(name.len())..])
+        }
+        Some((byte, rest)) => name.first() == Some(byte) && matches_segment(rest, &name[1..]),
+    }
+}
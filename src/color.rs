@@ -0,0 +1,66 @@
+//! Dumping of Gura values with syntax highlighting for terminals (ANSI escape codes). Useful
+//! for `--show-config` like commands.
+//!
+//! Requires the `color` feature.
+use crate::{
+    highlight::{highlight, TokenClass},
+    parser::{dump_content_with_options, DumpOptions, GuraType},
+};
+
+/// Code to restore the default style
+const RESET: &str = "\x1b[0m";
+
+/// Gets the ANSI escape code used for a class of token.
+fn style(class: TokenClass) -> &'static str {
+    match class {
+        TokenClass::Key => "\x1b[34m",
+        TokenClass::String => "\x1b[32m",
+        TokenClass::Number => "\x1b[33m",
+        TokenClass::Boolean | TokenClass::Null => "\x1b[35m",
+        TokenClass::Variable => "\x1b[36m",
+        TokenClass::Comment => "\x1b[90m",
+        TokenClass::Import => "\x1b[1m",
+    }
+}
+
+/// Dumps a value as `dump` does, highlighting its syntax with ANSI escape codes. If
+/// `max_depth` is defined, objects nested in more than `max_depth` objects are replaced by `…`.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{color::to_colored_string, object, GuraType};
+///
+/// let object = object! { server: { tls: { enabled: true } } };
+/// let colored = to_colored_string(&object, Some(1));
+/// assert_eq!(colored, "\x1b[34mserver\x1b[0m:\n    \x1b[34mtls\x1b[0m: …");
+/// ```
+pub fn to_colored_string(value: &GuraType, max_depth: Option<usize>) -> String {
+    let mut options = DumpOptions::default();
+    options.max_depth = max_depth;
+    let text = dump_content_with_options(value, &options, 0)
+        .trim()
+        .to_string();
+
+    let mut result = String::with_capacity(text.len() * 2);
+    let mut last_end = 0;
+    for (span, class) in highlight(&text) {
+        result.push_str(&text[last_end..span.byte_start]);
+        result.push_str(style(class));
+        result.push_str(&text[span.byte_start..span.byte_end]);
+        result.push_str(RESET);
+        last_end = span.byte_end;
+    }
+    result.push_str(&text[last_end..]);
+    result
+}
+
+/// Prints a value to the standard output highlighting its syntax.
+pub fn print_colored(value: &GuraType) {
+    println!("{}", to_colored_string(value, None));
+}
+
+/// Like `print_colored` but replacing objects nested in more than `max_depth` objects by `…`.
+pub fn print_colored_truncated(value: &GuraType, max_depth: usize) {
+    println!("{}", to_colored_string(value, Some(max_depth)));
+}
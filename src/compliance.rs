@@ -0,0 +1,304 @@
+//! Runner of compliance test suites: directories of Gura documents with their expected
+//! outcomes in JSON. Any Gura front-end (e.g. a WASM build or a CLI) can be checked against
+//! the same corpus by providing its own parsing function.
+//!
+//! Every `<name>.ura` file of the directory is a test case, which must have one of these
+//! files next to it:
+//!
+//! * `<name>.json`: the document must be parsed into this value. As JSON has no infinite or
+//!   NaN numbers, they are written as the strings `"inf"`, `"+inf"`, `"-inf"` and `"nan"`.
+//!   Integers too big for JSON numbers can be written as strings of digits.
+//! * `<name>.error.json`: parsing must fail with an object like
+//!   `{"kind": "DuplicatedKeyError", "line": 3}`, where `kind` is the name of an `Error`
+//!   variant and `line` is optional.
+//!
+//! Requires the `compliance` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use gura::{compliance::run_suite, parse};
+//!
+//! let report = run_suite("gura-tests/tests-files", parse).unwrap();
+//! for (name, reason) in report.failures() {
+//!     println!("{} failed: {}", name, reason);
+//! }
+//! assert!(report.is_success());
+//! ```
+use crate::{errors::GuraError, parser::GuraType};
+use serde_json::Value;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A Gura document of a suite with its expected outcome
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestCase {
+    /// File name without the `.ura` extension
+    pub name: String,
+    pub path: PathBuf,
+    pub expected: Expected,
+}
+
+/// Expected outcome of parsing a test case
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expected {
+    /// The document is parsed into this value
+    Value(Value),
+    /// Parsing fails with an error of this kind (the name of an `Error` variant), at `line` if
+    /// defined
+    Error { kind: String, line: Option<usize> },
+}
+
+/// Result of running a test case
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    /// The test case failed for the given reason
+    Failed(String),
+}
+
+/// Results of all the test cases of a suite, sorted by name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SuiteReport {
+    pub results: Vec<(String, Outcome)>,
+}
+
+impl SuiteReport {
+    /// Gets the number of test cases that passed.
+    pub fn passed(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|(_, outcome)| *outcome == Outcome::Passed)
+            .count()
+    }
+
+    /// Gets the name of the failed test cases with the reason of their failure.
+    pub fn failures(&self) -> Vec<(&str, &str)> {
+        self.results
+            .iter()
+            .filter_map(|(name, outcome)| match outcome {
+                Outcome::Failed(reason) => Some((name.as_str(), reason.as_str())),
+                Outcome::Passed => None,
+            })
+            .collect()
+    }
+
+    /// Checks if every test case passed.
+    pub fn is_success(&self) -> bool {
+        self.passed() == self.results.len()
+    }
+}
+
+/// Loads the test cases of a directory, sorted by name.
+///
+/// # Errors
+///
+/// * io::Error - If a file can not be read or a test case does not have a valid expected
+///   outcome.
+pub fn load_cases<P: AsRef<Path>>(dir: P) -> io::Result<Vec<TestCase>> {
+    let mut cases = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some("ura") {
+            continue;
+        }
+
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let expected = load_expected(&path, &name)?;
+        cases.push(TestCase {
+            name,
+            path,
+            expected,
+        });
+    }
+    cases.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(cases)
+}
+
+/// Reads the expected outcome of the test case located at `path`.
+fn load_expected(path: &Path, name: &str) -> io::Result<Expected> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let read_json = |path: PathBuf| -> io::Result<Value> {
+        let content = fs::read_to_string(&path)?;
+        serde_json::from_str(&content)
+            .map_err(|error| invalid(format!("{}: {}", path.display(), error)))
+    };
+
+    let value_path = path.with_file_name(format!("{}.json", name));
+    if value_path.exists() {
+        return read_json(value_path).map(Expected::Value);
+    }
+
+    let error_path = path.with_file_name(format!("{}.error.json", name));
+    if !error_path.exists() {
+        return Err(invalid(format!(
+            "{}: missing expected outcome",
+            path.display()
+        )));
+    }
+    let error = read_json(error_path.clone())?;
+    let kind = error["kind"]
+        .as_str()
+        .ok_or_else(|| invalid(format!("{}: missing error kind", error_path.display())))?;
+    Ok(Expected::Error {
+        kind: kind.to_string(),
+        line: error["line"].as_u64().map(|line| line as usize),
+    })
+}
+
+/// Runs a test case with a parsing function.
+pub fn run_case<F>(case: &TestCase, mut parse: F) -> Outcome
+where
+    F: FnMut(&str) -> Result<GuraType, GuraError>,
+{
+    let text = match fs::read_to_string(&case.path) {
+        Ok(text) => text,
+        Err(error) => return Outcome::Failed(format!("could not read the document: {}", error)),
+    };
+
+    match (parse(&text), &case.expected) {
+        (Ok(value), Expected::Value(expected)) => match mismatch(&value, expected, "") {
+            Some(reason) => Outcome::Failed(reason),
+            None => Outcome::Passed,
+        },
+        (Ok(_), Expected::Error { kind, .. }) => {
+            Outcome::Failed(format!("expected {}, but the document was parsed", kind))
+        }
+        (Err(error), Expected::Value(_)) => {
+            Outcome::Failed(format!("unexpected {:?}: {}", error.kind, error))
+        }
+        (Err(error), Expected::Error { kind, line }) => {
+            let found = format!("{:?}", error.kind);
+            if found != *kind {
+                Outcome::Failed(format!("expected {}, found {}: {}", kind, found, error))
+            } else if matches!(line, Some(line) if *line != error.line) {
+                Outcome::Failed(format!(
+                    "expected {} at line {}, found it at line {}",
+                    kind,
+                    line.unwrap_or_default(),
+                    error.line
+                ))
+            } else {
+                Outcome::Passed
+            }
+        }
+    }
+}
+
+/// Runs all the test cases of a directory with a parsing function.
+///
+/// # Errors
+///
+/// * io::Error - If the test cases can not be loaded (see `load_cases`).
+pub fn run_suite<P, F>(dir: P, mut parse: F) -> io::Result<SuiteReport>
+where
+    P: AsRef<Path>,
+    F: FnMut(&str) -> Result<GuraType, GuraError>,
+{
+    let results = load_cases(dir)?
+        .into_iter()
+        .map(|case| {
+            let outcome = run_case(&case, &mut parse);
+            (case.name, outcome)
+        })
+        .collect();
+    Ok(SuiteReport { results })
+}
+
+/// Compares a parsed value with the expected JSON value, describing the first difference.
+fn mismatch(value: &GuraType, expected: &Value, path: &str) -> Option<String> {
+    let matches = match (value, expected) {
+        (GuraType::Object(values), Value::Object(expected_values)) => {
+            if let Some(key) = expected_values
+                .keys()
+                .find(|key| !values.contains_key(key.as_str()))
+            {
+                return Some(format!("missing key {}", join_path(path, key)));
+            }
+            for (key, value) in values {
+                let key_path = join_path(path, key);
+                match expected_values.get(key) {
+                    Some(expected) => {
+                        if let Some(reason) = mismatch(value, expected, &key_path) {
+                            return Some(reason);
+                        }
+                    }
+                    None => return Some(format!("unexpected key {}", key_path)),
+                }
+            }
+            true
+        }
+        (GuraType::Array(values), Value::Array(expected_values)) => {
+            if values.len() != expected_values.len() {
+                return Some(format!(
+                    "expected {} elements, found {} at {}",
+                    expected_values.len(),
+                    values.len(),
+                    location(path)
+                ));
+            }
+            for (index, (value, expected)) in values.iter().zip(expected_values).enumerate() {
+                if let Some(reason) =
+                    mismatch(value, expected, &join_path(path, &index.to_string()))
+                {
+                    return Some(reason);
+                }
+            }
+            true
+        }
+        (GuraType::Null, Value::Null) => true,
+        (GuraType::Bool(value), Value::Bool(expected)) => value == expected,
+        (GuraType::String(value), Value::String(expected)) => value == expected,
+        (GuraType::Integer(value), Value::Number(expected)) => {
+            expected.as_i64() == Some(*value as i64)
+        }
+        (GuraType::BigInteger(value), Value::Number(expected)) => {
+            expected.as_u64().map(i128::from) == Some(*value)
+        }
+        (GuraType::Integer(value), Value::String(expected)) => expected == &value.to_string(),
+        (GuraType::BigInteger(value), Value::String(expected)) => expected == &value.to_string(),
+        (GuraType::Float(value), Value::Number(expected)) => expected.as_f64() == Some(*value),
+        (GuraType::Float(value), Value::String(expected)) => match expected.as_str() {
+            "inf" | "+inf" => *value == f64::INFINITY,
+            "-inf" => *value == f64::NEG_INFINITY,
+            "nan" => value.is_nan(),
+            _ => false,
+        },
+        _ => false,
+    };
+
+    if matches {
+        None
+    } else {
+        Some(format!(
+            "expected {}, found {} at {}",
+            expected,
+            value,
+            location(path)
+        ))
+    }
+}
+
+/// Describes a path for failure reasons.
+fn location(path: &str) -> &str {
+    if path.is_empty() {
+        "the root"
+    } else {
+        path
+    }
+}
+
+/// Appends a key to a path separated by a dot.
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
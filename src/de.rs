@@ -0,0 +1,514 @@
+//! Deserialization of Gura documents into Rust values with [serde](https://serde.rs).
+//!
+//! Objects can be deserialized into structs and maps, and arrays into sequences and tuples.
+//! Enums use the same representations as `serde_json`: unit variants are strings and the
+//! rest of variants are objects with a single key (the name of the variant). Internally
+//! tagged, adjacently tagged and untagged enums are supported as well. Bytes are read from
+//! base64 strings.
+//!
+//! Container and field attributes like `flatten`, `default` and `deny_unknown_fields` work as
+//! with `serde_json`. The path of errors points to the value that failed, or to the key
+//! itself for unknown fields (e.g. `server.prot` for an unexpected `prot` key in `server`).
+//!
+//! The `Deserializer` can feed any serde `Serializer` directly (e.g. with `serde_transcode`
+//! or with `transcode`), to convert Gura documents to other formats without intermediate
+//! values like `serde_json::Value`.
+//!
+//! Requires the `serde` feature.
+use crate::{
+    errors::{DeserializeError, ErrorSet},
+    parser::{parse, parse_spanned, GuraType},
+    schema::Schema,
+};
+use indexmap::map::Iter;
+use serde::de::{
+    self, value::BorrowedStrDeserializer, DeserializeOwned, DeserializeSeed, EnumAccess,
+    IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+use serde::{forward_to_deserialize_any, ser, Deserialize};
+use std::{convert::TryFrom, fmt, iter::Enumerate, slice};
+
+impl de::Error for DeserializeError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        DeserializeError::Invalid {
+            path: String::new(),
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Parses a Gura text and deserializes it into `T`.
+///
+/// # Examples
+///
+/// ```
+/// use gura::de::from_str;
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Server {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let server: Server = from_str("host: \"localhost\"\nport: 8080").unwrap();
+/// assert_eq!(server.port, 8080);
+///
+/// let error = from_str::<Server>("host: \"localhost\"\nport: -1").unwrap_err();
+/// assert_eq!(error.to_string(), "invalid value: integer `-1`, expected u16 at port");
+/// ```
+///
+/// # Errors
+///
+/// * Parse - If the text is not a valid Gura document.
+/// * Invalid - If the document does not match `T`.
+pub fn from_str<T: DeserializeOwned>(text: &str) -> Result<T, DeserializeError> {
+    let value = parse(text).map_err(DeserializeError::Parse)?;
+    from_value(&value)
+}
+
+/// Like `from_str` but validating the document against `schema` first, so every value that
+/// does not match it is reported at once (with its line) instead of only the first one.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{de::from_str_with_schema, schema::Schema, Kind};
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Server {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let schema = Schema::object()
+///     .field("host", Schema::new(Kind::String).required())
+///     .field("port", Schema::new(Kind::Integer).required());
+///
+/// let errors = from_str_with_schema::<Server>("host: 127\nport: \"80\"", &schema).unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[1].to_string(), "expected integer, found string at port (line 2)");
+/// ```
+///
+/// # Errors
+///
+/// The parsing error if the text is not a valid Gura document, every validation error if it
+/// does not match the schema, or the deserialization error if it does not match `T`.
+pub fn from_str_with_schema<T: DeserializeOwned>(
+    text: &str,
+    schema: &Schema,
+) -> Result<T, ErrorSet> {
+    let document = parse_spanned(text)?;
+    schema.validate_spanned(&document)?;
+    Ok(from_value(&document.value)?)
+}
+
+/// Parses a Gura text and writes it with any serde `Serializer`, converting it to another
+/// format.
+///
+/// # Examples
+///
+/// ```
+/// let mut json = Vec::new();
+/// gura::de::transcode(
+///     "name: \"cache\"\nports: [80, 443]",
+///     &mut serde_json::Serializer::new(&mut json),
+/// )
+/// .unwrap();
+/// assert_eq!(String::from_utf8(json).unwrap(), r#"{"name":"cache","ports":[80,443]}"#);
+/// ```
+///
+/// # Errors
+///
+/// * S::Error - If the text is not a valid Gura document (with the message of the parsing
+///   error) or the serializer fails.
+pub fn transcode<S: ser::Serializer>(text: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    let value = parse(text).map_err(ser::Error::custom)?;
+    ser::Serialize::serialize(&value, serializer)
+}
+
+/// Deserializes a Gura value into `T`. Strings can be borrowed from the value.
+///
+/// # Errors
+///
+/// * Invalid - If the value does not match `T`.
+pub fn from_value<'de, T: Deserialize<'de>>(value: &'de GuraType) -> Result<T, DeserializeError> {
+    T::deserialize(Deserializer::new(value))
+}
+
+/// Serde deserializer over a parsed Gura value
+#[derive(Debug, Clone, Copy)]
+pub struct Deserializer<'de> {
+    value: &'de GuraType,
+}
+
+impl<'de> Deserializer<'de> {
+    /// Creates a deserializer of a value. Custom scalars (`GuraType::Extension`) are
+    /// deserialized as the value returned by their recognizer.
+    pub fn new(mut value: &'de GuraType) -> Self {
+        while let GuraType::Extension(_, inner) = value {
+            value = inner;
+        }
+        Deserializer { value }
+    }
+
+    fn invalid_type<V: Visitor<'de>>(&self, visitor: &V) -> DeserializeError {
+        de::Error::invalid_type(unexpected(self.value), visitor)
+    }
+}
+
+/// Describes a value for error messages.
+fn unexpected(value: &GuraType) -> de::Unexpected<'_> {
+    match value {
+        GuraType::Null => de::Unexpected::Unit,
+        GuraType::Bool(value) => de::Unexpected::Bool(*value),
+        GuraType::String(value) => de::Unexpected::Str(value),
+        GuraType::Integer(value) => de::Unexpected::Signed(*value as i64),
+        GuraType::BigInteger(_) => de::Unexpected::Other("big integer"),
+        GuraType::Float(value) => de::Unexpected::Float(*value),
+        GuraType::Array(_) => de::Unexpected::Seq,
+        GuraType::Object(_) => de::Unexpected::Map,
+        _ => de::Unexpected::Other("internal value"),
+    }
+}
+
+/// Decodes a base64 string with the standard alphabet and padding.
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(4) {
+        return None;
+    }
+    let text = text.as_bytes();
+    let padding = text.iter().rev().take_while(|char| **char == b'=').count();
+    if padding > 2 {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+    for chunk in text[..text.len() - padding].chunks(4) {
+        let mut group = 0u32;
+        for (index, char) in chunk.iter().enumerate() {
+            let sextet = match char {
+                b'A'..=b'Z' => char - b'A',
+                b'a'..=b'z' => char - b'a' + 26,
+                b'0'..=b'9' => char - b'0' + 52,
+                b'+' => 62,
+                b'/' => 63,
+                _ => return None,
+            };
+            group |= (sextet as u32) << (18 - 6 * index);
+        }
+        for index in 0..chunk.len() - 1 {
+            bytes.push((group >> (16 - 8 * index)) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            GuraType::Null => visitor.visit_unit(),
+            GuraType::Bool(value) => visitor.visit_bool(*value),
+            GuraType::String(value) => visitor.visit_borrowed_str(value),
+            GuraType::Integer(value) => visitor.visit_i64(*value as i64),
+            GuraType::BigInteger(value) => match u64::try_from(*value) {
+                Ok(value) => visitor.visit_u64(value),
+                Err(_) => visitor.visit_i128(*value),
+            },
+            GuraType::Float(value) => visitor.visit_f64(*value),
+            GuraType::Array(values) => visitor.visit_seq(SeqDeserializer {
+                iter: values.iter().enumerate(),
+            }),
+            GuraType::Object(values) => visitor.visit_map(MapDeserializer {
+                iter: values.iter(),
+                value: None,
+            }),
+            _ => Err(self.invalid_type(&visitor)),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            GuraType::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    /// Accepts `null` and `empty`
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            GuraType::Null => visitor.visit_unit(),
+            GuraType::Object(values) if values.is_empty() => visitor.visit_unit(),
+            _ => Err(self.invalid_type(&visitor)),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value {
+            GuraType::String(variant) => visitor.visit_enum(variant.as_str().into_deserializer()),
+            GuraType::Object(values) if values.len() == 1 => {
+                let (variant, value) = values.iter().next().unwrap();
+                visitor.visit_enum(EnumDeserializer { variant, value })
+            }
+            _ => Err(de::Error::invalid_type(
+                unexpected(self.value),
+                &"a string or an object with a single key",
+            )),
+        }
+    }
+
+    /// Strings are decoded from base64, as written by the `ser` module
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            GuraType::String(value) => match decode_base64(value) {
+                Some(bytes) => visitor.visit_byte_buf(bytes),
+                None => Err(de::Error::invalid_value(
+                    de::Unexpected::Str(value),
+                    &"a base64 string",
+                )),
+            },
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string seq
+        tuple tuple_struct map struct identifier
+    }
+}
+
+/// Gives access to the elements of an array
+struct SeqDeserializer<'de> {
+    iter: Enumerate<slice::Iter<'de, GuraType>>,
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        match self.iter.next() {
+            Some((index, value)) => seed
+                .deserialize(Deserializer::new(value))
+                .map(Some)
+                .map_err(|error| error.in_segment(&index.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// Gives access to the pairs of an object
+struct MapDeserializer<'de> {
+    iter: Iter<'de, String, GuraType>,
+    /// Pair whose key was the last one deserialized
+    value: Option<(&'de String, &'de GuraType)>,
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some((key, value));
+                seed.deserialize(KeyDeserializer { key })
+                    .map(Some)
+                    .map_err(|error| error.in_segment(key))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (key, value) = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before its key"))?;
+        seed.deserialize(Deserializer::new(value))
+            .map_err(|error| error.in_segment(key))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// Deserializes the keys of objects, which are strings or, for maps with integer keys (as
+/// written by the `ser` module), the integers they contain
+struct KeyDeserializer<'de> {
+    key: &'de str,
+}
+
+/// Implements the methods of integer types, which parse the key. Keys that are not integers
+/// are passed as strings, so the visitor reports them.
+macro_rules! deserialize_integer_key {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                match self.key.parse() {
+                    Ok(integer) => visitor.$visit(integer),
+                    Err(_) => visitor.visit_borrowed_str(self.key),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_str(self.key)
+    }
+
+    deserialize_integer_key! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(BorrowedStrDeserializer::new(self.key))
+    }
+
+    forward_to_deserialize_any! {
+        bool f32 f64 char str string bytes byte_buf unit unit_struct seq tuple tuple_struct
+        map struct identifier ignored_any
+    }
+}
+
+/// Gives access to the variant of an enum represented as an object with a single key
+struct EnumDeserializer<'de> {
+    variant: &'de String,
+    value: &'de GuraType,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer<'de> {
+    type Error = DeserializeError;
+    type Variant = VariantDeserializer<'de>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant = seed.deserialize(BorrowedStrDeserializer::<DeserializeError>::new(
+            self.variant,
+        ))?;
+        Ok((
+            variant,
+            VariantDeserializer {
+                variant: self.variant,
+                value: self.value,
+            },
+        ))
+    }
+}
+
+/// Gives access to the content of an enum variant
+struct VariantDeserializer<'de> {
+    variant: &'de String,
+    value: &'de GuraType,
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        de::Deserialize::deserialize(Deserializer::new(self.value))
+            .map_err(|error: DeserializeError| error.in_segment(self.variant))
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(Deserializer::new(self.value))
+            .map_err(|error| error.in_segment(self.variant))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_seq(Deserializer::new(self.value), visitor)
+            .map_err(|error| error.in_segment(self.variant))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_map(Deserializer::new(self.value), visitor)
+            .map_err(|error| error.in_segment(self.variant))
+    }
+}
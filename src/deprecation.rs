@@ -0,0 +1,120 @@
+use crate::{
+    parser::GuraType,
+    span::{Span, SpannedDocument},
+};
+use indexmap::IndexMap;
+use std::fmt;
+
+/// A deprecated key found in a Gura document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeprecationWarning {
+    /// Path of the deprecated key (keys separated by dots)
+    pub path: String,
+    /// Path of the key that should be used instead, if any
+    pub replacement: Option<String>,
+    /// Location of the key, only available when checking spanned documents
+    pub span: Option<Span>,
+}
+
+impl fmt::Display for DeprecationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The key \"{}\" is deprecated", self.path)?;
+        if let Some(replacement) = &self.replacement {
+            write!(f, ", use \"{}\" instead", replacement)?;
+        }
+        if let Some(span) = &self.span {
+            write!(f, " (line {})", span.line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Set of deprecated key paths with optional replacement hints.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{deprecation::DeprecationMap, parse};
+///
+/// let mut deprecations = DeprecationMap::new();
+/// deprecations.deprecate("server.hostname", Some("server.host"));
+/// deprecations.deprecate("legacy_mode", None);
+///
+/// let parsed = parse("server:\n    hostname: \"localhost\"").unwrap();
+/// let warnings = deprecations.check_deprecations(&parsed);
+///
+/// assert_eq!(warnings.len(), 1);
+/// assert_eq!(
+///     warnings[0].to_string(),
+///     "The key \"server.hostname\" is deprecated, use \"server.host\" instead"
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DeprecationMap {
+    paths: IndexMap<String, Option<String>>,
+}
+
+impl DeprecationMap {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        DeprecationMap::default()
+    }
+
+    /// Registers a deprecated key path (keys separated by dots, as in `GuraType::get_path`).
+    ///
+    /// # Arguments
+    ///
+    /// * path - Path of the deprecated key.
+    /// * replacement - Path of the key that replaces the deprecated one, if any.
+    pub fn deprecate(&mut self, path: &str, replacement: Option<&str>) {
+        self.paths
+            .insert(path.to_string(), replacement.map(String::from));
+    }
+
+    /// Checks if a specific path was registered as deprecated
+    pub fn is_deprecated(&self, path: &str) -> bool {
+        self.paths.contains_key(path)
+    }
+
+    /// Gets a warning for every deprecated key defined in the document, in the order they
+    /// were registered.
+    pub fn check_deprecations(&self, document: &GuraType) -> Vec<DeprecationWarning> {
+        self.paths
+            .iter()
+            .filter(|(path, _)| document.get_path(path).is_some())
+            .map(|(path, replacement)| DeprecationWarning {
+                path: path.clone(),
+                replacement: replacement.clone(),
+                span: None,
+            })
+            .collect()
+    }
+
+    /// Like `check_deprecations` but including the span of the deprecated keys in the warnings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{deprecation::DeprecationMap, parse_spanned};
+    ///
+    /// let mut deprecations = DeprecationMap::new();
+    /// deprecations.deprecate("hostname", Some("host"));
+    ///
+    /// let document = parse_spanned("port: 8080\nhostname: \"localhost\"").unwrap();
+    /// let warnings = deprecations.check_deprecations_spanned(&document);
+    /// assert_eq!(
+    ///     warnings[0].to_string(),
+    ///     "The key \"hostname\" is deprecated, use \"host\" instead (line 2)"
+    /// );
+    /// ```
+    pub fn check_deprecations_spanned(
+        &self,
+        document: &SpannedDocument,
+    ) -> Vec<DeprecationWarning> {
+        let mut warnings = self.check_deprecations(&document.value);
+        for warning in warnings.iter_mut() {
+            warning.span = document.span(&warning.path);
+        }
+        warnings
+    }
+}
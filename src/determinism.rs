@@ -0,0 +1,138 @@
+//! Suite that checks that a build of the parser gives byte-identical results on every platform:
+//! the same parsed values (with the same order of keys), the same dumped texts (floats
+//! included) and the same error positions. Run it on every target you ship to (e.g.
+//! `cargo test --features determinism --test determinism`) or check your own corpus by
+//! comparing `outcome` across platforms.
+//!
+//! Line endings of the input are preserved in multiline strings, so texts must be compared
+//! with the same line endings (files of this repository are checked out with `\n`, see
+//! `.gitattributes`). Integers are stored as `isize`, so only 64 bits targets are covered:
+//! in 32 bits ones bigger integers are parsed as `GuraType::BigInteger`.
+//!
+//! Requires the `determinism` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use gura::determinism::{check, outcome};
+//!
+//! assert!(check().is_empty());
+//! assert_eq!(
+//!     outcome("a: 1\na: 2"),
+//!     "DuplicatedKeyError at line 2, position 5, byte 5, UTF-16 5: The key \"a\" has been already defined"
+//! );
+//! ```
+use crate::parser::{dump, parse};
+
+/// A text with the outcome it must have in every platform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Case {
+    pub name: &'static str,
+    pub text: &'static str,
+    /// Expected result of `outcome`
+    pub expected: &'static str,
+}
+
+/// Case whose outcome is not the expected one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub name: &'static str,
+    pub expected: &'static str,
+    pub actual: String,
+}
+
+/// Cases of the suite, covering the order of keys, numbers, strings, variables, line endings
+/// and error positions
+pub const CASES: &[Case] = &[
+    Case {
+        name: "key_order",
+        text: "zeta: 1\nalpha: 2\nmiddle:\n    beta: true\n    alpha: null",
+        expected: "zeta: 1\nalpha: 2\nmiddle:\n    beta: true\n    alpha: null\nfingerprint: e8936a4fb910cebc",
+    },
+    Case {
+        name: "floats",
+        text: "a: 0.1\nb: 1e300\nc: -0.0\nd: 1.5e-7\ne: 3.14159265358979\nf: inf\ng: -inf\nh: nan\ni: 0.30000000000000004\nj: 1e-300\nk: 123456789.123",
+        expected: "a: 0.1\nb: 1e300\nc: 0.0\nd: 1.5e-7\ne: 3.14159265358979\nf: inf\ng: -inf\nh: nan\ni: 0.3\nj: 1e-300\nk: 123456789.123\nfingerprint: 89a886ed7cc85eac",
+    },
+    Case {
+        name: "integers",
+        text: "a: 9223372036854775807\nb: 9223372036854775808\nc: -170141183460469231731687303715884105728\nd: 0xFFFFFFFFFFFFFFFFF\ne: 0o777\nf: 0b1010\ng: 1_000_000",
+        expected: "a: 9223372036854775807\nb: 9223372036854775808\nc: -170141183460469231731687303715884105728\nd: 295147905179352825855\ne: 511\nf: 10\ng: 1000000\nfingerprint: f1ca6b19af1ea927",
+    },
+    Case {
+        name: "strings",
+        text: "a: \"tab\\tand \\u00e9 and 👍🏽\"\nb: 'C:\\Users\\gura'\nc: \"\"\"\nmulti\nline\"\"\"",
+        expected: "a: \"tab\\tand é and 👍🏽\"\nb: \"C:\\\\Users\\\\gura\"\nc: \"multi\\nline\"\nfingerprint: 4602e363d443c358",
+    },
+    Case {
+        name: "variables",
+        text: "$host: \"localhost\"\n$port: 8080\nurl: \"http://$host:$port\"\nport: $port",
+        expected: "url: \"http://localhost:8080\"\nport: 8080\nfingerprint: 795cc79ba67916c8",
+    },
+    Case {
+        name: "arrays",
+        text: "tags: [\"a\", \"b\"]\nempty: []\nnested: [[1, 2], [3.5, null]]",
+        expected: "tags: [\"a\", \"b\"]\nempty: []\nnested: [[1, 2], [3.5, null]]\nfingerprint: 1cf4d3a62832958a",
+    },
+    Case {
+        name: "crlf",
+        text: "a: 1\r\nb:\r\n    c: \"x\"\r\n",
+        expected: "a: 1\nb:\n    c: \"x\"\nfingerprint: 4a64cf6a6f9a58b5",
+    },
+    Case {
+        name: "error_graphemes",
+        text: "emoji: \"👍🏽 é\"\nkey: @",
+        expected: "ParseError at line 2, position 18, byte 26, UTF-16 21: Expected \"[\" but got \"@\"",
+    },
+    Case {
+        name: "error_duplicated_key",
+        text: "a: 1\na: 2",
+        expected: "DuplicatedKeyError at line 2, position 5, byte 5, UTF-16 5: The key \"a\" has been already defined",
+    },
+    Case {
+        name: "error_indentation",
+        text: "a:\n   b: 1",
+        expected: "InvalidIndentationError at line 2, position 2, byte 2, UTF-16 2: Indentation block (3) must be divisible by 4",
+    },
+    Case {
+        name: "error_variable",
+        text: "a: $undefined_determinism_variable",
+        expected: "VariableNotDefinedError at line 1, position 3, byte 3, UTF-16 3: Variable \"undefined_determinism_variable\" is not defined in Gura nor as environment variable",
+    },
+];
+
+/// Gets a canonical description of the result of parsing a text: the dumped value followed by
+/// its fingerprint (see `GuraType::fingerprint`), or the kind, location and message of the
+/// error.
+pub fn outcome(text: &str) -> String {
+    match parse(text) {
+        Ok(value) => format!(
+            "{}\nfingerprint: {:016x}",
+            dump(&value),
+            value.fingerprint()
+        ),
+        Err(error) => format!(
+            "{:?} at line {}, position {}, byte {}, UTF-16 {}: {}",
+            error.kind, error.line, error.pos, error.byte_pos, error.utf16_pos, error.msg
+        ),
+    }
+}
+
+/// Runs the suite, returning the cases whose outcome in this platform is not the expected one.
+pub fn check() -> Vec<Mismatch> {
+    CASES
+        .iter()
+        .filter_map(|case| {
+            let actual = outcome(case.text);
+            if actual == case.expected {
+                None
+            } else {
+                Some(Mismatch {
+                    name: case.name,
+                    expected: case.expected,
+                    actual,
+                })
+            }
+        })
+        .collect()
+}
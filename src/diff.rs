@@ -0,0 +1,98 @@
+//! Structural comparison of documents, to review or block changes of configurations.
+use crate::{parser::GuraType, path::Path};
+use std::fmt;
+
+/// Difference found at a path (the root one for the root value)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// The key only exists in the new document
+    Added { path: Path, value: GuraType },
+    /// The key only exists in the old document
+    Removed { path: Path, value: GuraType },
+    /// The value of the key changed
+    Modified {
+        path: Path,
+        old: GuraType,
+        new: GuraType,
+    },
+}
+
+impl Change {
+    /// Gets the path of the changed value.
+    pub fn path(&self) -> &Path {
+        match self {
+            Change::Added { path, .. }
+            | Change::Removed { path, .. }
+            | Change::Modified { path, .. } => path,
+        }
+    }
+}
+
+/// Formats changes as `+ path: value`, `- path: value` and `~ path: old -> new`, with values in
+/// their compact form.
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Change::Added { path, value } => write!(f, "+ {}: {}", path, value),
+            Change::Removed { path, value } => write!(f, "- {}: {}", path, value),
+            Change::Modified { path, old, new } => write!(f, "~ {}: {} -> {}", path, old, new),
+        }
+    }
+}
+
+/// Compares two values. Objects are compared key by key recursively, in the order of `old`
+/// followed by the keys added in `new`. Any other value (including arrays) is compared as a
+/// whole.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{diff::{diff, Change}, object, GuraType};
+///
+/// let old = object! { server: { host: "localhost", port: 80 }, debug: true };
+/// let new = object! { server: { host: "localhost", port: 8080 }, name: "app" };
+///
+/// let changes = diff(&old, &new);
+/// assert_eq!(changes[0].to_string(), "~ server.port: 80 -> 8080");
+/// assert_eq!(changes[1], Change::Removed {
+///     path: "debug".parse().unwrap(),
+///     value: GuraType::Bool(true),
+/// });
+/// assert_eq!(changes[2].to_string(), "+ name: \"app\"");
+/// ```
+pub fn diff(old: &GuraType, new: &GuraType) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff_values(old, new, &Path::root(), &mut changes);
+    changes
+}
+
+fn diff_values(old: &GuraType, new: &GuraType, path: &Path, changes: &mut Vec<Change>) {
+    match (old, new) {
+        (GuraType::Object(old_values), GuraType::Object(new_values)) => {
+            for (key, old_value) in old_values {
+                let key_path = path.join_key(key);
+                match new_values.get(key) {
+                    Some(new_value) => diff_values(old_value, new_value, &key_path, changes),
+                    None => changes.push(Change::Removed {
+                        path: key_path,
+                        value: old_value.clone(),
+                    }),
+                }
+            }
+            for (key, new_value) in new_values {
+                if !old_values.contains_key(key) {
+                    changes.push(Change::Added {
+                        path: path.join_key(key),
+                        value: new_value.clone(),
+                    });
+                }
+            }
+        }
+        _ if old != new => changes.push(Change::Modified {
+            path: path.clone(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
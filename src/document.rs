@@ -0,0 +1,195 @@
+//! Gura documents that keep their import sentences, so tools editing multi-file configurations
+//! can write back a file without inlining the content of the imported ones.
+use crate::{
+    errors::GuraError,
+    parser::{dump, dump_value, parse_spanned, GuraType},
+};
+use indexmap::IndexMap;
+use std::{
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Name of the root file written by `dump_split`
+const ROOT_FILE_NAME: &str = "main.ura";
+
+/// A Gura document: the files it imports and its local keys.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{document::Document, object, GuraType};
+///
+/// let document = Document::new(object! { port: 8080 }).import("base.ura");
+/// assert_eq!(document.dump(), "import \"base.ura\"\n\nport: 8080");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    /// Paths of the imported files, as they are written in the import sentences
+    pub imports: Vec<String>,
+    /// Object with the keys defined in the document itself
+    pub value: GuraType,
+}
+
+impl Document {
+    /// Creates a document without imports.
+    pub fn new(value: GuraType) -> Self {
+        Document {
+            imports: Vec::new(),
+            value,
+        }
+    }
+
+    /// Adds an import sentence.
+    pub fn import(mut self, path: impl Into<String>) -> Self {
+        self.imports.push(path.into());
+        self
+    }
+
+    /// Parses a Gura text keeping its import sentences. Imported files are read (relative to
+    /// the working directory, as `parse` does) to validate the text and resolve its variables,
+    /// but only the keys defined in the text itself are kept in `value`. Variables are
+    /// replaced by their values.
+    ///
+    /// # Errors
+    ///
+    /// This function could throw any kind of error listed
+    /// in [Gura specs](https://gura.netlify.app/docs/gura#standard-errors).
+    pub fn parse(text: &str) -> Result<Self, GuraError> {
+        let spanned = parse_spanned(text)?;
+        let imports = spanned
+            .records
+            .imports
+            .iter()
+            .map(|import| import.path.clone())
+            .collect();
+
+        // Keys defined in imported files have no span in the text
+        let mut local_values = IndexMap::new();
+        if let GuraType::Object(values) = &spanned.value {
+            for (key, value) in values {
+                if spanned.span(key).is_some() {
+                    local_values.insert(key.clone(), value.clone());
+                }
+            }
+        }
+
+        Ok(Document {
+            imports,
+            value: GuraType::Object(local_values),
+        })
+    }
+
+    /// Generates the Gura text of the document: its import sentences followed by its keys.
+    pub fn dump(&self) -> String {
+        let mut result = String::new();
+        for path in self.imports.iter() {
+            let _ = writeln!(
+                result,
+                "import {}",
+                dump_value(&GuraType::String(path.clone()))
+            );
+        }
+
+        let has_keys = !matches!(&self.value, GuraType::Object(values) if values.is_empty());
+        if has_keys {
+            if !result.is_empty() {
+                result.push('\n');
+            }
+            result.push_str(&dump(&self.value));
+        }
+        result.trim_end().to_string()
+    }
+}
+
+/// Defines which top-level keys are written to their own file by `dump_split`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SplitStrategy {
+    /// Every key whose value is a non-empty object
+    Objects,
+    /// The given keys. Keys not found in the document are ignored
+    Keys(Vec<String>),
+}
+
+impl SplitStrategy {
+    fn is_selected(&self, key: &str, value: &GuraType) -> bool {
+        match self {
+            SplitStrategy::Objects => {
+                matches!(value, GuraType::Object(values) if !values.is_empty())
+            }
+            SplitStrategy::Keys(keys) => keys.iter().any(|selected| selected == key),
+        }
+    }
+}
+
+/// Writes a document into several files in `dir`: a `<key>.ura` file for every top-level key
+/// selected by `strategy` and a `main.ura` file importing all of them, followed by the rest of
+/// the keys. Import paths are relative to `dir`, as Gura resolves the imports of imported
+/// files. Returns the paths of the written files, starting with the root one.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{document::{dump_split, SplitStrategy}, object, GuraType};
+/// use std::fs;
+///
+/// let object = object! { title: "Gura", server: { port: 8080 } };
+/// let dir = std::env::temp_dir().join("gura_dump_split_example");
+/// let files = dump_split(&object, &dir, &SplitStrategy::Objects).unwrap();
+///
+/// assert_eq!(files, vec![dir.join("main.ura"), dir.join("server.ura")]);
+/// assert_eq!(fs::read_to_string(&files[0]).unwrap(), "import \"server.ura\"\n\ntitle: \"Gura\"\n");
+/// assert_eq!(fs::read_to_string(&files[1]).unwrap(), "server:\n    port: 8080\n");
+/// ```
+///
+/// # Errors
+///
+/// * io::Error - If `dir` can not be created or a file can not be written, or with kind
+///   `InvalidInput` if a key named `main` is selected, as its file would be the root one.
+pub fn dump_split(
+    content: &GuraType,
+    dir: impl AsRef<Path>,
+    strategy: &SplitStrategy,
+) -> io::Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    let mut root = Document::new(GuraType::Object(IndexMap::new()));
+    let mut sections = Vec::new();
+    match content {
+        GuraType::Object(values) => {
+            let mut root_values = IndexMap::new();
+            for (key, value) in values {
+                if strategy.is_selected(key, value) {
+                    let file_name = format!("{}.ura", key);
+                    if file_name == ROOT_FILE_NAME {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!(
+                                "Key \"{}\" can not be split, its file would be the root {}",
+                                key, ROOT_FILE_NAME
+                            ),
+                        ));
+                    }
+                    let mut section = IndexMap::new();
+                    section.insert(key.clone(), value.clone());
+                    sections.push((dir.join(&file_name), GuraType::Object(section)));
+                    root.imports.push(file_name);
+                } else {
+                    root_values.insert(key.clone(), value.clone());
+                }
+            }
+            root.value = GuraType::Object(root_values);
+        }
+        _ => root.value = content.clone(),
+    }
+
+    fs::create_dir_all(dir)?;
+    let root_path = dir.join(ROOT_FILE_NAME);
+    fs::write(&root_path, format!("{}\n", root.dump()))?;
+    let mut written_files = vec![root_path];
+    for (path, section) in sections {
+        fs::write(&path, format!("{}\n", dump(&section)))?;
+        written_files.push(path);
+    }
+    Ok(written_files)
+}
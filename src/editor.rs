@@ -0,0 +1,613 @@
+//! Format-preserving edition of Gura texts: keys are renamed and moved by rewriting only the
+//! affected lines, so comments, blank lines and the style of the rest of the text are kept.
+//! Useful to automate refactors of configuration files.
+//!
+//! # Examples
+//!
+//! ```
+//! use gura::editor::EditableDocument;
+//!
+//! let text = "# Name of the service\nname: \"app\"\nserver:\n    port: 8080 # Default one\n";
+//! let mut document = EditableDocument::parse(text).unwrap();
+//! document.rename_key("server.port", "listen_port").unwrap();
+//! document.move_key("name", "server").unwrap();
+//!
+//! let expected = "server:\n    listen_port: 8080 # Default one\n    # Name of the service\n    name: \"app\"\n";
+//! assert_eq!(document.text(), expected);
+//! assert_eq!(document.value()["server"]["name"], "app");
+//! ```
+use crate::{
+    errors::{EditError, GuraError},
+    parser::{dump, dump_value, is_valid_key, parse_spanned_in, GuraType, ParserOptions},
+    span::{SpannedDocument, VariableSource},
+};
+use std::{
+    cmp::Reverse,
+    fmt,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// Size of the indentation of the pairs written by the editor
+const INDENT_SIZE: usize = 4;
+
+/// A single change of a `DocumentEdit`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    /// Sets the value of a key (see `EditableDocument::set_value`)
+    Set { path: String, value: GuraType },
+    /// Removes a pair (see `EditableDocument::delete_key`)
+    Delete { path: String },
+    /// Appends a value to an array (see `EditableDocument::append_value`)
+    Append { path: String, value: GuraType },
+    /// Adds a comment before a pair (see `EditableDocument::add_comment`)
+    Comment { path: String, comment: String },
+}
+
+/// Batch of edits applied atomically by `EditableDocument::apply_edits`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentEdit {
+    pub edits: Vec<Edit>,
+}
+
+impl DocumentEdit {
+    /// Creates an empty batch.
+    pub fn new() -> Self {
+        DocumentEdit::default()
+    }
+
+    /// Adds an edit setting the value of the key located at `path`.
+    pub fn set(mut self, path: impl Into<String>, value: GuraType) -> Self {
+        self.edits.push(Edit::Set {
+            path: path.into(),
+            value,
+        });
+        self
+    }
+
+    /// Adds an edit removing the pair located at `path`.
+    pub fn delete(mut self, path: impl Into<String>) -> Self {
+        self.edits.push(Edit::Delete { path: path.into() });
+        self
+    }
+
+    /// Adds an edit appending a value to the array located at `path`.
+    pub fn append(mut self, path: impl Into<String>, value: GuraType) -> Self {
+        self.edits.push(Edit::Append {
+            path: path.into(),
+            value,
+        });
+        self
+    }
+
+    /// Adds an edit writing a comment before the pair located at `path`.
+    pub fn comment(mut self, path: impl Into<String>, comment: impl Into<String>) -> Self {
+        self.edits.push(Edit::Comment {
+            path: path.into(),
+            comment: comment.into(),
+        });
+        self
+    }
+}
+
+/// A Gura text that can be edited keeping its format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditableDocument {
+    text: String,
+    spanned: SpannedDocument,
+    /// Directory the imports of the text are relative to, if it is not the working directory
+    import_dir: Option<PathBuf>,
+}
+
+impl EditableDocument {
+    /// Parses a Gura text to edit it. Imported files are read as `parse_spanned` does, but
+    /// their keys can not be edited.
+    ///
+    /// # Errors
+    ///
+    /// This function could throw any kind of error listed
+    /// in [Gura specs](https://gura.netlify.app/docs/gura#standard-errors).
+    pub fn parse(text: &str) -> Result<Self, GuraError> {
+        Ok(EditableDocument {
+            text: text.to_string(),
+            spanned: parse_spanned_in(text, None, &ParserOptions::default())?,
+            import_dir: None,
+        })
+    }
+
+    /// Parses a Gura text to edit it as `parse` does, with its imports relative to `dir` (e.g.
+    /// the directory of the file the text was read from), also when it is parsed again after
+    /// every edit.
+    ///
+    /// # Errors
+    ///
+    /// This function could throw any kind of error listed
+    /// in [Gura specs](https://gura.netlify.app/docs/gura#standard-errors).
+    pub fn parse_in<P: AsRef<Path>>(text: &str, dir: P) -> Result<Self, GuraError> {
+        let dir = dir.as_ref();
+        Ok(EditableDocument {
+            text: text.to_string(),
+            spanned: parse_spanned_in(text, Some(dir), &ParserOptions::default())?,
+            import_dir: Some(dir.to_path_buf()),
+        })
+    }
+
+    /// Gets the current text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Gets the parsed value of the current text.
+    pub fn value(&self) -> &GuraType {
+        &self.spanned.value
+    }
+
+    /// Gets the spans of the current text.
+    pub fn spanned(&self) -> &SpannedDocument {
+        &self.spanned
+    }
+
+    /// Renames the key located at `path` (keys separated by dots, as in
+    /// `GuraType::get_path`), keeping its value and position.
+    ///
+    /// # Errors
+    ///
+    /// * PathNotFound - If there is no key at `path` in the text.
+    /// * InvalidKey - If `new_name` can not be written in Gura.
+    /// * DuplicatedKey - If the object of the key already has a key named `new_name`.
+    pub fn rename_key(&mut self, path: &str, new_name: &str) -> Result<(), EditError> {
+        let span = self
+            .spanned
+            .span(path)
+            .ok_or_else(|| EditError::PathNotFound(path.to_string()))?;
+        if !is_valid_key(new_name) {
+            return Err(EditError::InvalidKey(new_name.to_string()));
+        }
+
+        let (parent, key) = split_path(path);
+        let new_path = join_path(parent, new_name);
+        if key != new_name && self.spanned.value.get_path(&new_path).is_some() {
+            return Err(EditError::DuplicatedKey(new_path));
+        }
+
+        self.apply(vec![(span.byte_start..span.byte_end, new_name.to_string())])
+    }
+
+    /// Renames the variable called `name` (without the `$`), rewriting its definition and
+    /// every usage of it, both as a value and inside basic strings. Literal strings are left
+    /// untouched, as variables are not replaced in them.
+    ///
+    /// ```
+    /// use gura::editor::EditableDocument;
+    ///
+    /// let text = "$host: \"localhost\"\nurl: \"http://$host:80\"\nraw: '$host'\n";
+    /// let mut document = EditableDocument::parse(text).unwrap();
+    /// document.rename_variable("host", "server_host").unwrap();
+    ///
+    /// let expected = "$server_host: \"localhost\"\nurl: \"http://$server_host:80\"\nraw: '$host'\n";
+    /// assert_eq!(document.text(), expected);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * VariableNotFound - If the variable is not defined in the text.
+    /// * InvalidVariable - If `new_name` can not be written in Gura.
+    /// * DuplicatedVariable - If there is already a variable called `new_name` in the text or
+    ///   used in it.
+    /// * Parse - If there is an unused variable called `new_name` in the imported files.
+    pub fn rename_variable(&mut self, name: &str, new_name: &str) -> Result<(), EditError> {
+        let variable = self
+            .spanned
+            .variable(name)
+            .ok_or_else(|| EditError::VariableNotFound(name.to_string()))?;
+        if !is_valid_key(new_name) {
+            return Err(EditError::InvalidVariable(new_name.to_string()));
+        }
+        if name == new_name {
+            return Ok(());
+        }
+        let is_defined =
+            self.spanned.variable(new_name).is_some()
+                || self.spanned.variable_usages().iter().any(|usage| {
+                    usage.name == new_name && usage.source != VariableSource::Environment
+                });
+        if is_defined {
+            return Err(EditError::DuplicatedVariable(new_name.to_string()));
+        }
+
+        let replacement = format!("${}", new_name);
+        let edits = std::iter::once(variable.span)
+            .chain(variable.references)
+            .map(|span| (span.byte_start..span.byte_end, replacement.clone()))
+            .collect();
+        self.apply(edits)
+    }
+
+    /// Moves the pair located at `path` (along with its leading comments) to the end of the
+    /// object located at `new_parent`, re-indenting it. An empty `new_parent` moves it to the
+    /// root. If the pair was the only one of its object, the object is left `empty`.
+    ///
+    /// # Errors
+    ///
+    /// * PathNotFound - If there is no key at `path` in the text.
+    /// * InvalidParent - If `new_parent` is not the root or an object with pairs in the text,
+    ///   or if it is inside the moved pair.
+    /// * DuplicatedKey - If `new_parent` already has a key with the same name.
+    pub fn move_key(&mut self, path: &str, new_parent: &str) -> Result<(), EditError> {
+        let key_span = self
+            .spanned
+            .span(path)
+            .ok_or_else(|| EditError::PathNotFound(path.to_string()))?;
+        let (old_parent, key) = split_path(path);
+        if new_parent == old_parent {
+            return Ok(());
+        }
+
+        if new_parent == path || new_parent.starts_with(&format!("{}.", path)) {
+            return Err(EditError::InvalidParent(new_parent.to_string()));
+        }
+        let new_path = join_path(new_parent, key);
+        if self.spanned.value.get_path(&new_path).is_some() {
+            return Err(EditError::DuplicatedKey(new_path));
+        }
+        let (insert_pos, indentation) = self.end_of_object(new_parent)?;
+
+        // Lines inside multiline strings must not be re-indented
+        let subpath_prefix = format!("{}.", path);
+        let strings: Vec<Range<usize>> = self
+            .spanned
+            .records
+            .pair_spans
+            .iter()
+            .filter(|(pair_path, _)| *pair_path == path || pair_path.starts_with(&subpath_prefix))
+            .filter(|(pair_path, _)| {
+                matches!(
+                    self.spanned.value.get_path(pair_path),
+                    Some(GuraType::String(_))
+                )
+            })
+            .map(|(_, span)| span.byte_start..span.byte_end)
+            .collect();
+
+        let block_range = self.pair_block(path)?;
+        let old_indentation = self.column(key_span.byte_start);
+        let mut block = String::new();
+        let mut line_start = block_range.start;
+        for line in self.text[block_range.clone()].split_inclusive('\n') {
+            let is_string_content = strings
+                .iter()
+                .any(|string| string.start < line_start && line_start < string.end);
+            block.push_str(&if is_string_content || line.trim().is_empty() {
+                line.to_string()
+            } else {
+                reindent(line, old_indentation, indentation)
+            });
+            line_start += line.len();
+        }
+
+        let mut edits = vec![
+            self.insertion(insert_pos, block, Some(block_range.end)),
+            (block_range, String::new()),
+        ];
+        edits.extend(self.emptied_parent(path));
+        self.apply(edits)
+    }
+
+    /// Sets the value of the key located at `path`, replacing the text of its current value.
+    /// If the key does not exist, a pair is appended to its object. Comments of the pair are
+    /// kept.
+    ///
+    /// # Errors
+    ///
+    /// * InvalidKey - If the key does not exist and its name can not be written in Gura.
+    /// * InvalidParent - If the key does not exist and its object is not the root or an object
+    ///   with pairs in the text.
+    pub fn set_value(&mut self, path: &str, value: &GuraType) -> Result<(), EditError> {
+        if let (Some(key_span), Some(pair_span)) =
+            (self.spanned.span(path), self.spanned.pair_span(path))
+        {
+            // Replaces everything after the colon
+            let dumped = dump_after_colon(value, self.column(key_span.byte_start));
+            return self.apply(vec![(key_span.byte_end + 1..pair_span.byte_end, dumped)]);
+        }
+
+        let (parent, key) = split_path(path);
+        if !is_valid_key(key) {
+            return Err(EditError::InvalidKey(key.to_string()));
+        }
+        let (insert_pos, indentation) = self.end_of_object(parent)?;
+        let pair = format!(
+            "{}{}:{}\n",
+            " ".repeat(indentation),
+            key,
+            dump_after_colon(value, indentation)
+        );
+        self.apply(vec![self.insertion(insert_pos, pair, None)])
+    }
+
+    /// Removes the pair located at `path` along with its leading comments. If the pair was the
+    /// only one of its object, the object is left `empty`.
+    ///
+    /// # Errors
+    ///
+    /// * PathNotFound - If there is no key at `path` in the text.
+    pub fn delete_key(&mut self, path: &str) -> Result<(), EditError> {
+        let mut edits = vec![(self.pair_block(path)?, String::new())];
+        edits.extend(self.emptied_parent(path));
+        self.apply(edits)
+    }
+
+    /// Appends a value to the array located at `path`, following the layout of the array
+    /// (single line or one element per line, with or without trailing comma).
+    ///
+    /// # Errors
+    ///
+    /// * PathNotFound - If there is no key at `path` in the text.
+    /// * NotAnArray - If the value at `path` is not an array.
+    pub fn append_value(&mut self, path: &str, value: &GuraType) -> Result<(), EditError> {
+        let pair_span = self
+            .spanned
+            .pair_span(path)
+            .ok_or_else(|| EditError::PathNotFound(path.to_string()))?;
+        if !matches!(self.spanned.value.get_path(path), Some(GuraType::Array(_))) {
+            return Err(EditError::NotAnArray(path.to_string()));
+        }
+
+        let close = pair_span.byte_end - 1; // Position of the "]"
+        let last = self.text[..close].trim_end().len(); // After the last element (or the "[")
+        let is_multiline = self.text[last..close].contains('\n');
+        let line_start = self.line_start(last);
+        let indentation = if is_multiline {
+            self.text[line_start..last].len() - self.text[line_start..last].trim_start().len()
+        } else {
+            0
+        };
+        let dumped = dump_after_colon(value, indentation);
+        let dumped = dumped.trim_start();
+
+        let (range, element) = match &self.text[last - 1..last] {
+            // Empty arrays, whose whitespaces are replaced
+            "[" => (last..close, dumped.to_string()),
+            "," if is_multiline => (
+                last..last,
+                format!("\n{}{},", " ".repeat(indentation), dumped),
+            ),
+            "," => (last..last, format!(" {},", dumped)),
+            _ if is_multiline => (
+                last..last,
+                format!(",\n{}{}", " ".repeat(indentation), dumped),
+            ),
+            _ => (last..last, format!(", {}", dumped)),
+        };
+        self.apply(vec![(range, element)])
+    }
+
+    /// Adds a comment (one line per line of `comment`) right before the key located at
+    /// `path`, with its indentation.
+    ///
+    /// # Errors
+    ///
+    /// * PathNotFound - If there is no key at `path` in the text.
+    pub fn add_comment(&mut self, path: &str, comment: &str) -> Result<(), EditError> {
+        let key_span = self
+            .spanned
+            .span(path)
+            .ok_or_else(|| EditError::PathNotFound(path.to_string()))?;
+        let indentation = " ".repeat(self.column(key_span.byte_start));
+        let lines: String = comment
+            .lines()
+            .map(|line| format!("{}# {}\n", indentation, line).replace("# \n", "#\n"))
+            .collect();
+        let line_start = self.line_start(key_span.byte_start);
+        self.apply(vec![(line_start..line_start, lines)])
+    }
+
+    /// Applies a batch of edits in order. Either all of them are applied or, if any of them
+    /// fails, none. Returns the new text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{editor::{DocumentEdit, EditableDocument}, GuraType};
+    ///
+    /// let mut document = EditableDocument::parse("port: 8080\nhosts: [\"a\"]\ndebug: true").unwrap();
+    /// let edits = DocumentEdit::new()
+    ///     .set("port", GuraType::Integer(8081))
+    ///     .append("hosts", GuraType::String(String::from("b")))
+    ///     .delete("debug")
+    ///     .comment("port", "Changed by the deploy");
+    /// let text = document.apply_edits(&edits).unwrap();
+    /// assert_eq!(text, "# Changed by the deploy\nport: 8081\nhosts: [\"a\", \"b\"]\n");
+    ///
+    /// // Nothing changes if an edit fails
+    /// assert!(document.apply_edits(&DocumentEdit::new().delete("port").delete("debug")).is_err());
+    /// assert!(document.text().contains("port: 8081"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// The error of the first edit that could not be applied.
+    pub fn apply_edits(&mut self, edits: &DocumentEdit) -> Result<&str, EditError> {
+        let mut edited = self.clone();
+        for edit in edits.edits.iter() {
+            match edit {
+                Edit::Set { path, value } => edited.set_value(path, value)?,
+                Edit::Delete { path } => edited.delete_key(path)?,
+                Edit::Append { path, value } => edited.append_value(path, value)?,
+                Edit::Comment { path, comment } => edited.add_comment(path, comment)?,
+            }
+        }
+        *self = edited;
+        Ok(&self.text)
+    }
+
+    /// Gets the range of the lines of the pair located at `path`, including its leading
+    /// comments and its trailing new line.
+    fn pair_block(&self, path: &str) -> Result<Range<usize>, EditError> {
+        let not_found = || EditError::PathNotFound(path.to_string());
+        let key_span = self.spanned.span(path).ok_or_else(not_found)?;
+        let pair_span = self.spanned.pair_span(path).ok_or_else(not_found)?;
+        let leading_span = self
+            .spanned
+            .trivia(path)
+            .and_then(|trivia| trivia.leading_span);
+        let start = leading_span.map_or(key_span.byte_start, |span| span.byte_start);
+        Ok(self.line_start(start)..self.line_end(pair_span.byte_end))
+    }
+
+    /// Gets the position after the last line of the object located at `path` (the root if
+    /// empty), where pairs are appended, and the indentation of its pairs.
+    fn end_of_object(&self, path: &str) -> Result<(usize, usize), EditError> {
+        if path.is_empty() {
+            let last_end = self
+                .spanned
+                .records
+                .pair_spans
+                .iter()
+                .filter(|(path, _)| !path.contains('.'))
+                .map(|(_, span)| span.byte_end)
+                .max()
+                .unwrap_or(0);
+            return Ok((self.line_end(last_end), 0));
+        }
+
+        let invalid_parent = || EditError::InvalidParent(path.to_string());
+        let first_child = match self.spanned.value.get_path(path) {
+            Some(GuraType::Object(values)) => values.keys().next(),
+            _ => None,
+        };
+        let parent_span = self.spanned.pair_span(path).ok_or_else(invalid_parent)?;
+        let child_span = first_child
+            .and_then(|child| self.spanned.span(&join_path(path, child)))
+            .ok_or_else(invalid_parent)?;
+        Ok((
+            self.line_end(parent_span.byte_end),
+            self.column(child_span.byte_start),
+        ))
+    }
+
+    /// Gets the edit that inserts whole lines at `pos`, which must be the start of a line or
+    /// the end of the text. `removed_end` is the end of the lines removed by the same change,
+    /// if any.
+    fn insertion(
+        &self,
+        pos: usize,
+        mut lines: String,
+        removed_end: Option<usize>,
+    ) -> (Range<usize>, String) {
+        if !lines.ends_with('\n') {
+            lines.push('\n');
+        }
+        // The last line has no new line, unless it is being removed
+        let is_after_last_line = pos == self.text.len() && !self.text.ends_with('\n');
+        if is_after_last_line && removed_end != Some(pos) {
+            lines.insert(0, '\n');
+        }
+        (pos..pos, lines)
+    }
+
+    /// Gets the edit that leaves the object of the pair located at `path` `empty`, if the
+    /// pair is its only one, as objects can not be left without pairs.
+    fn emptied_parent(&self, path: &str) -> Option<(Range<usize>, String)> {
+        let (parent, _) = split_path(path);
+        let is_only_child = matches!(
+            self.spanned.value.get_path(parent),
+            Some(GuraType::Object(values)) if values.len() == 1
+        );
+        if parent.is_empty() || !is_only_child {
+            return None;
+        }
+
+        let colon_end = self.spanned.span(parent)?.byte_end + 1;
+        Some((colon_end..colon_end, String::from(" empty")))
+    }
+
+    /// Applies replacements of byte ranges of the text, which must not overlap, parsing the
+    /// result again. The text is not modified if the result is not valid.
+    fn apply(&mut self, mut edits: Vec<(Range<usize>, String)>) -> Result<(), EditError> {
+        // Replaces from the end so the ranges of the remaining edits are still valid
+        edits.sort_by_key(|(range, _)| Reverse((range.start, range.end)));
+        let mut text = self.text.clone();
+        for (range, replacement) in edits {
+            text.replace_range(range, &replacement);
+        }
+
+        self.spanned =
+            parse_spanned_in(&text, self.import_dir.as_deref(), &ParserOptions::default())
+                .map_err(EditError::Parse)?;
+        self.text = text;
+        Ok(())
+    }
+
+    /// Gets the byte offset where the line of `pos` starts.
+    fn line_start(&self, pos: usize) -> usize {
+        self.text[..pos].rfind('\n').map_or(0, |index| index + 1)
+    }
+
+    /// Gets the byte offset after the line of `pos`, including its new line.
+    fn line_end(&self, pos: usize) -> usize {
+        self.text[pos..]
+            .find('\n')
+            .map_or(self.text.len(), |index| pos + index + 1)
+    }
+
+    /// Gets the number of chars before `pos` in its line.
+    fn column(&self, pos: usize) -> usize {
+        self.text[self.line_start(pos)..pos].chars().count()
+    }
+}
+
+impl fmt::Display for EditableDocument {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Replaces the `old` spaces of indentation of a line by `new` spaces. Lines less indented lose
+/// all their indentation.
+fn reindent(line: &str, old: usize, new: usize) -> String {
+    let content = line.trim_start_matches(' ');
+    let indentation = line.len() - content.len();
+    let indentation = (indentation + new).saturating_sub(old);
+    format!("{}{}", " ".repeat(indentation), content)
+}
+
+/// Dumps a value as it is written after the colon of a pair whose key has `indentation`
+/// spaces. Objects are written in the following lines, indented one level more.
+fn dump_after_colon(value: &GuraType, indentation: usize) -> String {
+    let (dumped, first_line_prefix, indentation) = match value {
+        GuraType::Object(values) if !values.is_empty() => {
+            (dump(value), "\n", indentation + INDENT_SIZE)
+        }
+        _ => (dump_value(value).trim_end().to_string(), " ", indentation),
+    };
+
+    let mut lines = dumped.split('\n');
+    let mut result = String::from(first_line_prefix);
+    if first_line_prefix == "\n" {
+        result.push_str(&" ".repeat(indentation));
+    }
+    result.push_str(lines.next().unwrap_or_default());
+    for line in lines {
+        result.push('\n');
+        if !line.is_empty() {
+            result.push_str(&" ".repeat(indentation));
+        }
+        result.push_str(line);
+    }
+    result
+}
+
+/// Splits a path into the path of the parent object and the last key.
+fn split_path(path: &str) -> (&str, &str) {
+    path.rsplit_once('.').unwrap_or(("", path))
+}
+
+/// Appends a key to a path separated by a dot.
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
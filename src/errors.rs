@@ -1,4 +1,9 @@
-use std::fmt;
+use crate::parser::Kind;
+use crate::path::Path;
+#[cfg(feature = "serde")]
+use crate::path::Segment;
+use crate::span::Span;
+use std::{fmt, iter::FromIterator};
 
 /// All Gura error variants
 #[derive(Debug, PartialEq, Eq)]
@@ -17,15 +22,35 @@ pub enum Error {
     FileNotFoundError,
     /// Raises when a file is imported more than once
     DuplicatedImportError,
+    /// Raises when the parser finds an inconsistency in its own definitions (e.g. a malformed
+    /// char range). It is a bug of the crate rather than a problem of the text
+    InternalError,
 }
 
 /// A Gura error with position, line and custom message
 #[derive(Debug, PartialEq, Eq)]
 pub struct GuraError {
+    /// Position of the error as an index of grapheme clusters
     pub pos: isize,
     pub line: usize,
     pub msg: String,
     pub kind: Error,
+    /// Position of the error as a byte offset
+    pub byte_pos: usize,
+    /// Position of the error as a UTF-16 code unit offset (as used by LSP)
+    pub utf16_pos: usize,
+    /// Text of the line where the error was found. Only captured if enabled in `ParserOptions`
+    pub line_content: Option<String>,
+    /// Hint on how to fix the most common causes of the error (e.g. a missing `]`)
+    pub suggestion: Option<String>,
+}
+
+impl GuraError {
+    /// Sets the hint on how to fix the error.
+    pub(crate) fn with_suggestion(mut self, suggestion: String) -> Self {
+        self.suggestion = Some(suggestion);
+        self
+    }
 }
 
 impl fmt::Display for GuraError {
@@ -34,16 +59,657 @@ impl fmt::Display for GuraError {
             f,
             "{} at line {} (text position = {})",
             self.msg, self.line, self.pos
-        )
+        )?;
+        if let Some(line_content) = &self.line_content {
+            write!(f, ":\n{}", line_content)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error raised when a value is not of the expected type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeError {
+    pub expected: Kind,
+    pub found: Kind,
+    /// Location of the key of the value, only available when it was got from a spanned
+    /// document (see `SpannedDocument::expect`)
+    pub span: Option<Span>,
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected {}, found {}", self.expected, self.found)?;
+        if let Some(span) = &self.span {
+            write!(f, " (line {})", span.line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error raised when a value can not be converted into a specific integer type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegerConversionError {
+    /// The value is not an integer
+    WrongType(TypeError),
+    /// The integer does not fit in the `target` type
+    OutOfRange { target: &'static str, value: i128 },
+}
+
+impl From<TypeError> for IntegerConversionError {
+    fn from(error: TypeError) -> Self {
+        IntegerConversionError::WrongType(error)
+    }
+}
+
+impl fmt::Display for IntegerConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IntegerConversionError::WrongType(error) => error.fmt(f),
+            IntegerConversionError::OutOfRange { target, value } => {
+                write!(f, "integer {} is out of range for {}", value, target)
+            }
+        }
+    }
+}
+
+/// Error raised when an array can not be converted into a `Vec` of a specific type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArrayConversionError<E> {
+    /// The value is not an array
+    NotAnArray(TypeError),
+    /// The element at `index` could not be converted
+    Element { index: usize, error: E },
+}
+
+impl<E: fmt::Display> fmt::Display for ArrayConversionError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArrayConversionError::NotAnArray(error) => error.fmt(f),
+            ArrayConversionError::Element { index, error } => {
+                write!(f, "invalid element at index {}: {}", index, error)
+            }
+        }
+    }
+}
+
+/// All the validation error variants
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationErrorKind {
+    /// Raises when a required key is not defined
+    MissingKey,
+    /// Raises when a value is not of the expected type
+    WrongType(TypeError),
+    /// Raises when a key is not defined in a schema that denies unknown keys
+    UnknownKey,
+}
+
+/// An error found validating a value against a schema, with the path of the value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub path: Path,
+    pub kind: ValidationErrorKind,
+    /// Location of the key, only available when validating spanned documents
+    pub span: Option<Span>,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ValidationErrorKind::MissingKey => write!(f, "missing required key")?,
+            ValidationErrorKind::WrongType(error) => error.fmt(f)?,
+            ValidationErrorKind::UnknownKey => write!(f, "unknown key")?,
+        }
+
+        if !self.path.is_root() {
+            write!(f, " at {}", self.path)?;
+        }
+        if let Some(span) = &self.span {
+            write!(f, " (line {})", span.line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error raised when a lint configuration is not valid, with the path of the wrong value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintConfigError {
+    pub path: String,
+    pub msg: String,
+}
+
+impl LintConfigError {
+    pub(crate) fn new(path: &str, msg: impl fmt::Display) -> Self {
+        LintConfigError {
+            path: path.to_string(),
+            msg: msg.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for LintConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.msg)?;
+        if !self.path.is_empty() {
+            write!(f, " at {}", self.path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error raised when a text is not a valid path (see `path::Path`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathError {
+    /// Position (in bytes) of the problem in the text
+    pub pos: usize,
+    pub msg: String,
+}
+
+impl PathError {
+    pub(crate) fn new(pos: usize, msg: &str) -> Self {
+        PathError {
+            pos,
+            msg: msg.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at position {}", self.msg, self.pos)
     }
 }
 
-/// ValueError (for internal usage)
+impl std::error::Error for PathError {}
+
+/// Error raised when a secret can not be encrypted or decrypted (see `secrets`), with the path
+/// of its value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretError {
+    pub path: Path,
+    pub msg: String,
+}
+
+impl SecretError {
+    pub(crate) fn new(path: &Path, msg: impl fmt::Display) -> Self {
+        SecretError {
+            path: path.clone(),
+            msg: msg.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SecretError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.msg)?;
+        if !self.path.is_root() {
+            write!(f, " at {}", self.path)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SecretError {}
+
+/// Error raised when a value can not be extracted from a specific path of a Gura text
+#[derive(Debug, PartialEq)]
+pub enum ExtractError<E> {
+    /// The text is not a valid Gura document
+    Parse(GuraError),
+    /// The path does not exist in the document
+    PathNotFound(String),
+    /// The value at `path` could not be converted
+    Conversion { path: String, error: E },
+}
+
+impl<E: fmt::Display> fmt::Display for ExtractError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExtractError::Parse(error) => error.fmt(f),
+            ExtractError::PathNotFound(path) => write!(f, "The path \"{}\" does not exist", path),
+            ExtractError::Conversion { path, error } => {
+                write!(f, "Invalid value at \"{}\": {}", path, error)
+            }
+        }
+    }
+}
+
+/// Error raised when an `EditableDocument` can not be edited
+#[derive(Debug, PartialEq)]
+pub enum EditError {
+    /// There is no key at the path in the text (it does not exist or it was defined in an
+    /// imported file)
+    PathNotFound(String),
+    /// The new name of a key can not be written in Gura
+    InvalidKey(String),
+    /// The destination object already has a key with the same name (the path of the existing
+    /// key)
+    DuplicatedKey(String),
+    /// The destination of a move is not an object with pairs (or the root) or it is inside the
+    /// moved pair
+    InvalidParent(String),
+    /// The value at the path is not an array
+    NotAnArray(String),
+    /// The variable is not defined in the text (it does not exist or it was defined in an
+    /// imported file)
+    VariableNotFound(String),
+    /// The new name of a variable can not be written in Gura
+    InvalidVariable(String),
+    /// There is already a variable with the new name of a variable
+    DuplicatedVariable(String),
+    /// The edited text is not a valid Gura document
+    Parse(GuraError),
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EditError::PathNotFound(path) => {
+                write!(f, "The path \"{}\" does not exist in the text", path)
+            }
+            EditError::InvalidKey(key) => write!(
+                f,
+                "Invalid key \"{}\", keys can only contain ASCII letters, digits and underscores",
+                key
+            ),
+            EditError::DuplicatedKey(path) => write!(f, "The key \"{}\" already exists", path),
+            EditError::InvalidParent(path) => {
+                write!(f, "The key can not be moved into \"{}\"", path)
+            }
+            EditError::NotAnArray(path) => write!(f, "The value at \"{}\" is not an array", path),
+            EditError::VariableNotFound(name) => {
+                write!(f, "The variable \"{}\" is not defined in the text", name)
+            }
+            EditError::InvalidVariable(name) => write!(
+                f,
+                "Invalid variable name \"{}\", names can only contain ASCII letters, digits and underscores",
+                name
+            ),
+            EditError::DuplicatedVariable(name) => {
+                write!(f, "The variable \"{}\" already exists", name)
+            }
+            EditError::Parse(error) => write!(f, "The edited text is not valid: {}", error),
+        }
+    }
+}
+
+/// Kinds of errors raised when a value can not be dumped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DumpErrorKind {
+    /// Raises when a NaN value is found and `NanPolicy::Error` is used
+    NaN,
+    /// Raises when a key (the one defined) can not be written in Gura, as keys can only
+    /// contain ASCII letters, digits and underscores
+    InvalidKey(String),
+    /// Raises when a value intended to be used internally (e.g. `GuraType::Pair`) is found,
+    /// as it has no representation in Gura
+    UnsupportedValue,
+}
+
+/// Error raised by `try_dump` with the path of the value that could not be dumped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpError {
+    pub path: String,
+    pub kind: DumpErrorKind,
+}
+
+impl fmt::Display for DumpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            DumpErrorKind::NaN => write!(f, "NaN values are not allowed")?,
+            DumpErrorKind::InvalidKey(key) => write!(
+                f,
+                "Invalid key \"{}\", keys can only contain ASCII letters, digits and underscores",
+                key
+            )?,
+            DumpErrorKind::UnsupportedValue => write!(f, "Internal values can not be dumped")?,
+        }
+
+        if !self.path.is_empty() {
+            write!(f, " at {}", self.path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error raised when a Gura document can not be deserialized into a Rust value (requires
+/// the `serde` feature)
+#[cfg(feature = "serde")]
+#[derive(Debug, PartialEq)]
+pub enum DeserializeError {
+    /// The text is not a valid Gura document
+    Parse(GuraError),
+    /// The value at `path` does not match the expected Rust type. The path is empty for the
+    /// root value
+    Invalid { path: String, message: String },
+}
+
+#[cfg(feature = "serde")]
+impl DeserializeError {
+    /// Prepends a segment (key or array index) to the path of the error.
+    pub(crate) fn in_segment(self, segment: &str) -> Self {
+        match self {
+            DeserializeError::Invalid { path, message } => DeserializeError::Invalid {
+                path: if path.is_empty() {
+                    segment.to_string()
+                } else {
+                    format!("{}.{}", segment, path)
+                },
+                message,
+            },
+            error => error,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeserializeError::Parse(error) => error.fmt(f),
+            DeserializeError::Invalid { path, message } if path.is_empty() => {
+                write!(f, "{}", message)
+            }
+            DeserializeError::Invalid { path, message } => write!(f, "{} at {}", message, path),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for DeserializeError {}
+
+/// Error raised when a Rust value can not be serialized into a Gura document (requires the
+/// `serde` feature)
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerializeError {
+    /// Path of the value that could not be serialized. Empty for the root value
+    pub path: String,
+    pub message: String,
+}
+
+#[cfg(feature = "serde")]
+impl SerializeError {
+    /// Creates an error for the root value.
+    pub(crate) fn new(message: String) -> Self {
+        SerializeError {
+            path: String::new(),
+            message,
+        }
+    }
+
+    /// Prepends a segment (key or array index) to the path of the error.
+    pub(crate) fn in_segment(mut self, segment: &str) -> Self {
+        self.path = if self.path.is_empty() {
+            segment.to_string()
+        } else {
+            format!("{}.{}", segment, self.path)
+        };
+        self
+    }
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{} at {}", self.message, self.path)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for SerializeError {}
+
+/// Error raised when a Gura document can not be read from a stream or file (see
+/// `parse_reader`)
 #[derive(Debug)]
-pub struct ValueError {}
+pub enum ReadError {
+    /// The stream or file could not be read
+    Io(std::io::Error),
+    /// The content is not valid UTF-8, with the byte offset of the first invalid byte
+    InvalidUtf8(usize),
+    /// The content is not a valid Gura document
+    Parse(GuraError),
+}
 
-impl fmt::Display for ValueError {
+impl fmt::Display for ReadError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Bad character range")
+        match self {
+            ReadError::Io(error) => error.fmt(f),
+            ReadError::InvalidUtf8(byte_pos) => {
+                write!(f, "Invalid UTF-8 at byte position {}", byte_pos)
+            }
+            ReadError::Parse(error) => error.fmt(f),
+        }
     }
 }
+
+impl std::error::Error for ReadError {}
+
+/// Error raised when a version of a three-way merge is not a valid Gura document (see
+/// `merge_three_way`)
+#[derive(Debug, PartialEq)]
+pub enum MergeError {
+    /// The common ancestor is not valid
+    Base(GuraError),
+    /// Our version is not valid
+    Ours(GuraError),
+    /// Their version is not valid
+    Theirs(GuraError),
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MergeError::Base(error) => write!(f, "Invalid base version: {}", error),
+            MergeError::Ours(error) => write!(f, "Invalid our version: {}", error),
+            MergeError::Theirs(error) => write!(f, "Invalid their version: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// An error of an `ErrorSet`
+#[derive(Debug, PartialEq)]
+pub enum ErrorItem {
+    /// The text is not a valid Gura document
+    Parse(GuraError),
+    /// A value does not match a schema
+    Validation(ValidationError),
+    /// A value does not match the Rust type it is deserialized into
+    Deserialize { path: Path, message: String },
+}
+
+impl ErrorItem {
+    /// Gets the path of the value with the error. `None` for parsing errors.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            ErrorItem::Parse(_) => None,
+            ErrorItem::Validation(error) => Some(&error.path),
+            ErrorItem::Deserialize { path, .. } => Some(path),
+        }
+    }
+
+    /// Gets the line of the error, if it is known.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ErrorItem::Parse(error) => Some(error.line),
+            ErrorItem::Validation(error) => error.span.map(|span| span.line),
+            ErrorItem::Deserialize { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for ErrorItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorItem::Parse(error) => error.fmt(f),
+            ErrorItem::Validation(error) => error.fmt(f),
+            ErrorItem::Deserialize { path, message } if path.is_root() => f.write_str(message),
+            ErrorItem::Deserialize { path, message } => write!(f, "{} at {}", message, path),
+        }
+    }
+}
+
+impl From<GuraError> for ErrorItem {
+    fn from(error: GuraError) -> Self {
+        ErrorItem::Parse(error)
+    }
+}
+
+impl From<ValidationError> for ErrorItem {
+    fn from(error: ValidationError) -> Self {
+        ErrorItem::Validation(error)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<DeserializeError> for ErrorItem {
+    /// Paths of deserialization errors are keys separated by dots, indices included
+    fn from(error: DeserializeError) -> Self {
+        match error {
+            DeserializeError::Parse(error) => ErrorItem::Parse(error),
+            DeserializeError::Invalid { path, message } => ErrorItem::Deserialize {
+                path: if path.is_empty() {
+                    Path::root()
+                } else {
+                    Path::from(
+                        path.split('.')
+                            .map(|key| Segment::Key(key.to_string()))
+                            .collect::<Vec<_>>(),
+                    )
+                },
+                message,
+            },
+        }
+    }
+}
+
+/// Every error found checking a document (e.g. all the values that do not match a schema),
+/// to report them at once instead of only the first one.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{errors::ErrorSet, parse, schema::Schema, Kind};
+///
+/// let schema = Schema::object()
+///     .field("host", Schema::new(Kind::String).required())
+///     .field("port", Schema::new(Kind::Integer));
+///
+/// let errors = ErrorSet::from(schema.validate(&parse("port: \"80\"").unwrap()).unwrap_err());
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(
+///     errors.to_string(),
+///     "2 errors found:\n- missing required key at host\n- expected integer, found string at port"
+/// );
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct ErrorSet {
+    errors: Vec<ErrorItem>,
+}
+
+impl ErrorSet {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        ErrorSet::default()
+    }
+
+    /// Adds an error to the set.
+    pub fn push<E: Into<ErrorItem>>(&mut self, error: E) {
+        self.errors.push(error.into());
+    }
+
+    /// Gets the number of errors.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Checks if the set has no errors.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Gets the errors in the order they were found.
+    pub fn iter(&self) -> std::slice::Iter<'_, ErrorItem> {
+        self.errors.iter()
+    }
+}
+
+impl<E: Into<ErrorItem>> From<E> for ErrorSet {
+    fn from(error: E) -> Self {
+        ErrorSet {
+            errors: vec![error.into()],
+        }
+    }
+}
+
+impl From<Vec<ValidationError>> for ErrorSet {
+    fn from(errors: Vec<ValidationError>) -> Self {
+        errors.into_iter().collect()
+    }
+}
+
+impl<E: Into<ErrorItem>> FromIterator<E> for ErrorSet {
+    fn from_iter<I: IntoIterator<Item = E>>(iter: I) -> Self {
+        ErrorSet {
+            errors: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<E: Into<ErrorItem>> Extend<E> for ErrorSet {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, iter: I) {
+        self.errors.extend(iter.into_iter().map(Into::into));
+    }
+}
+
+impl IntoIterator for ErrorSet {
+    type Item = ErrorItem;
+    type IntoIter = std::vec::IntoIter<ErrorItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ErrorSet {
+    type Item = &'a ErrorItem;
+    type IntoIter = std::slice::Iter<'a, ErrorItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.iter()
+    }
+}
+
+impl std::ops::Index<usize> for ErrorSet {
+    type Output = ErrorItem;
+
+    fn index(&self, index: usize) -> &ErrorItem {
+        &self.errors[index]
+    }
+}
+
+/// A single error is written as it is. Several errors are written one per line after their
+/// count.
+impl fmt::Display for ErrorSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.errors.as_slice() {
+            [] => f.write_str("no errors found"),
+            [error] => error.fmt(f),
+            errors => {
+                write!(f, "{} errors found:", errors.len())?;
+                for error in errors {
+                    write!(f, "\n- {}", error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ErrorSet {}
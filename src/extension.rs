@@ -0,0 +1,111 @@
+//! Custom scalar syntaxes (e.g. `@duration 5s`) for organizations with domain-specific
+//! configuration needs. They are not part of the Gura specification, so they are only parsed
+//! when explicitly enabled with `ParserOptions::scalar_extensions`.
+//!
+//! A custom scalar is a `@`, a tag (ASCII letters, digits and underscores), some spaces and a
+//! text that ends before the next `,`, `]`, `#` or new line. The text is handed to the
+//! recognizer registered for the tag, whose result is stored in a `GuraType::Extension`.
+//! Custom scalars are dumped as `@<tag> <value>` with the value in its natural form (see
+//! `GuraType::raw_display`), so recognizers should accept it to parse dumped documents back.
+//!
+//! Recognizers written in other languages can be registered through the C ABI of the `ffi`
+//! module.
+//!
+//! # Examples
+//!
+//! ```
+//! use gura::{
+//!     extension::ScalarExtensions, parse_with_options, parser::ParserOptions, GuraType,
+//! };
+//!
+//! let mut extensions = ScalarExtensions::new();
+//! extensions.register("duration", |text: &str| {
+//!     let seconds = text.strip_suffix('s').ok_or("durations must end with \"s\"")?;
+//!     seconds
+//!         .parse::<isize>()
+//!         .map(|seconds| GuraType::Integer(seconds * 1000))
+//!         .map_err(|error| error.to_string())
+//! });
+//!
+//! let options = ParserOptions::default().scalar_extensions(extensions);
+//! let parsed = parse_with_options("timeout: @duration 5s", &options).unwrap();
+//! assert_eq!(
+//!     parsed["timeout"],
+//!     GuraType::Extension(String::from("duration"), Box::new(GuraType::Integer(5000)))
+//! );
+//!
+//! let error = parse_with_options("timeout: @duration 5m", &options).unwrap_err();
+//! assert_eq!(error.msg, "Invalid @duration value \"5m\": durations must end with \"s\"");
+//! ```
+use crate::parser::GuraType;
+use indexmap::IndexMap;
+use std::{fmt, sync::Arc};
+
+/// Function that converts the text of a custom scalar into its value, or explains why the text
+/// is not valid
+pub type Recognizer = dyn Fn(&str) -> Result<GuraType, String> + Send + Sync;
+
+/// Recognizers of custom scalars indexed by their tag. Cloning it is cheap.
+#[derive(Clone, Default)]
+pub struct ScalarExtensions {
+    recognizers: IndexMap<String, Arc<Recognizer>>,
+}
+
+impl ScalarExtensions {
+    /// Creates a set without recognizers.
+    pub fn new() -> Self {
+        ScalarExtensions::default()
+    }
+
+    /// Registers the recognizer of a tag, replacing the previous one if any. Tags with chars
+    /// other than ASCII letters, digits and underscores are never matched.
+    ///
+    /// # Arguments
+    ///
+    /// * tag - Tag of the custom scalar, without the `@`.
+    /// * recognizer - Function that converts the text after the tag into a value.
+    pub fn register<F>(&mut self, tag: &str, recognizer: F)
+    where
+        F: Fn(&str) -> Result<GuraType, String> + Send + Sync + 'static,
+    {
+        self.recognizers
+            .insert(tag.to_string(), Arc::new(recognizer));
+    }
+
+    /// Checks if no recognizer was registered.
+    pub fn is_empty(&self) -> bool {
+        self.recognizers.is_empty()
+    }
+
+    /// Gets the registered tags, in the order they were registered.
+    pub fn tags(&self) -> impl Iterator<Item = &str> + '_ {
+        self.recognizers.keys().map(String::as_str)
+    }
+
+    /// Gets the recognizer of a tag.
+    pub(crate) fn recognizer(&self, tag: &str) -> Option<&Recognizer> {
+        self.recognizers.get(tag).map(|recognizer| &**recognizer)
+    }
+}
+
+impl fmt::Debug for ScalarExtensions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.tags()).finish()
+    }
+}
+
+/// Sets are equal if they have the same recognizers (the same functions, not equivalent ones)
+/// for the same tags.
+impl PartialEq for ScalarExtensions {
+    fn eq(&self, other: &Self) -> bool {
+        self.recognizers.len() == other.recognizers.len()
+            && self.recognizers.iter().all(|(tag, recognizer)| {
+                matches!(
+                    other.recognizers.get(tag),
+                    Some(other) if Arc::ptr_eq(recognizer, other)
+                )
+            })
+    }
+}
+
+impl Eq for ScalarExtensions {}
@@ -0,0 +1,468 @@
+//! C bindings, so other languages (C, C++, Swift, etc.) can use this parser. The header
+//! `include/gura.h` is generated with [cbindgen](https://github.com/mozilla/cbindgen) from
+//! this module (`cbindgen --config cbindgen.toml --output include/gura.h`).
+//!
+//! Build the shared or static library with
+//! `cargo rustc --release --features ffi --crate-type cdylib` (or `staticlib`).
+//!
+//! Parsed documents are opaque handles that must be released with `gura_free`, and every
+//! string returned by these functions must be released with `gura_string_free`.
+//!
+//! Custom scalars (see the `extension` module) can be implemented as plugins: a
+//! `GuraScalarPlugin` registered with `gura_extensions_register` and used by
+//! `gura_parse_with_extensions`. The plugin layout is versioned with
+//! `GURA_PLUGIN_ABI_VERSION`, which only changes when that layout does.
+//!
+//! Requires the `ffi` feature.
+//!
+//! # Examples
+//!
+//! ```c
+//! #include "gura.h"
+//!
+//! GuraErrorInfo error;
+//! GuraDocument *document = gura_parse("server:\n    port: 8080", &error);
+//! if (document == NULL) {
+//!     printf("Line %zu: %s\n", error.line, error.message);
+//!     gura_error_free(&error);
+//!     return;
+//! }
+//! char *port = gura_get(document, "server.port");
+//! char *text = gura_dump(document);
+//! gura_string_free(port);
+//! gura_string_free(text);
+//! gura_free(document);
+//! ```
+//!
+//! A plugin for `@flag on` and `@flag off` scalars:
+//!
+//! ```c
+//! bool recognize_flag(const char *text, void *user_data, GuraScalar *result) {
+//!     if (strcmp(text, "on") != 0 && strcmp(text, "off") != 0) {
+//!         result->string = "expected on or off";
+//!         return false;
+//!     }
+//!     result->kind = GURA_SCALAR_KIND_BOOL;
+//!     result->bool_value = strcmp(text, "on") == 0;
+//!     return true;
+//! }
+//!
+//! GuraScalarPlugin plugin = {GURA_PLUGIN_ABI_VERSION, "flag", recognize_flag, NULL, NULL};
+//! GuraExtensions *extensions = gura_extensions_new();
+//! gura_extensions_register(extensions, &plugin);
+//! GuraDocument *document = gura_parse_with_extensions("debug: @flag on", extensions, NULL);
+//! gura_extensions_free(extensions);
+//! ```
+use crate::{
+    errors::{Error, GuraError},
+    extension::ScalarExtensions,
+    parser::{dump, dump_value, parse_with_options, GuraType, ParserOptions},
+};
+use std::{
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_void},
+    ptr,
+};
+
+/// Version of the layout of `GuraScalarPlugin` and `GuraScalar`
+pub const GURA_PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Parsed Gura document, opaque for C
+pub struct GuraDocument(GuraType);
+
+/// Kinds of errors, `GURA_ERROR_KIND_NONE` when no error happened
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuraErrorKind {
+    None = 0,
+    ParseError,
+    VariableNotDefinedError,
+    InvalidIndentationError,
+    DuplicatedVariableError,
+    DuplicatedKeyError,
+    FileNotFoundError,
+    DuplicatedImportError,
+    InternalError,
+    /// The text is not valid UTF-8 or a pointer is NULL
+    InvalidArgument,
+}
+
+impl From<&Error> for GuraErrorKind {
+    fn from(kind: &Error) -> Self {
+        match kind {
+            Error::ParseError => GuraErrorKind::ParseError,
+            Error::VariableNotDefinedError => GuraErrorKind::VariableNotDefinedError,
+            Error::InvalidIndentationError => GuraErrorKind::InvalidIndentationError,
+            Error::DuplicatedVariableError => GuraErrorKind::DuplicatedVariableError,
+            Error::DuplicatedKeyError => GuraErrorKind::DuplicatedKeyError,
+            Error::FileNotFoundError => GuraErrorKind::FileNotFoundError,
+            Error::DuplicatedImportError => GuraErrorKind::DuplicatedImportError,
+            Error::InternalError => GuraErrorKind::InternalError,
+        }
+    }
+}
+
+/// Error filled by `gura_parse`. `message` must be released with `gura_error_free`
+#[repr(C)]
+#[derive(Debug)]
+pub struct GuraErrorInfo {
+    pub kind: GuraErrorKind,
+    /// Line of the error (one based), 0 if unknown
+    pub line: usize,
+    /// Position of the error as a byte offset
+    pub byte_pos: usize,
+    /// Message of the error, NULL if no error happened
+    pub message: *mut c_char,
+}
+
+impl GuraErrorInfo {
+    fn none() -> Self {
+        GuraErrorInfo {
+            kind: GuraErrorKind::None,
+            line: 0,
+            byte_pos: 0,
+            message: ptr::null_mut(),
+        }
+    }
+
+    fn invalid_argument(message: &str) -> Self {
+        GuraErrorInfo {
+            kind: GuraErrorKind::InvalidArgument,
+            message: to_c_string(message.to_string()),
+            ..GuraErrorInfo::none()
+        }
+    }
+}
+
+impl From<&GuraError> for GuraErrorInfo {
+    fn from(error: &GuraError) -> Self {
+        GuraErrorInfo {
+            kind: GuraErrorKind::from(&error.kind),
+            line: error.line,
+            byte_pos: error.byte_pos,
+            message: to_c_string(error.to_string()),
+        }
+    }
+}
+
+/// Kind of `GuraScalar` with a null value
+pub const GURA_SCALAR_KIND_NULL: u32 = 0;
+/// Kind of `GuraScalar` with a `bool_value`
+pub const GURA_SCALAR_KIND_BOOL: u32 = 1;
+/// Kind of `GuraScalar` with an `int_value`
+pub const GURA_SCALAR_KIND_INTEGER: u32 = 2;
+/// Kind of `GuraScalar` with a `float_value`
+pub const GURA_SCALAR_KIND_FLOAT: u32 = 3;
+/// Kind of `GuraScalar` with a `string`
+pub const GURA_SCALAR_KIND_STRING: u32 = 4;
+
+/// Value filled by the recognizer of a plugin. Only the field of its `kind` is read
+#[repr(C)]
+#[derive(Debug)]
+pub struct GuraScalar {
+    /// Type of the value, one of the `GURA_SCALAR_KIND_*` constants. It is an integer rather
+    /// than an enum as it is written by plugins, so unknown values can be rejected
+    pub kind: u32,
+    pub bool_value: bool,
+    pub int_value: i64,
+    pub float_value: f64,
+    /// NUL terminated UTF-8 string, owned by the plugin (it is copied by the parser). When the
+    /// text is rejected, it can be set to the reason
+    pub string: *const c_char,
+}
+
+/// Converts the text of a custom scalar (NUL terminated UTF-8) into a value. Returns false if
+/// the text is not valid
+pub type GuraScalarRecognizer = unsafe extern "C" fn(
+    text: *const c_char,
+    user_data: *mut c_void,
+    result: *mut GuraScalar,
+) -> bool;
+
+/// Custom scalar implemented by a plugin
+#[repr(C)]
+#[derive(Debug)]
+pub struct GuraScalarPlugin {
+    /// Must be `GURA_PLUGIN_ABI_VERSION`
+    pub abi_version: u32,
+    /// Tag of the custom scalar, without the `@`. It is copied when the plugin is registered
+    pub tag: *const c_char,
+    pub recognize: Option<GuraScalarRecognizer>,
+    /// Passed to every call of `recognize` and `release`
+    pub user_data: *mut c_void,
+    /// Called with `user_data` when the plugin is not used anymore. Can be NULL
+    pub release: Option<unsafe extern "C" fn(user_data: *mut c_void)>,
+}
+
+/// Set of custom scalars, opaque for C
+pub struct GuraExtensions(ScalarExtensions);
+
+/// Registered plugin, released when the last parser options using it are dropped.
+struct Plugin {
+    recognize: GuraScalarRecognizer,
+    user_data: *mut c_void,
+    release: Option<unsafe extern "C" fn(user_data: *mut c_void)>,
+}
+
+// Plugins must be thread safe, as documented in `gura_extensions_register`
+unsafe impl Send for Plugin {}
+unsafe impl Sync for Plugin {}
+
+impl Plugin {
+    /// Calls the recognizer of the plugin, converting its result into a value.
+    fn recognize(&self, text: &str) -> Result<GuraType, String> {
+        let text = CString::new(text).map_err(|_| String::from("NUL chars are not allowed"))?;
+        let mut result = GuraScalar {
+            kind: GURA_SCALAR_KIND_NULL,
+            bool_value: false,
+            int_value: 0,
+            float_value: 0.0,
+            string: ptr::null(),
+        };
+        let recognized = unsafe { (self.recognize)(text.as_ptr(), self.user_data, &mut result) };
+        let string = if result.string.is_null() {
+            None
+        } else {
+            Some(unsafe { CStr::from_ptr(result.string) }.to_string_lossy())
+        };
+
+        if !recognized {
+            return Err(string.map_or_else(|| String::from("rejected by its plugin"), String::from));
+        }
+        match result.kind {
+            GURA_SCALAR_KIND_NULL => Ok(GuraType::Null),
+            GURA_SCALAR_KIND_BOOL => Ok(GuraType::Bool(result.bool_value)),
+            GURA_SCALAR_KIND_INTEGER => Ok(GuraType::Integer(result.int_value as isize)),
+            GURA_SCALAR_KIND_FLOAT => Ok(GuraType::Float(result.float_value)),
+            GURA_SCALAR_KIND_STRING => string
+                .map(|string| GuraType::String(string.into_owned()))
+                .ok_or_else(|| String::from("the plugin returned a NULL string")),
+            kind => Err(format!(
+                "the plugin returned an unknown kind of value ({})",
+                kind
+            )),
+        }
+    }
+}
+
+impl Drop for Plugin {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self.user_data) };
+        }
+    }
+}
+
+/// Converts a string into a C string, removing the NUL chars it could contain.
+fn to_c_string(text: String) -> *mut c_char {
+    CString::new(text.replace('\0', ""))
+        .unwrap_or_default()
+        .into_raw()
+}
+
+/// Parses a NUL terminated UTF-8 Gura text. Returns NULL if the text is not valid, filling
+/// `error` (if it is not NULL) with the reason.
+///
+/// # Safety
+///
+/// `text` must be NULL or a valid NUL terminated string, and `error` must be NULL or point to
+/// a writable `GuraErrorInfo`.
+#[no_mangle]
+pub unsafe extern "C" fn gura_parse(
+    text: *const c_char,
+    error: *mut GuraErrorInfo,
+) -> *mut GuraDocument {
+    parse_text(text, &ParserOptions::default(), error)
+}
+
+/// Like `gura_parse` but also parsing the custom scalars of `extensions` (ignored if it is
+/// NULL).
+///
+/// # Safety
+///
+/// The same as `gura_parse`, and `extensions` must be NULL or a set returned by
+/// `gura_extensions_new` not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn gura_parse_with_extensions(
+    text: *const c_char,
+    extensions: *const GuraExtensions,
+    error: *mut GuraErrorInfo,
+) -> *mut GuraDocument {
+    let mut options = ParserOptions::default();
+    if let Some(extensions) = extensions.as_ref() {
+        options = options.scalar_extensions(extensions.0.clone());
+    }
+    parse_text(text, &options, error)
+}
+
+/// Parses a C text, filling `error` if it is not NULL.
+unsafe fn parse_text(
+    text: *const c_char,
+    options: &ParserOptions,
+    error: *mut GuraErrorInfo,
+) -> *mut GuraDocument {
+    let result = if text.is_null() {
+        Err(GuraErrorInfo::invalid_argument("The text is NULL"))
+    } else {
+        match CStr::from_ptr(text).to_str() {
+            Ok(text) => {
+                parse_with_options(text, options).map_err(|error| GuraErrorInfo::from(&error))
+            }
+            Err(_) => Err(GuraErrorInfo::invalid_argument(
+                "The text is not valid UTF-8",
+            )),
+        }
+    };
+
+    let (document, info) = match result {
+        Ok(value) => (
+            Box::into_raw(Box::new(GuraDocument(value))),
+            GuraErrorInfo::none(),
+        ),
+        Err(info) => (ptr::null_mut(), info),
+    };
+    if error.is_null() {
+        gura_string_free(info.message);
+    } else {
+        // The error could be uninitialized
+        error.write(info);
+    }
+    document
+}
+
+/// Creates an empty set of custom scalars, which must be released with
+/// `gura_extensions_free`.
+#[no_mangle]
+pub extern "C" fn gura_extensions_new() -> *mut GuraExtensions {
+    Box::into_raw(Box::new(GuraExtensions(ScalarExtensions::new())))
+}
+
+/// Registers a plugin, replacing the previous one with the same tag. Returns
+/// `GURA_ERROR_KIND_INVALID_ARGUMENT` if a pointer is NULL, the tag is not valid UTF-8 or the
+/// plugin was built for another ABI version. Rejected plugins are released, except the ones of
+/// other ABI versions: their layout is unknown, so only `abi_version` is read.
+///
+/// Recognizers can be called from any thread, so they must be thread safe.
+///
+/// # Safety
+///
+/// `extensions` must be NULL or a set returned by `gura_extensions_new` not released yet, and
+/// `plugin` must be NULL or point to a plugin whose pointers are valid until it is released.
+#[no_mangle]
+pub unsafe extern "C" fn gura_extensions_register(
+    extensions: *mut GuraExtensions,
+    plugin: *const GuraScalarPlugin,
+) -> GuraErrorKind {
+    // The version is the first field in every layout, so it is read before referencing the
+    // rest of the plugin
+    if plugin.is_null() || ptr::addr_of!((*plugin).abi_version).read() != GURA_PLUGIN_ABI_VERSION {
+        return GuraErrorKind::InvalidArgument;
+    }
+    let plugin = &*plugin;
+    let tag = if plugin.tag.is_null() {
+        None
+    } else {
+        CStr::from_ptr(plugin.tag).to_str().ok()
+    };
+    // Wrapped first so it is released on errors
+    let recognizer = plugin.recognize.map(|recognize| Plugin {
+        recognize,
+        user_data: plugin.user_data,
+        release: plugin.release,
+    });
+
+    match (extensions.as_mut(), tag, recognizer) {
+        (Some(extensions), Some(tag), Some(recognizer)) => {
+            extensions
+                .0
+                .register(tag, move |text: &str| recognizer.recognize(text));
+            GuraErrorKind::None
+        }
+        _ => GuraErrorKind::InvalidArgument,
+    }
+}
+
+/// Releases a set of custom scalars. Documents parsed with it are still valid and plugins are
+/// released once no parser uses them. NULL is ignored.
+///
+/// # Safety
+///
+/// `extensions` must be NULL or a set returned by `gura_extensions_new` not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn gura_extensions_free(extensions: *mut GuraExtensions) {
+    if !extensions.is_null() {
+        drop(Box::from_raw(extensions));
+    }
+}
+
+/// Dumps a document into a Gura text. Returns NULL if `document` is NULL.
+///
+/// # Safety
+///
+/// `document` must be NULL or a document returned by `gura_parse` not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn gura_dump(document: *const GuraDocument) -> *mut c_char {
+    match document.as_ref() {
+        Some(document) => to_c_string(dump(&document.0)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Gets the value located at a path (keys separated by dots) as a Gura text (e.g. strings
+/// are quoted). Returns NULL if the path does not exist or an argument is not valid.
+///
+/// # Safety
+///
+/// `document` must be NULL or a document returned by `gura_parse` not released yet, and
+/// `path` must be NULL or a valid NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gura_get(
+    document: *const GuraDocument,
+    path: *const c_char,
+) -> *mut c_char {
+    if path.is_null() {
+        return ptr::null_mut();
+    }
+    let value = match (document.as_ref(), CStr::from_ptr(path).to_str()) {
+        (Some(document), Ok(path)) => document.0.get_path(path),
+        _ => None,
+    };
+    value.map_or(ptr::null_mut(), |value| to_c_string(dump_value(value)))
+}
+
+/// Releases a document returned by `gura_parse`. NULL is ignored.
+///
+/// # Safety
+///
+/// `document` must be NULL or a document returned by `gura_parse` not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn gura_free(document: *mut GuraDocument) {
+    if !document.is_null() {
+        drop(Box::from_raw(document));
+    }
+}
+
+/// Releases a string returned by this library. NULL is ignored.
+///
+/// # Safety
+///
+/// `text` must be NULL or a string returned by this library not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn gura_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
+
+/// Releases the message of an error, setting it to NULL. NULL is ignored.
+///
+/// # Safety
+///
+/// `error` must be NULL or point to an error filled by `gura_parse`.
+#[no_mangle]
+pub unsafe extern "C" fn gura_error_free(error: *mut GuraErrorInfo) {
+    if let Some(error) = error.as_mut() {
+        gura_string_free(error.message);
+        error.message = ptr::null_mut();
+    }
+}
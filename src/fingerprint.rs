@@ -0,0 +1,94 @@
+use crate::parser::GuraType;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64 bits FNV-1a hasher. Unlike `std::collections::hash_map::DefaultHasher`, its output is
+/// guaranteed to be the same in every platform and Rust version, so fingerprints can be stored.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Writes a length prefixed string, so consecutive strings can not be confused.
+    fn write_str(&mut self, value: &str) {
+        self.write(&(value.len() as u64).to_le_bytes());
+        self.write(value.as_bytes());
+    }
+}
+
+/// Computes the fingerprint of a value (see `GuraType::fingerprint`).
+pub(crate) fn fingerprint(value: &GuraType) -> u64 {
+    let mut hasher = Fnv1a(FNV_OFFSET_BASIS);
+    write_value(&mut hasher, value);
+    hasher.0
+}
+
+/// Computes the fingerprint of a text, e.g. to detect files with the same content.
+pub(crate) fn fingerprint_text(text: &str) -> u64 {
+    let mut hasher = Fnv1a(FNV_OFFSET_BASIS);
+    hasher.write_str(text);
+    hasher.0
+}
+
+/// Writes the canonical form of a value: a tag for its variant followed by its content.
+fn write_value(hasher: &mut Fnv1a, value: &GuraType) {
+    match value {
+        GuraType::Null => hasher.write(b"n"),
+        GuraType::Bool(value) => hasher.write(if *value { b"t" } else { b"f" }),
+        GuraType::String(value) => {
+            hasher.write(b"s");
+            hasher.write_str(value);
+        }
+        GuraType::Integer(value) => {
+            hasher.write(b"i");
+            hasher.write(&(*value as i128).to_le_bytes());
+        }
+        GuraType::BigInteger(value) => {
+            hasher.write(b"I");
+            hasher.write(&value.to_le_bytes());
+        }
+        GuraType::Float(value) => {
+            // All NaNs are the same value and zeros are equal regardless of their sign
+            let bits = if value.is_nan() {
+                f64::NAN.to_bits()
+            } else if *value == 0.0 {
+                0
+            } else {
+                value.to_bits()
+            };
+            hasher.write(b"d");
+            hasher.write(&bits.to_le_bytes());
+        }
+        GuraType::Extension(tag, value) => {
+            hasher.write(b"x");
+            hasher.write_str(tag);
+            write_value(hasher, value);
+        }
+        GuraType::Array(values) => {
+            hasher.write(b"a");
+            hasher.write(&(values.len() as u64).to_le_bytes());
+            for value in values {
+                write_value(hasher, value);
+            }
+        }
+        GuraType::Object(values) => {
+            // Objects are equal regardless of the order of their keys
+            let mut keys: Vec<&String> = values.keys().collect();
+            keys.sort();
+            hasher.write(b"o");
+            hasher.write(&(keys.len() as u64).to_le_bytes());
+            for key in keys {
+                hasher.write_str(key);
+                write_value(hasher, &values[key]);
+            }
+        }
+        // Internal variants never appear in parsed documents
+        _ => hasher.write(b"?"),
+    }
+}
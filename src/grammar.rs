@@ -0,0 +1,292 @@
+//! The grammar accepted by the parser, as data, so documentation tools and external validators
+//! can stay in sync with this implementation. Rules are written in the EBNF notation of the
+//! [XML specification](https://www.w3.org/TR/xml/#sec-notation), which tools like railroad
+//! diagram generators read directly, and are named after the functions of the parser that match
+//! them (the ones that appear in its traces, see the `trace-parser` feature).
+//!
+//! The grammar depends on the options of the parser, as some extensions change the syntax
+//! (see `ParserOptions::grammar`). Constraints that are not syntactic (indentation levels, the
+//! range of numbers, duplicated keys, etc.) are only described.
+//!
+//! # Examples
+//!
+//! ```
+//! use gura::{grammar, parser::ParserOptions, spec::Extension};
+//!
+//! let grammar = grammar();
+//! assert_eq!(grammar.rules[0].name, "start");
+//! assert_eq!(grammar.rule("boolean").unwrap().definition, "\"true\" | \"false\"");
+//! assert!(grammar.to_ebnf().contains("key ::= unquoted_string \":\""));
+//!
+//! let lenient = ParserOptions::default().lenient_arrays(true).grammar();
+//! let separator = lenient.rule("array_separator").unwrap();
+//! assert_eq!(separator.extension, Some(Extension::LenientArrays));
+//! ```
+use crate::parser::ParserOptions;
+use crate::spec::{ComplianceLevel, Extension, SpecVersion};
+use std::fmt;
+
+/// Rule of the grammar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrammarRule {
+    /// Name of the rule, referenced by the definitions of other rules
+    pub name: &'static str,
+    /// Definition of the rule in EBNF (e.g. `"true" | "false"`)
+    pub definition: String,
+    /// What the rule matches, including the constraints not expressed by its definition
+    pub description: String,
+    /// Extension of the parser that changed the rule from the one of the specification, if any
+    pub extension: Option<Extension>,
+}
+
+/// Rules of the syntax accepted by some options of the parser. The first rule matches whole
+/// documents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grammar {
+    /// Version of the specification targeted
+    pub spec_version: SpecVersion,
+    /// Rules of the grammar, starting with the one of documents
+    pub rules: Vec<GrammarRule>,
+}
+
+impl Grammar {
+    /// Builds the grammar of the given configuration of the parser, with indentation levels of
+    /// `indent_unit` spaces.
+    pub(crate) fn new(compliance: &ComplianceLevel, indent_unit: usize) -> Self {
+        let lenient_arrays = compliance.has_extension(Extension::LenientArrays);
+        let scalar_extensions = compliance.has_extension(Extension::ScalarExtensions);
+        let hex_escapes = compliance.has_extension(Extension::HexEscapes);
+
+        let mut builder = RulesBuilder::default();
+        builder.rule(
+            "start",
+            "(gura_import | variable | useless_line)* object",
+            "A document: imports and variables (the imported files are inlined at the beginning of the document) followed by the pairs of the root object",
+        );
+        builder.rule(
+            "object",
+            "(variable | pair | useless_line)*",
+            "Pairs of an object at the same indentation level, which must be indented one level more than the pair that contains them (if any). Keys can not be repeated",
+        );
+        builder.rule(
+            "pair",
+            "ws_with_indentation key ws any_type new_line?",
+            "Key and value of an object. The value can not be empty",
+        );
+        let indent_definition = format!("\"{}\"", " ".repeat(indent_unit));
+        let indent_description = format!("A level of indentation of {} spaces", indent_unit);
+        if indent_unit == 4 {
+            builder.rule("indent", &indent_definition, &indent_description);
+        } else {
+            builder.extension_rule(
+                "indent",
+                &indent_definition,
+                &indent_description,
+                Extension::IndentUnit,
+            );
+        }
+        builder.rule(
+            "ws_with_indentation",
+            "indent*",
+            "Indentation of a pair. Tabs are not allowed",
+        );
+        builder.rule("key", "unquoted_string \":\"", "Key of a pair");
+        builder.rule(
+            "unquoted_string",
+            "[0-9A-Za-z_]+",
+            "Names of keys, variables and custom scalars",
+        );
+        builder.rule("any_type", "primitive_type | complex_type", "A value");
+        let primitive_types = "null | boolean | basic_string | literal_string | number | variable_value | empty_object";
+        if scalar_extensions {
+            builder.extension_rule(
+                "primitive_type",
+                &format!("ws ({} | scalar_extension) ws", primitive_types),
+                "A value that is not an array nor an object",
+                Extension::ScalarExtensions,
+            );
+            builder.extension_rule(
+                "scalar_extension",
+                "\"@\" unquoted_string ws [^,#x5D#x23#xA#xD]+",
+                "A custom scalar: a tag and a text, up to the end of the line, a comment or the delimiters of arrays, accepted by the recognizer of the tag",
+                Extension::ScalarExtensions,
+            );
+        } else {
+            builder.rule(
+                "primitive_type",
+                &format!("ws ({}) ws", primitive_types),
+                "A value that is not an array nor an object",
+            );
+        }
+        builder.rule("complex_type", "list | object", "An array or an object");
+        builder.rule(
+            "list",
+            "ws \"[\" (useless_line | any_type array_separator)* (any_type ws new_line?)? ws \"]\"",
+            "An array. The last element can be followed by a comma",
+        );
+        if lenient_arrays {
+            builder.extension_rule(
+                "array_separator",
+                "ws (new_line? \",\" | new_line)",
+                "Separator of the elements of an array: a comma or a new line",
+                Extension::LenientArrays,
+            );
+        } else {
+            builder.rule(
+                "array_separator",
+                "ws new_line? \",\"",
+                "Separator of the elements of an array",
+            );
+        }
+        builder.rule("null", "\"null\"", "The null value");
+        builder.rule("boolean", "\"true\" | \"false\"", "A boolean value");
+        builder.rule("empty_object", "\"empty\"", "An object without pairs");
+        builder.rule(
+            "basic_string",
+            "'\"\"\"' new_line? (basic_char - '\"\"\"')* '\"\"\"' | '\"' (basic_char - '\"')* '\"'",
+            "A string with escape sequences and variables. The new line after the opening delimiter of multiline strings is not part of them",
+        );
+        builder.rule(
+            "basic_char",
+            "escape | variable_value | [^\\$]",
+            "A char of a basic string. Variables are replaced by their values",
+        );
+        let escape_description = "An escape sequence. A backslash at the end of a line removes the following whitespaces and new lines";
+        if hex_escapes {
+            builder.extension_rule(
+                "escape",
+                "\"\\\" ([bfnrt\"\\$] | \"x\" hex hex | \"u\" hex hex hex hex | \"U\" hex hex hex hex hex hex hex hex | ws new_line)",
+                escape_description,
+                Extension::HexEscapes,
+            );
+        } else {
+            builder.rule(
+                "escape",
+                "\"\\\" ([bfnrt\"\\$] | \"u\" hex hex hex hex | \"U\" hex hex hex hex hex hex hex hex | ws new_line)",
+                escape_description,
+            );
+        }
+        builder.rule("hex", "[0-9A-Fa-f]", "A hexadecimal digit");
+        builder.rule(
+            "literal_string",
+            "\"'''\" new_line? (char - \"'''\")* \"'''\" | \"'\" [^']* \"'\"",
+            "A string without escape sequences nor variables. The new line after the opening delimiter of multiline strings is not part of them",
+        );
+        builder.rule("char", "[#x0-#x10FFFF]", "Any char");
+        builder.rule(
+            "number",
+            "[+-]? (decimal | \"inf\" | \"nan\") | \"0x\" [0-9A-Fa-f_]+ | \"0o\" [0-7_]+ | \"0b\" [01_]+",
+            "A number. Integers must fit in 64 bits (128 bits unless the parser is in strict mode)",
+        );
+        builder.rule(
+            "decimal",
+            "[0-9_]+ (\".\" [0-9_]+)? ([eE] [+-]? [0-9_]+)?",
+            "An integer or a float in decimal notation",
+        );
+        builder.rule(
+            "variable",
+            "\"$\" key ws (basic_string | literal_string | number | variable_value)",
+            "Definition of a variable. Variables can not be redefined",
+        );
+        builder.rule(
+            "variable_value",
+            "\"$\" unquoted_string",
+            "Value of a variable defined before or of an environment variable",
+        );
+        builder.rule(
+            "gura_import",
+            "\"import\" \" \" quoted_string_with_var ws new_line?",
+            "Import of a file, relative to the directory of the importing one. Files can only be imported once",
+        );
+        builder.rule(
+            "quoted_string_with_var",
+            "'\"' ([^\"$] | variable_value)* '\"'",
+            "A string with variables and without escape sequences",
+        );
+        builder.rule(
+            "useless_line",
+            "ws (comment | new_line)?",
+            "A line without values. Only matches if it is followed by a comment, a new line or the end of the text",
+        );
+        builder.rule(
+            "comment",
+            "\"#\" (char - new_line)* new_line?",
+            "A comment, up to the end of the line",
+        );
+        builder.rule("new_line", "#xD #xA | [#xA#xD#xC#xB#x8]", "A new line");
+        builder.rule("ws", "[#x20#x9]*", "Blanks");
+
+        Grammar {
+            spec_version: compliance.spec_version,
+            rules: builder.rules,
+        }
+    }
+
+    /// Gets a rule by its name.
+    pub fn rule(&self, name: &str) -> Option<&GrammarRule> {
+        self.rules.iter().find(|rule| rule.name == name)
+    }
+
+    /// Writes the grammar in EBNF, with the description of every rule as a comment above it.
+    pub fn to_ebnf(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Writes the grammar in EBNF (see `Grammar::to_ebnf`).
+impl fmt::Display for Grammar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "/* Gura {} */", self.spec_version)?;
+        for rule in &self.rules {
+            write!(
+                f,
+                "\n\n/* {} */\n{} ::= {}",
+                rule.description, rule.name, rule.definition
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Accumulates the rules of a grammar
+#[derive(Default)]
+struct RulesBuilder {
+    rules: Vec<GrammarRule>,
+}
+
+impl RulesBuilder {
+    fn rule(&mut self, name: &'static str, definition: &str, description: &str) {
+        self.push(name, definition, description, None);
+    }
+
+    fn extension_rule(
+        &mut self,
+        name: &'static str,
+        definition: &str,
+        description: &str,
+        extension: Extension,
+    ) {
+        self.push(name, definition, description, Some(extension));
+    }
+
+    fn push(
+        &mut self,
+        name: &'static str,
+        definition: &str,
+        description: &str,
+        extension: Option<Extension>,
+    ) {
+        self.rules.push(GrammarRule {
+            name,
+            definition: definition.to_string(),
+            description: description.to_string(),
+            extension,
+        });
+    }
+}
+
+/// Gets the grammar of the parser with the default options (see `ParserOptions::grammar` for
+/// the one of other options).
+pub fn grammar() -> Grammar {
+    ParserOptions::default().grammar()
+}
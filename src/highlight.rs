@@ -0,0 +1,277 @@
+//! Classification of the tokens of a Gura text for syntax highlighting.
+use crate::span::Span;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Kind of a highlighted token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenClass {
+    /// Key of a pair, without the colon
+    Key,
+    /// Quoted string, including the quotes
+    String,
+    /// Integer or float number, including `inf` and `nan`
+    Number,
+    /// `true` or `false`
+    Boolean,
+    /// `null` or `empty`
+    Null,
+    /// Variable name with the `$`, either in its definition or in a usage (also inside strings)
+    Variable,
+    /// Comment, from the `#` to the end of the line
+    Comment,
+    /// `import` keyword
+    Import,
+}
+
+/// Classifies the tokens of a Gura text. The text does not need to be valid: unknown content
+/// is skipped, so it can be used to highlight documents while they are being edited.
+///
+/// Tokens are returned in order. Basic strings that use variables are split into several
+/// `String` and `Variable` tokens.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{highlight, highlight::TokenClass};
+///
+/// let text = "port: 8080 # Default port";
+/// let classes: Vec<(&str, TokenClass)> = highlight(text)
+///     .into_iter()
+///     .map(|(span, class)| (&text[span.byte_start..span.byte_end], class))
+///     .collect();
+///
+/// assert_eq!(
+///     classes,
+///     vec![
+///         ("port", TokenClass::Key),
+///         ("8080", TokenClass::Number),
+///         ("# Default port", TokenClass::Comment),
+///     ]
+/// );
+/// ```
+pub fn highlight(text: &str) -> Vec<(Span, TokenClass)> {
+    let mut scanner = Scanner::new(text);
+    scanner.scan();
+    scanner.tokens
+}
+
+/// Walks through the grapheme clusters of a text collecting tokens
+struct Scanner<'a> {
+    text: &'a str,
+    graphemes: Vec<&'a str>,
+    /// Byte and UTF-16 code unit offsets of every grapheme cluster (plus the end of the text)
+    byte_offsets: Vec<usize>,
+    utf16_offsets: Vec<usize>,
+    /// Line of every grapheme cluster (plus the end of the text)
+    lines: Vec<usize>,
+    pos: usize,
+    tokens: Vec<(Span, TokenClass)>,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str) -> Self {
+        let graphemes: Vec<&str> = text.graphemes(true).collect();
+        let mut byte_offsets = Vec::with_capacity(graphemes.len() + 1);
+        let mut utf16_offsets = Vec::with_capacity(graphemes.len() + 1);
+        let mut lines = Vec::with_capacity(graphemes.len() + 1);
+        let (mut byte_offset, mut utf16_offset, mut line) = (0, 0, 1);
+        for grapheme in graphemes.iter() {
+            byte_offsets.push(byte_offset);
+            utf16_offsets.push(utf16_offset);
+            lines.push(line);
+            byte_offset += grapheme.len();
+            utf16_offset += grapheme.encode_utf16().count();
+            if is_new_line(grapheme) {
+                line += 1;
+            }
+        }
+        byte_offsets.push(byte_offset);
+        utf16_offsets.push(utf16_offset);
+        lines.push(line);
+
+        Scanner {
+            text,
+            graphemes,
+            byte_offsets,
+            utf16_offsets,
+            lines,
+            pos: 0,
+            tokens: Vec::new(),
+        }
+    }
+
+    /// Gets the grapheme cluster at `pos`, or an empty string at the end of the text.
+    fn at(&self, pos: usize) -> &'a str {
+        self.graphemes.get(pos).copied().unwrap_or("")
+    }
+
+    /// Checks if the text at `pos` starts with `expected`.
+    fn starts_with(&self, pos: usize, expected: &str) -> bool {
+        self.text[self.byte_offsets[pos.min(self.graphemes.len())]..].starts_with(expected)
+    }
+
+    /// Gets the position after the chars from `pos` matching `predicate`.
+    fn skip_while(&self, mut pos: usize, predicate: impl Fn(&str) -> bool) -> usize {
+        while pos < self.graphemes.len() && predicate(self.graphemes[pos]) {
+            pos += 1;
+        }
+        pos
+    }
+
+    /// Checks if only whitespaces precede `pos` in its line.
+    fn is_line_start(&self, pos: usize) -> bool {
+        self.graphemes[..pos]
+            .iter()
+            .rev()
+            .take_while(|grapheme| !is_new_line(grapheme))
+            .all(|grapheme| *grapheme == " " || *grapheme == "\t")
+    }
+
+    fn push(&mut self, start: usize, end: usize, class: TokenClass) {
+        if start < end {
+            let span = Span {
+                start,
+                end,
+                line: self.lines[start],
+                byte_start: self.byte_offsets[start],
+                byte_end: self.byte_offsets[end],
+                utf16_start: self.utf16_offsets[start],
+                utf16_end: self.utf16_offsets[end],
+            };
+            self.tokens.push((span, class));
+        }
+    }
+
+    fn scan(&mut self) {
+        while self.pos < self.graphemes.len() {
+            let grapheme = self.at(self.pos);
+            match grapheme {
+                "#" => {
+                    let end = self.skip_while(self.pos, |grapheme| !is_new_line(grapheme));
+                    self.push(self.pos, end, TokenClass::Comment);
+                    self.pos = end;
+                }
+                "\"" | "'" => self.string(),
+                "$" => {
+                    let end = self.skip_while(self.pos + 1, is_key_char);
+                    self.push(self.pos, end, TokenClass::Variable);
+                    self.pos = end.max(self.pos + 1);
+                }
+                "+" | "-" => self.number(self.pos),
+                _ if is_key_char(grapheme) => self.word(),
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    /// Classifies a word: a key, a keyword or a number.
+    fn word(&mut self) {
+        let start = self.pos;
+        let end = self.skip_while(start, is_key_char);
+        let word: String = self.graphemes[start..end].concat();
+
+        if self.at(end) == ":" {
+            self.push(start, end, TokenClass::Key);
+            self.pos = end;
+            return;
+        }
+
+        let class = match word.as_str() {
+            "true" | "false" => Some(TokenClass::Boolean),
+            "null" | "empty" => Some(TokenClass::Null),
+            "import" if self.at(end) == " " && self.is_line_start(start) => {
+                Some(TokenClass::Import)
+            }
+            _ => None,
+        };
+        match class {
+            Some(class) => {
+                self.push(start, end, class);
+                self.pos = end;
+            }
+            None => self.number(start),
+        }
+    }
+
+    /// Classifies a number starting at `start` (optionally with a sign). Other words are skipped.
+    fn number(&mut self, start: usize) {
+        let digits_start = if matches!(self.at(start), "+" | "-") {
+            start + 1
+        } else {
+            start
+        };
+
+        let mut end = digits_start;
+        while end < self.graphemes.len() {
+            let grapheme = self.at(end);
+            let is_exponent_sign = matches!(grapheme, "+" | "-")
+                && end > digits_start
+                && matches!(self.at(end - 1), "e" | "E")
+                && !self.starts_with(digits_start, "0x");
+            if is_key_char(grapheme) || grapheme == "." || is_exponent_sign {
+                end += 1;
+            } else {
+                break;
+            }
+        }
+
+        let word: String = self.graphemes[digits_start..end].concat();
+        let is_number =
+            word.starts_with(|char: char| char.is_ascii_digit()) || word == "inf" || word == "nan";
+        if is_number {
+            self.push(start, end, TokenClass::Number);
+        }
+        self.pos = end.max(start + 1);
+    }
+
+    /// Classifies a quoted string. Variables of basic strings are reported as `Variable`.
+    fn string(&mut self) {
+        let quote = self.at(self.pos);
+        let is_basic = quote == "\"";
+        let is_multiline = self.starts_with(self.pos, &quote.repeat(3));
+        let delimiter_len = if is_multiline { 3 } else { 1 };
+
+        let mut segment_start = self.pos;
+        let mut pos = self.pos + delimiter_len;
+        loop {
+            let grapheme = self.at(pos);
+            if grapheme.is_empty() || (!is_multiline && is_new_line(grapheme)) {
+                break;
+            }
+
+            if grapheme == quote && (!is_multiline || self.starts_with(pos, &quote.repeat(3))) {
+                pos += delimiter_len;
+                break;
+            }
+
+            if is_basic && grapheme == "\\" {
+                pos += 2;
+            } else if is_basic && grapheme == "$" && is_key_char(self.at(pos + 1)) {
+                let variable_end = self.skip_while(pos + 1, is_key_char);
+                self.push(segment_start, pos, TokenClass::String);
+                self.push(pos, variable_end, TokenClass::Variable);
+                segment_start = variable_end;
+                pos = variable_end;
+            } else {
+                pos += 1;
+            }
+        }
+
+        let end = pos.min(self.graphemes.len());
+        self.push(segment_start, end, TokenClass::String);
+        self.pos = end;
+    }
+}
+
+/// Checks if a grapheme cluster is a new line char.
+fn is_new_line(grapheme: &str) -> bool {
+    matches!(grapheme, "\n" | "\r\n" | "\x0C" | "\x0B" | "\x08")
+}
+
+/// Checks if a grapheme cluster can be part of a key.
+fn is_key_char(grapheme: &str) -> bool {
+    grapheme.len() == 1
+        && grapheme
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '_')
+}
@@ -0,0 +1,175 @@
+//! Graph of the files imported by a Gura file, so teams can visualize how a configuration split
+//! in many files is assembled. Graphs can be exported to [DOT](https://graphviz.org/doc/info/lang.html)
+//! (e.g. `dot -Tsvg`) and JSON.
+//!
+//! # Examples
+//!
+//! ```
+//! use gura::import_graph;
+//! use std::fs;
+//!
+//! let dir = tempfile::tempdir().unwrap();
+//! fs::write(dir.path().join("base.ura"), "name: \"app\"").unwrap();
+//! fs::write(dir.path().join("main.ura"), "import \"base.ura\"\nport: 8080").unwrap();
+//!
+//! let graph = import_graph(dir.path().join("main.ura")).unwrap();
+//! assert_eq!(graph.files[0].keys, vec!["port"]);
+//! assert_eq!(graph.files[1].keys, vec!["name"]);
+//! assert_eq!(graph.edges, vec![(0, 1)]);
+//! ```
+use crate::{
+    errors::ReadError,
+    parser::{import_path, parse_spanned_file, ParserOptions},
+};
+use indexmap::IndexMap;
+use std::{
+    collections::VecDeque,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// File of an import graph with the keys it defines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedFile {
+    /// Path of the file, as it is resolved by the parser
+    pub path: PathBuf,
+    /// Path of every key defined in the file (nested ones included, keys separated by dots as
+    /// in `GuraType::get_path`), in the order they are defined. Keys of its imported files are
+    /// not included
+    pub keys: Vec<String>,
+}
+
+/// Files imported by a Gura file, directly or through other files (see `import_graph`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportGraph {
+    /// Every file of the graph, starting with the parsed one. Every file appears once, however
+    /// many files import it
+    pub files: Vec<ImportedFile>,
+    /// Imports as indexes of the importing and the imported files in `files`, in the order
+    /// they are written
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl ImportGraph {
+    /// Exports the graph in DOT format, labeling every file with its path and its top-level
+    /// keys.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph imports {\n    node [shape=box];\n");
+        for (index, file) in self.files.iter().enumerate() {
+            let mut label = file.path.to_string_lossy().to_string();
+            for key in file.keys.iter().filter(|key| !key.contains('.')) {
+                label.push('\n');
+                label.push_str(key);
+            }
+            writeln!(dot, "    file{} [label={}];", index, quote(&label)).unwrap();
+        }
+        for (from, to) in self.edges.iter() {
+            writeln!(dot, "    file{} -> file{};", from, to).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Exports the graph in JSON, as an object with a `files` array (objects with the `path`
+    /// and the `keys` of every file) and an `edges` array (objects with the `from` and `to`
+    /// paths of every import).
+    pub fn to_json(&self) -> String {
+        let path = |index: usize| quote(&self.files[index].path.to_string_lossy());
+        let files = self
+            .files
+            .iter()
+            .map(|file| {
+                let keys: Vec<String> = file.keys.iter().map(|key| quote(key)).collect();
+                format!(
+                    "{{\"path\":{},\"keys\":[{}]}}",
+                    quote(&file.path.to_string_lossy()),
+                    keys.join(",")
+                )
+            })
+            .collect::<Vec<String>>();
+        let edges = self
+            .edges
+            .iter()
+            .map(|(from, to)| format!("{{\"from\":{},\"to\":{}}}", path(*from), path(*to)))
+            .collect::<Vec<String>>();
+        format!(
+            "{{\"files\":[{}],\"edges\":[{}]}}",
+            files.join(","),
+            edges.join(",")
+        )
+    }
+}
+
+/// Builds the graph of the files imported by the Gura file at `path`, directly or through
+/// other files. Imports are relative to the files that contain them, as in `parse_file`, and
+/// files imported through several paths are accepted, as in
+/// `ParserOptions::deduplicate_imports`.
+///
+/// # Errors
+///
+/// * Io - If the file can not be read.
+/// * InvalidUtf8 - If the file is not valid UTF-8.
+/// * Parse - If the file or any of its imported files is not valid on its own (e.g. an
+///   imported file does not exist).
+pub fn import_graph<P: AsRef<Path>>(path: P) -> Result<ImportGraph, ReadError> {
+    let mut graph = ImportGraph {
+        files: Vec::new(),
+        edges: Vec::new(),
+    };
+    // Indexes of the files by canonical path, so every file is visited once
+    let mut indexes: IndexMap<PathBuf, usize> = IndexMap::new();
+    let options = ParserOptions::default().deduplicate_imports(true);
+    let mut pending = VecDeque::from(vec![path.as_ref().to_path_buf()]);
+    indexes.insert(canonical(path.as_ref()), 0);
+
+    while let Some(path) = pending.pop_front() {
+        let document = parse_spanned_file(&path, &options)?;
+        let index = indexes[&canonical(&path)];
+        let parent_dir_path = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(|dir| dir.to_string_lossy().to_string());
+
+        for import in document.records.imports.iter() {
+            let imported = PathBuf::from(import_path(&import.path, parent_dir_path.as_deref()));
+            let next_index = indexes.len();
+            let imported_index = *indexes.entry(canonical(&imported)).or_insert_with(|| {
+                pending.push_back(imported);
+                next_index
+            });
+            graph.edges.push((index, imported_index));
+        }
+
+        graph.files.push(ImportedFile {
+            path,
+            keys: document
+                .keys_with_spans()
+                .map(|(key, _)| key.to_string())
+                .collect(),
+        });
+    }
+
+    Ok(graph)
+}
+
+/// Gets the path that identifies a file, however it is referenced.
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Writes a string between double quotes, escaping it as JSON and DOT require.
+fn quote(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for char in value.chars() {
+        match char {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            char if (char as u32) < 0x20 => write!(quoted, "\\u{:04x}", char as u32).unwrap(),
+            char => quoted.push(char),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,182 @@
+//! Pre-processing of imports: the import sentences of a Gura text are replaced by the content
+//! of the imported files (recursively), keeping comments and blank lines. Useful for build
+//! systems that vendor a configuration split in many files into a single one.
+//!
+//! # Examples
+//!
+//! ```
+//! use gura::resolve_imports;
+//! use std::fs;
+//!
+//! let dir = tempfile::tempdir().unwrap();
+//! fs::write(dir.path().join("base.ura"), "# Shared values\n$host: \"localhost\"\n").unwrap();
+//!
+//! let text = "import \"base.ura\"\n\n# Server\nhost: $host\n";
+//! let resolved = resolve_imports(text, dir.path()).unwrap();
+//! assert_eq!(resolved.text, "# Shared values\n$host: \"localhost\"\n\n# Server\nhost: $host\n");
+//!
+//! // Line 4 of the result is the line 3 of the text
+//! assert_eq!(resolved.source_line(4), Some((None, 3)));
+//! ```
+use crate::{
+    errors::ReadError,
+    parser::{import_path, parse_spanned_in, ParserOptions},
+    span::SpannedDocument,
+};
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// Lines of a resolved text that come from the same source (see `ResolvedImports::source_map`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceRegion {
+    /// Lines of the resolved text (starting at 1)
+    pub lines: Range<usize>,
+    /// Imported file the lines come from. `None` for the resolved text itself
+    pub path: Option<PathBuf>,
+    /// Line of the source where the region starts
+    pub source_line: usize,
+}
+
+/// A Gura text with its imports replaced by the content of the imported files (see
+/// `resolve_imports`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolvedImports {
+    /// Text without import sentences
+    pub text: String,
+    /// Sources of the lines of `text`, in order
+    pub source_map: Vec<SourceRegion>,
+}
+
+impl ResolvedImports {
+    /// Gets the source of a line of the resolved text (e.g. the `line` of a `GuraError` found
+    /// parsing it): the imported file it comes from (`None` for the resolved text itself) and
+    /// its line in it.
+    ///
+    /// Returns `None` if the line is out of the text.
+    pub fn source_line(&self, line: usize) -> Option<(Option<&Path>, usize)> {
+        self.source_map
+            .iter()
+            .find(|region| region.lines.contains(&line))
+            .map(|region| {
+                (
+                    region.path.as_deref(),
+                    region.source_line + line - region.lines.start,
+                )
+            })
+    }
+
+    /// Appends the text of a source replacing its import sentences.
+    fn append(
+        &mut self,
+        text: &str,
+        path: Option<&Path>,
+        document: &SpannedDocument,
+        import_dir: Option<&Path>,
+        options: &ParserOptions,
+    ) -> Result<(), ReadError> {
+        let import_dir = import_dir.map(|dir| dir.to_string_lossy().to_string());
+        let mut last_end = 0;
+        for import in document.records.imports.iter() {
+            // Import sentences take whole lines
+            let start = text[..import.span.byte_start]
+                .rfind('\n')
+                .map_or(0, |pos| pos + 1);
+            let end = text[import.span.byte_end..]
+                .find('\n')
+                .map_or(text.len(), |pos| import.span.byte_end + pos + 1);
+            self.push(&text[last_end..start], path, line_of(text, last_end));
+
+            let imported_path = PathBuf::from(import_path(&import.path, import_dir.as_deref()));
+            let content = options
+                .platform_ref()
+                .read_to_string(&imported_path)
+                .map_err(ReadError::Io)?;
+            let imported_dir = imported_path.parent();
+            let imported =
+                parse_spanned_in(&content, imported_dir, options).map_err(ReadError::Parse)?;
+            self.append(
+                &content,
+                Some(&imported_path),
+                &imported,
+                imported_dir,
+                options,
+            )?;
+            if !self.text.is_empty() && !self.text.ends_with('\n') {
+                self.text.push('\n');
+            }
+            last_end = end;
+        }
+        self.push(&text[last_end..], path, line_of(text, last_end));
+        Ok(())
+    }
+
+    /// Appends a piece of a source that starts at `source_line`.
+    fn push(&mut self, piece: &str, path: Option<&Path>, source_line: usize) {
+        if piece.is_empty() {
+            return;
+        }
+
+        let first_line = line_of(&self.text, self.text.len());
+        let mut last_line = first_line + piece.matches('\n').count();
+        if !piece.ends_with('\n') {
+            last_line += 1;
+        }
+        self.text.push_str(piece);
+        self.source_map.push(SourceRegion {
+            lines: first_line..last_line,
+            path: path.map(Path::to_path_buf),
+            source_line,
+        });
+    }
+}
+
+/// Gets the line (starting at 1) of a byte offset of a text.
+fn line_of(text: &str, byte_offset: usize) -> usize {
+    text[..byte_offset].matches('\n').count() + 1
+}
+
+/// Replaces the import sentences of a Gura text with the content of the imported files
+/// (recursively), relative to the directory `base` (imported files import others relative to
+/// their own directories). The result is equivalent to the text, with the comments and blank
+/// lines of every file, and comes with a source map to trace every line back to its file.
+///
+/// # Errors
+///
+/// * Io - If an imported file can not be read.
+/// * Parse - If the text (along with its imported files) is not a valid Gura document.
+pub fn resolve_imports<P: AsRef<Path>>(text: &str, base: P) -> Result<ResolvedImports, ReadError> {
+    resolve_imports_with_options(text, base, &ParserOptions::default())
+}
+
+/// Like `resolve_imports`, but parsing the files with some options and reading them from the
+/// platform of the options (see `ParserOptions::platform`).
+///
+/// # Examples
+///
+/// ```
+/// use gura::{imports::resolve_imports_with_options, parser::ParserOptions, platform::FakePlatform};
+///
+/// let platform = FakePlatform::new().file("config/base.ura", "$host: \"localhost\"");
+/// let options = ParserOptions::default().platform(platform);
+/// let text = "import \"base.ura\"\nhost: $host";
+/// let resolved = resolve_imports_with_options(text, "config", &options).unwrap();
+/// assert_eq!(resolved.text, "$host: \"localhost\"\nhost: $host");
+/// ```
+///
+/// # Errors
+///
+/// * Io - If an imported file can not be read.
+/// * Parse - If the text (along with its imported files) is not a valid Gura document.
+pub fn resolve_imports_with_options<P: AsRef<Path>>(
+    text: &str,
+    base: P,
+    options: &ParserOptions,
+) -> Result<ResolvedImports, ReadError> {
+    let document =
+        parse_spanned_in(text, Some(base.as_ref()), options).map_err(ReadError::Parse)?;
+    let mut resolved = ResolvedImports::default();
+    resolved.append(text, None, &document, Some(base.as_ref()), options)?;
+    Ok(resolved)
+}
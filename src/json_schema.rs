@@ -0,0 +1,244 @@
+//! Conversion of the JSON Schemas derived by [schemars](https://docs.rs/schemars) into
+//! schemas, so the structs that deserialize a configuration are the only definition of its
+//! shape.
+//!
+//! JSON Schema types are mapped to kinds, `number` being a float (which accepts integers too).
+//! Properties (with their description, default value and whether they are required), array
+//! items, references and `additionalProperties: false` (i.e. `deny_unknown_fields`) are
+//! converted. Values that can be `null` (e.g. `Option` fields) are nullable. Values with
+//! several possible shapes (e.g. untagged enums) accept any kind.
+//!
+//! Requires the `schemars` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use gura::{parse, schema::Schema};
+//! use schemars::JsonSchema;
+//!
+//! #[derive(JsonSchema)]
+//! #[serde(deny_unknown_fields)]
+//! struct Server {
+//!     /// Name of the host
+//!     host: String,
+//!     port: Option<u16>,
+//! }
+//!
+//! let schema = Schema::for_type::<Server>();
+//! assert_eq!(schema.fields()["host"].get_description(), Some("Name of the host"));
+//! assert!(schema.validate(&parse("host: \"localhost\"\nport: null").unwrap()).is_ok());
+//!
+//! let errors = schema.validate(&parse("host: \"localhost\"\nprot: 80").unwrap()).unwrap_err();
+//! assert_eq!(errors[0].to_string(), "unknown key at prot");
+//! ```
+use crate::{
+    de::from_str_with_schema, errors::ErrorSet, parser::Kind, schema::Schema, ser::to_value,
+};
+use schemars::{
+    schema::{InstanceType, RootSchema, Schema as JsonSchemaNode, SchemaObject, SingleOrVec},
+    JsonSchema,
+};
+use serde::de::DeserializeOwned;
+
+/// Parses a Gura text and deserializes it into `T`, validating it first against the schema
+/// derived from `T` so every value that does not match is reported at once (see
+/// `de::from_str_with_schema`).
+///
+/// # Examples
+///
+/// ```
+/// use gura::json_schema::from_str_validated;
+/// use schemars::JsonSchema;
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize, JsonSchema)]
+/// struct Server {
+///     host: String,
+///     port: u16,
+///     tls: bool,
+/// }
+///
+/// let errors = from_str_validated::<Server>("host: \"localhost\"\nport: \"80\"").unwrap_err();
+/// assert_eq!(
+///     errors.to_string(),
+///     "2 errors found:\n- expected integer, found string at port (line 2)\n- missing required key at tls"
+/// );
+/// ```
+///
+/// # Errors
+///
+/// As in `de::from_str_with_schema`.
+pub fn from_str_validated<T: DeserializeOwned + JsonSchema>(text: &str) -> Result<T, ErrorSet> {
+    from_str_with_schema(text, &Schema::for_type::<T>())
+}
+
+/// Prefix of the references to definitions of the root schema
+const DEFINITIONS_PREFIX: &str = "#/definitions/";
+
+impl Schema {
+    /// Derives the schema of a type implementing `JsonSchema`.
+    pub fn for_type<T: JsonSchema>() -> Self {
+        Schema::from_json_schema(&schemars::schema_for!(T))
+    }
+
+    /// Converts a JSON Schema generated by `schemars`.
+    pub fn from_json_schema(root: &RootSchema) -> Self {
+        Converter {
+            root,
+            references: Vec::new(),
+        }
+        .convert_object(&root.schema)
+    }
+}
+
+/// Converts JSON Schemas resolving the references to the definitions of `root`
+struct Converter<'a> {
+    root: &'a RootSchema,
+    /// References being converted, to stop at recursive definitions
+    references: Vec<&'a str>,
+}
+
+impl<'a> Converter<'a> {
+    fn convert(&mut self, schema: &'a JsonSchemaNode) -> Schema {
+        match schema {
+            JsonSchemaNode::Object(object) => self.convert_object(object),
+            JsonSchemaNode::Bool(_) => Schema::any(),
+        }
+    }
+
+    fn convert_object(&mut self, object: &'a SchemaObject) -> Schema {
+        let mut schema = self.convert_shape(object);
+        if let Some(metadata) = &object.metadata {
+            if let Some(description) = &metadata.description {
+                schema = schema.description(description);
+            }
+            if let Some(default) = metadata
+                .default
+                .as_ref()
+                .and_then(|default| to_value(default).ok())
+            {
+                schema = schema.default(default);
+            }
+        }
+        schema
+    }
+
+    /// Converts everything but the metadata of a schema.
+    fn convert_shape(&mut self, object: &'a SchemaObject) -> Schema {
+        if let Some(reference) = &object.reference {
+            return self.convert_reference(reference);
+        }
+
+        if let Some(subschemas) = &object.subschemas {
+            // Fields with metadata are wrapped in an `allOf` with their actual schema
+            if let Some([schema]) = subschemas.all_of.as_deref() {
+                return self.convert(schema);
+            }
+
+            // `Option` of references (and other schemas without a type) are an `anyOf`
+            // whose only other member is `null`
+            let alternatives = subschemas.any_of.as_ref().or(subschemas.one_of.as_ref());
+            if let Some(alternatives) = alternatives {
+                let mut not_null = alternatives.iter().filter(|schema| !is_null(schema));
+                return match (not_null.next(), not_null.next()) {
+                    (Some(schema), None) => self.convert(schema).nullable(),
+                    _ => Schema::any(),
+                };
+            }
+        }
+
+        let (kind, nullable) = match &object.instance_type {
+            Some(SingleOrVec::Single(instance_type)) => (Some(kind_of(**instance_type)), false),
+            Some(SingleOrVec::Vec(instance_types)) => {
+                let mut not_null = instance_types
+                    .iter()
+                    .filter(|instance_type| **instance_type != InstanceType::Null);
+                let kind = match (not_null.next(), not_null.next()) {
+                    (Some(instance_type), None) => Some(kind_of(*instance_type)),
+                    _ => None,
+                };
+                (kind, instance_types.contains(&InstanceType::Null))
+            }
+            None => (None, false),
+        };
+
+        let mut schema = match kind {
+            Some(Kind::Object) => self.convert_fields(object),
+            Some(Kind::Array) => self.convert_items(object),
+            Some(kind) => Schema::new(kind),
+            None => Schema::any(),
+        };
+        if nullable && kind != Some(Kind::Null) {
+            schema = schema.nullable();
+        }
+        schema
+    }
+
+    fn convert_reference(&mut self, reference: &'a str) -> Schema {
+        let definition = reference
+            .strip_prefix(DEFINITIONS_PREFIX)
+            .and_then(|name| self.root.definitions.get(name));
+        match definition {
+            Some(definition) if !self.references.contains(&reference) => {
+                self.references.push(reference);
+                let schema = self.convert(definition);
+                self.references.pop();
+                schema
+            }
+            _ => Schema::any(),
+        }
+    }
+
+    fn convert_fields(&mut self, object: &'a SchemaObject) -> Schema {
+        let mut schema = Schema::object();
+        if let Some(validation) = &object.object {
+            for (key, property) in validation.properties.iter() {
+                let mut field = self.convert(property);
+                if validation.required.contains(key) {
+                    field = field.required();
+                }
+                schema = schema.field(key, field);
+            }
+
+            if let Some(JsonSchemaNode::Bool(false)) = validation.additional_properties.as_deref() {
+                schema = schema.deny_unknown_keys();
+            }
+        }
+        schema
+    }
+
+    fn convert_items(&mut self, object: &'a SchemaObject) -> Schema {
+        let items = object
+            .array
+            .as_ref()
+            .and_then(|validation| validation.items.as_ref());
+        match items {
+            Some(SingleOrVec::Single(items)) => Schema::array(self.convert(items)),
+            _ => Schema::array(Schema::any()),
+        }
+    }
+}
+
+/// Gets the kind of a JSON Schema type.
+fn kind_of(instance_type: InstanceType) -> Kind {
+    match instance_type {
+        InstanceType::Null => Kind::Null,
+        InstanceType::Boolean => Kind::Bool,
+        InstanceType::Object => Kind::Object,
+        InstanceType::Array => Kind::Array,
+        InstanceType::Number => Kind::Float,
+        InstanceType::String => Kind::String,
+        InstanceType::Integer => Kind::Integer,
+    }
+}
+
+/// Checks if a JSON Schema only accepts `null`.
+fn is_null(schema: &JsonSchemaNode) -> bool {
+    match schema {
+        JsonSchemaNode::Object(object) => matches!(
+            &object.instance_type,
+            Some(SingleOrVec::Single(instance_type)) if **instance_type == InstanceType::Null
+        ),
+        JsonSchemaNode::Bool(_) => false,
+    }
+}
@@ -26,16 +26,17 @@
 //!
 //! // Debug and Display
 //! // println!("{:#?}", parsed);
-//! // println!("{}", parsed);
+//! // println!("{}", parsed); // Compact, single line
+//! // println!("{:#}", parsed); // Indented Gura document
 //!
 //! // Access a specific field
-//! println!("Title -> {}", parsed["title"]);
+//! println!("Title -> {}", parsed["title"].raw_display());
 //!
 //! // Iterate over structure
 //! println!("\nHosts:");
 //! if let GuraType::Array(hosts) = &parsed["hosts"] {
 //!     for host in hosts.iter() {
-//!         println!("Host -> {}", *host);
+//!         println!("Host -> {}", host.raw_display());
 //!     }
 //! }
 //!
@@ -95,7 +96,7 @@
 //! // Checks parsing result
 //! match parse(&gura_string) {
 //!     Ok(parsed) => {
-//!         println!("Title -> {}", parsed["title"]);
+//!         println!("Title -> {}", parsed["title"].raw_display());
 //!     }
 //!     Err(e) => {
 //!         println!("Error: {}", e); // Error implements fmt::Display
@@ -112,18 +113,160 @@
 //!             Error::DuplicatedImportError => {
 //!                 println!("The same Gura file was imported more than once!")
 //!             }
+//!             Error::InternalError => println!("The parser has a bug!"),
 //!         }
 //!     }
 //! }
 //! ```
+//!
+//! ## Thread safety
+//!
+//! Every public type of the crate (values, errors, options, documents, etc.) is `Send + Sync`,
+//! which is checked at compile time: parsed documents can be shared across threads or async
+//! tasks behind an `Arc` (or as `SharedGuraType` values, cheap to clone) without locks, and
+//! parsers can run concurrently with the same `ParserOptions`. The only exceptions are the raw
+//! pointers of the C bindings (`ffi` feature).
+//!
+//! ## Debugging the parser
+//!
+//! With the `trace-parser` feature, the parser emits trace-level events through
+//! [`log`](https://docs.rs/log) with the target `gura::parser`: every rule of the grammar it
+//! tries (with the text ahead), whether it matched or failed, the backtracking after a failure,
+//! and the resolution of every import. They show why a document fails to parse at some point,
+//! and can be printed by any `log` logger (e.g. `env_logger` with
+//! `RUST_LOG=gura::parser=trace`) or by `tracing` subscribers through `tracing-log`. Lines and
+//! positions are the ones of the text after imports are inlined, like the ones of errors.
 
-
+#[cfg(feature = "arena")]
+pub mod arena;
+pub mod builder;
+pub mod ci;
+#[cfg(feature = "color")]
+pub mod color;
+#[cfg(feature = "compliance")]
+pub mod compliance;
+#[cfg(feature = "serde")]
+pub mod de;
+pub mod deprecation;
+#[cfg(feature = "determinism")]
+pub mod determinism;
+pub mod diff;
+pub mod document;
+pub mod editor;
 pub mod errors;
+pub mod extension;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod fingerprint;
+pub mod grammar;
+pub mod highlight;
+pub mod import_graph;
+pub mod imports;
+#[cfg(feature = "schemars")]
+pub mod json_schema;
+pub mod lint;
+#[cfg(feature = "lsp")]
+pub mod lsp;
 pub mod macros;
+pub mod merge;
+pub mod metrics;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod overlay;
 pub mod parser;
+pub mod path;
+pub mod platform;
 mod pretty_print_float;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod schema;
+pub mod secrets;
+#[cfg(feature = "serde")]
+pub mod ser;
+pub mod shared;
+pub mod span;
+pub mod spec;
+#[cfg(feature = "stats")]
+pub mod stats;
+pub mod summary;
+#[cfg(feature = "tracing")]
+pub mod tracing;
 
 // Re-exporting
+#[cfg(feature = "color")]
+pub use self::color::print_colored;
+pub use self::document::dump_split;
+pub use self::grammar::grammar;
+pub use self::highlight::highlight;
+pub use self::import_graph::import_graph;
+pub use self::imports::resolve_imports;
+pub use self::merge::merge_sources;
+#[cfg(feature = "mmap")]
+pub use self::mmap::parse_mmap;
 pub use self::parser::dump;
+pub use self::parser::dump_truncated;
+pub use self::parser::dump_value;
+pub use self::parser::from_str_at;
+pub use self::parser::normalize_key;
 pub use self::parser::parse;
+pub use self::parser::parse_file;
+pub use self::parser::parse_many;
+pub use self::parser::parse_many_with_scope;
+pub use self::parser::parse_reader;
+pub use self::parser::parse_spanned;
+pub use self::parser::parse_spanned_in;
+pub use self::parser::parse_with_options;
+#[cfg(feature = "stats")]
+pub use self::parser::parse_with_stats;
+pub use self::parser::parse_with_warnings;
+pub use self::parser::try_dump;
 pub use self::parser::GuraType;
+pub use self::parser::Kind;
+pub use self::path::Path;
+pub use self::spec::SPEC_VERSION;
+
+/// Checks at compile time that public types can be shared across threads, so that adding
+/// non-thread-safe state (e.g. an `Rc` or a `RefCell`) to any of them breaks the build.
+#[allow(dead_code)]
+fn assert_send_sync() {
+    fn check<T: Send + Sync>() {}
+
+    check::<GuraType>();
+    check::<Kind>();
+    check::<Path>();
+    check::<errors::GuraError>();
+    check::<errors::ReadError>();
+    check::<errors::EditError>();
+    check::<errors::ErrorSet>();
+    check::<parser::ParserOptions>();
+    check::<parser::DumpOptions>();
+    check::<parser::ParseWarning>();
+    check::<parser::VariableScope>();
+    check::<extension::ScalarExtensions>();
+    check::<shared::SharedGuraType>();
+    check::<document::Document>();
+    check::<overlay::Overlay>();
+    check::<span::SpannedDocument>();
+    check::<grammar::Grammar>();
+    check::<editor::EditableDocument>();
+    check::<builder::DocumentBuilder>();
+    check::<schema::Schema>();
+    check::<lint::LintConfig>();
+    check::<ci::CheckOptions>();
+    check::<ci::Report>();
+    check::<merge::MergeOptions>();
+    check::<secrets::SecretOptions>();
+    check::<errors::SecretError>();
+    check::<merge::ThreeWayMerge>();
+    check::<errors::MergeError>();
+    check::<deprecation::DeprecationMap>();
+    check::<metrics::Metrics>();
+    check::<summary::Summary>();
+    check::<import_graph::ImportGraph>();
+    check::<imports::ResolvedImports>();
+    check::<platform::FakePlatform>();
+    #[cfg(feature = "arena")]
+    check::<arena::ArenaGuraType>();
+    #[cfg(feature = "stats")]
+    check::<stats::ParseStats>();
+}
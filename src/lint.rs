@@ -0,0 +1,331 @@
+//! Style and maintenance checks of Gura documents (e.g. unused variables or deprecated keys),
+//! configured with a set of rules that can be loaded from a Gura document.
+use crate::{
+    deprecation::DeprecationMap,
+    errors::LintConfigError,
+    parser::GuraType,
+    span::{Span, SpannedDocument},
+};
+use std::fmt;
+
+/// Checks that can be enabled in a `LintConfig`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintRule {
+    /// A variable is defined but never used
+    UnusedVariable,
+    /// A deprecated key is defined
+    DeprecatedKey,
+    /// A key is nested deeper than allowed
+    MaxNesting,
+    /// A key does not follow the naming convention
+    NamingConvention,
+}
+
+impl LintRule {
+    /// Gets the name of the rule, as used in the configuration files.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintRule::UnusedVariable => "unused_variables",
+            LintRule::DeprecatedKey => "deprecated_keys",
+            LintRule::MaxNesting => "max_nesting",
+            LintRule::NamingConvention => "naming_convention",
+        }
+    }
+}
+
+/// Naming conventions of keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamingConvention {
+    /// Lowercase words separated by underscores (`max_connections`)
+    SnakeCase,
+    /// Words starting with an uppercase letter except the first one (`maxConnections`)
+    CamelCase,
+}
+
+impl NamingConvention {
+    /// Gets the convention named as in the configuration files (`snake_case` or `camelCase`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "snake_case" => Some(NamingConvention::SnakeCase),
+            "camelCase" => Some(NamingConvention::CamelCase),
+            _ => None,
+        }
+    }
+
+    /// Gets the name of the convention, as used in the configuration files.
+    pub fn name(&self) -> &'static str {
+        match self {
+            NamingConvention::SnakeCase => "snake_case",
+            NamingConvention::CamelCase => "camelCase",
+        }
+    }
+
+    /// Checks if a key follows the convention.
+    pub fn matches(&self, key: &str) -> bool {
+        let mut chars = key.chars();
+        let starts_lowercase = chars.next().is_some_and(|char| char.is_ascii_lowercase());
+        starts_lowercase
+            && match self {
+                NamingConvention::SnakeCase => {
+                    !key.contains("__")
+                        && !key.ends_with('_')
+                        && key.chars().all(|char| {
+                            char.is_ascii_lowercase() || char.is_ascii_digit() || char == '_'
+                        })
+                }
+                NamingConvention::CamelCase => chars.all(|char| char.is_ascii_alphanumeric()),
+            }
+    }
+}
+
+/// Rules checked by `lint`. By default only unused variables are reported.
+///
+/// Configurations can be loaded from Gura documents (e.g. a `.guralint.ura` file) with
+/// `from_document`.
+#[derive(Debug, Clone)]
+pub struct LintConfig {
+    unused_variables: bool,
+    deprecations: DeprecationMap,
+    max_nesting: Option<usize>,
+    naming_convention: Option<NamingConvention>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        LintConfig {
+            unused_variables: true,
+            deprecations: DeprecationMap::new(),
+            max_nesting: None,
+            naming_convention: None,
+        }
+    }
+}
+
+impl LintConfig {
+    /// Reports variables that are defined but never used. Enabled by default.
+    pub fn unused_variables(mut self, enabled: bool) -> Self {
+        self.unused_variables = enabled;
+        self
+    }
+
+    /// Reports the keys registered as deprecated.
+    pub fn deprecations(mut self, deprecations: DeprecationMap) -> Self {
+        self.deprecations = deprecations;
+        self
+    }
+
+    /// Reports keys nested in more than `max_nesting` levels (top-level keys are in the level
+    /// 1). Only the first exceeding key of every branch is reported.
+    pub fn max_nesting(mut self, max_nesting: Option<usize>) -> Self {
+        self.max_nesting = max_nesting;
+        self
+    }
+
+    /// Reports keys that do not follow a naming convention.
+    pub fn naming_convention(mut self, convention: Option<NamingConvention>) -> Self {
+        self.naming_convention = convention;
+        self
+    }
+
+    /// Loads a configuration from a Gura document. Every key is optional and missing rules
+    /// keep their default:
+    ///
+    /// ```text
+    /// unused_variables: true
+    /// max_nesting: 4
+    /// naming_convention: "snake_case" # Or "camelCase"
+    /// # Paths of deprecated keys, alone or with the path of their replacement
+    /// deprecated_keys: ["legacy_mode", ["server.hostname", "server.host"]]
+    /// ```
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{lint::LintConfig, parse};
+    ///
+    /// let config = parse("max_nesting: 2\nnaming_convention: \"kebab\"").unwrap();
+    /// let error = LintConfig::from_document(&config).unwrap_err();
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "expected \"snake_case\" or \"camelCase\" at naming_convention"
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * LintConfigError - If a key is unknown or a value is not valid.
+    pub fn from_document(document: &GuraType) -> Result<Self, LintConfigError> {
+        let values = document
+            .expect_object()
+            .map_err(|error| LintConfigError::new("", error))?;
+
+        let mut config = LintConfig::default();
+        for (key, value) in values {
+            match key.as_str() {
+                "unused_variables" => {
+                    config.unused_variables = value
+                        .expect_bool()
+                        .map_err(|error| LintConfigError::new(key, error))?;
+                }
+                "max_nesting" => {
+                    config.max_nesting = match value {
+                        GuraType::Null => None,
+                        _ => Some(value.to_usize().ok_or_else(|| {
+                            LintConfigError::new(key, "expected a non negative integer or null")
+                        })?),
+                    };
+                }
+                "naming_convention" => {
+                    config.naming_convention = match value {
+                        GuraType::Null => None,
+                        _ => Some(
+                            value
+                                .expect_str()
+                                .ok()
+                                .and_then(NamingConvention::from_name)
+                                .ok_or_else(|| {
+                                    LintConfigError::new(
+                                        key,
+                                        "expected \"snake_case\" or \"camelCase\"",
+                                    )
+                                })?,
+                        ),
+                    };
+                }
+                "deprecated_keys" => {
+                    let paths = value
+                        .expect_array()
+                        .map_err(|error| LintConfigError::new(key, error))?;
+                    for (index, path) in paths.iter().enumerate() {
+                        let (path, replacement) = deprecated_key(path).ok_or_else(|| {
+                            LintConfigError::new(
+                                &format!("{}.{}", key, index),
+                                "expected a path or an array with a path and its replacement",
+                            )
+                        })?;
+                        config.deprecations.deprecate(path, replacement);
+                    }
+                }
+                _ => return Err(LintConfigError::new(key, "unknown rule")),
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Gets the path and replacement of a deprecated key defined as `"path"` or
+/// `["path", "replacement"]`.
+fn deprecated_key(value: &GuraType) -> Option<(&str, Option<&str>)> {
+    match value {
+        GuraType::String(path) => Some((path, None)),
+        GuraType::Array(values) => match values.as_slice() {
+            [GuraType::String(path), GuraType::String(replacement)] => {
+                Some((path, Some(replacement)))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Problem found by `lint`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintWarning {
+    pub rule: LintRule,
+    pub msg: String,
+    /// Location of the problem. `None` for keys defined in imported files
+    pub span: Option<Span>,
+}
+
+/// Formats warnings as `<msg> [<rule>]`
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} [{}]", self.msg, self.rule.name())
+    }
+}
+
+/// Checks the rules of `config` on a document, returning the warnings sorted by their
+/// position in the text (warnings without span go last).
+///
+/// # Examples
+///
+/// ```
+/// use gura::{lint::{lint, LintConfig, NamingConvention}, parse_spanned};
+///
+/// let text = "$host: \"localhost\"\n$port: 8080\nserver:\n    Host: $host";
+/// let document = parse_spanned(text).unwrap();
+/// let config = LintConfig::default().naming_convention(Some(NamingConvention::SnakeCase));
+///
+/// let warnings = lint(&document, &config);
+/// assert_eq!(warnings[0].to_string(), "The variable \"port\" is never used [unused_variables]");
+/// assert_eq!(warnings[0].span.unwrap().line, 2);
+/// assert_eq!(
+///     warnings[1].to_string(),
+///     "The key \"server.Host\" does not follow the snake_case convention [naming_convention]"
+/// );
+/// ```
+pub fn lint(document: &SpannedDocument, config: &LintConfig) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    let records = &document.records;
+
+    if config.unused_variables {
+        for definition in records.variables.values() {
+            let used = records
+                .references
+                .values()
+                .any(|reference| reference.name == definition.name);
+            if !used {
+                warnings.push(LintWarning {
+                    rule: LintRule::UnusedVariable,
+                    msg: format!("The variable \"{}\" is never used", definition.name),
+                    span: Some(definition.span),
+                });
+            }
+        }
+    }
+
+    for mut deprecation in config.deprecations.check_deprecations_spanned(document) {
+        // Lint warnings report the span themselves, so it is left out of the message
+        let span = deprecation.span.take();
+        warnings.push(LintWarning {
+            rule: LintRule::DeprecatedKey,
+            span,
+            msg: deprecation.to_string(),
+        });
+    }
+
+    if let Some(max_nesting) = config.max_nesting {
+        for (path, span) in document.keys_with_spans() {
+            if path.split('.').count() == max_nesting + 1 {
+                warnings.push(LintWarning {
+                    rule: LintRule::MaxNesting,
+                    msg: format!(
+                        "The key \"{}\" is nested in more than {} levels",
+                        path, max_nesting
+                    ),
+                    span: Some(span),
+                });
+            }
+        }
+    }
+
+    if let Some(convention) = config.naming_convention {
+        for (path, span) in document.keys_with_spans() {
+            let key = path.rsplit('.').next().unwrap_or(path);
+            if !convention.matches(key) {
+                warnings.push(LintWarning {
+                    rule: LintRule::NamingConvention,
+                    msg: format!(
+                        "The key \"{}\" does not follow the {} convention",
+                        path,
+                        convention.name()
+                    ),
+                    span: Some(span),
+                });
+            }
+        }
+    }
+
+    warnings.sort_by_key(|warning| warning.span.map_or(usize::MAX, |span| span.start));
+    warnings
+}
@@ -0,0 +1,385 @@
+//! Building blocks to implement a Gura language server: document symbols, folding ranges,
+//! hover information for variables, go to definition and diagnostics. Positions follow the Language Server
+//! Protocol conventions: lines and characters are zero based and characters are counted in
+//! UTF-16 code units.
+//!
+//! All the functions work on a `SpannedDocument` (see `parse_spanned`) and the text it was
+//! parsed from. Keys and variables defined in imported files are not reported.
+//!
+//! Requires the `lsp` feature.
+use crate::{
+    errors::{Error, GuraError},
+    parser::{GuraType, Kind},
+    span::{Reference, Span, SpannedDocument},
+};
+use std::path::PathBuf;
+
+/// Position in a text document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    /// Zero based line
+    pub line: usize,
+    /// Zero based offset in the line in UTF-16 code units
+    pub character: usize,
+}
+
+/// Range in a text document, the end is exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// Translates byte offsets of a text into LSP positions and vice versa.
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+    text: &'a str,
+    /// Byte offset where every line starts
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    /// Indexes the lines of `text`.
+    pub fn new(text: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        LineIndex { text, line_starts }
+    }
+
+    /// Gets the position of a byte offset. Offsets out of the text are clamped.
+    pub fn position(&self, byte_offset: usize) -> Position {
+        let mut byte_offset = byte_offset.min(self.text.len());
+        while !self.text.is_char_boundary(byte_offset) {
+            byte_offset -= 1;
+        }
+
+        let line = self
+            .line_starts
+            .partition_point(|start| *start <= byte_offset)
+            - 1;
+        let line_start = self.line_starts[line];
+        Position {
+            line,
+            character: self.text[line_start..byte_offset].encode_utf16().count(),
+        }
+    }
+
+    /// Gets the byte offset of a position. Returns `None` if the position is out of the text.
+    pub fn offset(&self, position: Position) -> Option<usize> {
+        let line_start = *self.line_starts.get(position.line)?;
+        let line_end = self
+            .line_starts
+            .get(position.line + 1)
+            .copied()
+            .unwrap_or(self.text.len());
+
+        let mut utf16_offset = 0;
+        for (index, char) in self.text[line_start..line_end].char_indices() {
+            if utf16_offset >= position.character {
+                return Some(line_start + index);
+            }
+            utf16_offset += char.len_utf16();
+        }
+
+        if utf16_offset >= position.character {
+            Some(line_end)
+        } else {
+            None
+        }
+    }
+
+    /// Gets the range covered by a span.
+    pub fn range(&self, span: &Span) -> Range {
+        Range {
+            start: self.position(span.byte_start),
+            end: self.position(span.byte_end),
+        }
+    }
+}
+
+/// A key of the document with its location and nested keys
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentSymbol {
+    pub name: String,
+    /// Kind of the value of the key
+    pub kind: Kind,
+    /// Range of the whole pair, including the value
+    pub range: Range,
+    /// Range of the key
+    pub selection_range: Range,
+    /// Keys of the value if it is an object. For arrays, the keys of their objects
+    pub children: Vec<DocumentSymbol>,
+}
+
+/// Lines of a pair whose value takes more than one line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FoldingRange {
+    /// Zero based line where the pair starts
+    pub start_line: usize,
+    /// Zero based line where the pair ends
+    pub end_line: usize,
+}
+
+/// Information about a variable under the cursor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hover {
+    pub name: String,
+    pub value: GuraType,
+    /// Range of the definition of the variable. `None` for environment variables
+    pub definition: Option<Range>,
+    /// Range of the hovered variable name
+    pub range: Range,
+}
+
+/// Location of a definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Definition {
+    /// A range of the same document
+    Range(Range),
+    /// An imported file
+    File(PathBuf),
+}
+
+/// Severity of a diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+/// A problem of a document to be reported to the editor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub range: Range,
+    pub severity: DiagnosticSeverity,
+    /// Name of the error kind (e.g. `"DuplicatedKeyError"`)
+    pub code: String,
+    pub message: String,
+}
+
+/// Gets the tree of keys of a document.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{lsp::document_symbols, parse_spanned, Kind};
+///
+/// let text = "server:\n    port: 8080";
+/// let document = parse_spanned(text).unwrap();
+/// let symbols = document_symbols(&document, text);
+///
+/// assert_eq!(symbols[0].name, "server");
+/// assert_eq!(symbols[0].children[0].name, "port");
+/// assert_eq!(symbols[0].children[0].kind, Kind::Integer);
+/// ```
+pub fn document_symbols(document: &SpannedDocument, text: &str) -> Vec<DocumentSymbol> {
+    let line_index = LineIndex::new(text);
+    let mut symbols = Vec::new();
+    collect_symbols(document, &line_index, &document.value, "", &mut symbols);
+    symbols
+}
+
+/// Adds to `symbols` the keys of the object (or the objects of the array) located at `path`.
+fn collect_symbols(
+    document: &SpannedDocument,
+    line_index: &LineIndex,
+    value: &GuraType,
+    path: &str,
+    symbols: &mut Vec<DocumentSymbol>,
+) {
+    match value {
+        GuraType::Object(values) => {
+            for (key, child) in values {
+                let child_path = join_path(path, key);
+                if let Some(key_span) = document.span(&child_path) {
+                    let pair_span = document.pair_span(&child_path).unwrap_or(key_span);
+                    let mut children = Vec::new();
+                    collect_symbols(document, line_index, child, &child_path, &mut children);
+                    symbols.push(DocumentSymbol {
+                        name: key.clone(),
+                        kind: child.kind(),
+                        range: line_index.range(&pair_span),
+                        selection_range: line_index.range(&key_span),
+                        children,
+                    });
+                }
+            }
+        }
+        GuraType::Array(values) => {
+            for (index, element) in values.iter().enumerate() {
+                let element_path = join_path(path, &index.to_string());
+                collect_symbols(document, line_index, element, &element_path, symbols);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Gets the pairs that can be folded, that is, the ones whose value takes more than one line.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{lsp::{folding_ranges, FoldingRange}, parse_spanned};
+///
+/// let text = "title: \"Gura\"\nserver:\n    host: \"localhost\"\n    port: 8080";
+/// let document = parse_spanned(text).unwrap();
+///
+/// assert_eq!(folding_ranges(&document, text), vec![FoldingRange { start_line: 1, end_line: 3 }]);
+/// ```
+pub fn folding_ranges(document: &SpannedDocument, text: &str) -> Vec<FoldingRange> {
+    let line_index = LineIndex::new(text);
+    document
+        .records
+        .pair_spans
+        .values()
+        .filter_map(|span| {
+            let range = line_index.range(span);
+            if range.end.line > range.start.line {
+                Some(FoldingRange {
+                    start_line: range.start.line,
+                    end_line: range.end.line,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Gets the information of the variable placed at `position`, either in a definition or in
+/// a usage (as a value or inside a basic string). Returns `None` if there is no variable there.
+/// The values of environment variables are the ones read when the document was parsed (from
+/// the platform of its options).
+///
+/// # Examples
+///
+/// ```
+/// use gura::{lsp::{hover, Position}, parse_spanned};
+///
+/// let text = "$port: 8080\nserver:\n    port: $port";
+/// let document = parse_spanned(text).unwrap();
+/// let info = hover(&document, text, Position { line: 2, character: 12 }).unwrap();
+///
+/// assert_eq!(info.name, "port");
+/// assert_eq!(info.value, 8080);
+/// assert_eq!(info.definition.unwrap().start, Position { line: 0, character: 0 });
+/// ```
+pub fn hover(document: &SpannedDocument, text: &str, position: Position) -> Option<Hover> {
+    let line_index = LineIndex::new(text);
+    let offset = line_index.offset(position)?;
+
+    if let Reference::Variable { name, span, .. } = document.reference_at(offset)? {
+        let range = line_index.range(&span);
+        match document.records.variables.get(&name) {
+            Some(definition) => Some(Hover {
+                name,
+                value: definition.value.clone(),
+                definition: Some(line_index.range(&definition.span)),
+                range,
+            }),
+            // The value the parser resolved, read from the platform of its options
+            None => document
+                .records
+                .references
+                .get(&span.start)
+                .map(|reference| Hover {
+                    name,
+                    value: reference.value.clone(),
+                    definition: None,
+                    range,
+                }),
+        }
+    } else {
+        None
+    }
+}
+
+/// Gets the definition of the variable or the imported file placed at `position`.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{lsp::{goto_definition, Definition, Position, Range}, parse_spanned};
+///
+/// let text = "$port: 8080\nport: $port";
+/// let document = parse_spanned(text).unwrap();
+/// let definition = goto_definition(&document, text, Position { line: 1, character: 7 });
+///
+/// let start = Position { line: 0, character: 0 };
+/// let end = Position { line: 0, character: 5 };
+/// assert_eq!(definition, Some(Definition::Range(Range { start, end })));
+/// ```
+pub fn goto_definition(
+    document: &SpannedDocument,
+    text: &str,
+    position: Position,
+) -> Option<Definition> {
+    let line_index = LineIndex::new(text);
+    let offset = line_index.offset(position)?;
+
+    match document.reference_at(offset)? {
+        Reference::Variable { definition, .. } => {
+            definition.map(|span| Definition::Range(line_index.range(&span)))
+        }
+        Reference::Import { path, .. } => Some(Definition::File(path)),
+    }
+}
+
+/// Converts a parsing error into a diagnostic. The range covers the char where the error
+/// was found.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{lsp::{diagnostic, Position}, parse};
+///
+/// let text = "title: \"Gura\"\ntitle: \"Other\"";
+/// let error = parse(text).unwrap_err();
+/// let diagnostic = diagnostic(&error, text);
+///
+/// assert_eq!(diagnostic.code, "DuplicatedKeyError");
+/// assert_eq!(diagnostic.range.start, Position { line: 1, character: 0 });
+/// ```
+pub fn diagnostic(error: &GuraError, text: &str) -> Diagnostic {
+    let line_index = LineIndex::new(text);
+    let start = line_index.position(error.byte_pos);
+    let end = match text
+        .get(error.byte_pos..)
+        .and_then(|rest| rest.chars().next())
+    {
+        Some(char) if char != '\n' => line_index.position(error.byte_pos + char.len_utf8()),
+        _ => start,
+    };
+
+    Diagnostic {
+        range: Range { start, end },
+        severity: DiagnosticSeverity::Error,
+        code: error_code(&error.kind).to_string(),
+        message: error.msg.clone(),
+    }
+}
+
+/// Gets the name of an error kind.
+fn error_code(kind: &Error) -> &'static str {
+    match kind {
+        Error::ParseError => "ParseError",
+        Error::VariableNotDefinedError => "VariableNotDefinedError",
+        Error::InvalidIndentationError => "InvalidIndentationError",
+        Error::DuplicatedVariableError => "DuplicatedVariableError",
+        Error::DuplicatedKeyError => "DuplicatedKeyError",
+        Error::FileNotFoundError => "FileNotFoundError",
+        Error::DuplicatedImportError => "DuplicatedImportError",
+        Error::InternalError => "InternalError",
+    }
+}
+
+/// Joins a path and a key with a dot.
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
@@ -29,6 +29,12 @@ impl Attribute for isize {
     }
 }
 
+impl Attribute for GuraType {
+    fn process(&self) -> GuraType {
+        self.clone()
+    }
+}
+
 impl Attribute for &str {
     fn process(&self) -> GuraType {
         GuraType::String(self.to_string())
@@ -182,3 +188,32 @@ macro_rules! object {
         $crate::object!(@END $( $k => $crate::value!($v), )*)
     };
 }
+
+/// Gets the value located at a path of keys (and array indexes) separated by dots, written
+/// without quotes. With `as T` the value is converted into `T` (see `GuraType::extract`),
+/// returning an `ExtractError` which mentions the full path. Otherwise an
+/// `Option<&GuraType>` is returned.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{gura_get, parse};
+///
+/// let parsed = parse("services:\n    nginx:\n        hosts: [\"alpha\"]\n        port: 80").unwrap();
+///
+/// let port = gura_get!(parsed, services.nginx.port as u16).unwrap();
+/// assert_eq!(port, 80);
+/// assert_eq!(*gura_get!(parsed, services.nginx.hosts.0).unwrap(), "alpha");
+///
+/// let error = gura_get!(parsed, services.apache.port as u16).unwrap_err();
+/// assert_eq!(error.to_string(), "The path \"services.apache.port\" does not exist");
+/// ```
+#[macro_export]
+macro_rules! gura_get {
+    ($doc:expr, $first:tt $(. $segment:tt)* as $t:ty) => {
+        ($doc).extract::<$t, _>(concat!(stringify!($first) $(, ".", stringify!($segment))*))
+    };
+    ($doc:expr, $first:tt $(. $segment:tt)*) => {
+        ($doc).get_path(concat!(stringify!($first) $(, ".", stringify!($segment))*))
+    };
+}
@@ -0,0 +1,353 @@
+//! Merging of layered configurations: values of later sources override the ones of the
+//! previous sources. Also three-way merges of versions of a document, as done by version
+//! control systems (see `merge_three_way`).
+use crate::{
+    diff::{diff, Change},
+    editor::EditableDocument,
+    errors::{GuraError, MergeError},
+    parser::{dump, parse_spanned, parse_spanned_in, GuraType, ParserOptions},
+    path::Path,
+};
+use indexmap::IndexMap;
+use std::fmt;
+
+/// Defines how two arrays found at the same path are merged
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArrayMerge {
+    /// The array of the overlay replaces the base one
+    #[default]
+    Replace,
+    /// The elements of the overlay are added after the base ones
+    Append,
+}
+
+/// Options to customize `merge` and `merge_sources`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergeOptions {
+    arrays: ArrayMerge,
+    preserve_comments: bool,
+    conflict_comments: bool,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        MergeOptions {
+            arrays: ArrayMerge::default(),
+            preserve_comments: true,
+            conflict_comments: false,
+        }
+    }
+}
+
+impl MergeOptions {
+    /// Defines how arrays are merged. By default they are replaced.
+    pub fn arrays(mut self, arrays: ArrayMerge) -> Self {
+        self.arrays = arrays;
+        self
+    }
+
+    /// Keeps the comments placed right before the top-level keys in `merge_sources`. Enabled
+    /// by default.
+    pub fn preserve_comments(mut self, preserve: bool) -> Self {
+        self.preserve_comments = preserve;
+        self
+    }
+
+    /// Writes a comment describing every conflict of `merge_three_way` before the conflicting
+    /// key (or, if the key does not exist in our version, before its closest parent that does,
+    /// or at the beginning of the document). Disabled by default.
+    pub fn conflict_comments(mut self, enabled: bool) -> Self {
+        self.conflict_comments = enabled;
+        self
+    }
+}
+
+/// Merges `overlay` into `base`. Objects are merged key by key recursively, arrays are merged
+/// as defined in `options` and any other value of `overlay` replaces the base one.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{merge::{merge, MergeOptions}, object, GuraType};
+///
+/// let base = object! { server: { host: "localhost", port: 80 } };
+/// let overlay = object! { server: { port: 8080 } };
+///
+/// let merged = merge(&base, &overlay, &MergeOptions::default());
+/// assert_eq!(merged, object! { server: { host: "localhost", port: 8080 } });
+/// ```
+pub fn merge(base: &GuraType, overlay: &GuraType, options: &MergeOptions) -> GuraType {
+    match (base, overlay) {
+        (GuraType::Object(base_values), GuraType::Object(overlay_values)) => {
+            let mut values = base_values.clone();
+            for (key, overlay_value) in overlay_values {
+                let value = match base_values.get(key) {
+                    Some(base_value) => merge(base_value, overlay_value, options),
+                    None => overlay_value.clone(),
+                };
+                values.insert(key.clone(), value);
+            }
+            GuraType::Object(values)
+        }
+        (GuraType::Array(base_values), GuraType::Array(overlay_values))
+            if options.arrays == ArrayMerge::Append =>
+        {
+            GuraType::Array(base_values.iter().chain(overlay_values).cloned().collect())
+        }
+        _ => overlay.clone(),
+    }
+}
+
+/// Parses several Gura texts, merges them in order (see `merge`) and dumps the result. Useful
+/// to flatten layered configurations into a single file.
+///
+/// Sources are parsed independently, so variables are not shared between them. If comments
+/// are preserved, the unindented comment lines right before every top-level key are kept,
+/// taken from the last source that defines the key with comments. Other comments are lost.
+///
+/// # Examples
+///
+/// ```
+/// use gura::merge::{merge_sources, MergeOptions};
+///
+/// let base = "# Server settings\nserver:\n    host: \"localhost\"\n    port: 80";
+/// let production = "server:\n    port: 8080";
+///
+/// let merged = merge_sources(&[base, production], &MergeOptions::default()).unwrap();
+/// assert_eq!(merged, "# Server settings\nserver:\n    host: \"localhost\"\n    port: 8080");
+/// ```
+///
+/// # Errors
+///
+/// This function could throw any kind of error listed
+/// in [Gura specs](https://gura.netlify.app/docs/gura#standard-errors) for any of the sources.
+pub fn merge_sources(texts: &[&str], options: &MergeOptions) -> Result<String, GuraError> {
+    let mut merged = GuraType::Object(IndexMap::new());
+    let mut comments: IndexMap<String, Vec<String>> = IndexMap::new();
+
+    for text in texts {
+        let document = parse_spanned(text)?;
+        if options.preserve_comments {
+            let lines: Vec<&str> = text.lines().collect();
+            if let GuraType::Object(values) = &document.value {
+                for key in values.keys() {
+                    let key_comments = document
+                        .pair_span(key)
+                        .map(|span| comments_before(&lines, span.line))
+                        .unwrap_or_default();
+                    if !key_comments.is_empty() {
+                        comments.insert(key.clone(), key_comments);
+                    }
+                }
+            }
+        }
+        merged = merge(&merged, &document.value, options);
+    }
+
+    let mut sections = Vec::new();
+    if let GuraType::Object(values) = merged {
+        for (key, value) in values {
+            let mut section = String::new();
+            if let Some(key_comments) = comments.get(&key) {
+                for comment in key_comments {
+                    section.push_str(comment);
+                    section.push('\n');
+                }
+            }
+
+            let mut pair = IndexMap::new();
+            pair.insert(key, value);
+            section.push_str(&dump(&GuraType::Object(pair)));
+            sections.push(section);
+        }
+    }
+    Ok(sections.join("\n"))
+}
+
+/// Gets the unindented comment lines placed right before `line` (one based).
+fn comments_before(lines: &[&str], line: usize) -> Vec<String> {
+    let mut comments: Vec<String> = lines[..line.saturating_sub(1).min(lines.len())]
+        .iter()
+        .rev()
+        .map(|line| line.trim_end())
+        .take_while(|line| line.starts_with('#'))
+        .map(String::from)
+        .collect();
+    comments.reverse();
+    comments
+}
+
+/// Value changed differently by both versions of a three-way merge (see `merge_three_way`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    /// Path of the conflicting key
+    pub path: Path,
+    /// Value in the common ancestor, `None` if the key did not exist
+    pub base: Option<GuraType>,
+    /// Value in our version, `None` if the key does not exist
+    pub ours: Option<GuraType>,
+    /// Value in their version, `None` if the key does not exist
+    pub theirs: Option<GuraType>,
+}
+
+/// Formats conflicts as `path: ours <value>, theirs <value>, base <value>`, with values in
+/// their compact form (`missing` for keys that do not exist).
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = |value: &Option<GuraType>| match value {
+            Some(value) => value.to_string(),
+            None => String::from("missing"),
+        };
+        write!(
+            f,
+            "{}: ours {}, theirs {}, base {}",
+            self.path,
+            value(&self.ours),
+            value(&self.theirs),
+            value(&self.base)
+        )
+    }
+}
+
+/// Result of `merge_three_way`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreeWayMerge {
+    /// Text of our version with the changes of theirs applied
+    pub text: String,
+    /// Keys changed by both versions, which keep our value
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl ThreeWayMerge {
+    /// Checks if the versions were merged without conflicts.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Merges two versions of a document (`ours` and `theirs`) that derive from a common ancestor
+/// (`base`), key by key: the changes of theirs (as found by `diff`) are applied to the text of
+/// ours, keeping its format and comments. A change is a conflict if ours changed the same key
+/// to a different value (objects are compared key by key, any other value as a whole), or if
+/// it can not be written in our text (e.g. its object was removed). Conflicting keys keep
+/// our value, and can be annotated with comments (see `MergeOptions::conflict_comments`).
+///
+/// Documents are compared through their values, so changes of variables are applied to the
+/// keys that use them, as literal values.
+///
+/// # Examples
+///
+/// ```
+/// use gura::merge::{merge_three_way, MergeOptions};
+///
+/// let base = "# Server\nhost: \"localhost\"\nport: 80";
+/// let ours = "# Server\nhost: \"0.0.0.0\"\nport: 80";
+/// let theirs = "host: \"localhost\"\nport: 8080\ndebug: true";
+///
+/// let merged = merge_three_way(base, ours, theirs, &MergeOptions::default()).unwrap();
+/// assert!(merged.is_clean());
+/// assert_eq!(merged.text, "# Server\nhost: \"0.0.0.0\"\nport: 8080\ndebug: true\n");
+///
+/// let theirs = "host: \"127.0.0.1\"\nport: 80";
+/// let options = MergeOptions::default().conflict_comments(true);
+/// let merged = merge_three_way(base, ours, theirs, &options).unwrap();
+/// assert_eq!(merged.conflicts[0].to_string(), "host: ours \"0.0.0.0\", theirs \"127.0.0.1\", base \"localhost\"");
+/// assert!(merged.text.contains("# CONFLICT host: ours"));
+/// ```
+///
+/// # Errors
+///
+/// * Base, Ours or Theirs - If the version is not a valid Gura document, with any kind of
+///   error listed in [Gura specs](https://gura.netlify.app/docs/gura#standard-errors).
+pub fn merge_three_way(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    options: &MergeOptions,
+) -> Result<ThreeWayMerge, MergeError> {
+    merge_three_way_in(base, ours, theirs, None, options)
+}
+
+/// Like `merge_three_way`, but with the imports of the versions relative to `import_dir` (e.g.
+/// the directory of the merged file) instead of the working directory, if it is given.
+///
+/// # Errors
+///
+/// * Base, Ours or Theirs - If the version is not a valid Gura document, with any kind of
+///   error listed in [Gura specs](https://gura.netlify.app/docs/gura#standard-errors).
+pub fn merge_three_way_in(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    import_dir: Option<&std::path::Path>,
+    options: &MergeOptions,
+) -> Result<ThreeWayMerge, MergeError> {
+    let parse_version = |text| {
+        parse_spanned_in(text, import_dir, &ParserOptions::default()).map(|parsed| parsed.value)
+    };
+    let base_value = parse_version(base).map_err(MergeError::Base)?;
+    let theirs_value = parse_version(theirs).map_err(MergeError::Theirs)?;
+    let mut document = match import_dir {
+        Some(dir) => EditableDocument::parse_in(ours, dir),
+        None => EditableDocument::parse(ours),
+    }
+    .map_err(MergeError::Ours)?;
+    let ours_value = document.value().clone();
+
+    let mut conflicts = Vec::new();
+    for change in diff(&base_value, &theirs_value) {
+        let (path, base, theirs) = match change {
+            Change::Added { path, value } => (path, None, Some(value)),
+            Change::Removed { path, value } => (path, Some(value), None),
+            Change::Modified { path, old, new } => (path, Some(old), Some(new)),
+        };
+        let ours = ours_value.get_at(&path).cloned();
+        if ours == theirs {
+            continue;
+        }
+
+        let key = path.to_string();
+        let applied = ours == base
+            && match &theirs {
+                Some(value) => document.set_value(&key, value).is_ok(),
+                None => document.delete_key(&key).is_ok(),
+            };
+        if !applied {
+            conflicts.push(MergeConflict {
+                path,
+                base,
+                ours,
+                theirs,
+            });
+        }
+    }
+
+    // Comments of the conflicts whose keys have no parent in our text, placed at the beginning
+    let mut unplaced = String::new();
+    if options.conflict_comments {
+        for conflict in conflicts.iter() {
+            let comment = format!("CONFLICT {}", conflict);
+            // Comments go before the closest key that still exists
+            let mut path = Some(conflict.path.clone());
+            let placed = loop {
+                match path {
+                    Some(key) if !key.is_root() => {
+                        if document.add_comment(&key.to_string(), &comment).is_ok() {
+                            break true;
+                        }
+                        path = key.parent();
+                    }
+                    _ => break false,
+                }
+            };
+            if !placed {
+                unplaced.push_str(&format!("# {}\n", comment));
+            }
+        }
+    }
+
+    Ok(ThreeWayMerge {
+        text: unplaced + document.text(),
+        conflicts,
+    })
+}
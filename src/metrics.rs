@@ -0,0 +1,62 @@
+use crate::parser::GuraType;
+use indexmap::IndexMap;
+
+/// Size and complexity of a document (see `GuraType::metrics`), useful to reject huge or
+/// deeply nested documents supplied by users before processing them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// Number of values, the root and the objects and arrays included
+    pub nodes: usize,
+    /// Number of nested levels of objects and arrays. The values of the root are in the level 1
+    pub max_depth: usize,
+    /// Number of keys, nested ones included
+    pub keys: usize,
+    /// Number of keys (nested ones included) inside every top-level key, in the order they are
+    /// defined. Only filled when the root is an object
+    pub section_keys: IndexMap<String, usize>,
+    /// Number of bytes of all the string values (keys are not included)
+    pub string_bytes: usize,
+}
+
+impl Metrics {
+    /// Computes the metrics of a value.
+    pub(crate) fn new(value: &GuraType) -> Self {
+        let mut metrics = Metrics::default();
+        metrics.add(value, 0);
+
+        if let GuraType::Object(values) = value {
+            for (key, child) in values {
+                let mut section = Metrics::default();
+                section.add(child, 0);
+                metrics.section_keys.insert(key.clone(), section.keys);
+            }
+        }
+        metrics
+    }
+
+    fn add(&mut self, value: &GuraType, depth: usize) {
+        self.nodes += 1;
+        self.max_depth = self.max_depth.max(depth);
+
+        match value {
+            GuraType::Object(values) => {
+                self.keys += values.len();
+                for child in values.values() {
+                    self.add(child, depth + 1);
+                }
+            }
+            GuraType::Array(values) => {
+                for item in values {
+                    self.add(item, depth + 1);
+                }
+            }
+            GuraType::String(text) => self.string_bytes += text.len(),
+            GuraType::Extension(_, inner) => {
+                if let GuraType::String(text) = &**inner {
+                    self.string_bytes += text.len();
+                }
+            }
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Parsing of files mapped into memory, which skips reading the whole file into a `String`
+//! before parsing it.
+//!
+//! This does not reduce the memory needed to parse large documents much: the parser still
+//! copies the text into its own list of grapheme clusters, so the peak memory grows with the
+//! size of the input as in `parse_file`.
+//!
+//! Requires the `mmap` feature.
+use crate::{
+    errors::ReadError,
+    parser::{parse_file_text, ParserOptions},
+    GuraType,
+};
+use memmap2::Mmap;
+use std::{fs::File, path::Path};
+
+/// Parses a Gura file mapping it into memory. As in `parse_file` (with the default options),
+/// imports are relative to the directory of the file.
+///
+/// The file must not be modified while it is being parsed, as its mapped content would change
+/// too. Only files of the operating system can be mapped, so documents read from a custom
+/// `Platform` must be parsed with `parse_file` instead.
+///
+/// # Examples
+///
+/// ```
+/// use gura::parse_mmap;
+/// use std::fs;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("big.ura");
+/// fs::write(&path, "server:\n    port: 8080").unwrap();
+///
+/// let parsed = parse_mmap(&path).unwrap();
+/// assert_eq!(parsed["server"]["port"], 8080);
+/// ```
+///
+/// # Errors
+///
+/// * Io - If the file can not be opened or mapped.
+/// * InvalidUtf8 - If the file is not valid UTF-8.
+/// * Parse - If the content is not a valid Gura document.
+pub fn parse_mmap<P: AsRef<Path>>(path: P) -> Result<GuraType, ReadError> {
+    let path = path.as_ref();
+    let options = ParserOptions::default();
+    let file = File::open(path).map_err(ReadError::Io)?;
+    // Empty files can not be mapped
+    if file.metadata().map_err(ReadError::Io)?.len() == 0 {
+        return parse_file_text("", path, &options).map_err(ReadError::Parse);
+    }
+
+    // Safety: the file is only read and callers are warned to not modify it meanwhile
+    let map = unsafe { Mmap::map(&file) }.map_err(ReadError::Io)?;
+    let text =
+        std::str::from_utf8(&map).map_err(|error| ReadError::InvalidUtf8(error.valid_up_to()))?;
+    parse_file_text(text, path, &options).map_err(ReadError::Parse)
+}
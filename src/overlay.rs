@@ -0,0 +1,254 @@
+//! Copy-on-write modifications over an immutable document, to specialize a shared base
+//! configuration (e.g. per request or per tenant) without cloning it.
+use crate::parser::GuraType;
+use indexmap::IndexMap;
+use std::{borrow::Cow, sync::Arc};
+
+/// Modification of a key
+#[derive(Debug, Clone, PartialEq)]
+enum Change {
+    /// The key has this value
+    Set(GuraType),
+    /// The key was removed
+    Removed,
+    /// Some keys of the object placed at the key in the base were modified
+    Nested(IndexMap<String, Change>),
+}
+
+/// Modifications layered over a base document. Paths are keys separated by dots. Untouched
+/// values are read from the base, which is never copied until `materialize` is called.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{object, overlay::Overlay, GuraType};
+/// use std::sync::Arc;
+///
+/// let base = Arc::new(object! { server: { host: "localhost", port: 80 }, debug: false });
+///
+/// let mut tenant = Overlay::new(Arc::clone(&base));
+/// tenant.set("server.port", GuraType::Integer(8080));
+/// tenant.remove("debug");
+///
+/// assert_eq!(*tenant.get("server.host").unwrap(), "localhost");
+/// assert_eq!(*tenant.get("server.port").unwrap(), 8080);
+/// assert_eq!(tenant.materialize(), object! { server: { host: "localhost", port: 8080 } });
+/// assert_eq!(base["server"]["port"], 80);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overlay {
+    base: Arc<GuraType>,
+    changes: IndexMap<String, Change>,
+}
+
+impl Overlay {
+    /// Creates an overlay without modifications.
+    pub fn new(base: Arc<GuraType>) -> Self {
+        Overlay {
+            base,
+            changes: IndexMap::new(),
+        }
+    }
+
+    /// Gets the base document.
+    pub fn base(&self) -> &Arc<GuraType> {
+        &self.base
+    }
+
+    /// Checks if there are no modifications.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Sets the value of the key located at `path`. Missing objects of the path are created,
+    /// and values of the path that are not objects are replaced by one.
+    pub fn set(&mut self, path: &str, value: GuraType) {
+        let segments: Vec<&str> = path.split('.').collect();
+        set_change(&mut self.changes, Some(&self.base), &segments, value);
+    }
+
+    /// Removes the key located at `path`. Does nothing if it does not exist.
+    pub fn remove(&mut self, path: &str) {
+        if self.get(path).is_some() {
+            let segments: Vec<&str> = path.split('.').collect();
+            remove_change(&mut self.changes, Some(&self.base), &segments);
+        }
+    }
+
+    /// Gets the value located at `path`, considering the modifications. Values are borrowed
+    /// unless they are objects with modified keys, which are generated.
+    pub fn get(&self, path: &str) -> Option<Cow<'_, GuraType>> {
+        let mut base = Some(self.base.as_ref());
+        let mut changes = Some(&self.changes);
+        let mut segments = path.split('.');
+
+        while let Some(segment) = segments.next() {
+            base = child(base, segment);
+
+            match changes.and_then(|changes| changes.get(segment)) {
+                Some(Change::Set(value)) => {
+                    let rest: Vec<&str> = segments.collect();
+                    return if rest.is_empty() {
+                        Some(Cow::Borrowed(value))
+                    } else {
+                        value.get_path(&rest.join(".")).map(Cow::Borrowed)
+                    };
+                }
+                Some(Change::Removed) => return None,
+                Some(Change::Nested(nested)) => changes = Some(nested),
+                None => changes = None,
+            }
+        }
+
+        match (base, changes) {
+            (Some(base), Some(changes)) => Some(Cow::Owned(apply(base, changes))),
+            (base, _) => base.map(Cow::Borrowed),
+        }
+    }
+
+    /// Generates the final document, applying the modifications to a copy of the base.
+    pub fn materialize(&self) -> GuraType {
+        apply(&self.base, &self.changes)
+    }
+}
+
+/// Gets the value of a key of an object.
+fn child<'a>(value: Option<&'a GuraType>, key: &str) -> Option<&'a GuraType> {
+    match value {
+        Some(GuraType::Object(values)) => values.get(key),
+        _ => None,
+    }
+}
+
+/// Sets a value at the path given by `segments` in the changes of the object `base`.
+fn set_change(
+    changes: &mut IndexMap<String, Change>,
+    base: Option<&GuraType>,
+    segments: &[&str],
+    value: GuraType,
+) {
+    let (key, rest) = match segments.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+    if rest.is_empty() {
+        changes.insert(key.to_string(), Change::Set(value));
+        return;
+    }
+
+    let base_child = child(base, key);
+    let change = changes
+        .entry(key.to_string())
+        .or_insert_with(|| match base_child {
+            Some(GuraType::Object(_)) => Change::Nested(IndexMap::new()),
+            _ => Change::Set(GuraType::Object(IndexMap::new())),
+        });
+    if let Change::Removed = change {
+        *change = Change::Set(GuraType::Object(IndexMap::new()));
+    }
+
+    match change {
+        Change::Nested(nested) => set_change(nested, base_child, rest, value),
+        Change::Set(current) => set_value(current, rest, value),
+        Change::Removed => (),
+    }
+}
+
+/// Sets a value at the path given by `segments` in an owned value.
+fn set_value(target: &mut GuraType, segments: &[&str], value: GuraType) {
+    if !matches!(target, GuraType::Object(_)) {
+        *target = GuraType::Object(IndexMap::new());
+    }
+
+    if let (GuraType::Object(values), Some((key, rest))) = (target, segments.split_first()) {
+        if rest.is_empty() {
+            values.insert(key.to_string(), value);
+        } else {
+            let child = values.entry(key.to_string()).or_insert(GuraType::Null);
+            set_value(child, rest, value);
+        }
+    }
+}
+
+/// Removes the key at the path given by `segments` from the changes of the object `base`.
+/// The path must exist.
+fn remove_change(
+    changes: &mut IndexMap<String, Change>,
+    base: Option<&GuraType>,
+    segments: &[&str],
+) {
+    let (key, rest) = match segments.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+    let base_child = child(base, key);
+    if rest.is_empty() {
+        if base_child.is_some() {
+            changes.insert(key.to_string(), Change::Removed);
+        } else {
+            changes.shift_remove(*key);
+        }
+        return;
+    }
+
+    let change = changes
+        .entry(key.to_string())
+        .or_insert_with(|| Change::Nested(IndexMap::new()));
+    match change {
+        Change::Nested(nested) => remove_change(nested, base_child, rest),
+        Change::Set(current) => {
+            let (last, parents) = rest.split_last().unwrap();
+            let parent = parents
+                .iter()
+                .try_fold(current, |value, parent| match value {
+                    GuraType::Object(values) => values.get_mut(*parent),
+                    _ => None,
+                });
+            if let Some(GuraType::Object(values)) = parent {
+                values.shift_remove(*last);
+            }
+        }
+        Change::Removed => (),
+    }
+}
+
+/// Applies the changes of an object to a base value.
+fn apply(base: &GuraType, changes: &IndexMap<String, Change>) -> GuraType {
+    let empty = IndexMap::new();
+    let base_values = match base {
+        GuraType::Object(values) => values,
+        _ => &empty,
+    };
+
+    let mut values = IndexMap::new();
+    for (key, value) in base_values {
+        match changes.get(key) {
+            Some(Change::Set(value)) => {
+                values.insert(key.clone(), value.clone());
+            }
+            Some(Change::Removed) => (),
+            Some(Change::Nested(nested)) => {
+                values.insert(key.clone(), apply(value, nested));
+            }
+            None => {
+                values.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    for (key, change) in changes {
+        if base_values.contains_key(key) {
+            continue;
+        }
+        match change {
+            Change::Set(value) => {
+                values.insert(key.clone(), value.clone());
+            }
+            Change::Nested(nested) => {
+                values.insert(key.clone(), apply(&GuraType::Null, nested));
+            }
+            Change::Removed => (),
+        }
+    }
+    GuraType::Object(values)
+}
@@ -1,19 +1,40 @@
-use crate::errors::{Error, GuraError, ValueError};
+#[cfg(feature = "arena")]
+use crate::arena::{ArenaGuraType, Bump};
+use crate::errors::{
+    ArrayConversionError, DumpError, DumpErrorKind, Error, ExtractError, GuraError,
+    IntegerConversionError, ReadError, TypeError,
+};
+use crate::extension::ScalarExtensions;
+use crate::fingerprint;
+use crate::grammar::Grammar;
+use crate::metrics::Metrics;
+use crate::path::{Path as ValuePath, Segment};
+use crate::platform::{Platform, PlatformHandle};
 use crate::pretty_print_float::PrettyPrintFloatWithFallback;
+use crate::shared::SharedGuraType;
+use crate::span::{
+    ImportReference, Span, SpanRecords, SpannedDocument, Trivia, VariableDefinition,
+    VariableReference,
+};
+use crate::spec::{self, ComplianceLevel, ConformanceReport, Extension, SpecVersion};
+#[cfg(feature = "stats")]
+use crate::stats::ParseStats;
+use crate::summary::Summary;
 use indexmap::IndexMap;
 use itertools::Itertools;
 use lazy_static::lazy_static;
+use regex::Regex;
+#[cfg(feature = "stats")]
+use std::time::Instant;
 use std::{
     borrow::Cow,
     cmp::Ordering,
     collections::{HashMap, HashSet},
-    env,
-    f64::{INFINITY, NAN, NEG_INFINITY},
+    convert::TryFrom,
     fmt::{self, Write as _},
-    fs,
+    io::BufRead,
     ops::Index,
-    path::Path,
-    usize,
+    path::{Path, PathBuf},
 };
 use unicode_segmentation::UnicodeSegmentation;
 
@@ -61,6 +82,13 @@ lazy_static! {
         m.insert("\\", "\\\\");
         m
     };
+
+    /// Lines with a pair whose simple value (a string, number, boolean, etc.) is followed by
+    /// more text
+    static ref TEXT_AFTER_VALUE: Regex = Regex::new(
+        r#"^ *[\w-]+: *((\[[^\[\]]*\]|"([^"\\]|\\.)*"|'[^']*') *[^\s#]|[^\s"'\[\],#]+( *,| +[^\s#]))"#
+    )
+    .unwrap();
 }
 
 // Indentation of 4 spaces
@@ -74,7 +102,16 @@ enum NumberType {
 }
 
 type RuleResult = Result<GuraType, GuraError>;
-type Rules = Vec<Box<dyn Fn(&mut Input) -> RuleResult>>;
+type Rule = fn(&mut Input) -> RuleResult;
+
+/// Emits a trace-level event with the target `gura::parser`. Only enabled with the
+/// `trace-parser` feature, so arguments are not evaluated otherwise.
+macro_rules! trace_parser {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "trace-parser")]
+        log::trace!(target: "gura::parser", $($arg)+)
+    };
+}
 
 impl Eq for VariableValueType {}
 
@@ -103,6 +140,407 @@ enum VariableValueType {
     Float(f64),
 }
 
+impl VariableValueType {
+    /// Gets the value as a `GuraType`.
+    fn to_gura_type(&self) -> GuraType {
+        match self {
+            VariableValueType::String(value) => GuraType::String(value.clone()),
+            VariableValueType::Integer(value) => GuraType::Integer(*value),
+            VariableValueType::Float(value) => GuraType::Float(*value),
+        }
+    }
+}
+
+/// Defines how variables are shared between the sources parsed with `parse_many_with_scope`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VariableScope {
+    /// All the sources share the same variables
+    Shared,
+    /// Every source has its own variables, so they can define variables with the same name
+    Isolated,
+    /// Like `Isolated` but the listed variables are also visible from the following sources
+    Export(Vec<String>),
+}
+
+/// Options to customize the parsing of a Gura text (see `parse_with_options`).
+///
+/// # Examples
+///
+/// ```
+/// use gura::{parse_with_options, parser::ParserOptions};
+///
+/// let options = ParserOptions::default().capture_line_content(true);
+/// let error = parse_with_options("some_invalid: $non_existent_var", &options).unwrap_err();
+/// assert_eq!(error.line_content.unwrap(), "some_invalid: $non_existent_var");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParserOptions {
+    capture_line_content: bool,
+    lenient_arrays: bool,
+    /// Number of spaces of every indentation level. `None` for the 4 of the specification
+    indent_unit: Option<usize>,
+    trim_final_newline: bool,
+    raw_strings: bool,
+    spec_version: SpecVersion,
+    scalar_extensions: ScalarExtensions,
+    deduplicate_imports: bool,
+    builtin_variables: bool,
+    time_variables: bool,
+    normalize_newlines: bool,
+    hex_escapes: bool,
+    platform: PlatformHandle,
+    /// Only the behavior of the specification is allowed (see `conformance_strict`)
+    strict: bool,
+}
+
+impl ParserOptions {
+    /// Stores the text of the offending line in `GuraError::line_content`. Disabled by default
+    /// to avoid copies.
+    pub fn capture_line_content(mut self, capture: bool) -> Self {
+        self.capture_line_content = capture;
+        self
+    }
+
+    /// Accepts array elements separated by new lines without a comma, reporting a warning for
+    /// every missing comma (see `parse_with_warnings`). Disabled by default, as the
+    /// specification requires commas.
+    ///
+    /// ```
+    /// use gura::{parse_with_warnings, parser::ParserOptions};
+    ///
+    /// let options = ParserOptions::default().lenient_arrays(true);
+    /// let (parsed, warnings) = parse_with_warnings("ports: [\n    80\n    443\n]", &options).unwrap();
+    /// assert_eq!(parsed["ports"].to_string(), "[80, 443]");
+    /// assert_eq!(warnings[0].to_string(), "Missing \",\" after array element at line 2 (text position = 15)");
+    /// ```
+    pub fn lenient_arrays(mut self, lenient: bool) -> Self {
+        self.lenient_arrays = lenient;
+        self
+    }
+
+    /// Sets the number of spaces of every indentation level (at least 1), e.g. 2 for teams that
+    /// indent with 2 spaces. The specification requires 4, so texts parsed with another unit are
+    /// not portable to other Gura parsers and must be dumped with the same unit (see
+    /// `DumpOptions::indent_unit`).
+    ///
+    /// ```
+    /// use gura::{parse_with_options, parser::ParserOptions};
+    ///
+    /// let options = ParserOptions::default().indent_unit(2);
+    /// let parsed = parse_with_options("server:\n  host: \"localhost\"", &options).unwrap();
+    /// assert_eq!(parsed["server"]["host"], "localhost");
+    /// ```
+    pub fn indent_unit(mut self, spaces: usize) -> Self {
+        self.indent_unit = Some(spaces.max(1));
+        self
+    }
+
+    /// Removes the new line right before the closing delimiter of multiline strings, as the one
+    /// right after the opening delimiter is. Disabled by default, as the specification preserves
+    /// it. New lines written as escape sequences (`\n`) are always preserved.
+    ///
+    /// ```
+    /// use gura::{parse_with_options, parser::ParserOptions};
+    ///
+    /// let text = "text: \"\"\"\nRoses are red\n\"\"\"";
+    /// let options = ParserOptions::default().trim_final_newline(true);
+    /// assert_eq!(parse_with_options(text, &options).unwrap()["text"], "Roses are red");
+    /// assert_eq!(gura::parse(text).unwrap()["text"], "Roses are red\n");
+    /// ```
+    pub fn trim_final_newline(mut self, trim: bool) -> Self {
+        self.trim_final_newline = trim;
+        self
+    }
+
+    /// Keeps the references to variables inside basic strings (e.g. `"$host:80"`) as they are
+    /// written instead of replacing them with the values of the variables, which do not need
+    /// to be defined. Useful for tools that move or copy configuration text around. Escape
+    /// sequences, including `\$`, are still processed. Disabled by default.
+    ///
+    /// ```
+    /// use gura::{parse_with_options, parser::ParserOptions};
+    ///
+    /// let options = ParserOptions::default().raw_strings(true);
+    /// let parsed = parse_with_options("url: \"http://$host:$port\"", &options).unwrap();
+    /// assert_eq!(parsed["url"], "http://$host:$port");
+    /// ```
+    pub fn raw_strings(mut self, raw: bool) -> Self {
+        self.raw_strings = raw;
+        self
+    }
+
+    /// Parses custom scalars (e.g. `@duration 5s`) with the registered recognizers (see the
+    /// `extension` module). Without recognizers, which is the default, `@` is a syntax error
+    /// as the specification requires.
+    pub fn scalar_extensions(mut self, extensions: ScalarExtensions) -> Self {
+        self.scalar_extensions = extensions;
+        self
+    }
+
+    /// Skips imports of files that were already imported, or whose content is the same as the
+    /// one of an already imported file (e.g. copies or hard links), instead of raising
+    /// `DuplicatedImportError`. Useful when several files import a common one. Disabled by
+    /// default, as the specification forbids importing a file more than once.
+    pub fn deduplicate_imports(mut self, deduplicate: bool) -> Self {
+        self.deduplicate_imports = deduplicate;
+        self
+    }
+
+    /// Defines the variables `$__file__` and `$__dir__` as the path of the file being parsed
+    /// and its directory (`.` for files in the current directory), so documents can refer to
+    /// files next to them (e.g. `cert: "$__dir__/tls/cert.pem"`). Imported files get their own
+    /// paths. They are only defined in files (see `parse_file`) and variables of the document
+    /// with the same names take precedence. Disabled by default, as they are not part of the
+    /// specification.
+    ///
+    /// ```
+    /// use gura::{parse_file, parser::ParserOptions};
+    /// use std::fs;
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("app.ura");
+    /// fs::write(&path, "cert: \"$__dir__/cert.pem\"").unwrap();
+    ///
+    /// let options = ParserOptions::default().builtin_variables(true);
+    /// let parsed = parse_file(&path, &options).unwrap();
+    /// assert_eq!(parsed["cert"], format!("{}/cert.pem", dir.path().display()));
+    /// ```
+    pub fn builtin_variables(mut self, enabled: bool) -> Self {
+        self.builtin_variables = enabled;
+        self
+    }
+
+    /// Defines the variables `$__now_iso__` (e.g. `2024-02-29T13:45:00Z`), `$__now_unix__`
+    /// (seconds since the Unix epoch, as an integer) and `$__today__` (e.g. `2024-02-29`) with
+    /// the current UTC time, read once per parsing. Useful for generated snapshots and
+    /// templates, but parsing the same text twice gives different results. Variables of the
+    /// document with the same names take precedence. Disabled by default, as they are not part
+    /// of the specification.
+    ///
+    /// ```
+    /// use gura::{parse_with_options, parser::ParserOptions};
+    ///
+    /// let options = ParserOptions::default().time_variables(true);
+    /// let parsed = parse_with_options("generated_at: $__now_iso__", &options).unwrap();
+    /// assert!(parsed["generated_at"].to_string().ends_with("Z\""));
+    /// ```
+    pub fn time_variables(mut self, enabled: bool) -> Self {
+        self.time_variables = enabled;
+        self
+    }
+
+    /// Reads the new lines of multiline strings written as `\r\n` (e.g. in files checked out
+    /// on Windows) as `\n`, so values are the same in every platform. New lines written as
+    /// escape sequences (`\r\n`) are always preserved. Disabled by default, as the
+    /// specification preserves them.
+    ///
+    /// ```
+    /// use gura::{parse_with_options, parser::ParserOptions};
+    ///
+    /// let text = "text: '''\r\nRoses are red\r\nViolets are blue'''";
+    /// let options = ParserOptions::default().normalize_newlines(true);
+    /// assert_eq!(parse_with_options(text, &options).unwrap()["text"], "Roses are red\nViolets are blue");
+    /// assert_eq!(gura::parse(text).unwrap()["text"], "Roses are red\r\nViolets are blue");
+    /// ```
+    pub fn normalize_newlines(mut self, normalize: bool) -> Self {
+        self.normalize_newlines = normalize;
+        self
+    }
+
+    /// Reads the escape sequences `\xHH` of basic strings as the code point of their two
+    /// hexadecimal digits (e.g. `"\x41"` is `"A"`), as `\uHHHH` and `\UHHHHHHHH` are.
+    /// Disabled by default, as the specification does not define them: `\x` is then kept as
+    /// it is written, like any other unknown escape sequence (e.g. `"C:\xfiles"`).
+    ///
+    /// ```
+    /// use gura::{parse_with_options, parser::ParserOptions};
+    ///
+    /// let options = ParserOptions::default().hex_escapes(true);
+    /// assert_eq!(parse_with_options(r#"letter: "\x41""#, &options).unwrap()["letter"], "A");
+    /// assert_eq!(gura::parse(r#"letter: "\x41""#).unwrap()["letter"], "\\x41");
+    /// ```
+    pub fn hex_escapes(mut self, enabled: bool) -> Self {
+        self.hex_escapes = enabled;
+        self
+    }
+
+    /// Sets the platform imported files, environment variables and the current time are read
+    /// from (see the `platform` module). Defaults to the operating system.
+    ///
+    /// ```
+    /// use gura::{parse_with_options, parser::ParserOptions, platform::FakePlatform};
+    ///
+    /// let options = ParserOptions::default()
+    ///     .time_variables(true)
+    ///     .platform(FakePlatform::new().time(86400));
+    /// let parsed = parse_with_options("generated_on: $__today__", &options).unwrap();
+    /// assert_eq!(parsed["generated_on"], "1970-01-02");
+    /// ```
+    pub fn platform<P: Platform + 'static>(mut self, platform: P) -> Self {
+        self.platform = PlatformHandle::new(platform);
+        self
+    }
+
+    /// Gets the platform files and environment variables are read from.
+    pub(crate) fn platform_ref(&self) -> &dyn Platform {
+        &*self.platform
+    }
+
+    /// Gets the number of spaces of every indentation level.
+    fn indentation_unit(&self) -> usize {
+        self.indent_unit.unwrap_or(INDENT.len())
+    }
+
+    /// Sets the version of the specification to follow. Defaults to the latest one
+    /// (`SpecVersion::LATEST`).
+    pub fn spec_version(mut self, version: SpecVersion) -> Self {
+        self.spec_version = version;
+        self
+    }
+
+    /// Only allows the behavior defined by the specification: every extension is ignored, even
+    /// the ones enabled after calling it (e.g. `\xHH` escape sequences are kept as they are
+    /// written), and integers that do not fit in 64 bits are errors instead of big integers.
+    /// Useful for libraries that promise their documents are portable to other Gura parsers
+    /// (see `explain`, which lists every behavior that deviates from it).
+    ///
+    /// ```
+    /// use gura::{parse_with_options, parser::ParserOptions};
+    ///
+    /// let options = ParserOptions::default().conformance_strict().lenient_arrays(true);
+    /// assert!(options.explain().is_conformant());
+    /// assert!(parse_with_options("ports: [\n    80\n    443\n]", &options).is_err());
+    /// assert!(parse_with_options("big: 99999999999999999999", &options).is_err());
+    /// ```
+    pub fn conformance_strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Gets the options the parser applies, which in strict mode are the default ones without
+    /// extensions.
+    fn effective(&self) -> ParserOptions {
+        if self.strict {
+            ParserOptions {
+                spec_version: self.spec_version,
+                platform: self.platform.clone(),
+                strict: true,
+                ..ParserOptions::default()
+            }
+        } else {
+            self.clone()
+        }
+    }
+
+    /// Reports the behaviors of these options that deviate from the specification, with the
+    /// option that enables every one of them.
+    ///
+    /// ```
+    /// use gura::parser::ParserOptions;
+    ///
+    /// let report = ParserOptions::default().lenient_arrays(true).explain();
+    /// assert!(!report.is_conformant());
+    /// assert_eq!(
+    ///     report.to_string().lines().last().unwrap(),
+    ///     "- lenient_arrays: array elements separated by new lines without a comma are accepted"
+    /// );
+    /// ```
+    pub fn explain(&self) -> ConformanceReport {
+        let compliance = self.compliance();
+        ConformanceReport {
+            spec_version: compliance.spec_version,
+            deviations: compliance.extensions,
+        }
+    }
+
+    /// Gets the grammar of the syntax these options accept, with the rules changed by the
+    /// enabled extensions (see `grammar`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::parser::ParserOptions;
+    ///
+    /// let grammar = ParserOptions::default().indent_unit(2).grammar();
+    /// assert_eq!(grammar.rule("indent").unwrap().definition, "\"  \"");
+    /// ```
+    pub fn grammar(&self) -> Grammar {
+        Grammar::new(&self.compliance(), self.effective().indentation_unit())
+    }
+
+    /// Reports the version of the specification and the extensions these options parse with.
+    pub fn compliance(&self) -> ComplianceLevel {
+        let options = self.effective();
+        let mut extensions = Vec::new();
+        if !options.strict {
+            extensions.push(Extension::BigIntegers);
+        }
+        if options.capture_line_content {
+            extensions.push(Extension::LineContent);
+        }
+        if options.lenient_arrays {
+            extensions.push(Extension::LenientArrays);
+        }
+        if options.indentation_unit() != INDENT.len() {
+            extensions.push(Extension::IndentUnit);
+        }
+        if options.trim_final_newline {
+            extensions.push(Extension::TrimFinalNewline);
+        }
+        if options.raw_strings {
+            extensions.push(Extension::RawStrings);
+        }
+        if !options.scalar_extensions.is_empty() {
+            extensions.push(Extension::ScalarExtensions);
+        }
+        if options.deduplicate_imports {
+            extensions.push(Extension::DeduplicatedImports);
+        }
+        if options.builtin_variables {
+            extensions.push(Extension::BuiltinVariables);
+        }
+        if options.time_variables {
+            extensions.push(Extension::TimeVariables);
+        }
+        if options.normalize_newlines {
+            extensions.push(Extension::NormalizedNewlines);
+        }
+        if options.hex_escapes {
+            extensions.push(Extension::HexEscapes);
+        }
+        ComplianceLevel {
+            spec_version: options.spec_version,
+            extensions,
+            features: spec::enabled_features(),
+        }
+    }
+}
+
+/// Problem found in a text which, unlike a `GuraError`, did not prevent parsing it (e.g. a
+/// missing comma accepted by `ParserOptions::lenient_arrays`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// Position of the warning as an index of grapheme clusters
+    pub pos: isize,
+    pub line: usize,
+    pub msg: String,
+    /// Position of the warning as a byte offset
+    pub byte_pos: usize,
+    /// Position of the warning as a UTF-16 code unit offset (as used by LSP)
+    pub utf16_pos: usize,
+}
+
+/// Same format as `GuraError`'s `Display`
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {} (text position = {})",
+            self.msg, self.line, self.pos
+        )
+    }
+}
+
 /// Data types to be returned by match expression methods.
 #[derive(Debug, Clone, PartialEq)]
 pub enum GuraType {
@@ -137,15 +575,86 @@ pub enum GuraType {
     Float(f64),
     /// List of Gura values.
     Array(Vec<GuraType>),
+    /// Custom scalar (e.g. `@duration 5s`) with its tag and the value returned by the
+    /// recognizer registered for it (see `ParserOptions::scalar_extensions`).
+    Extension(String, Box<GuraType>),
     /// Spaces or new line characters (intended to be used internally).
     WsOrNewLine,
     /// Indicates the ending of an object (intended to be used internally).
     BreakParent,
 }
 
+/// Lightweight discriminant of `GuraType` variants. Useful to report type mismatches
+/// generically (e.g. "expected integer, found string").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    Null,
+    Bool,
+    String,
+    Integer,
+    BigInteger,
+    Float,
+    Array,
+    Object,
+    /// Custom scalar parsed by a registered extension
+    Extension,
+    /// Any of the variants intended to be used internally by the parser.
+    Internal,
+}
+
+impl Kind {
+    /// Gets a human readable name of the kind.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kind::Null => "null",
+            Kind::Bool => "bool",
+            Kind::String => "string",
+            Kind::Integer => "integer",
+            Kind::BigInteger => "big integer",
+            Kind::Float => "float",
+            Kind::Array => "array",
+            Kind::Object => "object",
+            Kind::Extension => "extension",
+            Kind::Internal => "internal",
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Formats the value in a compact, single line representation (`{}`) or, using the
+/// alternate flag (`{:#}`), as a fully indented Gura document (the same as `dump`).
+///
+/// ```
+/// use gura::{object, GuraType};
+///
+/// let object = object! { name: "Gura", nested: { port: 80 } };
+/// assert_eq!(format!("{}", object), r#"{name: "Gura", nested: {port: 80}}"#);
+/// assert_eq!(format!("{:#}", object), "name: \"Gura\"\nnested:\n    port: 80");
+/// ```
 impl fmt::Display for GuraType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str(&dump(self))
+        if f.alternate() {
+            f.write_str(&dump(self))
+        } else {
+            f.write_str(&dump_compact(self))
+        }
+    }
+}
+
+/// Wrapper returned by [`GuraType::raw_display`] to format values in their natural form.
+pub struct RawDisplay<'a>(&'a GuraType);
+
+impl<'a> fmt::Display for RawDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            GuraType::String(value) => f.write_str(value),
+            other => fmt::Display::fmt(other, f),
+        }
     }
 }
 
@@ -198,11 +707,7 @@ impl PartialEq<GuraType> for isize {
 
 impl PartialEq<i32> for GuraType {
     fn eq(&self, other: &i32) -> bool {
-        match self {
-            GuraType::Integer(value) => (*value as i32) == *other,
-            GuraType::BigInteger(value) => (*value as i32) == *other,
-            _ => false,
-        }
+        self.to_i32() == Some(*other)
     }
 }
 
@@ -214,11 +719,7 @@ impl PartialEq<GuraType> for i32 {
 
 impl PartialEq<i64> for GuraType {
     fn eq(&self, other: &i64) -> bool {
-        match self {
-            GuraType::Integer(value) => (*value as i64) == *other,
-            GuraType::BigInteger(value) => (*value as i64) == *other,
-            _ => false,
-        }
+        self.to_i64() == Some(*other)
     }
 }
 
@@ -230,11 +731,7 @@ impl PartialEq<GuraType> for i64 {
 
 impl PartialEq<i128> for GuraType {
     fn eq(&self, other: &i128) -> bool {
-        match self {
-            GuraType::Integer(value) => (*value as i128) == *other,
-            GuraType::BigInteger(value) => value == other,
-            _ => false,
-        }
+        self.to_i128() == Some(*other)
     }
 }
 
@@ -304,149 +801,1351 @@ impl PartialEq<GuraType> for String {
     }
 }
 
+/// Implements conversions from Gura values to primitive types. Integer conversions
+/// fail if the value does not fit in the target type.
+macro_rules! impl_try_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<&GuraType> for $t {
+                type Error = IntegerConversionError;
+
+                fn try_from(value: &GuraType) -> Result<Self, Self::Error> {
+                    let number = match value {
+                        GuraType::Integer(number) => *number as i128,
+                        GuraType::BigInteger(number) => *number,
+                        _ => return Err(value.type_error(Kind::Integer).into()),
+                    };
+                    <$t>::try_from(number).map_err(|_| IntegerConversionError::OutOfRange {
+                        target: stringify!($t),
+                        value: number,
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// Generates `GuraType` methods for checked integer conversions (`to_i32`, etc.).
+macro_rules! checked_integer_conversions {
+    ($($name:ident => $t:ty),*) => {
+        $(
+            #[doc = concat!(
+                "Converts an integer to `", stringify!($t), "`. Returns `None` if the value ",
+                "is not an integer or does not fit in the type."
+            )]
+            pub fn $name(&self) -> Option<$t> {
+                <$t>::try_from(self).ok()
+            }
+        )*
+    };
+}
+
+/// Largest integer magnitude that can be represented exactly by a `f64`
+const MAX_EXACT_F64_INTEGER: u128 = 1 << f64::MANTISSA_DIGITS;
+
+impl TryFrom<&GuraType> for f64 {
+    type Error = TypeError;
+
+    fn try_from(value: &GuraType) -> Result<Self, Self::Error> {
+        value.expect_f64()
+    }
+}
+
+impl TryFrom<&GuraType> for f32 {
+    type Error = TypeError;
+
+    fn try_from(value: &GuraType) -> Result<Self, Self::Error> {
+        value.expect_f64().map(|number| number as f32)
+    }
+}
+
+impl TryFrom<&GuraType> for bool {
+    type Error = TypeError;
+
+    fn try_from(value: &GuraType) -> Result<Self, Self::Error> {
+        value.expect_bool()
+    }
+}
+
+impl<'a> TryFrom<&'a GuraType> for &'a str {
+    type Error = TypeError;
+
+    fn try_from(value: &'a GuraType) -> Result<Self, Self::Error> {
+        value.expect_str()
+    }
+}
+
+impl TryFrom<&GuraType> for String {
+    type Error = TypeError;
+
+    fn try_from(value: &GuraType) -> Result<Self, Self::Error> {
+        value.expect_str().map(String::from)
+    }
+}
+
 impl GuraType {
-    /// Gets an iterator over the references to the elements of an object.
+    checked_integer_conversions!(
+        to_i8 => i8, to_i16 => i16, to_i32 => i32, to_i64 => i64, to_i128 => i128,
+        to_isize => isize, to_u8 => u8, to_u16 => u16, to_u32 => u32, to_u64 => u64,
+        to_u128 => u128, to_usize => usize
+    );
+
+    /// Converts a number to `f64` without losing precision: floats are returned as they are
+    /// and integers only if they can be represented exactly. Returns `None` otherwise.
     ///
-    /// Returns an error if the Gura type is not an object
-    pub fn iter(&self) -> Result<indexmap::map::Iter<'_, String, GuraType>, &str> {
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::GuraType;
+    ///
+    /// assert_eq!(GuraType::Integer(5).to_f64(), Some(5.0));
+    /// assert_eq!(GuraType::BigInteger(i128::MAX).to_f64(), None);
+    /// assert_eq!(GuraType::Bool(true).to_f64(), None);
+    /// ```
+    pub fn to_f64(&self) -> Option<f64> {
         match self {
-            GuraType::Object(hash_map) => Ok(hash_map.iter()),
-            _ => Err("This struct is not an object"),
+            GuraType::Float(value) => Some(*value),
+            GuraType::Integer(value) if (value.unsigned_abs() as u128) <= MAX_EXACT_F64_INTEGER => {
+                Some(*value as f64)
+            }
+            GuraType::BigInteger(value) if value.unsigned_abs() <= MAX_EXACT_F64_INTEGER => {
+                Some(*value as f64)
+            }
+            _ => None,
         }
     }
 
-    /// Gets an iterator over the elements of an object.
+    /// Converts any number to `f64`, rounding integers that can not be represented exactly.
+    /// Returns `None` if the value is not a number.
     ///
-    /// Returns an error if the Gura type is not an object
-    pub fn iter_mut(&mut self) -> Result<indexmap::map::IterMut<'_, String, GuraType>, &str> {
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::GuraType;
+    ///
+    /// assert_eq!(GuraType::BigInteger(i128::MAX).as_f64_lossy(), Some(1.7014118346046923e38));
+    /// assert_eq!(GuraType::Float(0.5).as_f64_lossy(), Some(0.5));
+    /// ```
+    pub fn as_f64_lossy(&self) -> Option<f64> {
         match self {
-            GuraType::Object(hash_map) => Ok(hash_map.iter_mut()),
-            _ => Err("This struct is not an object"),
+            GuraType::Float(value) => Some(*value),
+            GuraType::Integer(value) => Some(*value as f64),
+            GuraType::BigInteger(value) => Some(*value as f64),
+            _ => None,
         }
     }
 
-    /// Checks if a specific key is defined in the Gura Object
+    /// Gets the kind of the value.
     ///
-    /// If the Gura type is not an object it returns `false`
-    pub fn contains_key(&self, key: &str) -> bool {
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{parse, Kind};
+    ///
+    /// let parsed = parse("port: 8080").unwrap();
+    /// assert_eq!(parsed.kind(), Kind::Object);
+    /// assert_eq!(parsed["port"].kind(), Kind::Integer);
+    /// ```
+    pub fn kind(&self) -> Kind {
         match self {
-            GuraType::Object(hash_map) => hash_map.contains_key(key),
-            _ => false,
+            GuraType::Null => Kind::Null,
+            GuraType::Bool(_) => Kind::Bool,
+            GuraType::String(_) => Kind::String,
+            GuraType::Integer(_) => Kind::Integer,
+            GuraType::BigInteger(_) => Kind::BigInteger,
+            GuraType::Float(_) => Kind::Float,
+            GuraType::Array(_) => Kind::Array,
+            GuraType::Object(_) | GuraType::ObjectWithWs(..) => Kind::Object,
+            GuraType::Extension(..) => Kind::Extension,
+            _ => Kind::Internal,
         }
     }
-}
 
-/// Struct to handle user Input internally
-struct Input {
-    /// Text as a Vec of Unicode chars (grapheme clusters)
-    text: Vec<String>,
-    pos: isize,
-    line: usize,
-    len: isize,
-    /// Vec of Grapheme clusters vecs
-    cache: HashMap<String, Vec<Vec<String>>>,
-    variables: HashMap<String, VariableValueType>,
-    indentation_levels: Vec<usize>,
-    imported_files: HashSet<String>,
-}
+    /// Gets a human readable name of the value's type (e.g. `"integer"` or `"string"`).
+    pub fn type_name(&self) -> &'static str {
+        self.kind().name()
+    }
 
-impl Input {
-    // TODO: replace this with the same logic as restart_params
-    fn new() -> Self {
-        Input {
-            cache: HashMap::new(),
-            pos: -1,
-            line: 1,
-            len: 0,
-            text: Vec::new(),
-            variables: HashMap::new(),
-            indentation_levels: Vec::new(),
-            imported_files: HashSet::new(),
+    /// Gets the value for a key of an object.
+    ///
+    /// Returns `None` if the key does not exist or the Gura type is not an object.
+    pub fn get(&self, key: &str) -> Option<&GuraType> {
+        match self {
+            GuraType::Object(values) => values.get(key),
+            _ => None,
         }
     }
 
-    /// Sets the params to start parsing from a specific text.
+    /// Gets a nested value following a path of keys separated by dots (e.g. `"services.nginx.port"`).
+    /// Elements of arrays are selected by their index, between brackets or as a key (e.g.
+    /// `"hosts[0]"` or `"hosts.0"`). See the `path` module.
     ///
-    /// # Arguments
+    /// Returns `None` if any of the segments does not exist or the path is not valid.
     ///
-    /// * text - Text to set as the internal text to be parsed.
-    fn restart_params(&mut self, text: &str) {
-        let graph = get_graphemes_cluster(text);
-        self.text = graph;
-        self.pos = -1;
-        self.line = 1;
-        self.len = self.text.len() as isize - 1;
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::parse;
+    ///
+    /// let parsed = parse("services:\n    nginx:\n        hosts: [\"alpha\", \"omega\"]").unwrap();
+    /// assert_eq!(*parsed.get_path("services.nginx.hosts.1").unwrap(), "omega");
+    /// assert_eq!(*parsed.get_path("services.nginx.hosts[1]").unwrap(), "omega");
+    /// assert!(parsed.get_path("services.apache").is_none());
+    /// ```
+    pub fn get_path(&self, path: &str) -> Option<&GuraType> {
+        self.get_at(&path.parse().ok()?)
     }
 
-    /// Removes, if exists, the last indentation level.
-    fn remove_last_indentation_level(&mut self) {
-        if !self.indentation_levels.is_empty() {
-            self.indentation_levels.pop();
-        }
+    /// Like `get_path` but with an already parsed path.
+    pub fn get_at(&self, path: &ValuePath) -> Option<&GuraType> {
+        path.segments().iter().try_fold(self, |current, segment| {
+            current.get_segment(segment, GuraType::get)
+        })
     }
-}
 
-/// Generates a Vec with every Grapheme cluster from an String
-fn get_graphemes_cluster(text: &str) -> Vec<String> {
-    UnicodeSegmentation::graphemes(text, true)
-        .map(String::from)
-        .collect()
-}
+    /// Gets the value of a segment of a path, looking keys of objects up with `get`.
+    fn get_segment<'a>(
+        &'a self,
+        segment: &Segment,
+        get: impl Fn(&'a GuraType, &str) -> Option<&'a GuraType>,
+    ) -> Option<&'a GuraType> {
+        match (self, segment) {
+            (GuraType::Object(_), Segment::Key(key)) => get(self, key),
+            (GuraType::Array(values), _) => segment.index().and_then(|index| values.get(index)),
+            _ => None,
+        }
+    }
+
+    /// Converts the value located at `path` (as in `get_path`) into `T`. Errors contain the
+    /// full path (see also the `gura_get!` macro).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::parse;
+    ///
+    /// let parsed = parse("services:\n    nginx:\n        port: 80").unwrap();
+    /// assert_eq!(parsed.extract::<u16, _>("services.nginx.port"), Ok(80));
+    ///
+    /// let err = parsed.extract::<bool, _>("services.nginx.port").unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "Invalid value at \"services.nginx.port\": expected bool, found integer"
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * PathNotFound - If the path does not exist.
+    /// * Conversion - If the value at the path could not be converted into `T`.
+    pub fn extract<'a, T, E>(&'a self, path: &str) -> Result<T, ExtractError<E>>
+    where
+        T: TryFrom<&'a GuraType, Error = E>,
+    {
+        let value = self
+            .get_path(path)
+            .ok_or_else(|| ExtractError::PathNotFound(path.to_string()))?;
+        T::try_from(value).map_err(|error| ExtractError::Conversion {
+            path: path.to_string(),
+            error,
+        })
+    }
+
+    /// Like `get` but comparing keys after normalizing them with `normalize_key`, so
+    /// `"Max-Connections"` matches a `max_connections` key. An exact match is preferred.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::parse;
+    ///
+    /// let parsed = parse("max_connections: 10").unwrap();
+    /// assert_eq!(*parsed.get_normalized("Max-Connections").unwrap(), 10);
+    /// ```
+    pub fn get_normalized(&self, key: &str) -> Option<&GuraType> {
+        match self {
+            GuraType::Object(values) => values.get(key).or_else(|| {
+                let normalized = normalize_key(key);
+                values
+                    .iter()
+                    .find(|(current, _)| normalize_key(current) == normalized)
+                    .map(|(_, value)| value)
+            }),
+            _ => None,
+        }
+    }
+
+    /// Like `get_path` but looking for every key as explained in `get_normalized`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::parse;
+    ///
+    /// let parsed = parse("http_server:\n    max_connections: 10").unwrap();
+    /// assert_eq!(*parsed.get_path_normalized("HTTP-Server.max-connections").unwrap(), 10);
+    /// ```
+    pub fn get_path_normalized(&self, path: &str) -> Option<&GuraType> {
+        let path = path.parse::<ValuePath>().ok()?;
+        path.segments().iter().try_fold(self, |current, segment| {
+            current.get_segment(segment, GuraType::get_normalized)
+        })
+    }
+
+    /// Gets the value for a key converted to the type of `default`. If the key does not exist
+    /// or the value can not be converted, `default` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::parse;
+    ///
+    /// let parsed = parse("port: 80\nhost: \"localhost\"").unwrap();
+    /// assert_eq!(parsed.get_or("port", 8080), 80);
+    /// assert_eq!(parsed.get_or("timeout", 30), 30);
+    /// assert_eq!(parsed.get_or("host", "127.0.0.1"), "localhost");
+    /// ```
+    pub fn get_or<'a, T>(&'a self, key: &str, default: T) -> T
+    where
+        T: TryFrom<&'a GuraType>,
+    {
+        self.get(key)
+            .and_then(|value| T::try_from(value).ok())
+            .unwrap_or(default)
+    }
+
+    /// Like `get_or` but looking for a nested value as explained in `get_path`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::parse;
+    ///
+    /// let parsed = parse("server:\n    port: 80").unwrap();
+    /// assert_eq!(parsed.get_path_or("server.port", 8080), 80);
+    /// assert_eq!(parsed.get_path_or("server.tls.enabled", false), false);
+    /// ```
+    pub fn get_path_or<'a, T>(&'a self, path: &str, default: T) -> T
+    where
+        T: TryFrom<&'a GuraType>,
+    {
+        self.get_path(path)
+            .and_then(|value| T::try_from(value).ok())
+            .unwrap_or(default)
+    }
+
+    /// Generates a `TypeError` reporting that this value is not of the `expected` kind.
+    fn type_error(&self, expected: Kind) -> TypeError {
+        TypeError {
+            expected,
+            found: self.kind(),
+            span: None,
+        }
+    }
+
+    /// Gets the value as a string slice.
+    ///
+    /// # Errors
+    ///
+    /// * TypeError - If the value is not a string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{errors::TypeError, parse, Kind};
+    ///
+    /// let parsed = parse("title: \"Gura\"\nport: 80").unwrap();
+    /// assert_eq!(parsed["title"].expect_str(), Ok("Gura"));
+    ///
+    /// let err = parsed["port"].expect_str().unwrap_err();
+    /// assert_eq!(err, TypeError { expected: Kind::String, found: Kind::Integer, span: None });
+    /// assert_eq!(err.to_string(), "expected string, found integer");
+    /// ```
+    pub fn expect_str(&self) -> Result<&str, TypeError> {
+        match self {
+            GuraType::String(value) => Ok(value),
+            _ => Err(self.type_error(Kind::String)),
+        }
+    }
+
+    /// Gets the value as a boolean.
+    ///
+    /// # Errors
+    ///
+    /// * TypeError - If the value is not a boolean.
+    pub fn expect_bool(&self) -> Result<bool, TypeError> {
+        match self {
+            GuraType::Bool(value) => Ok(*value),
+            _ => Err(self.type_error(Kind::Bool)),
+        }
+    }
+
+    /// Gets the value as a 64 bits integer.
+    ///
+    /// # Errors
+    ///
+    /// * WrongType - If the value is not an integer.
+    /// * OutOfRange - If the value is a big integer that does not fit in 64 bits.
+    pub fn expect_i64(&self) -> Result<i64, IntegerConversionError> {
+        i64::try_from(self)
+    }
+
+    /// Gets the value as a float.
+    ///
+    /// # Errors
+    ///
+    /// * TypeError - If the value is not a float.
+    pub fn expect_f64(&self) -> Result<f64, TypeError> {
+        match self {
+            GuraType::Float(value) => Ok(*value),
+            _ => Err(self.type_error(Kind::Float)),
+        }
+    }
+
+    /// Gets the elements of an array.
+    ///
+    /// # Errors
+    ///
+    /// * TypeError - If the value is not an array.
+    pub fn expect_array(&self) -> Result<&Vec<GuraType>, TypeError> {
+        match self {
+            GuraType::Array(values) => Ok(values),
+            _ => Err(self.type_error(Kind::Array)),
+        }
+    }
+
+    /// Gets the key/value pairs of an object.
+    ///
+    /// # Errors
+    ///
+    /// * TypeError - If the value is not an object.
+    pub fn expect_object(&self) -> Result<&IndexMap<String, GuraType>, TypeError> {
+        match self {
+            GuraType::Object(values) => Ok(values),
+            _ => Err(self.type_error(Kind::Object)),
+        }
+    }
+
+    /// Converts every element of an array into `T`.
+    ///
+    /// # Errors
+    ///
+    /// * NotAnArray - If the value is not an array.
+    /// * Element - With the index and the error of the first element that could not be converted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{errors::ArrayConversionError, parse};
+    ///
+    /// let parsed = parse("ports: [80, 443]\nmixed: [80, \"443\"]").unwrap();
+    /// assert_eq!(parsed["ports"].to_vec_of::<u16>(), Ok(vec![80, 443]));
+    ///
+    /// match parsed["mixed"].to_vec_of::<u16>() {
+    ///     Err(ArrayConversionError::Element { index, .. }) => assert_eq!(index, 1),
+    ///     _ => panic!("Expected an error in the second element"),
+    /// }
+    /// ```
+    pub fn to_vec_of<'a, T>(&'a self) -> Result<Vec<T>, ArrayConversionError<T::Error>>
+    where
+        T: TryFrom<&'a GuraType>,
+    {
+        self.expect_array()
+            .map_err(ArrayConversionError::NotAnArray)?
+            .iter()
+            .enumerate()
+            .map(|(index, element)| {
+                T::try_from(element).map_err(|error| ArrayConversionError::Element { index, error })
+            })
+            .collect()
+    }
+
+    /// Gets an iterator over the references to the elements of an object.
+    ///
+    /// Returns an error if the Gura type is not an object
+    pub fn iter(&self) -> Result<indexmap::map::Iter<'_, String, GuraType>, &str> {
+        match self {
+            GuraType::Object(hash_map) => Ok(hash_map.iter()),
+            _ => Err("This struct is not an object"),
+        }
+    }
+
+    /// Gets an iterator over the elements of an object.
+    ///
+    /// Returns an error if the Gura type is not an object
+    pub fn iter_mut(&mut self) -> Result<indexmap::map::IterMut<'_, String, GuraType>, &str> {
+        match self {
+            GuraType::Object(hash_map) => Ok(hash_map.iter_mut()),
+            _ => Err("This struct is not an object"),
+        }
+    }
+
+    /// Gets a wrapper implementing `Display` that prints strings without surrounding quotes
+    /// nor escaped characters. Any other value is formatted as with `GuraType`'s `Display`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::parse;
+    ///
+    /// let parsed = parse("title: \"Gura \\\"Example\\\"\"").unwrap();
+    /// assert_eq!(parsed["title"].to_string(), r#""Gura \"Example\"""#);
+    /// assert_eq!(parsed["title"].raw_display().to_string(), r#"Gura "Example""#);
+    /// ```
+    pub fn raw_display(&self) -> RawDisplay<'_> {
+        RawDisplay(self)
+    }
+
+    /// Retains only the key/value pairs of an object specified by the predicate, preserving
+    /// the order of the remaining ones.
+    ///
+    /// Returns an error if the Gura type is not an object
+    pub fn retain<F>(&mut self, keep: F) -> Result<(), &'static str>
+    where
+        F: FnMut(&String, &mut GuraType) -> bool,
+    {
+        match self {
+            GuraType::Object(hash_map) => {
+                hash_map.retain(keep);
+                Ok(())
+            }
+            _ => Err("This struct is not an object"),
+        }
+    }
+
+    /// Retains only the keys of an object for which the predicate returns `true`.
+    ///
+    /// Returns an error if the Gura type is not an object
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::parse;
+    ///
+    /// let mut parsed = parse("host: \"localhost\"\nold_port: 80\nold_user: \"root\"").unwrap();
+    /// parsed.filter_keys(|key| !key.starts_with("old_")).unwrap();
+    /// assert_eq!(parsed.iter().unwrap().count(), 1);
+    /// ```
+    pub fn filter_keys<F>(&mut self, mut keep: F) -> Result<(), &'static str>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        self.retain(|key, _| keep(key))
+    }
+
+    /// Renames a key of an object keeping its value in the same position.
+    ///
+    /// Returns `Ok(true)` if the key was renamed or `Ok(false)` if `old_key` does not exist.
+    /// Returns an error if the Gura type is not an object or `new_key` is already defined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::parse;
+    ///
+    /// let mut parsed = parse("hostname: \"localhost\"\nport: 80").unwrap();
+    /// assert_eq!(parsed.rename_key("hostname", "host"), Ok(true));
+    /// assert_eq!(parsed.iter().unwrap().next().unwrap().0, "host");
+    /// assert_eq!(parsed.rename_key("hostname", "host"), Ok(false));
+    /// ```
+    pub fn rename_key(&mut self, old_key: &str, new_key: &str) -> Result<bool, &'static str> {
+        match self {
+            GuraType::Object(hash_map) => {
+                if !hash_map.contains_key(old_key) {
+                    return Ok(false);
+                }
+
+                if old_key != new_key && hash_map.contains_key(new_key) {
+                    return Err("The new key is already defined");
+                }
+
+                *hash_map = hash_map
+                    .drain(..)
+                    .map(|(key, value)| {
+                        if key == old_key {
+                            (new_key.to_string(), value)
+                        } else {
+                            (key, value)
+                        }
+                    })
+                    .collect();
+                Ok(true)
+            }
+            _ => Err("This struct is not an object"),
+        }
+    }
+
+    /// Checks if a specific key is defined in the Gura Object
+    ///
+    /// If the Gura type is not an object it returns `false`
+    pub fn contains_key(&self, key: &str) -> bool {
+        match self {
+            GuraType::Object(hash_map) => hash_map.contains_key(key),
+            _ => false,
+        }
+    }
+
+    /// Computes the size and complexity of the value: number of values and keys, nesting
+    /// depth, keys of every top-level section and bytes of strings. Cheaper than `summary`,
+    /// so it can be used to reject absurd documents before further processing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::parse;
+    ///
+    /// let parsed = parse("name: \"app\"\nserver:\n    host: \"localhost\"\n    ports: [80, 443]").unwrap();
+    /// let metrics = parsed.metrics();
+    ///
+    /// assert_eq!(metrics.nodes, 7);
+    /// assert_eq!(metrics.max_depth, 3);
+    /// assert_eq!(metrics.keys, 4);
+    /// assert_eq!(metrics.section_keys["server"], 2);
+    /// assert_eq!(metrics.string_bytes, 12);
+    /// ```
+    pub fn metrics(&self) -> Metrics {
+        Metrics::new(self)
+    }
+
+    /// Generates a structural summary of the value: for every path, the number of values, how
+    /// many of them are of every kind and some examples. Elements of arrays are aggregated,
+    /// which makes it useful to inspect huge generated documents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{parse, Kind};
+    ///
+    /// let parsed = parse("hosts: [\"alpha\", \"omega\", null]").unwrap();
+    /// let summary = parsed.summary();
+    /// let hosts = summary.get("hosts[]").unwrap();
+    ///
+    /// assert_eq!(hosts.count, 3);
+    /// assert_eq!(hosts.kinds[&Kind::String], 2);
+    /// assert_eq!(summary.to_string().lines().last().unwrap(),
+    ///     "hosts[]: 3 values (string: 2, null: 1), e.g. \"alpha\", \"omega\", null");
+    /// ```
+    pub fn summary(&self) -> Summary {
+        Summary::new(self)
+    }
+
+    /// Computes a 64 bits hash of the value, useful to detect cheaply if a configuration
+    /// actually changed (e.g. before restarting a service). Equal values have the same
+    /// fingerprint regardless of the order of their keys, and the result is stable across
+    /// platforms and versions of Rust, so it can be stored. It is not a cryptographic hash.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::parse;
+    ///
+    /// let old = parse("host: \"localhost\"\nport: 8080").unwrap();
+    /// let reordered = parse("port: 8080\nhost: \"localhost\"").unwrap();
+    /// let changed = parse("host: \"localhost\"\nport: 8081").unwrap();
+    ///
+    /// assert_eq!(old.fingerprint(), reordered.fingerprint());
+    /// assert_ne!(old.fingerprint(), changed.fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        fingerprint::fingerprint(self)
+    }
+
+    /// Gets a single line representation of the value with the type of every node and the keys
+    /// of objects sorted, meant for snapshot tests (e.g. with `insta`). Unlike `Display` and
+    /// `dump`, it does not change with the formatting of Gura documents, and integers are
+    /// written the same whether they are stored as `Integer` or `BigInteger`, so snapshots
+    /// are the same in every platform.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::parse;
+    ///
+    /// let parsed = parse("port: 8080\nhost: \"localhost\"\nratios: [0.5, null]").unwrap();
+    /// assert_eq!(
+    ///     parsed.to_debug_normalized(),
+    ///     "Object{host: String(\"localhost\"), port: Int(8080), ratios: Array[Float(0.5), Null]}"
+    /// );
+    /// ```
+    pub fn to_debug_normalized(&self) -> String {
+        let mut result = String::new();
+        write_debug_normalized(&mut result, self);
+        result
+    }
+
+    /// Converts the value into its reference counted representation, whose subtrees can be
+    /// cloned cheaply. Useful to hand the same sections of a big document to many consumers.
+    pub fn shared(&self) -> SharedGuraType {
+        SharedGuraType::from(self)
+    }
+
+    /// Copies the value into an arena, so it is freed at once along with it instead of node by
+    /// node. Useful for services that handle many documents per second (see the `arena`
+    /// module).
+    #[cfg(feature = "arena")]
+    pub fn deep_clone_into<'a>(&self, arena: &'a Bump) -> ArenaGuraType<'a> {
+        ArenaGuraType::new(self, arena)
+    }
+}
+
+/// Normalizes a key for lookup purposes: lowercases it and replaces dashes with underscores.
+/// Used by `GuraType::get_normalized` and `GuraType::get_path_normalized`.
+///
+/// # Examples
+///
+/// ```
+/// use gura::normalize_key;
+///
+/// assert_eq!(normalize_key("Max-Connections"), "max_connections");
+/// ```
+pub fn normalize_key(key: &str) -> String {
+    key.to_lowercase().replace('-', "_")
+}
+
+/// Struct to handle user Input internally
+struct Input {
+    options: ParserOptions,
+    /// Text as a Vec of Unicode chars (grapheme clusters)
+    text: Vec<String>,
+    /// Byte and UTF-16 code unit offsets of every grapheme cluster (plus the end of the text)
+    byte_offsets: Vec<usize>,
+    utf16_offsets: Vec<usize>,
+    pos: isize,
+    line: usize,
+    len: isize,
+    /// Vec of Grapheme clusters vecs
+    cache: HashMap<String, Vec<Vec<String>>>,
+    variables: HashMap<String, VariableValueType>,
+    indentation_levels: Vec<usize>,
+    /// Canonical paths of the imported files
+    imported_files: HashSet<String>,
+    /// Fingerprints of the content of the imported files. Only tracked if
+    /// `ParserOptions::deduplicate_imports` is enabled
+    imported_contents: HashSet<u64>,
+    /// Path of the parsed file. `None` for texts that were not read from a file
+    source_path: Option<PathBuf>,
+    /// Directory the imports of the text are relative to, if it is not the one of
+    /// `source_path` (see `resolve_imports`)
+    import_dir: Option<PathBuf>,
+    /// Ranges of positions of the text inlined from imported files, with the path of the file
+    imported_regions: Vec<(isize, isize, PathBuf)>,
+    /// Time read by the first time variable (see `ParserOptions::time_variables`)
+    now: Option<u64>,
+    /// Number of objects being matched. The root object has depth 1
+    object_depth: usize,
+    /// Root keys defined by previously parsed sources (see `parse_many`)
+    previous_keys: HashSet<String>,
+    /// Locations of the matched keys and variables. Only tracked in spanned mode (see `parse_spanned`)
+    spans: Option<SpanRecords>,
+    /// Path of the value being matched. Only tracked in spanned mode
+    path: Vec<String>,
+    /// Number of grapheme clusters of imported files placed before the original text
+    imported_len: isize,
+    /// Offsets to translate positions and lines after imports to the original text
+    source_pos_offset: isize,
+    source_line_offset: isize,
+    source_byte_offset: isize,
+    source_utf16_offset: isize,
+    /// Warnings found in the matched rules
+    warnings: Vec<ParseWarning>,
+    /// Timings and counters of the parsing (see `parse_with_stats`)
+    #[cfg(feature = "stats")]
+    stats: ParseStats,
+}
+
+impl Input {
+    // TODO: replace this with the same logic as restart_params
+    fn new() -> Self {
+        Input {
+            options: ParserOptions::default(),
+            cache: HashMap::new(),
+            pos: -1,
+            line: 1,
+            len: 0,
+            text: Vec::new(),
+            byte_offsets: vec![0],
+            utf16_offsets: vec![0],
+            variables: HashMap::new(),
+            indentation_levels: Vec::new(),
+            imported_files: HashSet::new(),
+            imported_contents: HashSet::new(),
+            source_path: None,
+            import_dir: None,
+            imported_regions: Vec::new(),
+            now: None,
+            object_depth: 0,
+            previous_keys: HashSet::new(),
+            spans: None,
+            path: Vec::new(),
+            imported_len: 0,
+            source_pos_offset: 0,
+            source_line_offset: 0,
+            source_byte_offset: 0,
+            source_utf16_offset: 0,
+            warnings: Vec::new(),
+            #[cfg(feature = "stats")]
+            stats: ParseStats::default(),
+        }
+    }
+
+    /// Sets the params to start parsing from a specific text.
+    ///
+    /// # Arguments
+    ///
+    /// * text - Text to set as the internal text to be parsed.
+    fn restart_params(&mut self, text: &str) {
+        #[cfg(feature = "stats")]
+        let segmentation_start = Instant::now();
+        let graph = get_graphemes_cluster(text);
+        self.byte_offsets = Vec::with_capacity(graph.len() + 1);
+        self.utf16_offsets = Vec::with_capacity(graph.len() + 1);
+        let (mut byte_offset, mut utf16_offset) = (0, 0);
+        for grapheme in graph.iter() {
+            self.byte_offsets.push(byte_offset);
+            self.utf16_offsets.push(utf16_offset);
+            byte_offset += grapheme.len();
+            utf16_offset += grapheme.encode_utf16().count();
+        }
+        self.byte_offsets.push(byte_offset);
+        self.utf16_offsets.push(utf16_offset);
+        self.text = graph;
+        self.pos = -1;
+        self.line = 1;
+        self.len = self.text.len() as isize - 1;
+        #[cfg(feature = "stats")]
+        {
+            self.stats.segmentation += segmentation_start.elapsed();
+            self.stats.graphemes += self.text.len();
+        }
+    }
+
+    /// Gets the grapheme cluster after the current position, if any.
+    fn next_grapheme(&self) -> Option<&str> {
+        if self.pos < self.len {
+            Some(&self.text[(self.pos + 1) as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Gets the first grapheme clusters of the text that has not been matched yet, for the
+    /// traces of the parser.
+    #[cfg(feature = "trace-parser")]
+    fn upcoming_text(&self) -> String {
+        let start = (self.pos + 1).clamp(0, self.len) as usize;
+        self.text[start..]
+            .iter()
+            .take(20)
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Gets the byte and UTF-16 code unit offsets of the grapheme cluster at `pos`. Positions
+    /// out of the text are clamped.
+    fn offsets(&self, pos: isize) -> (usize, usize) {
+        let index = pos.max(0).min(self.text.len() as isize) as usize;
+        (self.byte_offsets[index], self.utf16_offsets[index])
+    }
+
+    /// Generates an error located at `pos`.
+    fn error(&self, pos: isize, line: usize, msg: String, kind: Error) -> GuraError {
+        let (byte_pos, utf16_pos) = self.offsets(pos);
+        GuraError {
+            pos,
+            line,
+            msg,
+            kind,
+            byte_pos,
+            utf16_pos,
+            line_content: None,
+            suggestion: None,
+        }
+    }
+
+    /// Stores a warning located at `pos`.
+    fn warn(&mut self, pos: isize, line: usize, msg: String) {
+        let (byte_pos, utf16_pos) = self.offsets(pos);
+        self.warnings.push(ParseWarning {
+            pos,
+            line,
+            msg,
+            byte_pos,
+            utf16_pos,
+        });
+    }
+
+    /// Stores the text of the line where `error` was found if `capture_line_content` is
+    /// enabled. Only called for the errors returned to the user as it copies the line.
+    fn capture_line_content(&self, mut error: GuraError) -> GuraError {
+        if !self.options.capture_line_content || error.line_content.is_some() || error.line == 0 {
+            return error;
+        }
+
+        error.line_content = Some(self.line_at(error.pos));
+        error
+    }
+
+    /// Gets the text of the line containing `pos`.
+    fn line_at(&self, pos: isize) -> String {
+        let pos = pos.max(0).min(self.text.len() as isize) as usize;
+        let is_new_line = |grapheme: &&String| NEW_LINE_CHARS.contains(grapheme.as_str());
+        let line_start = self.text[..pos]
+            .iter()
+            .rposition(|grapheme| is_new_line(&grapheme))
+            .map_or(0, |index| index + 1);
+        let line_end = self.text[pos..]
+            .iter()
+            .position(|grapheme| is_new_line(&grapheme))
+            .map_or(self.text.len(), |index| pos + index);
+        get_string_from_slice(&self.text[line_start..line_end])
+    }
+
+    /// Completes an error returned to the user: captures its line (if enabled) and, for
+    /// syntax errors without a suggestion, checks if they are caused by text placed after a
+    /// value.
+    fn report_error(&self, error: GuraError) -> GuraError {
+        let error = self.capture_line_content(error);
+        if error.kind != Error::ParseError || error.suggestion.is_some() || error.line == 0 {
+            return error;
+        }
+
+        if TEXT_AFTER_VALUE.is_match(&self.line_at(error.pos)) {
+            error.with_suggestion(String::from(
+                "Remove the text after the value or move it to a new line",
+            ))
+        } else {
+            error
+        }
+    }
+
+    /// Removes, if exists, the last indentation level.
+    fn remove_last_indentation_level(&mut self) {
+        if !self.indentation_levels.is_empty() {
+            self.indentation_levels.pop();
+        }
+    }
+
+    /// Checks if spans must be tracked.
+    fn is_spanned(&self) -> bool {
+        self.spans.is_some()
+    }
+
+    /// Adds a segment (key or array index) to the path of the value being matched.
+    fn enter_path(&mut self, segment: String) {
+        if self.is_spanned() {
+            self.path.push(segment);
+        }
+    }
+
+    /// Removes the last segment of the path of the value being matched.
+    fn leave_path(&mut self) {
+        if self.is_spanned() {
+            self.path.pop();
+        }
+    }
+
+    /// Translates a span of the current text to the original one. Returns `None` if the span
+    /// belongs to an imported file.
+    fn source_span(&self, start: isize, end: isize, line: usize) -> Option<Span> {
+        if start < self.imported_len {
+            return None;
+        }
+
+        let (byte_start, utf16_start) = self.offsets(start);
+        let (byte_end, utf16_end) = self.offsets(end);
+        Some(Span {
+            start: (start + self.source_pos_offset) as usize,
+            end: (end + self.source_pos_offset) as usize,
+            line: (line as isize + self.source_line_offset) as usize,
+            byte_start: (byte_start as isize + self.source_byte_offset) as usize,
+            byte_end: (byte_end as isize + self.source_byte_offset) as usize,
+            utf16_start: (utf16_start as isize + self.source_utf16_offset) as usize,
+            utf16_end: (utf16_end as isize + self.source_utf16_offset) as usize,
+        })
+    }
+
+    /// Gets the path of a key placed in the value being matched.
+    fn key_path(&self, key: &str) -> String {
+        self.path
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(key))
+            .join(".")
+    }
+
+    /// Stores the span of a key (in spanned mode).
+    fn record_key_span(&mut self, key: &str, start: isize, end: isize, line: usize) {
+        if let Some(span) = self.source_span(start, end, line) {
+            let path = self.key_path(key);
+            if let Some(spans) = self.spans.as_mut() {
+                spans.key_spans.insert(path, span);
+            }
+        }
+    }
+
+    /// Stores the span of a whole pair (in spanned mode). If the value is an object, the
+    /// pair ends where its `last_child` pair does, so trailing useless lines are excluded.
+    fn record_pair_span(
+        &mut self,
+        key: &str,
+        start: isize,
+        end: isize,
+        line: usize,
+        last_child: Option<&str>,
+    ) {
+        if let Some(mut span) = self.source_span(start, end, line) {
+            let path = self.key_path(key);
+            if let Some(spans) = self.spans.as_mut() {
+                let last_child_span = last_child
+                    .and_then(|child| spans.pair_spans.get(&format!("{}.{}", path, child)));
+                if let Some(last_child_span) = last_child_span {
+                    span.end = last_child_span.end;
+                    span.byte_end = last_child_span.byte_end;
+                    span.utf16_end = last_child_span.utf16_end;
+                }
+                spans.pair_spans.insert(path, span);
+            }
+        }
+    }
+
+    /// Gets the comments and blank lines of a pair from the lines of the text before its key
+    /// (only those after `bound`, where the previous pair or key ends) and the rest of its
+    /// line (the one of the key for objects).
+    fn trivia(&self, key_span: &Span, pair_span: &Span, bound: isize, is_object: bool) -> Trivia {
+        let internal = |source_pos: usize| source_pos as isize - self.source_pos_offset;
+        let is_new_line = |pos: isize| NEW_LINE_CHARS.contains(self.text[pos as usize].as_str());
+        let line_text = |start: isize, end: isize| {
+            get_string_from_slice(&self.text[start as usize..end as usize])
+        };
+        let line_start = |mut pos: isize| {
+            while pos > bound && !is_new_line(pos - 1) {
+                pos -= 1;
+            }
+            pos
+        };
+
+        let mut trivia = Trivia::default();
+        // Positions where the leading comments start and end, with the line of the first one
+        let mut comments_range: Option<(isize, isize, usize)> = None;
+        let key_start = internal(key_span.start);
+        let mut start = line_start(key_start);
+        let mut line = key_span.line;
+        while start > bound {
+            let end = start - 1; // Position of the new line that ends the previous line
+            start = line_start(end);
+            line -= 1;
+            if start == bound && bound > 0 && !is_new_line(bound - 1) {
+                break; // The line starts before the bound
+            }
+
+            let content = line_text(start, end);
+            let trimmed = content.trim();
+            if trimmed.starts_with('#') && trivia.blank_lines_before == 0 {
+                trivia.leading_comments.insert(0, trimmed.to_string());
+                let comment_start = start + (content.len() - content.trim_start().len()) as isize;
+                let comment_end = end - (content.len() - content.trim_end().len()) as isize;
+                let range_end = comments_range.map_or(comment_end, |(_, range_end, _)| range_end);
+                comments_range = Some((comment_start, range_end, line));
+            } else if trimmed.is_empty() {
+                trivia.blank_lines_before += 1;
+            } else {
+                break;
+            }
+        }
+        trivia.leading_span =
+            comments_range.and_then(|(start, end, line)| self.source_span(start, end, line));
+
+        // The comment follows the value, or the key for objects
+        let rest_start = if is_object {
+            internal(key_span.end) + 1 // Skips the colon
+        } else {
+            internal(pair_span.end)
+        };
+        let rest_end = (rest_start..self.len + 1)
+            .find(|pos| is_new_line(*pos))
+            .unwrap_or(self.len + 1);
+        let rest = line_text(rest_start, rest_end);
+        if rest.trim().starts_with('#') {
+            trivia.trailing_comment = Some(rest.trim().to_string());
+        }
+        trivia
+    }
+
+    /// Stores the comments and blank lines of every pair (in spanned mode), once the spans of
+    /// the discarded pairs were removed.
+    fn record_trivia(&self, records: &mut SpanRecords, value: &GuraType) {
+        let ends: Vec<usize> = records
+            .key_spans
+            .values()
+            .chain(records.pair_spans.values())
+            .map(|span| span.end)
+            .collect();
+        for (path, key_span) in records.key_spans.iter() {
+            let pair_span = match records.pair_spans.get(path) {
+                Some(pair_span) => pair_span,
+                None => continue,
+            };
+            let bound = ends
+                .iter()
+                .filter(|end| **end <= key_span.start)
+                .max()
+                .map_or(self.imported_len, |end| {
+                    *end as isize - self.source_pos_offset
+                })
+                .max(self.imported_len);
+            let is_object = matches!(value.get_path(path), Some(GuraType::Object(_)));
+            let trivia = self.trivia(key_span, pair_span, bound, is_object);
+            records.trivia.insert(path.clone(), trivia);
+        }
+    }
+
+    /// Stores the definition of a variable (in spanned mode).
+    fn record_variable(
+        &mut self,
+        name: &str,
+        value: &VariableValueType,
+        start: isize,
+        end: isize,
+        line: usize,
+    ) {
+        if let Some(span) = self.source_span(start, end, line) {
+            if let Some(spans) = self.spans.as_mut() {
+                let definition = VariableDefinition {
+                    name: name.to_string(),
+                    value: value.to_gura_type(),
+                    span,
+                };
+                spans.variables.insert(name.to_string(), definition);
+            }
+        }
+    }
+
+    /// Stores an import sentence (in spanned mode).
+    fn record_import(&mut self, path: &str, start: isize, end: isize, line: usize) {
+        if let Some(span) = self.source_span(start, end, line) {
+            if let Some(spans) = self.spans.as_mut() {
+                spans.imports.push(ImportReference {
+                    path: path.to_string(),
+                    span,
+                });
+            }
+        }
+    }
+
+    /// Gets the value of a built-in variable for the text at `pos`: `__file__` and `__dir__`
+    /// (see `ParserOptions::builtin_variables`) or `__now_iso__`, `__now_unix__` and
+    /// `__today__` (see `ParserOptions::time_variables`). Returns `None` if the name is not a
+    /// built-in one, it is disabled or there is no file for the text.
+    fn builtin_variable(&mut self, name: &str, pos: isize) -> Option<GuraType> {
+        match name {
+            "__file__" | "__dir__" if self.options.builtin_variables => {
+                // Imported files can import others, so the innermost region is the right one
+                let path = self
+                    .imported_regions
+                    .iter()
+                    .filter(|(start, end, _)| *start <= pos && pos < *end)
+                    .min_by_key(|(start, end, _)| end - start)
+                    .map(|(_, _, path)| path)
+                    .or(self.source_path.as_ref())?;
+
+                let value = match path.parent() {
+                    _ if name == "__file__" => path.to_string_lossy().to_string(),
+                    Some(dir) if !dir.as_os_str().is_empty() => dir.to_string_lossy().to_string(),
+                    _ => String::from("."),
+                };
+                Some(GuraType::String(value))
+            }
+            "__now_iso__" | "__now_unix__" | "__today__" if self.options.time_variables => {
+                let now = self.now();
+                let (date, time) = utc_date_time(now);
+                Some(match name {
+                    "__now_iso__" => GuraType::String(format!("{}T{}Z", date, time)),
+                    "__now_unix__" => GuraType::Integer(now as isize),
+                    _ => GuraType::String(date),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Gets the current time as seconds since the Unix epoch. It is read once, so every time
+    /// variable of a text (and its imported files) has the same value.
+    fn now(&mut self) -> u64 {
+        let platform = &self.options.platform;
+        *self.now.get_or_insert_with(|| platform.now())
+    }
+
+    /// Stores a usage of a variable with its resolved value (in spanned mode).
+    fn record_variable_reference(
+        &mut self,
+        name: &str,
+        value: &GuraType,
+        start: isize,
+        end: isize,
+        line: usize,
+    ) {
+        if let Some(span) = self.source_span(start, end, line) {
+            let from_environment = !self.variables.contains_key(name);
+            if let Some(spans) = self.spans.as_mut() {
+                let reference = VariableReference {
+                    name: name.to_string(),
+                    value: value.clone(),
+                    from_environment,
+                    span,
+                };
+                spans.references.insert(span.start, reference);
+            }
+        }
+    }
+}
+
+/// Generates a Vec with every Grapheme cluster from an String
+fn get_graphemes_cluster(text: &str) -> Vec<String> {
+    UnicodeSegmentation::graphemes(text, true)
+        .map(String::from)
+        .collect()
+}
 
 /// Computes imports and matches the first expression of the file.Finally consumes all the useless lines.
 fn start(text: &mut Input) -> RuleResult {
-    compute_imports(text, None)?;
-    let result = matches(text, vec![Box::new(object)])?;
+    // Imports of files are relative to them
+    let parent_dir_path = text
+        .import_dir
+        .as_deref()
+        .or_else(|| text.source_path.as_ref().and_then(|path| path.parent()))
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map(|dir| dir.to_string_lossy().to_string());
+    #[cfg(feature = "stats")]
+    let (imports_start, segmentation_before) = (Instant::now(), text.stats.segmentation);
+    compute_imports(text, parent_dir_path)?;
+    #[cfg(feature = "stats")]
+    {
+        let segmentation = text.stats.segmentation - segmentation_before;
+        text.stats.imports += imports_start.elapsed().saturating_sub(segmentation);
+    }
+    let result = matches(text, &[object])?;
     eat_ws_and_new_lines(text);
     Ok(result)
 }
 
 /// Matches with any primitive or complex type.
 fn any_type(text: &mut Input) -> RuleResult {
-    let result = maybe_match(text, vec![Box::new(primitive_type)])?;
+    if !text.options.scalar_extensions.is_empty() {
+        // Keeps the furthest error so the ones of the recognizers are reported
+        return matches(text, &[primitive_type, complex_type]);
+    }
+
+    let result = maybe_match(text, &[primitive_type])?;
 
     if let Some(result) = result {
         Ok(result)
     } else {
-        matches(text, vec![Box::new(complex_type)])
+        matches(text, &[complex_type])
     }
 }
 
 /// Matches with a primitive value: null, bool, strings(all of the four kind of string), number or variables values.
 fn primitive_type(text: &mut Input) -> RuleResult {
-    maybe_match(text, vec![Box::new(ws)])?;
-    let result = matches(
-        text,
-        vec![
-            Box::new(null),
-            Box::new(boolean),
-            Box::new(basic_string),
-            Box::new(literal_string),
-            Box::new(number),
-            Box::new(variable_value),
-            Box::new(empty_object),
-        ],
-    );
-    maybe_match(text, vec![Box::new(ws)])?;
+    maybe_match(text, &[ws])?;
+    let rules: &[Rule] = if text.options.scalar_extensions.is_empty() {
+        &[
+            null,
+            boolean,
+            basic_string,
+            literal_string,
+            number,
+            variable_value,
+            empty_object,
+        ]
+    } else {
+        &[
+            null,
+            boolean,
+            basic_string,
+            literal_string,
+            number,
+            variable_value,
+            empty_object,
+            scalar_extension,
+        ]
+    };
+    let result = matches(text, rules);
+    maybe_match(text, &[ws])?;
     result
 }
 
+/// Matches with a custom scalar (`@tag text`) and gets the value of the recognizer of its
+/// tag. Only matched if scalar extensions are enabled (see `ParserOptions::scalar_extensions`).
+///
+/// # Errors
+///
+/// * ParseError - If the tag has no recognizer or the recognizer rejects the text.
+fn scalar_extension(text: &mut Input) -> RuleResult {
+    keyword(text, &["@"])?;
+    let tag_pos = text.pos + 1;
+    let tag = match unquoted_string(text)? {
+        GuraType::String(tag) => tag,
+        _ => String::new(),
+    };
+    matches(text, &[ws])?;
+
+    let value_pos = text.pos + 1;
+    let mut value = String::new();
+    while text.pos < text.len
+        && ![",", "]", "#", "\n", "\r\n"].contains(&text.text[(text.pos + 1) as usize].as_str())
+    {
+        text.pos += 1;
+        value.push_str(&text.text[text.pos as usize]);
+    }
+    let value = value.trim_end();
+    if value.is_empty() {
+        return Err(text.error(
+            value_pos,
+            text.line,
+            format!("Expected the value of the @{} custom scalar", tag),
+            Error::ParseError,
+        ));
+    }
+
+    let result = match text.options.scalar_extensions.recognizer(&tag) {
+        Some(recognizer) => recognizer(value).map_err(|msg| {
+            (
+                value_pos,
+                format!("Invalid @{} value \"{}\": {}", tag, value, msg),
+            )
+        }),
+        None => Err((tag_pos, format!("Unknown custom scalar \"@{}\"", tag))),
+    };
+    match result {
+        Ok(result) => Ok(GuraType::Extension(tag, Box::new(result))),
+        Err((pos, msg)) => Err(text.error(pos, text.line, msg, Error::ParseError)),
+    }
+}
+
 /// Matches with a useless line. A line is useless when it contains only whitespaces
 /// and/or a comment finishing in a new line.
 fn useless_line(text: &mut Input) -> RuleResult {
-    matches(text, vec![Box::new(ws)])?;
-    let comment = maybe_match(text, vec![Box::new(comment)])?;
-    let initial_line = text.line;
-    maybe_match(text, vec![Box::new(new_line)])?;
-    let is_new_line = (text.line - initial_line) == 1;
-
-    if comment.is_none() && !is_new_line && !is_end_of_file(text) {
-        return Err(GuraError {
-            pos: text.pos + 1,
-            line: text.line,
-            msg: String::from("It is a valid line"),
-            kind: Error::ParseError,
-        });
+    // Useless lines are the most common ones, so chars are checked directly instead of
+    // matching the generic rules
+    ws(text)?;
+    let is_comment = text.next_grapheme() == Some("#");
+    if is_comment {
+        comment(text)?;
+    }
+    let is_new_line = text.next_grapheme().is_some_and(is_new_line_grapheme);
+    if is_new_line {
+        new_line(text)?;
+    }
+
+    if !is_comment && !is_new_line && !is_end_of_file(text) {
+        return Err(text.error(
+            text.pos + 1,
+            text.line,
+            String::from("It is a valid line"),
+            Error::ParseError,
+        ));
     }
 
     Ok(GuraType::UselessLine)
@@ -454,7 +2153,7 @@ fn useless_line(text: &mut Input) -> RuleResult {
 
 /// Matches with a list or an object.
 fn complex_type(text: &mut Input) -> RuleResult {
-    matches(text, vec![Box::new(list), Box::new(object)])
+    matches(text, &[list, object])
 }
 
 /// Consumes `null` keyword and returns null.
@@ -488,24 +2187,27 @@ fn basic_string(text: &mut Input) -> RuleResult {
     }
 
     let mut final_string: String = String::new();
+    // Length of the string before its last char, if it was a new line of the text
+    let mut last_new_line: Option<usize> = None;
 
     loop {
-        let closing_quote = maybe_keyword(text, &[&quote])?;
+        let closing_quote = maybe_keyword(text, &[quote])?;
         if closing_quote.is_some() {
             break;
         }
 
+        let length_before = final_string.len();
         let current_char = char(text, &None)?;
+        let is_new_line = NEW_LINE_CHARS.contains(current_char.as_str());
         if current_char == "\\" {
-            let escape = char(text, &None)?;
-
-            // Checks backslash followed by a newline to trim all whitespaces
-            if is_multiline && (escape == "\n" || escape == "\r\n") {
-                eat_ws_and_new_lines(text)
+            // Checks backslash at the end of a line to trim all whitespaces and new lines
+            if is_multiline && is_line_continuation(text) {
+                eat_line_continuation(text)
             } else {
-                // Supports Unicode of 16 and 32 bits representation
-                if escape == "u" || escape == "U" {
-                    let num_chars_code_point = if escape == "u" { 4 } else { 8 };
+                let escape = char(text, &None)?;
+
+                // Supports Unicode of 16 and 32 bits representation (and 8 bits, if enabled)
+                if let Some(num_chars_code_point) = code_point_digits(&escape, &text.options) {
                     let mut code_point: String = String::with_capacity(num_chars_code_point);
                     for _ in 0..num_chars_code_point {
                         let code_point_char = char(text, &Some(String::from("0-9a-fA-F")))?;
@@ -513,20 +2215,19 @@ fn basic_string(text: &mut Input) -> RuleResult {
                     }
 
                     // Gets hex value and gets the corresponding char
-                    let hex_value = u32::from_str_radix(&code_point, 16);
-                    match hex_value {
-                        Err(_) => {
-                            return Err(GuraError {
-                                pos: text.pos,
-                                line: text.line,
-                                msg: String::from("Bad hex value"),
-                                kind: Error::ParseError,
-                            });
-                        }
-                        Ok(hex_value) => {
-                            let char_value = char::from_u32(hex_value).unwrap(); // Converts from UNICODE to string
-                            final_string.push(char_value)
+                    let char_value = u32::from_str_radix(&code_point, 16)
+                        .ok()
+                        .and_then(char::from_u32); // Converts from UNICODE to string
+                    match char_value {
+                        None => {
+                            return Err(text.error(
+                                text.pos,
+                                text.line,
+                                String::from("Bad hex value"),
+                                Error::ParseError,
+                            ));
                         }
+                        Some(char_value) => final_string.push(char_value),
                     };
                 } else {
                     // Gets escaped char or interprets as literal
@@ -540,28 +2241,84 @@ fn basic_string(text: &mut Input) -> RuleResult {
             }
         } else {
             // Computes variables values in string
-            if current_char == "$" {
+            if current_char == "$" && !text.options.raw_strings {
                 let initial_pos = text.pos;
                 let initial_line = text.line;
                 let var_name = get_var_name(text)?;
-                let var_value_str: String =
-                    match get_variable_value(text, &var_name, initial_pos, initial_line)? {
-                        GuraType::Integer(number) => number.to_string(),
-                        GuraType::Float(number) => number.to_string(),
-                        GuraType::String(value) => value,
-                        _ => "".to_string(),
-                    };
+                let var_value = get_variable_value(text, &var_name, initial_pos, initial_line)?;
+                if text.is_spanned() {
+                    text.record_variable_reference(
+                        &var_name,
+                        &var_value,
+                        initial_pos,
+                        text.pos + 1,
+                        initial_line,
+                    );
+                }
+                let var_value_str: String = match var_value {
+                    GuraType::Integer(number) => number.to_string(),
+                    GuraType::Float(number) => number.to_string(),
+                    GuraType::String(value) => value,
+                    _ => "".to_string(),
+                };
 
                 final_string.push_str(&var_value_str);
             } else {
-                final_string.push_str(&current_char);
+                if is_new_line {
+                    text.line += 1;
+                }
+                final_string.push_str(new_line_value(text, is_multiline, &current_char));
             }
         }
+
+        last_new_line = if is_new_line {
+            Some(length_before)
+        } else {
+            None
+        };
+    }
+
+    if is_multiline && text.options.trim_final_newline {
+        if let Some(length) = last_new_line {
+            final_string.truncate(length);
+        }
     }
 
     Ok(GuraType::String(final_string))
 }
 
+/// Gets the value of a char of a string, which for `\r\n` new lines of multiline strings is
+/// `\n` if they are normalized (see `ParserOptions::normalize_newlines`).
+fn new_line_value<'a>(text: &Input, is_multiline: bool, char: &'a str) -> &'a str {
+    if is_multiline && char == "\r\n" && text.options.normalize_newlines {
+        "\n"
+    } else {
+        char
+    }
+}
+
+/// Checks if the backslash just matched in a multiline basic string is the last non-whitespace
+/// char of its line, i.e. a line continuation.
+fn is_line_continuation(text: &Input) -> bool {
+    text.text[(text.pos + 1) as usize..]
+        .iter()
+        .find(|grapheme| *grapheme != " " && *grapheme != "\t")
+        .is_some_and(|grapheme| NEW_LINE_CHARS.contains(grapheme.as_str()))
+}
+
+/// Consumes the whitespaces and new lines that follow a line continuation, up to the next
+/// non-whitespace char or the closing delimiter.
+fn eat_line_continuation(text: &mut Input) {
+    while let Some(grapheme) = text.text.get((text.pos + 1) as usize) {
+        if NEW_LINE_CHARS.contains(grapheme.as_str()) {
+            text.line += 1;
+        } else if grapheme != " " && grapheme != "\t" {
+            break;
+        }
+        text.pos += 1;
+    }
+}
+
 /// Gets a variable name char by char.
 fn get_var_name(text: &mut Input) -> Result<String, GuraError> {
     let key_acceptable_chars = Some(String::from(KEY_ACCEPTABLE_CHARS));
@@ -586,14 +2343,7 @@ fn compute_imports(text: &mut Input, parent_dir_path: Option<String>) -> Result<
 
     // First, consumes all the import sentences to replace all of them
     while text.pos < text.len {
-        let match_result = maybe_match(
-            text,
-            vec![
-                Box::new(gura_import),
-                Box::new(variable),
-                Box::new(useless_line),
-            ],
-        )?;
+        let match_result = maybe_match(text, &[gura_import, variable, useless_line])?;
         if match_result.is_none() {
             break;
         }
@@ -605,80 +2355,184 @@ fn compute_imports(text: &mut Input, parent_dir_path: Option<String>) -> Result<
     }
 
     let mut final_content = String::new();
+    let mut imported_regions = Vec::new();
+    let mut final_content_len: isize = 0;
 
     if !files_to_import.is_empty() {
-        for (mut file_to_import, origin_file_path) in files_to_import {
+        for (file_to_import, origin_file_path) in files_to_import {
             // Gets the final file path considering parent directory
-            if let Some(origin_path) = origin_file_path {
-                file_to_import = Path::new(&origin_path)
-                    .join(&file_to_import)
-                    .to_string_lossy()
-                    .to_string();
-            }
-
-            // Files can be imported only once. This prevents circular reference
-            if text.imported_files.contains(&file_to_import) {
-                return Err(GuraError {
-                    pos: text.pos - file_to_import.len() as isize - 1, // -1 for the quotes (")
-                    line: text.line,
-                    msg: format!("The file \"{}\" has been already imported", file_to_import),
-                    kind: Error::DuplicatedImportError,
-                });
+            let file_to_import = import_path(&file_to_import, origin_file_path.as_deref());
+
+            // Files can be imported only once. This prevents circular reference. Paths are
+            // canonicalized so the same file is detected however it is referenced
+            let canonical_path = text
+                .options
+                .platform
+                .canonicalize(Path::new(&file_to_import))
+                .map_or_else(
+                    |_| file_to_import.clone(),
+                    |path| path.to_string_lossy().to_string(),
+                );
+            if text.imported_files.contains(&canonical_path) {
+                if text.options.deduplicate_imports {
+                    trace_parser!("import of \"{}\" skipped, already imported", file_to_import);
+                    continue;
+                }
+                return Err(text.error(
+                    text.pos - file_to_import.len() as isize - 1, // -1 for the quotes (")
+                    text.line,
+                    format!("The file \"{}\" has been already imported", file_to_import),
+                    Error::DuplicatedImportError,
+                ));
             }
 
             // Gets content considering imports
-            let content = match fs::read_to_string(&file_to_import) {
+            let platform = &text.options.platform;
+            let content = match platform.read_to_string(Path::new(&file_to_import)) {
                 Ok(content) => content,
                 Err(_) => {
-                    return Err(GuraError {
-                        pos: 0,
-                        line: 0,
-                        msg: format!("The file \"{}\" does not exist", file_to_import),
-                        kind: Error::FileNotFoundError,
-                    });
+                    return Err(text.error(
+                        0,
+                        0,
+                        format!("The file \"{}\" does not exist", file_to_import),
+                        Error::FileNotFoundError,
+                    ));
                 }
             };
+            trace_parser!(
+                "importing \"{}\" from \"{}\"",
+                file_to_import,
+                canonical_path
+            );
+            text.imported_files.insert(canonical_path);
+            if text.options.deduplicate_imports
+                && !text
+                    .imported_contents
+                    .insert(fingerprint::fingerprint_text(&content))
+            {
+                trace_parser!(
+                    "import of \"{}\" skipped, same content as an imported file",
+                    file_to_import
+                );
+                continue;
+            }
+
+            // Imported files share the already imported ones, so they can not import them again
             let parent_dir_path = Path::new(&file_to_import).parent().unwrap();
             let mut empty_input = Input::new();
+            empty_input.options = text.options.clone();
+            empty_input.source_path = Some(PathBuf::from(&file_to_import));
+            empty_input.now = text.now;
+            empty_input.imported_files = std::mem::take(&mut text.imported_files);
+            empty_input.imported_contents = std::mem::take(&mut text.imported_contents);
             let content_with_import = get_text_with_imports(
                 &mut empty_input,
                 &content,
                 parent_dir_path.to_str().unwrap().to_owned(),
-            )?;
+            )
+            .map_err(|error| empty_input.report_error(error));
+            text.imported_files = std::mem::take(&mut empty_input.imported_files);
+            text.now = empty_input.now;
+            #[cfg(feature = "stats")]
+            text.stats.add(&empty_input.stats);
+            text.imported_contents = std::mem::take(&mut empty_input.imported_contents);
+            let content_with_import = content_with_import?;
+
+            // Keeps track of the files every piece of the text comes from
+            let content_len = content_with_import.len() as isize;
+            for (start, end, path) in empty_input.imported_regions {
+                imported_regions.push((start + final_content_len, end + final_content_len, path));
+            }
+            imported_regions.push((
+                final_content_len,
+                final_content_len + content_len,
+                PathBuf::from(&file_to_import),
+            ));
+            final_content_len += content_len + 1;
 
             final_content.push_str(&(content_with_import.iter().cloned().collect::<String>()));
             final_content.push('\n');
-
-            text.imported_files.insert(file_to_import);
         }
 
         // Sets as new text
         let pos_usize = (text.pos + 1) as usize;
         let rest_of_content = get_string_from_slice(&text.text[pos_usize..]);
 
+        // Keeps track of the original positions to report spans
+        let imported_graphemes = get_graphemes_cluster(&final_content);
+        let imported_lines = imported_graphemes
+            .iter()
+            .filter(|grapheme| NEW_LINE_CHARS.contains(grapheme.as_str()))
+            .count();
+        text.imported_len = imported_graphemes.len() as isize;
+        text.source_pos_offset = pos_usize as isize - text.imported_len;
+        text.source_line_offset = text.line as isize - (imported_lines as isize + 1);
+        let (rest_byte_offset, rest_utf16_offset) = text.offsets(pos_usize as isize);
+        text.source_byte_offset = rest_byte_offset as isize - final_content.len() as isize;
+        text.source_utf16_offset =
+            rest_utf16_offset as isize - final_content.encode_utf16().count() as isize;
+
         text.restart_params(&(final_content + &rest_of_content));
+        text.imported_regions = imported_regions;
     }
 
     Ok(())
 }
 
+/// Gets the path of an imported file, relative to the directory of the file that imports it (if
+/// any). Separators are normalized to the ones of the platform, so imports written in Windows
+/// work in other platforms and vice versa. Paths with a drive letter (`C:\`) or UNC paths
+/// (`\\server\share`) are absolute in every platform, so they are never joined with the
+/// directory.
+pub(crate) fn import_path(path: &str, parent_dir_path: Option<&str>) -> String {
+    let is_windows_absolute = path.starts_with("\\\\")
+        || path.starts_with("//")
+        || matches!(
+            path.as_bytes(),
+            [letter, b':', b'\\' | b'/', ..] if letter.is_ascii_alphabetic()
+        );
+    let path = if cfg!(windows) {
+        path.replace('/', "\\")
+    } else {
+        path.replace('\\', "/")
+    };
+
+    match parent_dir_path {
+        Some(parent_dir_path) if !is_windows_absolute => Path::new(parent_dir_path)
+            .join(&path)
+            .to_string_lossy()
+            .to_string(),
+        _ => path,
+    }
+}
+
 /// Matches with an already defined variable and gets its value.
 fn variable_value(text: &mut Input) -> RuleResult {
     // TODO: consider using char(text, vec![String::from("\"")])
+    let reference_start = text.pos + 1;
     keyword(text, &["$"])?;
 
-    if let GuraType::String(key_name) = matches(text, vec![Box::new(unquoted_string)])? {
+    if let GuraType::String(key_name) = matches(text, &[unquoted_string])? {
         let pos = text.pos - key_name.len() as isize;
         let line = text.line;
         let var_value = get_variable_value(text, &key_name, pos, line)?;
+        if text.is_spanned() {
+            text.record_variable_reference(
+                &key_name,
+                &var_value,
+                reference_start,
+                text.pos + 1,
+                line,
+            );
+        }
         Ok(var_value)
     } else {
-        Err(GuraError {
-            pos: text.pos,
-            line: text.line,
-            msg: String::from("Invalid variable name"),
-            kind: Error::ParseError,
-        })
+        Err(text.error(
+            text.pos,
+            text.line,
+            String::from("Invalid variable name"),
+            Error::ParseError,
+        ))
     }
 }
 
@@ -690,20 +2544,200 @@ fn variable_value(text: &mut Input) -> RuleResult {
 fn assert_end(text: &mut Input) -> Result<(), GuraError> {
     if text.pos < text.len {
         let error_pos = if !is_end_of_file(text) { text.pos + 1} else { text.pos };
-        Err(GuraError {
-            pos: error_pos,
-            line: text.line,
-            msg: format!(
+        let error = text.error(
+            error_pos,
+            text.line,
+            format!(
                 "Expected end of string but got \"{}\"",
                 text.text[error_pos as usize]
             ),
-            kind: Error::ParseError,
-        })
+            Error::ParseError,
+        );
+        match trailing_text_suggestion(text, error_pos as usize) {
+            Some(suggestion) => Err(error.with_suggestion(suggestion)),
+            None => Err(error),
+        }
     } else {
         Ok(())
     }
 }
 
+/// Checks that every array and string of the text is closed before parsing it, so the error
+/// points where they were opened instead of far away (e.g. at the end of the text). Quotes and
+/// brackets inside strings and comments are ignored.
+fn check_balance(text: &Input) -> Result<(), GuraError> {
+    // Positions and lines of the unclosed `[`
+    let mut open_brackets: Vec<(usize, usize)> = Vec::new();
+    // Position, line and quote of the unclosed string
+    let mut open_string: Option<(usize, usize, &str)> = None;
+    let mut in_comment = false;
+    let mut line = 1;
+    let mut pos = 0;
+    let is_quote_at = |pos: usize, quote: &str| {
+        text.text[pos..]
+            .iter()
+            .take(quote.len())
+            .filter(|grapheme| **grapheme == quote[..1])
+            .count()
+            == quote.len()
+    };
+
+    while pos < text.text.len() {
+        let grapheme = text.text[pos].as_str();
+        if NEW_LINE_CHARS.contains(grapheme) {
+            line += 1;
+            in_comment = false;
+        } else if let Some((_, _, quote)) = open_string {
+            if grapheme == "\\" && quote.starts_with('"') {
+                check_escape(text, pos, line)?;
+                // Skips the escaped char, unless it is a new line which must be counted
+                let is_escaping_new_line = text
+                    .text
+                    .get(pos + 1)
+                    .is_some_and(|escaped| NEW_LINE_CHARS.contains(escaped.as_str()));
+                if !is_escaping_new_line {
+                    pos += 1;
+                }
+            } else if is_quote_at(pos, quote) {
+                pos += quote.len();
+                open_string = None;
+                continue;
+            }
+        } else if !in_comment {
+            match grapheme {
+                "#" => in_comment = true,
+                "[" => open_brackets.push((pos, line)),
+                "]" if open_brackets.pop().is_none() => {
+                    return Err(text
+                        .error(
+                            pos as isize,
+                            line,
+                            String::from("Unexpected \"]\" without a matching \"[\""),
+                            Error::ParseError,
+                        )
+                        .with_suggestion(String::from(
+                            "Remove the \"]\", it does not close any array",
+                        )));
+                }
+                "\"" | "'" => {
+                    let is_multiline = text.text[pos..].iter().take(3).all(|char| char == grapheme);
+                    let quote = match (grapheme, is_multiline) {
+                        ("\"", true) => "\"\"\"",
+                        ("\"", false) => "\"",
+                        (_, true) => "'''",
+                        (_, false) => "'",
+                    };
+                    open_string = Some((pos, line, quote));
+                    pos += quote.len();
+                    continue;
+                }
+                _ => (),
+            }
+        }
+        pos += 1;
+    }
+
+    if let Some((pos, line, quote)) = open_string {
+        return Err(text
+            .error(
+                pos as isize,
+                line,
+                format!("Unterminated string opened at line {}", line),
+                Error::ParseError,
+            )
+            .with_suggestion(format!("Close the string with {}", quote)));
+    }
+    if let Some((pos, line)) = open_brackets.pop() {
+        return Err(text
+            .error(
+                pos as isize,
+                line,
+                format!("Unclosed \"[\" opened at line {}", line),
+                Error::ParseError,
+            )
+            .with_suggestion(String::from(
+                "Close the array with \"]\" and separate its elements with \",\"",
+            )));
+    }
+    Ok(())
+}
+
+/// Gets the number of hexadecimal digits of the escape sequences of code points (`\uHHHH`,
+/// `\UHHHHHHHH` and, if `ParserOptions::hex_escapes` is enabled, `\xHH`), or `None` for
+/// other escape sequences.
+fn code_point_digits(escape: &str, options: &ParserOptions) -> Option<usize> {
+    match escape {
+        "x" if options.hex_escapes => Some(2),
+        "u" => Some(4),
+        "U" => Some(8),
+        _ => None,
+    }
+}
+
+/// Checks that the escape sequence of a basic string starting at `pos` (the backslash) has
+/// the right number of hexadecimal digits and a valid code point, if it defines one.
+fn check_escape(text: &Input, pos: usize, line: usize) -> Result<(), GuraError> {
+    let escape = match text.text.get(pos + 1) {
+        Some(escape) => escape.as_str(),
+        None => return Ok(()),
+    };
+    let num_digits = match code_point_digits(escape, &text.options) {
+        Some(num_digits) => num_digits,
+        None => return Ok(()),
+    };
+
+    let digits: String = text.text[pos + 2..]
+        .iter()
+        .take(num_digits)
+        .take_while(|grapheme| grapheme.len() == 1 && grapheme.as_bytes()[0].is_ascii_hexdigit())
+        .cloned()
+        .collect();
+    if digits.len() < num_digits {
+        return Err(text
+            .error(
+                pos as isize,
+                line,
+                format!(
+                    "Invalid escape sequence \"\\{}{}\", expected {} hexadecimal digits",
+                    escape, digits, num_digits
+                ),
+                Error::ParseError,
+            )
+            .with_suggestion(format!(
+                "Write the code point with exactly {} hexadecimal digits or escape the \
+                 backslash with \"\\\\\"",
+                num_digits
+            )));
+    }
+
+    let is_valid = u32::from_str_radix(&digits, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .is_some();
+    if !is_valid {
+        return Err(text.error(
+            pos as isize,
+            line,
+            format!("Invalid code point \"\\{}{}\"", escape, digits),
+            Error::ParseError,
+        ));
+    }
+    Ok(())
+}
+
+/// Guesses the cause of a character found where the document should have ended.
+fn trailing_text_suggestion(text: &Input, pos: usize) -> Option<String> {
+    match text.text[pos].as_str() {
+        "]" => Some(String::from(
+            "Remove the \"]\", it does not close any array",
+        )),
+        "," => Some(String::from(
+            "Remove the \",\", pairs are separated by new lines",
+        )),
+        _ => None,
+    }
+}
+
 /// Generates a String from a slice of Strings (Grapheme clusters)
 fn get_string_from_slice(slice: &[String]) -> String {
     slice.iter().cloned().collect()
@@ -712,7 +2746,11 @@ fn get_string_from_slice(slice: &[String]) -> String {
 /// Generates a list of char from a list of char which could container char ranges (i.e. a-z or 0-9).
 ///
 /// Returns a Vec of Grapheme clusters vectors.
-fn split_char_ranges(text: &mut Input, chars: &str) -> Result<Vec<Vec<String>>, ValueError> {
+///
+/// # Errors
+///
+/// * InternalError - If a range is malformed (its bottom is not lower than its top).
+fn split_char_ranges(text: &mut Input, chars: &str) -> Result<Vec<Vec<String>>, GuraError> {
     if text.cache.contains_key(chars) {
         return Ok(text.cache.get(chars).unwrap().to_vec());
     }
@@ -725,7 +2763,16 @@ fn split_char_ranges(text: &mut Input, chars: &str) -> Result<Vec<Vec<String>>,
     while index < length {
         if index + 2 < length && chars_graph[index + 1] == "-" {
             if chars_graph[index] >= chars_graph[index + 2] {
-                return Err(ValueError {});
+                return Err(text.error(
+                    text.pos + 1,
+                    text.line,
+                    format!(
+                        "Invalid char range \"{}\" in [{}]",
+                        get_string_from_slice(&chars_graph[index..index + 3]),
+                        chars
+                    ),
+                    Error::InternalError,
+                ));
             }
 
             let some_chars = &chars_graph[index..index + 3];
@@ -747,18 +2794,18 @@ fn split_char_ranges(text: &mut Input, chars: &str) -> Result<Vec<Vec<String>>,
 /// `chars` argument can be a range like "a-zA-Z" and they will be properly handled.
 fn char(text: &mut Input, chars: &Option<String>) -> Result<String, GuraError> {
     if text.pos >= text.len {
-        return Err(GuraError {
-            pos: text.pos + 1,
-            line: text.line,
-            msg: format!(
+        return Err(text.error(
+            text.pos + 1,
+            text.line,
+            format!(
                 "Expected {} but got end of string",
                 match chars {
                     None => String::from("next character"),
                     Some(chars) => format!("[{}]", chars),
                 }
             ),
-            kind: Error::ParseError,
-        });
+            Error::ParseError,
+        ));
     }
 
     let next_char_pos = text.pos + 1;
@@ -770,8 +2817,7 @@ fn char(text: &mut Input, chars: &Option<String>) -> Result<String, GuraError> {
             Ok(next_char.to_string())
         }
         Some(chars_value) => {
-            // Unwrap is safe as ValueError can only raise if the crate contains a bug in a char range
-            for char_range in split_char_ranges(text, chars_value).unwrap() {
+            for char_range in split_char_ranges(text, chars_value)? {
                 if char_range.len() == 1 {
                     let next_char = &text.text[next_char_pos_usize];
                     if *next_char == char_range[0] {
@@ -789,55 +2835,72 @@ fn char(text: &mut Input, chars: &Option<String>) -> Result<String, GuraError> {
                 }
             }
 
-            Err(GuraError {
-                pos: next_char_pos,
-                line: text.line,
-                msg: format!(
+            Err(text.error(
+                next_char_pos,
+                text.line,
+                format!(
                     "Expected chars [{}] but got \"{}\"",
                     chars_value, text.text[next_char_pos_usize]
                 ),
-                kind: Error::ParseError,
-            })
+                Error::ParseError,
+            ))
         }
     }
 }
 
 /// Matches specific keywords. If any matched, it will raise a `ParseError`.
-fn keyword(text: &mut Input, keywords: &[&str]) -> Result<String, GuraError> {
+fn keyword<'k>(text: &mut Input, keywords: &[&'k str]) -> Result<&'k str, GuraError> {
+    if let Some(keyword) = match_keyword(text, keywords) {
+        return Ok(keyword);
+    }
+
     if text.pos >= text.len {
-        return Err(GuraError {
-            pos: text.pos,
-            line: text.line,
-            msg: format!(
+        return Err(text.error(
+            text.pos,
+            text.line,
+            format!(
                 "Expected \"{}\" but got end of string",
                 keywords.iter().join(", ")
             ),
-            kind: Error::ParseError,
-        });
-    }
-
-    for keyword in keywords {
-        let low = (text.pos + 1) as usize;
-        let high = (low + keyword.len()).min(text.text.len());
-        // This checking prevents index out of range
-        let substring = get_string_from_slice(&text.text[low..high]);
-        if substring == *keyword {
-            text.pos += keyword.len() as isize;
-            return Ok(keyword.to_string());
-        }
+            Error::ParseError,
+        ));
     }
 
     let error_pos = if !is_end_of_file(text) { text.pos + 1} else { text.pos };
-    Err(GuraError {
-        pos: error_pos,
-        line: text.line,
-        msg: format!(
+    Err(text.error(
+        error_pos,
+        text.line,
+        format!(
             "Expected \"{}\" but got \"{}\"",
             keywords.iter().join(", "),
             text.text[error_pos as usize]
         ),
-        kind: Error::ParseError,
-    })
+        Error::ParseError,
+    ))
+}
+
+/// Matches the first keyword found at the current position, comparing the text in place, so
+/// nothing is allocated (it is called for every char of strings to find their closing quote).
+/// Returns `None` without consuming anything if none of them is found.
+fn match_keyword<'k>(text: &mut Input, keywords: &[&'k str]) -> Option<&'k str> {
+    if text.pos >= text.len {
+        return None;
+    }
+
+    let low = (text.pos + 1) as usize;
+    for keyword in keywords {
+        // This checking prevents index out of range
+        let high = (low + keyword.len()).min(text.text.len());
+        let mut rest = Some(*keyword);
+        for grapheme in text.text[low..high].iter() {
+            rest = rest.and_then(|rest| rest.strip_prefix(grapheme.as_str()));
+        }
+        if rest == Some("") {
+            text.pos += keyword.len() as isize;
+            return Some(keyword);
+        }
+    }
+    None
 }
 
 /// Gets the Exception line and position considering indentation. Useful for InvalidIndentationError exceptions
@@ -854,19 +2917,39 @@ fn exception_data_with_initial_data(
 /// Matches specific rules. A rule does not match if its method raises `ParseError`.
 ///
 /// Returns the first matched rule method's result.
-fn matches(text: &mut Input, rules: Rules) -> RuleResult {
+fn matches(text: &mut Input, rules: &[Rule]) -> RuleResult {
     let mut last_error_pos: isize = -1;
     let mut last_exception: Option<GuraError> = None;
 
     for rule in rules {
+        #[cfg(feature = "stats")]
+        {
+            text.stats.rule_attempts += 1;
+        }
         let initial_pos = text.pos;
         let initial_line = text.line;
+        let initial_warnings = text.warnings.len();
+        trace_parser!(
+            "{} at line {} (position {}): {:?}",
+            rule_name(*rule),
+            initial_line,
+            initial_pos + 1,
+            text.upcoming_text()
+        );
         match rule(text) {
             Err(an_error) => {
                 // Only considers ParseError instances
                 if an_error.kind == Error::ParseError {
+                    trace_parser!(
+                        "{} failed ({}), backtracking to line {} (position {})",
+                        rule_name(*rule),
+                        an_error.msg,
+                        initial_line,
+                        initial_pos + 1
+                    );
                     text.pos = initial_pos;
                     text.line = initial_line;
+                    text.warnings.truncate(initial_warnings);
 
                     if an_error.pos > last_error_pos {
                         last_error_pos = an_error.pos;
@@ -874,10 +2957,23 @@ fn matches(text: &mut Input, rules: Rules) -> RuleResult {
                     }
                 } else {
                     // Any other kind of exception must be raised
+                    trace_parser!(
+                        "{} raised {:?}: {}",
+                        rule_name(*rule),
+                        an_error.kind,
+                        an_error.msg
+                    );
                     return Err(an_error);
                 }
             }
-            result => return result,
+            result => {
+                trace_parser!(
+                    "{} matched up to position {}",
+                    rule_name(*rule),
+                    text.pos + 1
+                );
+                return result;
+            }
         }
     }
 
@@ -885,6 +2981,43 @@ fn matches(text: &mut Input, rules: Rules) -> RuleResult {
     Err(last_exception.unwrap())
 }
 
+/// Gets the name of a rule of the grammar, for the traces of the parser.
+#[cfg(feature = "trace-parser")]
+fn rule_name(rule: Rule) -> &'static str {
+    let names: [(Rule, &str); 26] = [
+        (start, "start"),
+        (any_type, "any_type"),
+        (primitive_type, "primitive_type"),
+        (scalar_extension, "scalar_extension"),
+        (useless_line, "useless_line"),
+        (complex_type, "complex_type"),
+        (null, "null"),
+        (empty_object, "empty_object"),
+        (boolean, "boolean"),
+        (basic_string, "basic_string"),
+        (variable_value, "variable_value"),
+        (new_line, "new_line"),
+        (comment, "comment"),
+        (ws_with_indentation, "ws_with_indentation"),
+        (ws, "ws"),
+        (quoted_string_with_var, "quoted_string_with_var"),
+        (gura_import, "gura_import"),
+        (variable, "variable"),
+        (key, "key"),
+        (unquoted_string, "unquoted_string"),
+        (number, "number"),
+        (list, "list"),
+        (literal_string, "literal_string"),
+        (object, "object"),
+        (object_content, "object_content"),
+        (pair, "pair"),
+    ];
+    names
+        .iter()
+        .find(|(other, _)| std::ptr::fn_addr_eq(*other, rule))
+        .map_or("rule", |(_, name)| name)
+}
+
 // TODO: consider changing chars: &Option<&str>
 /// Like char() but returns None instead of raising ParseError
 fn maybe_char(text: &mut Input, chars: &Option<String>) -> Result<Option<String>, GuraError> {
@@ -901,7 +3034,7 @@ fn maybe_char(text: &mut Input, chars: &Option<String>) -> Result<Option<String>
 }
 
 /// Like match() but returns None instead of raising ParseError
-fn maybe_match(text: &mut Input, rules: Rules) -> Result<Option<GuraType>, GuraError> {
+fn maybe_match(text: &mut Input, rules: &[Rule]) -> Result<Option<GuraType>, GuraError> {
     match matches(text, rules) {
         Err(e) => {
             if e.kind == Error::ParseError {
@@ -915,17 +3048,8 @@ fn maybe_match(text: &mut Input, rules: Rules) -> Result<Option<GuraType>, GuraE
 }
 
 /// Like keyword() but returns None instead of raising ParseError
-fn maybe_keyword(text: &mut Input, keywords: &[&str]) -> Result<Option<String>, GuraError> {
-    match keyword(text, keywords) {
-        Err(e) => {
-            if e.kind == Error::ParseError {
-                Ok(None)
-            } else {
-                Err(e)
-            }
-        }
-        result => Ok(result.ok()),
-    }
+fn maybe_keyword<'k>(text: &mut Input, keywords: &[&'k str]) -> Result<Option<&'k str>, GuraError> {
+    Ok(match_keyword(text, keywords))
 }
 
 /// Converts a GuraType::ObjectWithWs in GuraType::Object.
@@ -938,48 +3062,397 @@ fn object_ws_to_simple_object(object: GuraType) -> GuraType {
     }
 }
 
-/// Parses a text in Gura format.
+/// Parses a text in Gura format.
+///
+/// # Examples
+///
+/// ```
+/// use gura::parse;
+///
+/// let gura_string = r##"
+/// title: "Gura Example"
+/// number: 13.4
+/// an_object:
+///     name: "John"
+///     surname: "Wick"
+///     has_pet: false
+/// "##.to_string();
+///
+/// let parsed = parse(&gura_string).unwrap();
+///
+/// assert_eq!("Gura Example", parsed["title"]);
+/// assert_eq!(13.4, parsed["number"]);
+///
+/// let obj = &parsed["an_object"];
+/// assert_eq!("John", obj["name"]);
+/// assert_eq!("Wick", obj["surname"]);
+/// assert_eq!(false, obj["has_pet"]);
+/// ```
+///
+/// # Errors
+///
+/// This function could throw any kind of error listed
+/// in [Gura specs](https://gura.netlify.app/docs/gura#standard-errors).
+pub fn parse(text: &str) -> RuleResult {
+    parse_with_options(text, &ParserOptions::default())
+}
+
+/// Like `parse` but customizing the parser behavior with `options`.
+///
+/// # Errors
+///
+/// This function could throw any kind of error listed
+/// in [Gura specs](https://gura.netlify.app/docs/gura#standard-errors).
+pub fn parse_with_options(text: &str, options: &ParserOptions) -> RuleResult {
+    parse_with_warnings(text, options).map(|(parsed, _)| parsed)
+}
+
+/// Like `parse` but reading the text from a stream (e.g. the standard input, a socket or a
+/// decompressor). The whole stream is buffered before parsing, as imports and error reports
+/// need the full text.
+///
+/// # Examples
+///
+/// ```
+/// use gura::parse_reader;
+///
+/// let stream: &[u8] = b"server:\n    port: 8080";
+/// let parsed = parse_reader(stream).unwrap();
+/// assert_eq!(parsed["server"]["port"], 8080);
+/// ```
+///
+/// # Errors
+///
+/// * Io - If the stream can not be read.
+/// * InvalidUtf8 - If the stream is not valid UTF-8.
+/// * Parse - If the text is not a valid Gura document.
+pub fn parse_reader<R: BufRead>(mut reader: R) -> Result<GuraType, ReadError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).map_err(ReadError::Io)?;
+    let text = String::from_utf8(bytes)
+        .map_err(|error| ReadError::InvalidUtf8(error.utf8_error().valid_up_to()))?;
+    parse(&text).map_err(ReadError::Parse)
+}
+
+/// Like `parse_with_options` but also returning the warnings found (see `ParseWarning`).
+///
+/// # Errors
+///
+/// This function could throw any kind of error listed
+/// in [Gura specs](https://gura.netlify.app/docs/gura#standard-errors).
+pub fn parse_with_warnings(
+    text: &str,
+    options: &ParserOptions,
+) -> Result<(GuraType, Vec<ParseWarning>), GuraError> {
+    let text_parser: &mut Input = &mut Input::new();
+    text_parser.options = options.effective();
+    parse_input(text_parser, text)
+}
+
+/// Like `parse_with_options` but also returning the timings and counters of the phases of the
+/// parsing (see the `stats` module). Requires the `stats` feature.
+///
+/// # Errors
+///
+/// This function could throw any kind of error listed
+/// in [Gura specs](https://gura.netlify.app/docs/gura#standard-errors).
+#[cfg(feature = "stats")]
+pub fn parse_with_stats(
+    text: &str,
+    options: &ParserOptions,
+) -> Result<(GuraType, ParseStats), GuraError> {
+    let text_parser: &mut Input = &mut Input::new();
+    text_parser.options = options.effective();
+    let start = Instant::now();
+    let (parsed, _) = parse_input(text_parser, text)?;
+
+    let mut stats = text_parser.stats;
+    stats.matching = start
+        .elapsed()
+        .saturating_sub(stats.segmentation + stats.imports);
+    stats.imported_files = text_parser.imported_files.len();
+    Ok((parsed, stats))
+}
+
+/// Parses a Gura file with `options`. Unlike `parse`, imports are relative to the directory of
+/// the file, and the built-in variables of `ParserOptions::builtin_variables` are available.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{parse_file, parser::ParserOptions};
+/// use std::fs;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// fs::write(dir.path().join("server.ura"), "port: 8080").unwrap();
+/// fs::write(dir.path().join("app.ura"), "import \"server.ura\"\nname: \"app\"").unwrap();
+///
+/// let parsed = parse_file(dir.path().join("app.ura"), &ParserOptions::default()).unwrap();
+/// assert_eq!(parsed["port"], 8080);
+/// ```
+///
+/// # Errors
+///
+/// * Io - If the file can not be read.
+/// * InvalidUtf8 - If the file is not valid UTF-8.
+/// * Parse - If the content is not a valid Gura document.
+pub fn parse_file<P: AsRef<Path>>(path: P, options: &ParserOptions) -> Result<GuraType, ReadError> {
+    let text = read_source(path.as_ref(), options)?;
+    parse_file_text(&text, path.as_ref(), options).map_err(ReadError::Parse)
+}
+
+/// Parses the text read from the file at `path` as `parse_file` does, with its imports relative
+/// to the directory of the file.
+pub(crate) fn parse_file_text(
+    text: &str,
+    path: &Path,
+    options: &ParserOptions,
+) -> Result<GuraType, GuraError> {
+    let text_parser: &mut Input = &mut Input::new();
+    text_parser.options = options.effective();
+    text_parser.source_path = Some(path.to_path_buf());
+    parse_input(text_parser, text).map(|(parsed, _)| parsed)
+}
+
+/// Reads a file to parse through the platform of the options.
+fn read_source(path: &Path, options: &ParserOptions) -> Result<String, ReadError> {
+    let bytes = options.platform.read_file(path).map_err(ReadError::Io)?;
+    String::from_utf8(bytes)
+        .map_err(|error| ReadError::InvalidUtf8(error.utf8_error().valid_up_to()))
+}
+
+/// Parses a text with a prepared input, returning the parsed object and the warnings found.
+fn parse_input(
+    text_parser: &mut Input,
+    text: &str,
+) -> Result<(GuraType, Vec<ParseWarning>), GuraError> {
+    text_parser.restart_params(text);
+    let result = check_balance(text_parser)
+        .and_then(|_| start(text_parser))
+        .and_then(|result| assert_end(text_parser).map(|_| result))
+        .map_err(|error| text_parser.report_error(error))?;
+
+    // Only objects are valid as final result
+    let parsed = match result {
+        GuraType::ObjectWithWs(values, _) => GuraType::Object(values),
+        _ => GuraType::Object(IndexMap::new()),
+    };
+    Ok((parsed, std::mem::take(&mut text_parser.warnings)))
+}
+
+/// Like `parse` but also keeping the location of every key in the text. Useful for tools that
+/// need to report problems at their exact source location.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{parse_spanned, span::Span};
+///
+/// let document = parse_spanned("title: \"Gura\"\nprot: 8080").unwrap();
+/// assert_eq!(document.value["prot"], 8080);
+/// let span = document.span("prot").unwrap();
+/// assert_eq!((span.start, span.end, span.line), (14, 18, 2));
+/// ```
+///
+/// # Errors
+///
+/// This function could throw any kind of error listed
+/// in [Gura specs](https://gura.netlify.app/docs/gura#standard-errors).
+pub fn parse_spanned(text: &str) -> Result<SpannedDocument, GuraError> {
+    parse_spanned_input(&mut Input::new(), text)
+}
+
+/// Parses a file as `parse_spanned` does, with its imports relative to its directory (as in
+/// `parse_file`).
+pub(crate) fn parse_spanned_file(
+    path: &Path,
+    options: &ParserOptions,
+) -> Result<SpannedDocument, ReadError> {
+    let text = read_source(path, options)?;
+
+    let text_parser: &mut Input = &mut Input::new();
+    text_parser.options = options.effective();
+    text_parser.source_path = Some(path.to_path_buf());
+    parse_spanned_input(text_parser, &text).map_err(ReadError::Parse)
+}
+
+/// Parses a text as `parse_spanned` does with some options, with its imports relative to
+/// `import_dir` (e.g. the directory of the file the text was read from) instead of the working
+/// directory, if it is given.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{parse_spanned_in, parser::ParserOptions};
+/// use std::fs;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// fs::write(dir.path().join("server.ura"), "port: 8080").unwrap();
+///
+/// let text = "import \"server.ura\"\nname: \"app\"";
+/// let document = parse_spanned_in(text, Some(dir.path()), &ParserOptions::default()).unwrap();
+/// assert_eq!(document.value["port"], 8080);
+/// assert_eq!(document.span("name").unwrap().line, 2);
+/// ```
+///
+/// # Errors
+///
+/// This function could throw any kind of error listed
+/// in [Gura specs](https://gura.netlify.app/docs/gura#standard-errors).
+pub fn parse_spanned_in(
+    text: &str,
+    import_dir: Option<&Path>,
+    options: &ParserOptions,
+) -> Result<SpannedDocument, GuraError> {
+    let text_parser: &mut Input = &mut Input::new();
+    text_parser.options = options.effective();
+    text_parser.import_dir = import_dir.map(Path::to_path_buf);
+    parse_spanned_input(text_parser, text)
+}
+
+/// Parses a text with a prepared input in spanned mode.
+fn parse_spanned_input(text_parser: &mut Input, text: &str) -> Result<SpannedDocument, GuraError> {
+    text_parser.spans = Some(SpanRecords::default());
+    text_parser.restart_params(text);
+    let result = check_balance(text_parser)
+        .and_then(|_| start(text_parser))
+        .and_then(|result| assert_end(text_parser).map(|_| result))
+        .map_err(|error| text_parser.report_error(error))?;
+
+    let value = match result {
+        GuraType::ObjectWithWs(values, _) => GuraType::Object(values),
+        _ => GuraType::Object(IndexMap::new()),
+    };
+
+    // Discards the spans of keys matched by rules that were finally discarded
+    let mut records = text_parser.spans.take().unwrap_or_default();
+    records
+        .key_spans
+        .retain(|path, _| value.get_path(path).is_some());
+    records
+        .pair_spans
+        .retain(|path, _| value.get_path(path).is_some());
+    text_parser.record_trivia(&mut records, &value);
+
+    Ok(SpannedDocument { value, records })
+}
+
+/// Parses several texts in Gura format as if they were concatenated, returning a single object.
+///
+/// Sources are parsed in order sharing the same variables: a variable defined in a source can
+/// be used in the following ones, and defining it again raises a `DuplicatedVariableError`.
+/// Keys defined at the root of more than one source raise a `DuplicatedKeyError`. Positions of
+/// errors are relative to the source where they were found.
+///
+/// # Examples
+///
+/// ```
+/// use gura::parse_many;
+///
+/// let base = "$host: \"localhost\"\nname: \"app\"";
+/// let server = "server:\n    host: $host\n    port: 80";
+///
+/// let parsed = parse_many(&[base, server]).unwrap();
+/// assert_eq!(parsed["name"], "app");
+/// assert_eq!(parsed["server"]["host"], "localhost");
+/// ```
+///
+/// # Errors
+///
+/// This function could throw any kind of error listed
+/// in [Gura specs](https://gura.netlify.app/docs/gura#standard-errors).
+pub fn parse_many(texts: &[&str]) -> RuleResult {
+    parse_many_with_scope(texts, &VariableScope::Shared)
+}
+
+/// Like `parse_many` but defining how variables are shared between sources.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{parse_many_with_scope, parser::VariableScope};
+///
+/// let base = "$path: \"/base\"\n$host: \"localhost\"\nhost: $host";
+/// let logs = "$path: \"/logs\"\nlogs: $path\nlogs_host: $host";
+///
+/// // Only the exported variables are visible from the following sources
+/// let scope = VariableScope::Export(vec![String::from("host")]);
+/// let parsed = parse_many_with_scope(&[base, logs], &scope).unwrap();
+/// assert_eq!(parsed["logs"], "/logs");
+/// assert_eq!(parsed["logs_host"], "localhost");
+/// ```
+///
+/// # Errors
+///
+/// This function could throw any kind of error listed
+/// in [Gura specs](https://gura.netlify.app/docs/gura#standard-errors).
+pub fn parse_many_with_scope(texts: &[&str], scope: &VariableScope) -> RuleResult {
+    let text_parser: &mut Input = &mut Input::new();
+    let mut result: IndexMap<String, GuraType> = IndexMap::new();
+    let mut exported_variables: HashMap<String, VariableValueType> = HashMap::new();
+
+    for text in texts {
+        text_parser.restart_params(text);
+        text_parser.indentation_levels.clear();
+        if *scope != VariableScope::Shared {
+            text_parser.variables = exported_variables.clone();
+        }
+
+        check_balance(text_parser)?;
+        if let GuraType::ObjectWithWs(values, _) = start(text_parser)? {
+            assert_end(text_parser)?;
+            for (key, value) in values {
+                text_parser.previous_keys.insert(key.clone());
+                result.insert(key, value);
+            }
+        } else {
+            assert_end(text_parser)?;
+        }
+
+        if let VariableScope::Export(names) = scope {
+            for name in names {
+                if let Some(value) = text_parser.variables.get(name) {
+                    exported_variables.insert(name.clone(), value.clone());
+                }
+            }
+        }
+    }
+
+    Ok(GuraType::Object(result))
+}
+
+/// Parses a text in Gura format and converts only the value located at `path` (keys separated
+/// by dots, as in `GuraType::get_path`). Useful to consume pieces of big documents.
 ///
 /// # Examples
 ///
 /// ```
-/// use gura::parse;
-///
-/// let gura_string = r##"
-/// title: "Gura Example"
-/// number: 13.4
-/// an_object:
-///     name: "John"
-///     surname: "Wick"
-///     has_pet: false
-/// "##.to_string();
-///
-/// let parsed = parse(&gura_string).unwrap();
+/// use gura::from_str_at;
 ///
-/// assert_eq!("Gura Example", parsed["title"]);
-/// assert_eq!(13.4, parsed["number"]);
+/// let gura_string = "services:\n    nginx:\n        port: 80";
+/// let port: u16 = from_str_at(gura_string, "services.nginx.port").unwrap();
+/// assert_eq!(port, 80);
 ///
-/// let obj = &parsed["an_object"];
-/// assert_eq!("John", obj["name"]);
-/// assert_eq!("Wick", obj["surname"]);
-/// assert_eq!(false, obj["has_pet"]);
+/// let err = from_str_at::<u16, _>(gura_string, "services.apache.port").unwrap_err();
+/// assert_eq!(err.to_string(), "The path \"services.apache.port\" does not exist");
 /// ```
 ///
 /// # Errors
 ///
-/// This function could throw any kind of error listed
-/// in [Gura specs](https://gura.netlify.app/docs/gura#standard-errors).
-pub fn parse(text: &str) -> RuleResult {
-    let text_parser: &mut Input = &mut Input::new();
-    text_parser.restart_params(text);
-    let result = start(text_parser)?;
-    assert_end(text_parser)?;
+/// * Parse - If the text is not a valid Gura document.
+/// * PathNotFound - If the path does not exist in the document.
+/// * Conversion - If the value at the path could not be converted into `T`.
+pub fn from_str_at<T, E>(text: &str, path: &str) -> Result<T, ExtractError<E>>
+where
+    T: for<'a> TryFrom<&'a GuraType, Error = E>,
+{
+    let parsed = parse(text).map_err(ExtractError::Parse)?;
+    parsed.extract(path)
+}
 
-    // Only objects are valid as final result
-    match result {
-        GuraType::ObjectWithWs(values, _) => Ok(GuraType::Object(values)),
-        _ => Ok(GuraType::Object(IndexMap::new())),
-    }
+/// Checks if a grapheme cluster is one of the new lines of `NEW_LINE_CHARS`.
+fn is_new_line_grapheme(grapheme: &str) -> bool {
+    matches!(grapheme, "\n" | "\r\n" | "\x0c" | "\x0b" | "\x08")
 }
 
 /// Matches with a new line. I.e any of the following chars:
@@ -988,8 +3461,12 @@ pub fn parse(text: &str) -> RuleResult {
 /// * \v - U+000B
 /// * \r - U+0008
 fn new_line(text: &mut Input) -> RuleResult {
-    let new_line_chars = Some(String::from(NEW_LINE_CHARS));
-    char(text, &new_line_chars)?;
+    if text.next_grapheme().is_some_and(is_new_line_grapheme) {
+        text.pos += 1;
+    } else {
+        // Reports the same error as the other chars
+        char(text, &Some(String::from(NEW_LINE_CHARS)))?;
+    }
 
     // If this line is reached then new line matched as no exception was raised
     text.line += 1;
@@ -1004,7 +3481,7 @@ fn comment(text: &mut Input) -> RuleResult {
         let pos_usize = (text.pos + 1) as usize;
         let char = &text.text[pos_usize];
         text.pos += 1;
-        if String::from(NEW_LINE_CHARS).contains(char) {
+        if NEW_LINE_CHARS.contains(char.as_str()) {
             text.line += 1;
             break;
         }
@@ -1017,24 +3494,26 @@ fn comment(text: &mut Input) -> RuleResult {
 fn ws_with_indentation(text: &mut Input) -> RuleResult {
     let mut current_indentation_level = 0;
 
-    while text.pos < text.len {
-        match maybe_keyword(text, &[" ", "\t"])? {
-            // If it is not a blank or new line, returns from the method
-            None => break,
-            Some(blank) => {
-                // Tabs are not allowed
-                if blank == "\t" {
-                    return Err(GuraError {
-                        pos: text.pos,
-                        line: text.line,
-                        msg: String::from("Tabs are not allowed to define indentation blocks"),
-                        kind: Error::InvalidIndentationError,
-                    });
-                }
+    while let Some(blank @ (" " | "\t")) = text.next_grapheme() {
+        let is_tab = blank == "\t";
+        text.pos += 1;
 
-                current_indentation_level += 1
-            }
+        // Tabs are not allowed
+        if is_tab {
+            return Err(text
+                .error(
+                    text.pos,
+                    text.line,
+                    String::from("Tabs are not allowed to define indentation blocks"),
+                    Error::InvalidIndentationError,
+                )
+                .with_suggestion(format!(
+                    "Replace every tab with {} spaces",
+                    text.options.indentation_unit()
+                )));
         }
+
+        current_indentation_level += 1
     }
 
     Ok(GuraType::Indentation(current_indentation_level))
@@ -1042,8 +3521,8 @@ fn ws_with_indentation(text: &mut Input) -> RuleResult {
 
 /// Matches white spaces (blanks and tabs).
 fn ws(text: &mut Input) -> RuleResult {
-    while maybe_keyword(text, &[" ", "\t"])?.is_some() {
-        continue;
+    while let Some(" " | "\t") = text.next_grapheme() {
+        text.pos += 1;
     }
 
     Ok(GuraType::WsOrNewLine)
@@ -1087,9 +3566,11 @@ fn quoted_string_with_var(text: &mut Input) -> RuleResult {
 
 /// Consumes all the whitespaces and new lines.
 fn eat_ws_and_new_lines(text: &mut Input) {
-    let ws_and_new_lines_chars = Some(" ".to_owned() + NEW_LINE_CHARS);
-    while let Ok(Some(_)) = maybe_char(text, &ws_and_new_lines_chars) {
-        continue;
+    while text
+        .next_grapheme()
+        .is_some_and(|grapheme| grapheme == " " || is_new_line_grapheme(grapheme))
+    {
+        text.pos += 1;
     }
 }
 
@@ -1106,26 +3587,57 @@ fn eat_ws_and_new_lines(text: &mut Input) {
 /// * VariableNotDefinedError - If the variable is not defined in file nor environment.
 fn get_variable_value(text: &mut Input, key: &str, position: isize, line: usize) -> RuleResult {
     match text.variables.get(key) {
-        Some(ref value) => match value {
-            VariableValueType::Integer(number_value) => Ok(GuraType::Integer(*number_value)),
-            VariableValueType::Float(number_value) => Ok(GuraType::Float(*number_value)),
-            VariableValueType::String(str_value) => Ok(GuraType::String(str_value.clone())),
-        },
-        _ => match env::var(key) {
-            Ok(value) => Ok(GuraType::String(value)),
-            Err(_) => Err(GuraError {
-                pos: position,
-                line,
-                msg: format!(
-                    "Variable \"{}\" is not defined in Gura nor as environment variable",
-                    key
-                ),
-                kind: Error::VariableNotDefinedError,
-            }),
+        Some(value) => Ok(value.to_gura_type()),
+        _ => match text.builtin_variable(key, position) {
+            Some(value) => Ok(value),
+            None => match text.options.platform.env_var(key) {
+                Some(value) => Ok(GuraType::String(value)),
+                None => Err(text.error(
+                    position,
+                    line,
+                    format!(
+                        "Variable \"{}\" is not defined in Gura nor as environment variable",
+                        key
+                    ),
+                    Error::VariableNotDefinedError,
+                )),
+            },
         },
     }
 }
 
+/// Formats a time (seconds since the Unix epoch) as an UTC date (`YYYY-MM-DD`) and time
+/// (`HH:MM:SS`) of the proleptic Gregorian calendar.
+fn utc_date_time(seconds: u64) -> (String, String) {
+    let (days, seconds) = ((seconds / 86400) as i64, seconds % 86400);
+
+    // Civil from days algorithm by Howard Hinnant, with eras of 400 years starting on March 1
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (
+        format!("{:04}-{:02}-{:02}", year, month, day),
+        format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        ),
+    )
+}
+
 /// Gets final text taking in consideration imports in original text.
 /// Returns Final text with imported files' text on it and a HashSet with imported files.
 ///
@@ -1148,19 +3660,24 @@ fn get_text_with_imports(
 fn gura_import(text: &mut Input) -> RuleResult {
     keyword(text, &["import"])?;
     char(text, &Some(String::from(" ")))?;
-    let string_match = matches(text, vec![Box::new(quoted_string_with_var)])?;
+    let path_start = text.pos + 1;
+    let path_line = text.line;
+    let string_match = matches(text, &[quoted_string_with_var])?;
 
     if let GuraType::String(file_to_import) = string_match {
-        matches(text, vec![Box::new(ws)])?;
-        maybe_match(text, vec![Box::new(new_line)])?;
+        if text.is_spanned() {
+            text.record_import(&file_to_import, path_start, text.pos + 1, path_line);
+        }
+        matches(text, &[ws])?;
+        maybe_match(text, &[new_line])?;
         Ok(GuraType::Import(file_to_import))
     } else {
-        Err(GuraError {
-            pos: text.pos,
-            line: text.line,
-            msg: String::from("Gura import invalid"),
-            kind: Error::ParseError,
-        })
+        Err(text.error(
+            text.pos,
+            text.line,
+            String::from("Gura import invalid"),
+            Error::ParseError,
+        ))
     }
 }
 
@@ -1174,29 +3691,25 @@ fn variable(text: &mut Input) -> RuleResult {
     let initial_line = text.line;
 
     keyword(text, &["$"])?;
-    let matched_key = matches(text, vec![Box::new(key)])?;
+    let matched_key = matches(text, &[key])?;
+    let key_end = text.pos; // Position of the colon
 
     if let GuraType::String(key_value) = matched_key {
-        maybe_match(text, vec![Box::new(ws)])?;
+        maybe_match(text, &[ws])?;
 
         let match_result = matches(
             text,
-            vec![
-                Box::new(basic_string),
-                Box::new(literal_string),
-                Box::new(number),
-                Box::new(variable_value),
-            ],
+            &[basic_string, literal_string, number, variable_value],
         )?;
 
         // Checks duplicated
         if text.variables.contains_key(&key_value) {
-            return Err(GuraError {
-                pos: initial_pos + 1,
-                line: initial_line,
-                msg: format!("Variable \"{}\" has been already declared", key_value),
-                kind: Error::DuplicatedVariableError,
-            });
+            return Err(text.error(
+                initial_pos + 1,
+                initial_line,
+                format!("Variable \"{}\" has been already declared", key_value),
+                Error::DuplicatedVariableError,
+            ));
         }
 
         let final_var_value: VariableValueType = match match_result {
@@ -1204,26 +3717,47 @@ fn variable(text: &mut Input) -> RuleResult {
             GuraType::Integer(var_value) => VariableValueType::Integer(var_value),
             GuraType::Float(var_value) => VariableValueType::Float(var_value),
             _ => {
-                return Err(GuraError {
-                    pos: text.pos,
-                    line: text.line,
-                    msg: String::from("Invalid variable value"),
-                    kind: Error::ParseError,
-                });
+                return Err(text.error(
+                    text.pos,
+                    text.line,
+                    String::from("Invalid variable value"),
+                    Error::ParseError,
+                ));
             }
         };
 
         // Store as variable
+        if text.is_spanned() {
+            text.record_variable(
+                &key_value,
+                &final_var_value,
+                initial_pos + 1,
+                key_end,
+                initial_line,
+            );
+        }
         text.variables.insert(key_value, final_var_value);
         Ok(GuraType::Variable)
     } else {
-        Err(GuraError {
-            pos: text.pos,
-            line: text.line,
-            msg: String::from("Key not found"),
-            kind: Error::ParseError,
-        })
+        Err(text.error(
+            text.pos,
+            text.line,
+            String::from("Key not found"),
+            Error::ParseError,
+        ))
+    }
+}
+
+/// Gets the position after the last char matched before the current one, skipping whitespaces
+/// and new lines but not going before `start`.
+fn last_non_whitespace_end(text: &Input, start: isize) -> isize {
+    let mut end = text.pos + 1;
+    while end > start
+        && matches!(text.text.get((end - 1) as usize), Some(grapheme) if grapheme.trim().is_empty())
+    {
+        end -= 1;
     }
+    end
 }
 
 /// Checks if it's the last position of the text.
@@ -1238,7 +3772,7 @@ fn is_end_of_file(text: &mut Input) -> bool {
 ///
 /// * ParseError - If key is not a valid string.
 fn key(text: &mut Input) -> RuleResult {
-    let matched_key = matches(text, vec![Box::new(unquoted_string)]);
+    let matched_key = matches(text, &[unquoted_string]);
 
     if matched_key.is_ok() {
         // TODO: try char
@@ -1246,15 +3780,15 @@ fn key(text: &mut Input) -> RuleResult {
         matched_key
     } else {
         let error_pos = if !is_end_of_file(text) { text.pos + 1} else { text.pos };
-        Err(GuraError {
-            pos: error_pos,
-            line: text.line,
-            msg: format!(
+        Err(text.error(
+            error_pos,
+            text.line,
+            format!(
                 "Expected string for key but got \"{}\"",
                 text.text[error_pos as usize]
             ),
-            kind: Error::ParseError,
-        })
+            Error::ParseError,
+        ))
     }
 }
 
@@ -1330,8 +3864,23 @@ fn number(text: &mut Input) -> RuleResult {
             _ => 2,
         };
 
-        let int_value = isize::from_str_radix(&without_prefix, base).unwrap();
-        return Ok(GuraType::Integer(int_value));
+        // Big values are parsed as in decimal numbers, so results do not depend on the size
+        // of isize
+        return match isize::from_str_radix(&without_prefix, base) {
+            Ok(value) => Ok(GuraType::Integer(value)),
+            Err(_) => match (
+                text.options.strict,
+                i128::from_str_radix(&without_prefix, base),
+            ) {
+                (false, Ok(value)) => Ok(GuraType::BigInteger(value)),
+                _ => Err(text.error(
+                    text.pos + 1,
+                    text.line,
+                    format!("\"{}\" is not a valid number", result),
+                    Error::ParseError,
+                )),
+            },
+        };
     }
 
     // Checks inf or NaN
@@ -1345,11 +3894,11 @@ fn number(text: &mut Input) -> RuleResult {
 
     match last_three_chars {
         "inf" => Ok(GuraType::Float(if result.starts_with('-') {
-            NEG_INFINITY
+            f64::NEG_INFINITY
         } else {
-            INFINITY
+            f64::INFINITY
         })),
-        "nan" => Ok(GuraType::Float(NAN)),
+        "nan" => Ok(GuraType::Float(f64::NAN)),
         _ => {
             // It's a normal number
             if number_type == NumberType::Integer {
@@ -1357,7 +3906,7 @@ fn number(text: &mut Input) -> RuleResult {
                     return Ok(GuraType::Integer(value));
                 } else {
                     // Tries 128 bit integer
-                    if let Ok(value) = result.parse::<i128>() {
+                    if let (false, Ok(value)) = (text.options.strict, result.parse::<i128>()) {
                         return Ok(GuraType::BigInteger(value));
                     }
                 }
@@ -1367,12 +3916,12 @@ fn number(text: &mut Input) -> RuleResult {
                 }
             }
 
-            Err(GuraError {
-                pos: text.pos + 1,
-                line: text.line,
-                msg: format!("\"{}\" is not a valid number", result),
-                kind: Error::ParseError,
-            })
+            Err(text.error(
+                text.pos + 1,
+                text.line,
+                format!("\"{}\" is not a valid number", result),
+                Error::ParseError,
+            ))
         }
     }
 }
@@ -1381,15 +3930,19 @@ fn number(text: &mut Input) -> RuleResult {
 fn list(text: &mut Input) -> RuleResult {
     let mut result: Vec<GuraType> = Vec::new();
 
-    maybe_match(text, vec![Box::new(ws)])?;
+    maybe_match(text, &[ws])?;
     // TODO: try char
     keyword(text, &["["])?;
     loop {
         // Discards useless lines between elements of array
-        match maybe_match(text, vec![Box::new(useless_line)])? {
+        match maybe_match(text, &[useless_line])? {
             Some(_) => continue,
             _ => {
-                match maybe_match(text, vec![Box::new(any_type)])? {
+                let element_start = text.pos + 1;
+                text.enter_path(result.len().to_string());
+                let item = maybe_match(text, &[any_type]);
+                text.leave_path();
+                match item? {
                     None => break,
                     Some(GuraType::BreakParent) => (),
                     Some(value) => {
@@ -1398,23 +3951,63 @@ fn list(text: &mut Input) -> RuleResult {
                     }
                 }
 
-                maybe_match(text, vec![Box::new(ws)])?;
-                maybe_match(text, vec![Box::new(new_line)])?;
+                // Values can consume the blanks that follow them
+                let element_end = last_non_whitespace_end(text, element_start);
+                let element_line = text.line
+                    - text.text[element_end as usize..(text.pos + 1) as usize]
+                        .iter()
+                        .filter(|grapheme| NEW_LINE_CHARS.contains(grapheme.as_str()))
+                        .count();
+                maybe_match(text, &[ws])?;
+                let is_new_line = maybe_match(text, &[new_line])?.is_some();
                 // TODO: try char()
                 if maybe_keyword(text, &[","])?.is_none() {
-                    break;
+                    if is_array_end(text) {
+                        break;
+                    }
+
+                    if !(is_new_line && text.options.lenient_arrays) {
+                        return Err(text
+                            .error(
+                                element_end,
+                                element_line,
+                                String::from("Expected \",\" or \"]\" after array element"),
+                                Error::ParseError,
+                            )
+                            .with_suggestion(String::from(
+                                "Separate the elements of the array with \",\"",
+                            )));
+                    }
+                    text.warn(
+                        element_end,
+                        element_line,
+                        String::from("Missing \",\" after array element"),
+                    );
                 }
             }
         }
     }
 
-    maybe_match(text, vec![Box::new(ws)])?;
-    maybe_match(text, vec![Box::new(new_line)])?;
+    maybe_match(text, &[ws])?;
+    maybe_match(text, &[new_line])?;
     // TODO: try char()
-    keyword(text, &["]"])?;
+    keyword(text, &["]"]).map_err(|error| {
+        error.with_suggestion(String::from(
+            "Close the array with \"]\" and separate its elements with \",\"",
+        ))
+    })?;
     Ok(GuraType::Array(result))
 }
 
+/// Checks if the next non blank character closes an array, without consuming anything.
+fn is_array_end(text: &Input) -> bool {
+    let start = (text.pos + 1) as usize;
+    text.text[start.min(text.text.len())..]
+        .iter()
+        .find(|grapheme| !grapheme.trim().is_empty())
+        .is_none_or(|grapheme| grapheme == "]")
+}
+
 /// Matches with a simple/multiline literal string.
 fn literal_string(text: &mut Input) -> RuleResult {
     let quote = keyword(text, &["'''", "'"])?;
@@ -1428,17 +4021,35 @@ fn literal_string(text: &mut Input) -> RuleResult {
     }
 
     let mut final_string = String::new();
+    // Length of the string before its last char, if it was a new line of the text
+    let mut last_new_line: Option<usize> = None;
 
     loop {
-        match maybe_keyword(text, &[&quote])? {
+        match maybe_keyword(text, &[quote])? {
             Some(_) => break,
             _ => {
+                let length_before = final_string.len();
                 let matched_char = char(text, &None)?;
-                final_string.push_str(&matched_char);
+                let is_new_line = NEW_LINE_CHARS.contains(matched_char.as_str());
+                if is_new_line {
+                    text.line += 1;
+                }
+                final_string.push_str(new_line_value(text, is_multiline, &matched_char));
+                last_new_line = if is_new_line {
+                    Some(length_before)
+                } else {
+                    None
+                };
             }
         }
     }
 
+    if is_multiline && text.options.trim_final_newline {
+        if let Some(length) = last_new_line {
+            final_string.truncate(length);
+        }
+    }
+
     Ok(GuraType::String(final_string))
 }
 
@@ -1448,25 +4059,32 @@ fn literal_string(text: &mut Input) -> RuleResult {
 ///
 /// * DuplicatedKeyError - If any of the defined key was declared more than once.
 fn object(text: &mut Input) -> RuleResult {
+    // Keeps track of nesting to distinguish the root object from the nested ones
+    text.object_depth += 1;
+    let result = object_content(text);
+    text.object_depth -= 1;
+    result
+}
+
+/// Matches with the content of a Gura object. See `object()`.
+fn object_content(text: &mut Input) -> RuleResult {
     let mut result: IndexMap<String, GuraType> = IndexMap::new();
     let mut indentation_level = 0;
     while text.pos < text.len {
         let initial_pos = text.pos;
         let initial_line = text.line;
 
-        match matches(
-            text,
-            vec![Box::new(variable), Box::new(pair), Box::new(useless_line)],
-        )? {
+        match matches(text, &[variable, pair, useless_line])? {
             GuraType::BreakParent => break,
             GuraType::Pair(key, value, indentation) => {
-                if result.contains_key(&key) {
-                    return Err(GuraError {
-                        pos: initial_pos + 1 + indentation as isize,
-                        line: initial_line,
-                        msg: format!("The key \"{}\" has been already defined", key),
-                        kind: Error::DuplicatedKeyError,
-                    });
+                let is_previous_key = text.object_depth == 1 && text.previous_keys.contains(&key);
+                if result.contains_key(&key) || is_previous_key {
+                    return Err(text.error(
+                        initial_pos + 1 + indentation as isize,
+                        initial_line,
+                        format!("The key \"{}\" has been already defined", key),
+                        Error::DuplicatedKeyError,
+                    ));
                 }
 
                 result.insert(key, *value);
@@ -1476,7 +4094,7 @@ fn object(text: &mut Input) -> RuleResult {
         }
 
         let initial_pos = text.pos;
-        maybe_match(text, vec![Box::new(ws)])?;
+        maybe_match(text, &[ws])?;
         if maybe_keyword(text, &["]", ","])?.is_some() {
             // Breaks if it is the end of a list
             text.remove_last_indentation_level();
@@ -1498,28 +4116,37 @@ fn object(text: &mut Input) -> RuleResult {
 fn pair(text: &mut Input) -> RuleResult {
     let pos_before_pair = text.pos; // To report correct position in case of exception
 
-    if let GuraType::Indentation(current_indentation_level) =
-        matches(text, vec![Box::new(ws_with_indentation)])?
+    if let GuraType::Indentation(current_indentation_level) = matches(text, &[ws_with_indentation])?
     {
-        let matched_key = matches(text, vec![Box::new(key)])?;
+        let key_start = text.pos + 1;
+        let key_line = text.line;
+        let matched_key = matches(text, &[key])?;
+        let key_end = text.pos; // Position of the colon
 
         if let GuraType::String(key_value) = matched_key {
-            maybe_match(text, vec![Box::new(ws)])?;
+            maybe_match(text, &[ws])?;
 
             // Check indentation
             let last_indentation_block = get_last_indentation_level(text);
 
-            // Check if indentation is divisible by 4
-            if current_indentation_level % 4 != 0 {
-                return Err(GuraError {
-                    pos: pos_before_pair,
-                    line: text.line,
-                    msg: format!(
-                        "Indentation block ({}) must be divisible by 4",
-                        current_indentation_level
-                    ),
-                    kind: Error::InvalidIndentationError,
-                });
+            // Check if indentation is divisible by the indentation unit (4 by default)
+            let unit = text.options.indentation_unit();
+            if !current_indentation_level.is_multiple_of(unit) {
+                return Err(text
+                    .error(
+                        pos_before_pair,
+                        text.line,
+                        format!(
+                            "Indentation block ({}) must be divisible by {}",
+                            current_indentation_level, unit
+                        ),
+                        Error::InvalidIndentationError,
+                    )
+                    .with_suggestion(format!(
+                        "Indent the line with {} or {} spaces",
+                        current_indentation_level / unit * unit,
+                        current_indentation_level / unit * unit + unit
+                    )));
             }
 
             if let Some(last_indentation_block_val) = last_indentation_block {
@@ -1538,12 +4165,16 @@ fn pair(text: &mut Input) -> RuleResult {
             } else {
                 // If it's the first pair, the indentation level is should be 0
                 if current_indentation_level > 0 {
-                    return Err(GuraError {
-                        pos: pos_before_pair,
-                        line: text.line,
-                        msg: String::from("First pair must have indentation level 0"),
-                        kind: Error::InvalidIndentationError,
-                    });
+                    return Err(text
+                        .error(
+                            pos_before_pair,
+                            text.line,
+                            String::from("First pair must have indentation level 0"),
+                            Error::InvalidIndentationError,
+                        )
+                        .with_suggestion(String::from(
+                            "Remove the indentation of the first pair",
+                        )));
                 }
 
                 text.indentation_levels.push(current_indentation_level);
@@ -1553,17 +4184,24 @@ fn pair(text: &mut Input) -> RuleResult {
             let initial_pos = text.pos;
             let initial_line = text.line;
 
+            if text.is_spanned() {
+                text.record_key_span(&key_value, key_start, key_end, key_line);
+            }
+
             // If it is a BreakParent indicator then is an empty expression, and therefore invalid
-            let matched_any = matches(text, vec![Box::new(any_type)])?;
-            let mut result: Box<GuraType> = Box::new(matched_any.clone());
-            match matched_any {
+            text.enter_path(key_value.clone());
+            let matched_any = matches(text, &[any_type]);
+            text.leave_path();
+            let matched_any = matched_any?;
+            // The matched value is moved into the pair, so nested objects are never copied
+            let result: Box<GuraType> = Box::new(match matched_any {
                 GuraType::BreakParent => {
-                    return Err(GuraError {
-                        pos: text.pos + 1,
-                        line: text.line,
-                        msg: String::from("Invalid pair"),
-                        kind: Error::ParseError,
-                    });
+                    return Err(text.error(
+                        text.pos + 1,
+                        text.line,
+                        String::from("Invalid pair"),
+                        Error::ParseError,
+                    ));
                 }
                 GuraType::ObjectWithWs(object_values, child_indentation_level) => {
                     if child_indentation_level == current_indentation_level {
@@ -1575,35 +4213,60 @@ fn pair(text: &mut Input) -> RuleResult {
                         );
                         let child_key = object_values.keys().next().unwrap();
 
-                        return Err(GuraError {
-                            pos: exception_pos,
-                            line: exception_line,
-                            msg: format!("Wrong indentation level for pair with key \"{}\" (parent \"{}\" has the same indentation level)", child_key, key_value),
-                            kind: Error::InvalidIndentationError,
-                        });
+                        return Err(text.error(
+                            exception_pos,
+                            exception_line,
+                            format!("Wrong indentation level for pair with key \"{}\" (parent \"{}\" has the same indentation level)", child_key, key_value),
+                            Error::InvalidIndentationError,
+                        )
+                        .with_suggestion(format!(
+                            "Indent the pairs of \"{}\" {} spaces more than it",
+                            key_value, unit
+                        )));
                     } else {
                         let diff = current_indentation_level.max(child_indentation_level)
                             - current_indentation_level.min(child_indentation_level);
-                        if diff != 4 {
+                        if diff != unit {
                             let (exception_line, exception_pos) = exception_data_with_initial_data(
                                 child_indentation_level,
                                 initial_line,
                                 initial_pos,
                             );
-                            return Err(GuraError {
-                                pos: exception_pos,
-                                line: exception_line,
-                                msg: String::from(
-                                    "Difference between different indentation levels must be 4",
+                            return Err(text
+                                .error(
+                                    exception_pos,
+                                    exception_line,
+                                    format!(
+                                    "Difference between different indentation levels must be {}",
+                                    unit
                                 ),
-                                kind: Error::InvalidIndentationError,
-                            });
+                                    Error::InvalidIndentationError,
+                                )
+                                .with_suggestion(format!(
+                                    "Indent nested pairs exactly {} spaces more than their parent",
+                                    unit
+                                )));
                         }
                     }
 
-                    result = Box::new(GuraType::Object(object_values));
+                    GuraType::Object(object_values)
                 }
-                _ => (),
+                value => value,
+            });
+
+            if text.is_spanned() {
+                let last_child = match &*result {
+                    GuraType::Object(object_values) => object_values.keys().last().cloned(),
+                    _ => None,
+                };
+                let value_end = last_non_whitespace_end(text, initial_pos + 1);
+                text.record_pair_span(
+                    &key_value,
+                    key_start,
+                    value_end,
+                    key_line,
+                    last_child.as_deref(),
+                );
             }
 
             // Prevents issues with indentation inside a list that break objects
@@ -1612,29 +4275,253 @@ fn pair(text: &mut Input) -> RuleResult {
                 text.indentation_levels.push(current_indentation_level);
             }
 
-            maybe_match(text, vec![Box::new(new_line)])?;
+            maybe_match(text, &[new_line])?;
 
             Ok(GuraType::Pair(key_value, result, current_indentation_level))
         } else {
-            Err(GuraError {
-                pos: text.pos,
-                line: text.line,
-                msg: String::from("Invalid key"),
-                kind: Error::ParseError,
-            })
+            Err(text.error(
+                text.pos,
+                text.line,
+                String::from("Invalid key"),
+                Error::ParseError,
+            ))
         }
     } else {
-        Err(GuraError {
-            pos: text.pos,
-            line: text.line,
-            msg: String::from("Invalid indentation value"),
-            kind: Error::ParseError,
-        })
+        Err(text.error(
+            text.pos,
+            text.line,
+            String::from("Invalid indentation value"),
+            Error::ParseError,
+        ))
+    }
+}
+
+/// Marker of the content omitted by `dump_content_with_options`
+const ELLIPSIS: &str = "…";
+
+/// Defines what to do with NaN values when dumping with `try_dump`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NanPolicy {
+    /// Emits `nan`
+    #[default]
+    Emit,
+    /// Fails with `DumpErrorKind::NaN`, for consumers that must guarantee NaN-free output
+    Error,
+}
+
+/// Options to customize the output of `try_dump`.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{object, parser::{DumpOptions, NanPolicy}, try_dump, GuraType};
+///
+/// let object = object! { offset: -0.0, ratio: f64::NAN };
+///
+/// let options = DumpOptions::default().preserve_negative_zero(true);
+/// assert_eq!(try_dump(&object, &options).unwrap(), "offset: -0.0\nratio: nan");
+///
+/// let options = DumpOptions::default().nan_policy(NanPolicy::Error);
+/// assert_eq!(try_dump(&object, &options).unwrap_err().path, "ratio");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DumpOptions {
+    preserve_negative_zero: bool,
+    nan_policy: NanPolicy,
+    /// Minimum number of occurrences of a value to define it as a variable
+    min_variable_occurrences: Option<usize>,
+    /// Levels of nested objects to dump. Deeper objects are replaced by `…`
+    pub(crate) max_depth: Option<usize>,
+    /// Number of items to dump of every array. The rest are replaced by a single `…`
+    pub(crate) max_array_items: Option<usize>,
+    /// Number of spaces of every indentation level. `None` for the 4 of the specification
+    indent_unit: Option<usize>,
+}
+
+impl DumpOptions {
+    /// Emits `-0.0` for negative zeros instead of `0.0`. Disabled by default.
+    pub fn preserve_negative_zero(mut self, preserve: bool) -> Self {
+        self.preserve_negative_zero = preserve;
+        self
+    }
+
+    /// Defines what to do with NaN values. By default `nan` is emitted.
+    pub fn nan_policy(mut self, policy: NanPolicy) -> Self {
+        self.nan_policy = policy;
+        self
+    }
+
+    /// Defines strings and numbers found at least `min_occurrences` times (and at least twice)
+    /// as variables at the beginning of the document, referencing them where they were found.
+    /// Big integers are kept in place, as variables can not hold them. Variables are named
+    /// after the first key where their value was found. Only applies when dumping objects.
+    /// Disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{object, parser::DumpOptions, try_dump, GuraType};
+    ///
+    /// let object = object! { primary: { host: "db.local" }, replica: { host: "db.local" } };
+    /// let options = DumpOptions::default().factor_variables(2);
+    ///
+    /// let expected = "$host: \"db.local\"\n\nprimary:\n    host: $host\nreplica:\n    host: $host";
+    /// assert_eq!(try_dump(&object, &options).unwrap(), expected);
+    /// ```
+    pub fn factor_variables(mut self, min_occurrences: usize) -> Self {
+        self.min_variable_occurrences = Some(min_occurrences.max(2));
+        self
+    }
+
+    /// Indents nested objects and array elements with `spaces` spaces (at least 1) instead of
+    /// 4. Other units are not part of the specification: the output can only be parsed with
+    /// `ParserOptions::indent_unit` set to the same number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{object, parser::DumpOptions, try_dump, GuraType};
+    ///
+    /// let object = object! { server: { host: "localhost" } };
+    /// let options = DumpOptions::default().indent_unit(2);
+    /// assert_eq!(try_dump(&object, &options).unwrap(), "server:\n  host: \"localhost\"");
+    /// ```
+    pub fn indent_unit(mut self, spaces: usize) -> Self {
+        self.indent_unit = Some(spaces.max(1));
+        self
+    }
+
+    /// Gets the indentation of one level.
+    fn indentation(&self) -> String {
+        self.indent_unit
+            .map_or_else(|| INDENT.to_string(), |spaces| " ".repeat(spaces))
+    }
+}
+
+/// Dumped values to be replaced by a variable, with the name of the variable
+type DumpVariables = IndexMap<String, String>;
+
+/// Checks if a value can be defined as a variable by `DumpOptions::factor_variables`. Big
+/// integers are not, as variables can not hold them.
+fn is_factorable(value: &GuraType) -> bool {
+    matches!(
+        value,
+        GuraType::String(_) | GuraType::Integer(_) | GuraType::Float(_)
+    )
+}
+
+/// Counts the occurrences of the factorable values of `content`, placed at `key`. Stores the
+/// first key where every dumped value was found along with its number of occurrences.
+fn count_values(
+    content: &GuraType,
+    key: &str,
+    options: &DumpOptions,
+    occurrences: &mut IndexMap<String, (String, usize)>,
+) {
+    match content {
+        GuraType::Object(values) => {
+            for (key, value) in values {
+                count_values(value, key, options, occurrences);
+            }
+        }
+        GuraType::Array(values) => {
+            for value in values {
+                count_values(value, key, options, occurrences);
+            }
+        }
+        _ if is_factorable(content) => {
+            let dumped = dump_content_with_options(content, options, 0);
+            occurrences
+                .entry(dumped)
+                .or_insert_with(|| (key.to_string(), 0))
+                .1 += 1;
+        }
+        _ => (),
+    }
+}
+
+/// Gets the values of `content` repeated at least `min_occurrences` times with a unique
+/// variable name for each of them.
+fn repeated_values(
+    content: &GuraType,
+    options: &DumpOptions,
+    min_occurrences: usize,
+) -> DumpVariables {
+    let mut occurrences = IndexMap::new();
+    count_values(content, "", options, &mut occurrences);
+
+    let mut variables = DumpVariables::new();
+    for (dumped, (key, count)) in occurrences {
+        if count < min_occurrences {
+            continue;
+        }
+
+        let mut base_name: String = key
+            .chars()
+            .map(|char| {
+                if char.is_ascii_alphanumeric() {
+                    char
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        if base_name.is_empty() {
+            base_name = String::from("value");
+        }
+
+        let mut name = base_name.clone();
+        let mut suffix = 2;
+        while variables.values().any(|used| *used == name) {
+            name = format!("{}_{}", base_name, suffix);
+            suffix += 1;
+        }
+        variables.insert(dumped, name);
     }
+    variables
 }
 
 /// Auxiliary function for dumping
 fn dump_content(content: &GuraType) -> String {
+    dump_content_with_options(content, &DumpOptions::default(), 0)
+}
+
+/// Checks if a value is an object that must be omitted as it is placed at `depth`.
+fn is_elided_object(value: &GuraType, options: &DumpOptions, depth: usize) -> bool {
+    match value {
+        GuraType::Object(values) => {
+            !values.is_empty() && matches!(options.max_depth, Some(max_depth) if depth >= max_depth)
+        }
+        _ => false,
+    }
+}
+
+/// Like `dump_content` but omitting the content that exceeds `options`. `depth` is the number
+/// of objects that contain `content`.
+pub(crate) fn dump_content_with_options(
+    content: &GuraType,
+    options: &DumpOptions,
+    depth: usize,
+) -> String {
+    dump_content_with_variables(content, options, &DumpVariables::new(), depth)
+}
+
+/// Like `dump_content_with_options` but replacing the values found in `variables` by a
+/// reference to their variable.
+fn dump_content_with_variables(
+    content: &GuraType,
+    options: &DumpOptions,
+    variables: &DumpVariables,
+    depth: usize,
+) -> String {
+    if !variables.is_empty() && is_factorable(content) {
+        let dumped = dump_content_with_options(content, options, depth);
+        if let Some(name) = variables.get(&dumped) {
+            return format!("${}", name);
+        }
+        return dumped;
+    }
+
     match content {
         GuraType::Null => "null".to_string(),
         GuraType::String(str_content) => {
@@ -1665,6 +4552,9 @@ fn dump_content(content: &GuraType) -> String {
                 } else {
                     String::from("-inf")
                 };
+            } else if *number == 0.0 && number.is_sign_negative() && options.preserve_negative_zero
+            {
+                value = String::from("-0.0");
             } else {
                 value = format!("{}", PrettyPrintFloatWithFallback(*number));
             }
@@ -1672,39 +4562,59 @@ fn dump_content(content: &GuraType) -> String {
             value
         }
         GuraType::Bool(bool_value) => bool_value.to_string(),
+        GuraType::Extension(tag, value) => format!("@{} {}", tag, value.raw_display()),
         GuraType::Pair(key, value, _) => format!("{}: {}", key, value),
         GuraType::Object(values) => {
             if values.is_empty() {
                 return "empty".to_string();
             }
 
+            let indent = options.indentation();
             let mut result = String::new();
             for (key, gura_value) in values.iter() {
                 let _ = write!(result, "{}:", key);
 
+                if is_elided_object(gura_value, options, depth) {
+                    let _ = writeln!(result, " {}", ELLIPSIS);
+                    continue;
+                }
+
                 // If the value is an object, splits the stringified value by
                 // newline and indents each line before adding it to the result
                 if let GuraType::Object(obj) = gura_value {
-                    let dumped = dump_content(gura_value);
+                    let dumped =
+                        dump_content_with_variables(gura_value, options, variables, depth + 1);
                     let stringified_value = dumped.trim_end();
                     if !obj.is_empty() {
                         result.push('\n');
 
                         for line in stringified_value.split('\n') {
-                            let _ = writeln!(result, "{}{}", INDENT, line);
+                            let _ = writeln!(result, "{}{}", indent, line);
                         }
                     } else {
                         // Prevents indentation on empty objects
                         let _ = writeln!(result, " {}", stringified_value);
                     }
                 } else {
-                    let _ = writeln!(result, " {}", dump_content(gura_value));
+                    let _ = writeln!(
+                        result,
+                        " {}",
+                        dump_content_with_variables(gura_value, options, variables, depth)
+                    );
                 }
             }
 
             result
         }
         GuraType::Array(array) => {
+            let shown_items = options
+                .max_array_items
+                .map_or(array.len(), |max_array_items| {
+                    max_array_items.min(array.len())
+                });
+            let is_truncated = shown_items < array.len();
+            let array = &array[..shown_items];
+
             // Lists are a special case: if it has an object, and indented representation must be returned. In case
             // of primitive values or nested arrays, a plain representation is more appropriated
             let should_multiline = array.iter().any(|e| {
@@ -1715,17 +4625,32 @@ fn dump_content(content: &GuraType) -> String {
                 }
             });
 
+            let mut stringify_values: Vec<String> = array
+                .iter()
+                .map(|elem| {
+                    if is_elided_object(elem, options, depth) {
+                        String::from(ELLIPSIS)
+                    } else if let GuraType::Object(_) = elem {
+                        dump_content_with_variables(elem, options, variables, depth + 1)
+                    } else {
+                        dump_content_with_variables(elem, options, variables, depth)
+                    }
+                })
+                .collect();
+            if is_truncated {
+                stringify_values.push(String::from(ELLIPSIS));
+            }
+
             if !should_multiline {
-                let stringify_values: Vec<String> = array.iter().map(dump_content).collect();
                 let joined = stringify_values.iter().cloned().join(", ");
                 return format!("[{}]", joined);
             }
 
+            let indent = options.indentation();
             let mut result = String::from("[");
-            let last_idx = array.len() - 1;
+            let last_idx = stringify_values.len() - 1;
 
-            for (idx, elem) in array.iter().enumerate() {
-                let dumped = dump_content(elem);
+            for (idx, dumped) in stringify_values.iter().enumerate() {
                 let stringified_value = dumped.trim_end();
 
                 result.push('\n');
@@ -1735,12 +4660,12 @@ fn dump_content(content: &GuraType) -> String {
                 if stringified_value.contains('\n') {
                     let splitted = stringified_value.split('\n');
                     let splitted: Vec<String> = splitted
-                        .map(|element| format!("{}{}", INDENT, element))
+                        .map(|element| format!("{}{}", indent, element))
                         .collect();
                     result += &splitted.iter().cloned().join("\n");
                 } else {
                     // Otherwise indent the value and add to result
-                    let _ = write!(result, "{}{}", INDENT, stringified_value);
+                    let _ = write!(result, "{}{}", indent, stringified_value);
                 }
 
                 // Add a comma if this entry is not the final entry in the list
@@ -1756,6 +4681,81 @@ fn dump_content(content: &GuraType) -> String {
     }
 }
 
+/// Auxiliary function for compact (single line) representations used by `Display`
+fn dump_compact(content: &GuraType) -> String {
+    match content {
+        GuraType::Object(values) if !values.is_empty() => {
+            let pairs = values
+                .iter()
+                .map(|(key, value)| format!("{}: {}", key, dump_compact(value)))
+                .join(", ");
+            format!("{{{}}}", pairs)
+        }
+        GuraType::Array(array) => {
+            let values = array.iter().map(dump_compact).join(", ");
+            format!("[{}]", values)
+        }
+        _ => dump_content(content),
+    }
+}
+
+/// Auxiliary function of `GuraType::to_debug_normalized`
+fn write_debug_normalized(result: &mut String, content: &GuraType) {
+    match content {
+        GuraType::Null => result.push_str("Null"),
+        GuraType::Bool(value) => {
+            let _ = write!(result, "Bool({})", value);
+        }
+        GuraType::String(value) => {
+            let _ = write!(result, "String({:?})", value);
+        }
+        GuraType::Integer(value) => {
+            let _ = write!(result, "Int({})", value);
+        }
+        GuraType::BigInteger(value) => {
+            let _ = write!(result, "Int({})", value);
+        }
+        GuraType::Float(value) => {
+            let _ = write!(result, "Float({:?})", value);
+        }
+        GuraType::Extension(tag, value) => {
+            let _ = write!(result, "Extension({}, ", tag);
+            write_debug_normalized(result, value);
+            result.push(')');
+        }
+        GuraType::Array(values) => {
+            result.push_str("Array[");
+            for (index, value) in values.iter().enumerate() {
+                if index > 0 {
+                    result.push_str(", ");
+                }
+                write_debug_normalized(result, value);
+            }
+            result.push(']');
+        }
+        GuraType::Object(values) | GuraType::ObjectWithWs(values, _) => {
+            result.push_str("Object{");
+            for (index, (key, value)) in values.iter().sorted_by_key(|(key, _)| *key).enumerate() {
+                if index > 0 {
+                    result.push_str(", ");
+                }
+                if is_valid_key(key) {
+                    result.push_str(key);
+                } else {
+                    let _ = write!(result, "{:?}", key);
+                }
+                result.push_str(": ");
+                write_debug_normalized(result, value);
+            }
+            result.push('}');
+        }
+        // Internal values are not normalized
+        _ => {
+            let _ = write!(result, "{:?}", content);
+        }
+    }
+}
+
 /// Generates a Gura string from a GuraType (aka.stringify).
 ///
 /// # Examples
@@ -1787,3 +4787,149 @@ fn dump_content(content: &GuraType) -> String {
 pub fn dump(content: &GuraType) -> String {
     dump_content(content).trim().to_string()
 }
+
+/// Like `dump` but omitting content to get a short representation, useful for logging.
+/// Objects nested in more than `max_depth` objects are replaced by `…`, and only the first
+/// `max_array_items` items of every array are dumped, followed by `…` if there are more.
+/// The result is not valid Gura if something was omitted.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{dump_truncated, object, GuraType};
+///
+/// let object = object! {
+///     ports: [80, 443, 8080, 8443],
+///     server: { tls: { enabled: true } }
+/// };
+///
+/// let expected = "ports: [80, 443, …]\nserver:\n    tls: …";
+/// assert_eq!(dump_truncated(&object, 1, 2), expected);
+/// ```
+pub fn dump_truncated(content: &GuraType, max_depth: usize, max_array_items: usize) -> String {
+    let options = DumpOptions {
+        max_depth: Some(max_depth),
+        max_array_items: Some(max_array_items),
+        ..DumpOptions::default()
+    };
+    dump_content_with_options(content, &options, 0)
+        .trim()
+        .to_string()
+}
+
+/// Like `dump` but customizing the output with `options`.
+///
+/// # Errors
+///
+/// * DumpError - If the value contains something that could not be parsed back: a key that
+///   can not be written in Gura (e.g. with spaces or dots) or a value intended to be used
+///   internally (e.g. `GuraType::Pair`). Also if it can not be dumped with the given options
+///   (e.g. it contains NaN values and `NanPolicy::Error` is used).
+pub fn try_dump(content: &GuraType, options: &DumpOptions) -> Result<String, DumpError> {
+    check_dumpable(content, "", options)?;
+
+    let variables = match (options.min_variable_occurrences, content) {
+        (Some(min_occurrences), GuraType::Object(_)) => {
+            repeated_values(content, options, min_occurrences)
+        }
+        _ => DumpVariables::new(),
+    };
+
+    let mut result = String::new();
+    for (dumped, name) in variables.iter() {
+        let _ = writeln!(result, "${}: {}", name, dumped);
+    }
+    if !variables.is_empty() {
+        result.push('\n');
+    }
+    result.push_str(&dump_content_with_variables(
+        content, options, &variables, 0,
+    ));
+    Ok(result.trim().to_string())
+}
+
+/// Checks if a key can be written in a Gura document without quotes.
+pub(crate) fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '_')
+}
+
+/// Appends a segment (key or array index) to a path of `try_dump` errors.
+fn join_dump_path(path: &str, segment: &str) -> String {
+    if path.is_empty() {
+        segment.to_string()
+    } else {
+        format!("{}.{}", path, segment)
+    }
+}
+
+/// Checks that `content` (located at `path`) can be dumped with `options` and parsed back,
+/// failing at the first value that can not.
+fn check_dumpable(content: &GuraType, path: &str, options: &DumpOptions) -> Result<(), DumpError> {
+    let error = |kind: DumpErrorKind| {
+        Err(DumpError {
+            path: path.to_string(),
+            kind,
+        })
+    };
+
+    match content {
+        GuraType::Object(values) => {
+            for (key, value) in values {
+                let key_path = join_dump_path(path, key);
+                if !is_valid_key(key) {
+                    return Err(DumpError {
+                        path: key_path,
+                        kind: DumpErrorKind::InvalidKey(key.clone()),
+                    });
+                }
+                check_dumpable(value, &key_path, options)?;
+            }
+            Ok(())
+        }
+        GuraType::Array(values) => values.iter().enumerate().try_for_each(|(index, value)| {
+            check_dumpable(value, &join_dump_path(path, &index.to_string()), options)
+        }),
+        GuraType::Float(number) if number.is_nan() && options.nan_policy == NanPolicy::Error => {
+            error(DumpErrorKind::NaN)
+        }
+        GuraType::Extension(tag, _) if !is_valid_key(tag) => {
+            error(DumpErrorKind::InvalidKey(tag.clone()))
+        }
+        GuraType::Extension(_, value) => match value.kind() {
+            Kind::Null
+            | Kind::Bool
+            | Kind::String
+            | Kind::Integer
+            | Kind::BigInteger
+            | Kind::Float => check_dumpable(value, path, options),
+            _ => error(DumpErrorKind::UnsupportedValue),
+        },
+        _ if content.kind() == Kind::Internal || matches!(content, GuraType::ObjectWithWs(..)) => {
+            error(DumpErrorKind::UnsupportedValue)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Generates the Gura representation of a single value, exactly as it would appear
+/// after the colon of a key/value pair. Useful to embed dumped fragments into bigger
+/// hand-built documents or error messages.
+///
+/// Unlike [`dump`], the result is not trimmed, so objects keep their trailing new line.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{array, dump_value, GuraType};
+///
+/// let title = GuraType::String(String::from("Gura \"Rust\""));
+/// assert_eq!(dump_value(&title), r#""Gura \"Rust\"""#);
+/// assert_eq!(dump_value(&array![1, 2, 3]), "[1, 2, 3]");
+/// assert_eq!(dump_value(&GuraType::Null), "null");
+/// ```
+pub fn dump_value(value: &GuraType) -> String {
+    dump_content(value)
+}
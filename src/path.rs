@@ -0,0 +1,242 @@
+//! Paths of values inside documents: keys of objects and indices of arrays from the root value,
+//! written as `services.nginx.hosts[1]`. They are used to look values up (see
+//! `GuraType::get_at`) and to report where changes and errors were found (see `diff` and
+//! `schema`).
+//!
+//! # Examples
+//!
+//! ```
+//! use gura::{parse, path::{Path, Segment}};
+//!
+//! let path: Path = "services.nginx.hosts[1]".parse().unwrap();
+//! assert_eq!(path.segments().last(), Some(&Segment::Index(1)));
+//! assert_eq!(path.parent().unwrap().to_string(), "services.nginx.hosts");
+//!
+//! let parsed = parse("services:\n    nginx:\n        hosts: [\"alpha\", \"omega\"]").unwrap();
+//! assert_eq!(*parsed.get_at(&path).unwrap(), "omega");
+//! ```
+use crate::errors::PathError;
+use crate::parser::GuraType;
+use std::{fmt, str::FromStr};
+
+/// Step of a path
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Segment {
+    /// Key of an object
+    Key(String),
+    /// Index of an array
+    Index(usize),
+}
+
+impl Segment {
+    /// Gets the index of an element of an array selected by the segment. Keys select elements
+    /// if they are indices, as in `hosts.0`.
+    pub(crate) fn index(&self) -> Option<usize> {
+        match self {
+            Segment::Key(key) => key.parse().ok(),
+            Segment::Index(index) => Some(*index),
+        }
+    }
+}
+
+/// Location of a value inside a document. The empty path is the root value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Path {
+    segments: Vec<Segment>,
+}
+
+impl Path {
+    /// Creates the path of the root value.
+    pub fn root() -> Self {
+        Path::default()
+    }
+
+    /// Checks if this is the path of the root value.
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Gets the segments of the path, from the root value.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Appends a segment to the path.
+    pub fn push(&mut self, segment: Segment) {
+        self.segments.push(segment);
+    }
+
+    /// Gets the path of a key of the object located at this path.
+    pub fn join_key(&self, key: &str) -> Path {
+        self.join(Segment::Key(key.to_string()))
+    }
+
+    /// Gets the path of an element of the array located at this path.
+    pub fn join_index(&self, index: usize) -> Path {
+        self.join(Segment::Index(index))
+    }
+
+    fn join(&self, segment: Segment) -> Path {
+        let mut path = self.clone();
+        path.push(segment);
+        path
+    }
+
+    /// Gets the path of the value that contains this one. Returns `None` for the root value.
+    pub fn parent(&self) -> Option<Path> {
+        let (_, parent) = self.segments.split_last()?;
+        Some(Path {
+            segments: parent.to_vec(),
+        })
+    }
+
+    /// Writes the path with indices as keys (e.g. `hosts.1`), as the paths of
+    /// `SpannedDocument`.
+    pub(crate) fn to_dotted(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Key(key) => key.clone(),
+                Segment::Index(index) => index.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+impl From<Vec<Segment>> for Path {
+    fn from(segments: Vec<Segment>) -> Self {
+        Path { segments }
+    }
+}
+
+/// Parses keys separated by dots, each one followed by any number of indices between brackets
+/// (e.g. `matrix[0][1].name`). Paths that start with an index refer to an element of a root
+/// array, and the empty text is the root value.
+impl FromStr for Path {
+    type Err = PathError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut path = Path::root();
+        if text.is_empty() {
+            return Ok(path);
+        }
+
+        let mut chars = text.char_indices().peekable();
+        let mut first = true;
+        loop {
+            // Key, only optional for the first segment
+            let start = chars.peek().map_or(text.len(), |(pos, _)| *pos);
+            while let Some((pos, char)) = chars.peek() {
+                match char {
+                    '.' | '[' => break,
+                    ']' => return Err(PathError::new(*pos, "Unexpected \"]\"")),
+                    _ => {
+                        chars.next();
+                    }
+                }
+            }
+            let end = chars.peek().map_or(text.len(), |(pos, _)| *pos);
+            if start < end {
+                path.push(Segment::Key(text[start..end].to_string()));
+            } else if !(first && matches!(chars.peek(), Some((_, '[')))) {
+                return Err(PathError::new(start, "Expected a key"));
+            }
+            first = false;
+
+            // Indices
+            while let Some((open, '[')) = chars.peek().copied() {
+                chars.next();
+                let mut digits = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, ']')) => break,
+                        Some((_, char)) if char.is_ascii_digit() => digits.push(char),
+                        Some((pos, _)) => return Err(PathError::new(pos, "Expected a digit")),
+                        None => return Err(PathError::new(open, "Unclosed \"[\"")),
+                    }
+                }
+                let index = digits
+                    .parse()
+                    .map_err(|_| PathError::new(open, "Invalid index"))?;
+                path.push(Segment::Index(index));
+            }
+
+            match chars.next() {
+                Some((_, '.')) => (),
+                None => return Ok(path),
+                Some((pos, _)) => return Err(PathError::new(pos, "Expected \".\" or \"[\"")),
+            }
+        }
+    }
+}
+
+/// Writes the path as it is parsed (e.g. `services.nginx.hosts[1]`). The root value is the
+/// empty text.
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (position, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Key(key) if position == 0 => f.write_str(key)?,
+                Segment::Key(key) => write!(f, ".{}", key)?,
+                Segment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Paths are equal to the texts they are parsed from
+impl PartialEq<str> for Path {
+    fn eq(&self, other: &str) -> bool {
+        other.parse::<Path>().is_ok_and(|other| *self == other)
+    }
+}
+
+impl PartialEq<&str> for Path {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+/// Gets the paths of the values of `value` that complete a partial path (e.g. the ones of
+/// `services.nginx` and `services.apache` for `services.`), in the order of the document. Only
+/// the keys of objects (or the indices of arrays) after the last complete segment are
+/// completed, so nested values are listed by completing their parents first. Returns an empty
+/// list if nothing matches or the path before the last segment does not exist.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{parse, path::completions};
+///
+/// let parsed = parse("services:\n    nginx:\n        hosts: [\"alpha\", \"omega\"]").unwrap();
+/// let paths: Vec<String> = completions(&parsed, "services.nginx.h")
+///     .iter()
+///     .map(ToString::to_string)
+///     .collect();
+/// assert_eq!(paths, ["services.nginx.hosts"]);
+/// assert_eq!(completions(&parsed, "services.nginx.hosts[")[1], "services.nginx.hosts[1]");
+/// ```
+pub fn completions(value: &GuraType, partial: &str) -> Vec<Path> {
+    // The segment being written starts at the last separator
+    let parent = match partial.rfind(['.', '[']) {
+        Some(pos) => &partial[..pos],
+        None => "",
+    };
+    let parent: Path = match parent.parse() {
+        Ok(parent) => parent,
+        Err(_) => return Vec::new(),
+    };
+    let children = match value.get_at(&parent) {
+        Some(GuraType::Object(values)) => values.keys().map(|key| parent.join_key(key)).collect(),
+        Some(GuraType::Array(values)) => (0..values.len())
+            .map(|index| parent.join_index(index))
+            .collect(),
+        _ => Vec::new(),
+    };
+    children
+        .into_iter()
+        .filter(|child| child.to_string().starts_with(partial))
+        .collect()
+}
@@ -0,0 +1,187 @@
+//! Access of the parser to its environment: the imported files, the environment variables
+//! used as Gura variables and the clock of the time variables. The real ones are used by
+//! default, and other implementations can be set with `ParserOptions::platform` to parse
+//! hermetically (e.g. in tests, without mutating the environment of the process) or in
+//! sandboxed hosts without a filesystem. On WASI the default platform only reads the
+//! directories preopened by the host (e.g. `wasmtime --dir`), and imports of the same file
+//! through different paths are not detected, as paths can not be canonicalized.
+//!
+//! # Examples
+//!
+//! ```
+//! use gura::{parse_with_options, parser::ParserOptions, platform::FakePlatform};
+//!
+//! let platform = FakePlatform::new()
+//!     .file("server.ura", "port: 8080")
+//!     .env("USER", "gura");
+//! let options = ParserOptions::default().platform(platform);
+//! let parsed = parse_with_options("import \"server.ura\"\nuser: $USER", &options).unwrap();
+//! assert_eq!(parsed["port"], 8080);
+//! assert_eq!(parsed["user"], "gura");
+//! ```
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    env, fmt, fs, io,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Environment of the parser. Implementations must be thread-safe, as options are shared
+/// across threads.
+pub trait Platform: Send + Sync {
+    /// Reads the content of a file (e.g. an imported one).
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+
+    /// Reads the bytes of a file (e.g. the one given to `parse_file`), which may not be valid
+    /// UTF-8. By default the file is read with `read_to_string`.
+    fn read_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.read_to_string(path).map(String::into_bytes)
+    }
+
+    /// Gets the canonical form of a path, to detect a file imported more than once however it
+    /// is referenced. Errors make the parser use the path as it is.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// Gets the value of an environment variable, `None` if it is not defined.
+    fn env_var(&self, name: &str) -> Option<String>;
+
+    /// Gets the current time as seconds since the Unix epoch.
+    fn now(&self) -> u64;
+}
+
+/// The filesystem, environment and clock of the operating system. The default platform.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsPlatform;
+
+impl Platform for OsPlatform {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        fs::canonicalize(path)
+    }
+
+    fn env_var(&self, name: &str) -> Option<String> {
+        env::var(name).ok()
+    }
+
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs())
+    }
+}
+
+/// In-memory platform with the given files and environment variables, and a fixed time (the
+/// Unix epoch unless it is set), so results do not depend on the machine.
+///
+/// Files are found by the path the parser computes for them (the imported path joined to the
+/// directory of the importing file, if any), which is also their canonical form.
+#[derive(Debug, Clone, Default)]
+pub struct FakePlatform {
+    files: HashMap<PathBuf, String>,
+    env: HashMap<String, String>,
+    now: u64,
+}
+
+impl FakePlatform {
+    /// Creates a platform without files nor environment variables.
+    pub fn new() -> Self {
+        FakePlatform::default()
+    }
+
+    /// Adds a file, replacing the previous content of the path if any.
+    pub fn file<P: Into<PathBuf>>(mut self, path: P, content: &str) -> Self {
+        self.files.insert(path.into(), content.to_string());
+        self
+    }
+
+    /// Adds an environment variable, replacing the previous value if any.
+    pub fn env(mut self, name: &str, value: &str) -> Self {
+        self.env.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Sets the current time, as seconds since the Unix epoch.
+    pub fn time(mut self, now: u64) -> Self {
+        self.now = now;
+        self
+    }
+}
+
+impl Platform for FakePlatform {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        self.files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        if self.files.contains_key(path) {
+            Ok(path.to_path_buf())
+        } else {
+            Err(io::Error::from(io::ErrorKind::NotFound))
+        }
+    }
+
+    fn env_var(&self, name: &str) -> Option<String> {
+        self.env.get(name).cloned()
+    }
+
+    fn now(&self) -> u64 {
+        self.now
+    }
+}
+
+lazy_static! {
+    /// Shared by the default options, so they are equal to each other
+    static ref OS_PLATFORM: Arc<dyn Platform> = Arc::new(OsPlatform);
+}
+
+/// Platform of some options. Cloning it is cheap.
+#[derive(Clone)]
+pub(crate) struct PlatformHandle(Arc<dyn Platform>);
+
+impl PlatformHandle {
+    pub(crate) fn new<P: Platform + 'static>(platform: P) -> Self {
+        PlatformHandle(Arc::new(platform))
+    }
+}
+
+impl Default for PlatformHandle {
+    fn default() -> Self {
+        PlatformHandle(Arc::clone(&OS_PLATFORM))
+    }
+}
+
+impl Deref for PlatformHandle {
+    type Target = dyn Platform;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl fmt::Debug for PlatformHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Platform")
+    }
+}
+
+/// Handles are equal if they have the same platform (the same instance, not an equivalent one)
+impl PartialEq for PlatformHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for PlatformHandle {}
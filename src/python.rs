@@ -0,0 +1,204 @@
+//! Python bindings with [PyO3](https://pyo3.rs): `parse` returns native dicts and lists and
+//! `dump` accepts them back. The extension module itself is built from the companion crate of
+//! the `python` folder (`maturin build --manifest-path python/Cargo.toml`), which registers
+//! these functions with `register`.
+//!
+//! Errors are raised as exceptions named like the `Error` variants (e.g. `ParseError`), all
+//! of them subclasses of `GuraError` (itself a `ValueError`).
+//!
+//! Requires the `python` feature.
+//!
+//! # Examples
+//!
+//! ```python
+//! import gura
+//!
+//! parsed = gura.parse('server:\n    port: 8080')
+//! assert parsed == {'server': {'port': 8080}}
+//! print(gura.dump(parsed))
+//! ```
+use crate::{
+    errors::{Error, GuraError as ParsingError},
+    parser::{dump as dump_gura, parse as parse_gura, GuraType},
+};
+use indexmap::IndexMap;
+use pyo3::{
+    create_exception,
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple},
+    IntoPyObjectExt,
+};
+
+create_exception!(
+    gura,
+    GuraError,
+    PyValueError,
+    "Base class of the Gura errors"
+);
+create_exception!(gura, ParseError, GuraError, "Gura syntax is invalid");
+create_exception!(
+    gura,
+    VariableNotDefinedError,
+    GuraError,
+    "A variable is not defined"
+);
+create_exception!(
+    gura,
+    InvalidIndentationError,
+    GuraError,
+    "Indentation is invalid"
+);
+create_exception!(
+    gura,
+    DuplicatedVariableError,
+    GuraError,
+    "A variable is defined more than once"
+);
+create_exception!(
+    gura,
+    DuplicatedKeyError,
+    GuraError,
+    "A key is defined more than once"
+);
+create_exception!(
+    gura,
+    FileNotFoundError,
+    GuraError,
+    "An imported file was not found"
+);
+create_exception!(
+    gura,
+    DuplicatedImportError,
+    GuraError,
+    "A file is imported more than once"
+);
+create_exception!(gura, InternalError, GuraError, "The parser has a bug");
+
+/// Converts a parsing error into the exception of its kind.
+fn to_exception(error: &ParsingError) -> PyErr {
+    let message = error.to_string();
+    match error.kind {
+        Error::ParseError => ParseError::new_err(message),
+        Error::VariableNotDefinedError => VariableNotDefinedError::new_err(message),
+        Error::InvalidIndentationError => InvalidIndentationError::new_err(message),
+        Error::DuplicatedVariableError => DuplicatedVariableError::new_err(message),
+        Error::DuplicatedKeyError => DuplicatedKeyError::new_err(message),
+        Error::FileNotFoundError => FileNotFoundError::new_err(message),
+        Error::DuplicatedImportError => DuplicatedImportError::new_err(message),
+        Error::InternalError => InternalError::new_err(message),
+    }
+}
+
+/// Converts a value into its Python equivalent: `None`, `bool`, `int`, `float`, `str`, `list`
+/// or `dict`. Internal values are converted into `None`.
+pub fn to_python<'py>(py: Python<'py>, value: &GuraType) -> PyResult<Bound<'py, PyAny>> {
+    match value {
+        GuraType::Bool(value) => value.into_bound_py_any(py),
+        GuraType::Integer(value) => value.into_bound_py_any(py),
+        GuraType::BigInteger(value) => value.into_bound_py_any(py),
+        GuraType::Float(value) => value.into_bound_py_any(py),
+        GuraType::String(value) => value.into_bound_py_any(py),
+        GuraType::Array(values) => {
+            let values = values
+                .iter()
+                .map(|value| to_python(py, value))
+                .collect::<PyResult<Vec<_>>>()?;
+            PyList::new(py, values)?.into_bound_py_any(py)
+        }
+        GuraType::Object(values) => {
+            let dict = PyDict::new(py);
+            for (key, value) in values {
+                dict.set_item(key, to_python(py, value)?)?;
+            }
+            dict.into_bound_py_any(py)
+        }
+        GuraType::Extension(_, value) => to_python(py, value),
+        _ => Ok(py.None().into_bound(py)),
+    }
+}
+
+/// Converts a Python value into a Gura value. Lists and tuples are arrays and dicts (with
+/// string keys) are objects.
+///
+/// # Errors
+///
+/// * TypeError - If the value (or any value inside it) has another type.
+pub fn from_python(value: &Bound<'_, PyAny>) -> PyResult<GuraType> {
+    if value.is_none() {
+        Ok(GuraType::Null)
+    } else if value.is_instance_of::<PyBool>() {
+        Ok(GuraType::Bool(value.extract()?))
+    } else if value.is_instance_of::<PyInt>() {
+        match value.extract::<isize>() {
+            Ok(number) => Ok(GuraType::Integer(number)),
+            Err(_) => Ok(GuraType::BigInteger(value.extract()?)),
+        }
+    } else if value.is_instance_of::<PyFloat>() {
+        Ok(GuraType::Float(value.extract()?))
+    } else if value.is_instance_of::<PyString>() {
+        Ok(GuraType::String(value.extract()?))
+    } else if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        value
+            .try_iter()?
+            .map(|item| from_python(&item?))
+            .collect::<PyResult<Vec<GuraType>>>()
+            .map(GuraType::Array)
+    } else if let Ok(dict) = value.downcast::<PyDict>() {
+        let mut values = IndexMap::new();
+        for (key, item) in dict.iter() {
+            let key: String = key
+                .extract()
+                .map_err(|_| PyTypeError::new_err("Keys of dicts must be strings"))?;
+            values.insert(key, from_python(&item)?);
+        }
+        Ok(GuraType::Object(values))
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "Values of type {} can not be dumped into Gura",
+            value.get_type().name()?
+        )))
+    }
+}
+
+/// Parses a Gura text into a dict.
+#[pyfunction]
+fn parse<'py>(py: Python<'py>, text: &str) -> PyResult<Bound<'py, PyAny>> {
+    let parsed = parse_gura(text).map_err(|error| to_exception(&error))?;
+    to_python(py, &parsed)
+}
+
+/// Dumps a dict into a Gura text.
+#[pyfunction]
+fn dump(value: &Bound<'_, PyAny>) -> PyResult<String> {
+    Ok(dump_gura(&from_python(value)?))
+}
+
+/// Adds the functions and exceptions of the bindings to a Python module.
+pub fn register(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = module.py();
+    module.add_function(wrap_pyfunction!(parse, module)?)?;
+    module.add_function(wrap_pyfunction!(dump, module)?)?;
+    module.add("GuraError", py.get_type::<GuraError>())?;
+    module.add("ParseError", py.get_type::<ParseError>())?;
+    module.add(
+        "VariableNotDefinedError",
+        py.get_type::<VariableNotDefinedError>(),
+    )?;
+    module.add(
+        "InvalidIndentationError",
+        py.get_type::<InvalidIndentationError>(),
+    )?;
+    module.add(
+        "DuplicatedVariableError",
+        py.get_type::<DuplicatedVariableError>(),
+    )?;
+    module.add("DuplicatedKeyError", py.get_type::<DuplicatedKeyError>())?;
+    module.add("FileNotFoundError", py.get_type::<FileNotFoundError>())?;
+    module.add(
+        "DuplicatedImportError",
+        py.get_type::<DuplicatedImportError>(),
+    )?;
+    module.add("InternalError", py.get_type::<InternalError>())?;
+    Ok(())
+}
@@ -0,0 +1,591 @@
+use crate::errors::{TypeError, ValidationError, ValidationErrorKind};
+use crate::parser::{dump_value, GuraType, Kind};
+use crate::path::Path;
+use crate::span::SpannedDocument;
+use indexmap::IndexMap;
+use std::fmt::Write as _;
+
+// Indentation of 4 spaces
+const INDENT: &str = "    ";
+
+/// Describes the expected shape of a Gura value: its type, documentation and, for objects
+/// and arrays, the schemas of their children.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{parse, schema::Schema, GuraType, Kind};
+///
+/// let schema = Schema::object()
+///     .field("host", Schema::new(Kind::String).required())
+///     .field(
+///         "port",
+///         Schema::new(Kind::Integer)
+///             .description("Port to listen to")
+///             .default(GuraType::Integer(8080)),
+///     );
+///
+/// assert!(schema.validate(&parse("host: \"localhost\"").unwrap()).is_ok());
+///
+/// let errors = schema.validate(&parse("port: \"80\"").unwrap()).unwrap_err();
+/// assert_eq!(errors[0].to_string(), "missing required key at host");
+/// assert_eq!(errors[1].to_string(), "expected integer, found string at port");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schema {
+    kind: Option<Kind>,
+    description: Option<String>,
+    default: Option<GuraType>,
+    required: bool,
+    nullable: bool,
+    fields: IndexMap<String, Schema>,
+    deny_unknown_keys: bool,
+    items: Option<Box<Schema>>,
+}
+
+impl Schema {
+    /// Creates a schema for values of a specific kind.
+    pub fn new(kind: Kind) -> Self {
+        Schema {
+            kind: Some(kind),
+            description: None,
+            default: None,
+            required: false,
+            nullable: false,
+            fields: IndexMap::new(),
+            deny_unknown_keys: false,
+            items: None,
+        }
+    }
+
+    /// Creates a schema accepting values of any kind.
+    pub fn any() -> Self {
+        Schema {
+            kind: None,
+            ..Schema::new(Kind::Null)
+        }
+    }
+
+    /// Creates a schema for objects. Fields can be defined with `field`.
+    pub fn object() -> Self {
+        Schema::new(Kind::Object)
+    }
+
+    /// Creates a schema for arrays whose elements must match `items`.
+    pub fn array(items: Schema) -> Self {
+        Schema {
+            items: Some(Box::new(items)),
+            ..Schema::new(Kind::Array)
+        }
+    }
+
+    /// Infers a schema from an example document: every value defines the kind of its key and
+    /// is used as its default value. Arrays take the schema of their first element.
+    pub fn from_document(document: &GuraType) -> Self {
+        match document {
+            GuraType::Object(values) => {
+                let mut schema = Schema::object();
+                for (key, value) in values {
+                    schema = schema.field(key, Schema::from_document(value));
+                }
+                schema
+            }
+            GuraType::Array(values) => {
+                let items = values
+                    .first()
+                    .map_or_else(Schema::any, Schema::from_document);
+                Schema::array(items).default(document.clone())
+            }
+            other => Schema::new(other.kind()).default(other.clone()),
+        }
+    }
+
+    /// Like `from_document` but taking the comments right before every key as the description
+    /// of its field, so annotated example documents produce documented schemas.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{parse_spanned, schema::Schema};
+    ///
+    /// let document = parse_spanned("# Port to listen to\nport: 8080").unwrap();
+    /// let schema = Schema::from_spanned_document(&document);
+    /// assert_eq!(schema.fields()["port"].get_description(), Some("Port to listen to"));
+    /// ```
+    pub fn from_spanned_document(document: &SpannedDocument) -> Self {
+        let mut schema = Schema::from_document(&document.value);
+        schema.describe_fields(document, "");
+        schema
+    }
+
+    /// Sets a human readable description of the value.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Sets the value used when the key is not defined.
+    pub fn default(mut self, default: GuraType) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Marks the key as required.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Accepts `null` besides the values of the expected kind.
+    pub fn nullable(mut self) -> Self {
+        self.nullable = true;
+        self
+    }
+
+    /// Makes the validation of an object fail if it contains keys not defined as fields.
+    /// Useful to catch typos like `prot: 8080`.
+    pub fn deny_unknown_keys(mut self) -> Self {
+        self.deny_unknown_keys = true;
+        self
+    }
+
+    /// Adds a field to an object schema.
+    pub fn field(mut self, key: &str, schema: Schema) -> Self {
+        self.fields.insert(key.to_string(), schema);
+        self
+    }
+
+    /// Gets the expected kind. `None` if any kind is accepted.
+    pub fn get_kind(&self) -> Option<Kind> {
+        self.kind
+    }
+
+    /// Gets the description, if any.
+    pub fn get_description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Gets the default value, if any.
+    pub fn get_default(&self) -> Option<&GuraType> {
+        self.default.as_ref()
+    }
+
+    /// Checks if the key is required.
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Checks if `null` is accepted besides the values of the expected kind.
+    pub fn is_nullable(&self) -> bool {
+        self.nullable
+    }
+
+    /// Gets the fields of an object schema.
+    pub fn fields(&self) -> &IndexMap<String, Schema> {
+        &self.fields
+    }
+
+    /// Gets the schema of the elements of an array schema.
+    pub fn items(&self) -> Option<&Schema> {
+        self.items.as_deref()
+    }
+
+    /// Validates a value against the schema.
+    ///
+    /// # Errors
+    ///
+    /// All the errors found in the value (not only the first one) with the path where they were found.
+    pub fn validate(&self, value: &GuraType) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        self.validate_at(value, &Path::root(), &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Like `validate` but including the span of the invalid keys in the errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{parse_spanned, schema::Schema, Kind};
+    ///
+    /// let schema = Schema::object()
+    ///     .field("host", Schema::new(Kind::String))
+    ///     .field("port", Schema::new(Kind::Integer))
+    ///     .deny_unknown_keys();
+    ///
+    /// let document = parse_spanned("host: \"localhost\"\nprot: 8080").unwrap();
+    /// let errors = schema.validate_spanned(&document).unwrap_err();
+    /// assert_eq!(errors[0].to_string(), "unknown key at prot (line 2)");
+    /// ```
+    pub fn validate_spanned(&self, document: &SpannedDocument) -> Result<(), Vec<ValidationError>> {
+        self.validate(&document.value).map_err(|mut errors| {
+            for error in errors.iter_mut() {
+                error.span = document.span(&error.path.to_dotted());
+            }
+            errors
+        })
+    }
+
+    /// Sets the leading comments of the keys of the fields located at `path` (and nested ones)
+    /// as their description, without their `#`.
+    fn describe_fields(&mut self, document: &SpannedDocument, path: &str) {
+        for (key, field) in self.fields.iter_mut() {
+            let field_path = join_path(path, key);
+            if let Some(trivia) = document.trivia(&field_path) {
+                let lines: Vec<&str> = trivia
+                    .leading_comments
+                    .iter()
+                    .map(|comment| comment.trim_start_matches('#').trim())
+                    .filter(|line| !line.is_empty())
+                    .collect();
+                if !lines.is_empty() {
+                    field.description = Some(lines.join(" "));
+                }
+            }
+            field.describe_fields(document, &field_path);
+        }
+    }
+
+    /// Validates a value located at `path` storing all the found errors.
+    fn validate_at(&self, value: &GuraType, path: &Path, errors: &mut Vec<ValidationError>) {
+        if self.nullable && matches!(value, GuraType::Null) {
+            return;
+        }
+
+        if let Some(expected) = self.kind {
+            if !kind_matches(expected, value.kind()) {
+                errors.push(ValidationError {
+                    path: path.clone(),
+                    kind: ValidationErrorKind::WrongType(TypeError {
+                        expected,
+                        found: value.kind(),
+                        span: None,
+                    }),
+                    span: None,
+                });
+                return;
+            }
+        }
+
+        match value {
+            GuraType::Object(values) => {
+                for (key, field) in self.fields.iter() {
+                    let field_path = path.join_key(key);
+                    match values.get(key) {
+                        Some(field_value) => field.validate_at(field_value, &field_path, errors),
+                        None if field.required => errors.push(ValidationError {
+                            path: field_path,
+                            kind: ValidationErrorKind::MissingKey,
+                            span: None,
+                        }),
+                        None => (),
+                    }
+                }
+
+                if self.deny_unknown_keys {
+                    for key in values.keys().filter(|key| !self.fields.contains_key(*key)) {
+                        errors.push(ValidationError {
+                            path: path.join_key(key),
+                            kind: ValidationErrorKind::UnknownKey,
+                            span: None,
+                        });
+                    }
+                }
+            }
+            GuraType::Array(values) => {
+                if let Some(items) = &self.items {
+                    for (index, element) in values.iter().enumerate() {
+                        items.validate_at(element, &path.join_index(index), errors);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Calls `prompt` for every required key missing in `value`, inserting the returned value
+    /// (if any). `prompt` receives the path of the key and its expected kind (`None` if any
+    /// kind is accepted). Useful to ask users for missing settings in `init`-style commands.
+    ///
+    /// Missing required objects are not prompted directly: they are created and their own
+    /// required keys are prompted instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{parse, schema::Schema, GuraType, Kind};
+    ///
+    /// let schema = Schema::object()
+    ///     .field("name", Schema::new(Kind::String).required())
+    ///     .field("db", Schema::object().required().field("port", Schema::new(Kind::Integer).required()));
+    ///
+    /// let mut parsed = parse("name: \"app\"").unwrap();
+    /// schema.fill_missing(&mut parsed, |path, kind| {
+    ///     assert_eq!(*path, "db.port");
+    ///     assert_eq!(kind, Some(Kind::Integer));
+    ///     Some(GuraType::Integer(5432))
+    /// });
+    ///
+    /// assert_eq!(parsed["db"]["port"], 5432);
+    /// assert!(schema.validate(&parsed).is_ok());
+    /// ```
+    pub fn fill_missing<F>(&self, value: &mut GuraType, mut prompt: F)
+    where
+        F: FnMut(&Path, Option<Kind>) -> Option<GuraType>,
+    {
+        self.fill_missing_at(value, &Path::root(), &mut prompt);
+    }
+
+    fn fill_missing_at<F>(&self, value: &mut GuraType, path: &Path, prompt: &mut F)
+    where
+        F: FnMut(&Path, Option<Kind>) -> Option<GuraType>,
+    {
+        let values = match value {
+            GuraType::Object(values) => values,
+            _ => return,
+        };
+
+        for (key, field) in self.fields.iter() {
+            let field_path = path.join_key(key);
+            if let Some(field_value) = values.get_mut(key) {
+                field.fill_missing_at(field_value, &field_path, prompt);
+            } else if field.required {
+                if field.kind == Some(Kind::Object) && !field.fields.is_empty() {
+                    let mut object = GuraType::Object(IndexMap::new());
+                    field.fill_missing_at(&mut object, &field_path, prompt);
+                    values.insert(key.clone(), object);
+                } else if let Some(prompted) = prompt(&field_path, field.kind) {
+                    values.insert(key.clone(), prompted);
+                }
+            }
+        }
+    }
+
+    /// Generates an example value for the schema. Keys take their default value or, if they
+    /// do not have one, a placeholder value of the expected kind (`""`, `0`, `false`, etc.).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{dump, schema::Schema, GuraType, Kind};
+    ///
+    /// let schema = Schema::object()
+    ///     .field("host", Schema::new(Kind::String))
+    ///     .field("port", Schema::new(Kind::Integer).default(GuraType::Integer(8080)));
+    ///
+    /// assert_eq!(dump(&schema.generate_example()), "host: \"\"\nport: 8080");
+    /// ```
+    pub fn generate_example(&self) -> GuraType {
+        if let Some(default) = &self.default {
+            return default.clone();
+        }
+
+        match self.kind {
+            Some(Kind::Object) => GuraType::Object(
+                self.fields
+                    .iter()
+                    .map(|(key, field)| (key.clone(), field.generate_example()))
+                    .collect(),
+            ),
+            Some(Kind::Array) => GuraType::Array(Vec::new()),
+            Some(Kind::String) => GuraType::String(String::new()),
+            Some(Kind::Integer) => GuraType::Integer(0),
+            Some(Kind::BigInteger) => GuraType::BigInteger(0),
+            Some(Kind::Float) => GuraType::Float(0.0),
+            Some(Kind::Bool) => GuraType::Bool(false),
+            _ => GuraType::Null,
+        }
+    }
+
+    /// Like `generate_example` but generating a Gura text where every key is preceded by
+    /// its description as a comment. Useful to write `config.example.ura` files.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{schema::Schema, Kind};
+    ///
+    /// let schema = Schema::object().field(
+    ///     "server",
+    ///     Schema::object().field("port", Schema::new(Kind::Integer).description("Port to listen to")),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     schema.generate_example_text(),
+    ///     "server:\n    # Port to listen to\n    port: 0\n"
+    /// );
+    /// ```
+    pub fn generate_example_text(&self) -> String {
+        let mut result = String::new();
+        self.write_example(&mut result, "");
+        result
+    }
+
+    /// Writes the example of every field of an object schema with the specified indentation.
+    fn write_example(&self, result: &mut String, indentation: &str) {
+        for (key, field) in self.fields.iter() {
+            if let Some(description) = &field.description {
+                for line in description.lines() {
+                    let _ = writeln!(result, "{}# {}", indentation, line);
+                }
+            }
+
+            let is_object_without_default =
+                field.default.is_none() && field.kind == Some(Kind::Object);
+            if is_object_without_default && !field.fields.is_empty() {
+                // Keeps the descriptions of the nested fields
+                let _ = writeln!(result, "{}{}:", indentation, key);
+                field.write_example(result, &format!("{}{}", indentation, INDENT));
+                continue;
+            }
+
+            let example = field.generate_example();
+            let dumped = dump_value(&example);
+            match example {
+                GuraType::Object(values) if !values.is_empty() => {
+                    let _ = writeln!(result, "{}{}:", indentation, key);
+                    for line in dumped.trim_end().split('\n') {
+                        let _ = writeln!(result, "{}{}{}", indentation, INDENT, line);
+                    }
+                }
+                _ => {
+                    let mut lines = dumped.split('\n');
+                    let first_line = lines.next().unwrap_or_default();
+                    let _ = writeln!(result, "{}{}: {}", indentation, key, first_line);
+                    for line in lines {
+                        let _ = writeln!(result, "{}{}", indentation, line);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Generates a Markdown table documenting every key of the schema: its path, type,
+    /// whether it is required, its default value and its description.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{schema::Schema, Kind};
+    ///
+    /// let schema = Schema::object().field(
+    ///     "port",
+    ///     Schema::new(Kind::Integer).required().description("Port to listen to"),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     schema.to_markdown(),
+    ///     "| Key | Type | Required | Default | Description |\n\
+    ///      | --- | --- | --- | --- | --- |\n\
+    ///      | `port` | integer | yes |  | Port to listen to |\n"
+    /// );
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let mut result = String::from("| Key | Type | Required | Default | Description |\n");
+        result.push_str("| --- | --- | --- | --- | --- |\n");
+        for (path, schema) in self.documented_keys() {
+            let default = schema
+                .default
+                .as_ref()
+                .map(|value| format!("`{}`", value))
+                .unwrap_or_default();
+            let _ = writeln!(
+                result,
+                "| `{}` | {} | {} | {} | {} |",
+                path,
+                schema.type_label(),
+                if schema.required { "yes" } else { "no" },
+                default.replace('|', "\\|"),
+                schema
+                    .description
+                    .as_deref()
+                    .unwrap_or("")
+                    .replace('|', "\\|"),
+            );
+        }
+        result
+    }
+
+    /// Like `to_markdown` but generating an HTML table.
+    pub fn to_html(&self) -> String {
+        let mut result = String::from("<table>\n<thead>\n<tr><th>Key</th><th>Type</th><th>Required</th><th>Default</th><th>Description</th></tr>\n</thead>\n<tbody>\n");
+        for (path, schema) in self.documented_keys() {
+            let default = schema
+                .default
+                .as_ref()
+                .map(|value| format!("<code>{}</code>", escape_html(&value.to_string())))
+                .unwrap_or_default();
+            let _ = writeln!(
+                result,
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_html(&path),
+                schema.type_label(),
+                if schema.required { "yes" } else { "no" },
+                default,
+                escape_html(schema.description.as_deref().unwrap_or("")),
+            );
+        }
+        result.push_str("</tbody>\n</table>\n");
+        result
+    }
+
+    /// Gets all the keys of the schema (depth first) with their full path. Elements of arrays
+    /// are represented with `[]`.
+    fn documented_keys(&self) -> Vec<(String, &Schema)> {
+        let mut result = Vec::new();
+        self.collect_keys("", &mut result);
+        result
+    }
+
+    fn collect_keys<'a>(&'a self, path: &str, result: &mut Vec<(String, &'a Schema)>) {
+        for (key, field) in self.fields.iter() {
+            let field_path = join_path(path, key);
+            result.push((field_path.clone(), field));
+            field.collect_keys(&field_path, result);
+        }
+
+        if let Some(items) = &self.items {
+            items.collect_keys(&format!("{}[]", path), result);
+        }
+    }
+
+    /// Gets the type as shown in documentation (e.g. `array of integer`).
+    fn type_label(&self) -> String {
+        match (self.kind, &self.items) {
+            (Some(Kind::Array), Some(items)) => format!("array of {}", items.type_label()),
+            (Some(kind), _) => kind.to_string(),
+            (None, _) => String::from("any"),
+        }
+    }
+}
+
+/// Checks if a found kind satisfies the expected one. Integers accept big integers and floats
+/// accept both, as they are deserialized into floats too.
+fn kind_matches(expected: Kind, found: Kind) -> bool {
+    match expected {
+        Kind::Integer => matches!(found, Kind::Integer | Kind::BigInteger),
+        Kind::Float => matches!(found, Kind::Float | Kind::Integer | Kind::BigInteger),
+        _ => expected == found,
+    }
+}
+
+/// Appends a key to a path separated by a dot.
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Escapes the characters with special meaning in HTML.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,226 @@
+//! Encrypted values, as in the workflow of tools like SOPS: secrets are stored in the documents
+//! as strings wrapped in an envelope (`ENC[<ciphertext>]` by default), decrypted after parsing
+//! with `resolve_secrets` and encrypted before dumping with `encrypt_secrets`. The encryption
+//! itself is left to the application (e.g. a KMS client or an age key), which plugs it in
+//! through the `ValueDecryptor` and `ValueEncryptor` traits, implemented by closures.
+//!
+//! # Examples
+//!
+//! ```
+//! use gura::{
+//!     dump, object, parse,
+//!     secrets::{encrypt_secrets, resolve_secrets, SecretOptions},
+//!     GuraType,
+//! };
+//!
+//! // A real application would call its encryption library here
+//! let encrypt = |text: &str| Ok::<_, String>(text.chars().rev().collect::<String>());
+//! let decrypt = |text: &str| Ok::<_, String>(text.chars().rev().collect::<String>());
+//! let options = SecretOptions::default();
+//!
+//! let config = object! { db: { user: "admin", password: "hunter2" } };
+//! let paths = ["db.password".parse().unwrap()];
+//! let encrypted = encrypt_secrets(&config, &paths, &encrypt, &options).unwrap();
+//! let text = dump(&encrypted);
+//! assert_eq!(text, "db:\n    user: \"admin\"\n    password: \"ENC[2retnuh]\"");
+//!
+//! let parsed = parse(&text).unwrap();
+//! assert_eq!(resolve_secrets(&parsed, &decrypt, &options).unwrap(), config);
+//! ```
+use crate::{
+    errors::SecretError,
+    parser::GuraType,
+    path::{Path, Segment},
+};
+use indexmap::IndexMap;
+
+/// Hook that decrypts the ciphertext of the secrets (the text inside their envelope), or
+/// explains why it can not be decrypted
+pub trait ValueDecryptor: Send + Sync {
+    fn decrypt(&self, ciphertext: &str) -> Result<String, String>;
+}
+
+impl<F> ValueDecryptor for F
+where
+    F: Fn(&str) -> Result<String, String> + Send + Sync,
+{
+    fn decrypt(&self, ciphertext: &str) -> Result<String, String> {
+        self(ciphertext)
+    }
+}
+
+/// Hook that encrypts the value of the secrets into the ciphertext placed inside their
+/// envelope, or explains why it can not be encrypted
+pub trait ValueEncryptor: Send + Sync {
+    fn encrypt(&self, plaintext: &str) -> Result<String, String>;
+}
+
+impl<F> ValueEncryptor for F
+where
+    F: Fn(&str) -> Result<String, String> + Send + Sync,
+{
+    fn encrypt(&self, plaintext: &str) -> Result<String, String> {
+        self(plaintext)
+    }
+}
+
+/// Options to customize `resolve_secrets` and `encrypt_secrets`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretOptions {
+    prefix: String,
+    suffix: String,
+}
+
+impl Default for SecretOptions {
+    fn default() -> Self {
+        SecretOptions {
+            prefix: String::from("ENC["),
+            suffix: String::from("]"),
+        }
+    }
+}
+
+impl SecretOptions {
+    /// Sets the envelope of the secrets: the texts written before and after their ciphertext.
+    /// By default secrets are written as `ENC[<ciphertext>]`.
+    pub fn envelope(mut self, prefix: &str, suffix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self.suffix = suffix.to_string();
+        self
+    }
+
+    /// Gets the ciphertext of a secret, `None` if the text is not wrapped in the envelope.
+    pub fn ciphertext<'a>(&self, text: &'a str) -> Option<&'a str> {
+        text.strip_prefix(self.prefix.as_str())?
+            .strip_suffix(self.suffix.as_str())
+    }
+
+    /// Wraps a ciphertext in the envelope.
+    pub fn wrap(&self, ciphertext: &str) -> String {
+        format!("{}{}{}", self.prefix, ciphertext, self.suffix)
+    }
+}
+
+/// Decrypts every string of a value wrapped in the envelope of `options`, in objects and
+/// arrays at any depth. The rest of values are left as they are.
+///
+/// # Errors
+///
+/// * SecretError - If the decryptor fails, with the path of the secret.
+pub fn resolve_secrets<D: ValueDecryptor + ?Sized>(
+    value: &GuraType,
+    decryptor: &D,
+    options: &SecretOptions,
+) -> Result<GuraType, SecretError> {
+    map_strings(
+        value,
+        &Path::root(),
+        &mut |path, text| match options.ciphertext(text) {
+            Some(ciphertext) => decryptor
+                .decrypt(ciphertext)
+                .map(Some)
+                .map_err(|msg| SecretError::new(path, format!("Could not decrypt: {}", msg))),
+            None => Ok(None),
+        },
+    )
+}
+
+/// Encrypts the strings located at `paths` (or at any depth inside the objects and arrays
+/// located at them), wrapping their ciphertext in the envelope of `options`, so the result can
+/// be dumped with its secrets protected. Elements of arrays can be selected by their index as
+/// a key too, as in `hosts.0`.
+///
+/// Strings already wrapped in the envelope are taken as secrets encrypted before and left as
+/// they are, so encrypting a document again does not change it. Note that this includes
+/// plaintexts that happen to look like an envelope (e.g. `ENC[abc]` with the default one):
+/// they are not encrypted, so choose an envelope that the values can not contain.
+///
+/// # Errors
+///
+/// * SecretError - If a path does not exist, the value located at it is not a string, an
+///   object or an array, or the encryptor fails, with the path of the value.
+pub fn encrypt_secrets<E: ValueEncryptor + ?Sized>(
+    value: &GuraType,
+    paths: &[Path],
+    encryptor: &E,
+    options: &SecretOptions,
+) -> Result<GuraType, SecretError> {
+    let mut selected = Vec::with_capacity(paths.len());
+    for path in paths {
+        let (normalized, found) =
+            normalize(value, path).ok_or_else(|| SecretError::new(path, "Path not found"))?;
+        if !matches!(
+            found,
+            GuraType::String(_) | GuraType::Object(_) | GuraType::Array(_)
+        ) {
+            let msg = format!("Only strings can be encrypted, found {}", found.kind());
+            return Err(SecretError::new(path, msg));
+        }
+        selected.push(normalized);
+    }
+
+    map_strings(value, &Path::root(), &mut |path, text| {
+        let is_selected = selected
+            .iter()
+            .any(|selected| path.segments().starts_with(selected.segments()));
+        if !is_selected || options.ciphertext(text).is_some() {
+            return Ok(None);
+        }
+        encryptor
+            .encrypt(text)
+            .map(|ciphertext| Some(options.wrap(&ciphertext)))
+            .map_err(|msg| SecretError::new(path, format!("Could not encrypt: {}", msg)))
+    })
+}
+
+/// Gets the value located at `path` along with its path as `map_strings` builds it, with the
+/// keys that select elements of arrays (as in `hosts.0`) turned into indices.
+fn normalize<'a>(value: &'a GuraType, path: &Path) -> Option<(Path, &'a GuraType)> {
+    let mut normalized = Path::root();
+    let mut current = value;
+    for segment in path.segments() {
+        current = match (current, segment) {
+            (GuraType::Object(values), Segment::Key(key)) => {
+                normalized.push(segment.clone());
+                values.get(key)?
+            }
+            (GuraType::Array(values), segment) => {
+                let index = segment.index()?;
+                normalized.push(Segment::Index(index));
+                values.get(index)?
+            }
+            _ => return None,
+        };
+    }
+    Some((normalized, current))
+}
+
+/// Copies a value replacing the strings for which `replace` returns a new text.
+fn map_strings<F>(value: &GuraType, path: &Path, replace: &mut F) -> Result<GuraType, SecretError>
+where
+    F: FnMut(&Path, &str) -> Result<Option<String>, SecretError>,
+{
+    let mapped = match value {
+        GuraType::String(text) => match replace(path, text)? {
+            Some(text) => GuraType::String(text),
+            None => value.clone(),
+        },
+        GuraType::Object(values) => {
+            let mut mapped = IndexMap::with_capacity(values.len());
+            for (key, value) in values {
+                let value = map_strings(value, &path.join_key(key), replace)?;
+                mapped.insert(key.clone(), value);
+            }
+            GuraType::Object(mapped)
+        }
+        GuraType::Array(values) => {
+            let mut mapped = Vec::with_capacity(values.len());
+            for (index, value) in values.iter().enumerate() {
+                mapped.push(map_strings(value, &path.join_index(index), replace)?);
+            }
+            GuraType::Array(mapped)
+        }
+        value => value.clone(),
+    };
+    Ok(mapped)
+}
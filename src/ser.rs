@@ -0,0 +1,698 @@
+//! Serialization of Rust values into Gura documents with [serde](https://serde.rs).
+//!
+//! Values are encoded following these rules, which mirror `serde_json` and the rules of the
+//! `de` module so serialized documents can be deserialized back:
+//!
+//! * Structs and maps are objects. Their keys must be valid Gura keys (ASCII letters, digits
+//!   and underscores): string and char keys are used as they are and integer keys are
+//!   stringified. Any other key (e.g. booleans, floats or negative integers) is an error.
+//! * Sequences, tuples and tuple structs are arrays.
+//! * `None`, `()` and unit structs are `null`. `Some` and newtype structs are their content.
+//! * Unit enum variants are strings with the name of the variant. The rest of variants are
+//!   objects with a single key, the name of the variant, whose value is the content of the
+//!   variant (an array for tuple variants and an object for struct variants).
+//! * Bytes are strings encoded in base64 (standard alphabet, with padding).
+//! * Integers that do not fit in an `isize` are big integers. Integers that do not fit in an
+//!   `i128` are an error.
+//! * Chars are strings.
+//!
+//! Requires the `serde` feature.
+use crate::{
+    errors::SerializeError,
+    parser::{dump, is_valid_key, GuraType},
+};
+use indexmap::IndexMap;
+use serde::ser::{self, Impossible, Serialize, SerializeMap, SerializeSeq};
+use std::{convert::TryFrom, fmt};
+
+impl ser::Error for SerializeError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        SerializeError::new(message.to_string())
+    }
+}
+
+/// Serializes parsed values into any serde format (e.g. to convert a Gura document to JSON
+/// with `serde_json::to_string`). Objects are maps, arrays are sequences and `null` is the
+/// unit value. Internal variants (e.g. `GuraType::Comment`) are serialized as the unit value.
+impl Serialize for GuraType {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            GuraType::Object(values) => {
+                let mut map = serializer.serialize_map(Some(values.len()))?;
+                for (key, value) in values {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            GuraType::Array(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+            GuraType::String(value) => serializer.serialize_str(value),
+            GuraType::Integer(value) => serializer.serialize_i64(*value as i64),
+            GuraType::BigInteger(value) => serializer.serialize_i128(*value),
+            GuraType::Float(value) => serializer.serialize_f64(*value),
+            GuraType::Bool(value) => serializer.serialize_bool(*value),
+            GuraType::Extension(_, value) => value.serialize(serializer),
+            _ => serializer.serialize_unit(),
+        }
+    }
+}
+
+/// Serializes a value into a Gura document. The value must be serialized as an object
+/// (e.g. a struct or a map).
+///
+/// # Examples
+///
+/// ```
+/// use gura::ser::to_string;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Server {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let server = Server { host: String::from("localhost"), port: 8080 };
+/// assert_eq!(to_string(&server).unwrap(), "host: \"localhost\"\nport: 8080");
+/// ```
+///
+/// # Errors
+///
+/// * SerializeError - If the value does not follow the rules of this module or it is not
+///   serialized as an object.
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, SerializeError> {
+    match to_value(value)? {
+        value @ GuraType::Object(_) => Ok(dump(&value)),
+        value => Err(SerializeError::new(format!(
+            "the root value must be an object, found {}",
+            value.kind()
+        ))),
+    }
+}
+
+/// Serializes a value into a `GuraType`.
+///
+/// # Errors
+///
+/// * SerializeError - If the value does not follow the rules of this module.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<GuraType, SerializeError> {
+    value.serialize(Serializer)
+}
+
+/// Serde serializer producing `GuraType` values
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Serializer;
+
+/// Converts an unsigned integer into the smallest fitting Gura integer type.
+fn unsigned(value: u128) -> Result<GuraType, SerializeError> {
+    if let Ok(value) = isize::try_from(value) {
+        Ok(GuraType::Integer(value))
+    } else if let Ok(value) = i128::try_from(value) {
+        Ok(GuraType::BigInteger(value))
+    } else {
+        Err(SerializeError::new(format!(
+            "integer {} is too big for a Gura integer",
+            value
+        )))
+    }
+}
+
+/// Converts a signed integer into the smallest fitting Gura integer type.
+fn signed(value: i128) -> GuraType {
+    match isize::try_from(value) {
+        Ok(value) => GuraType::Integer(value),
+        Err(_) => GuraType::BigInteger(value),
+    }
+}
+
+/// Encodes bytes in base64 with the standard alphabet and padding.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut result = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | (*byte as u32) << (16 - 8 * index)
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (group >> (18 - 6 * index)) & 0x3f;
+                result.push(ALPHABET[sextet as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
+/// Checks if a key can be written in a Gura document.
+fn check_key(key: String) -> Result<String, SerializeError> {
+    if is_valid_key(&key) {
+        Ok(key)
+    } else {
+        Err(SerializeError::new(format!(
+            "invalid key \"{}\", keys can only contain ASCII letters, digits and underscores",
+            key
+        )))
+    }
+}
+
+/// Creates an object with a single key.
+fn single_key_object(key: &str, value: GuraType) -> Result<GuraType, SerializeError> {
+    let mut values = IndexMap::new();
+    values.insert(check_key(key.to_string())?, value);
+    Ok(GuraType::Object(values))
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = GuraType;
+    type Error = SerializeError;
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeVariant<SerializeArray>;
+    type SerializeMap = SerializeObject;
+    type SerializeStruct = SerializeObject;
+    type SerializeStructVariant = SerializeVariant<SerializeObject>;
+
+    fn serialize_bool(self, value: bool) -> Result<GuraType, SerializeError> {
+        Ok(GuraType::Bool(value))
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<GuraType, SerializeError> {
+        Ok(signed(value as i128))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<GuraType, SerializeError> {
+        Ok(signed(value as i128))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<GuraType, SerializeError> {
+        Ok(signed(value as i128))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<GuraType, SerializeError> {
+        Ok(signed(value as i128))
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<GuraType, SerializeError> {
+        Ok(signed(value))
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<GuraType, SerializeError> {
+        unsigned(value as u128)
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<GuraType, SerializeError> {
+        unsigned(value as u128)
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<GuraType, SerializeError> {
+        unsigned(value as u128)
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<GuraType, SerializeError> {
+        unsigned(value as u128)
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<GuraType, SerializeError> {
+        unsigned(value)
+    }
+
+    /// Keeps the shortest representation of the number (e.g. `1.1` instead of
+    /// `1.100000023841858`)
+    fn serialize_f32(self, value: f32) -> Result<GuraType, SerializeError> {
+        let value = value.to_string().parse().unwrap_or(value as f64);
+        Ok(GuraType::Float(value))
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<GuraType, SerializeError> {
+        Ok(GuraType::Float(value))
+    }
+
+    fn serialize_char(self, value: char) -> Result<GuraType, SerializeError> {
+        Ok(GuraType::String(value.to_string()))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<GuraType, SerializeError> {
+        Ok(GuraType::String(value.to_string()))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<GuraType, SerializeError> {
+        Ok(GuraType::String(base64(value)))
+    }
+
+    fn serialize_none(self) -> Result<GuraType, SerializeError> {
+        Ok(GuraType::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<GuraType, SerializeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<GuraType, SerializeError> {
+        Ok(GuraType::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<GuraType, SerializeError> {
+        Ok(GuraType::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<GuraType, SerializeError> {
+        Ok(GuraType::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<GuraType, SerializeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<GuraType, SerializeError> {
+        let value = value
+            .serialize(self)
+            .map_err(|error| error.in_segment(variant))?;
+        single_key_object(variant, value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray, SerializeError> {
+        Ok(SerializeArray {
+            values: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray, SerializeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, SerializeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeArray>, SerializeError> {
+        Ok(SerializeVariant {
+            variant,
+            content: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeObject, SerializeError> {
+        Ok(SerializeObject {
+            values: IndexMap::new(),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeObject, SerializeError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeObject>, SerializeError> {
+        Ok(SerializeVariant {
+            variant,
+            content: self.serialize_map(Some(len))?,
+        })
+    }
+}
+
+/// Collects the elements of an array
+pub struct SerializeArray {
+    values: Vec<GuraType>,
+}
+
+impl SerializeArray {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializeError> {
+        let index = self.values.len();
+        let value = value
+            .serialize(Serializer)
+            .map_err(|error| error.in_segment(&index.to_string()))?;
+        self.values.push(value);
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = GuraType;
+    type Error = SerializeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SerializeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<GuraType, SerializeError> {
+        Ok(GuraType::Array(self.values))
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = GuraType;
+    type Error = SerializeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SerializeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<GuraType, SerializeError> {
+        Ok(GuraType::Array(self.values))
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = GuraType;
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<GuraType, SerializeError> {
+        Ok(GuraType::Array(self.values))
+    }
+}
+
+/// Collects the pairs of an object
+pub struct SerializeObject {
+    values: IndexMap<String, GuraType>,
+    /// Key of the pair whose value has not been serialized yet
+    next_key: Option<String>,
+}
+
+impl SerializeObject {
+    fn insert<T: Serialize + ?Sized>(
+        &mut self,
+        key: String,
+        value: &T,
+    ) -> Result<(), SerializeError> {
+        let value = value
+            .serialize(Serializer)
+            .map_err(|error| error.in_segment(&key))?;
+        self.values.insert(key, value);
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for SerializeObject {
+    type Ok = GuraType;
+    type Error = SerializeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SerializeError> {
+        self.next_key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializeError> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| SerializeError::new(String::from("value serialized before its key")))?;
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<GuraType, SerializeError> {
+        Ok(GuraType::Object(self.values))
+    }
+}
+
+impl ser::SerializeStruct for SerializeObject {
+    type Ok = GuraType;
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerializeError> {
+        let key = check_key(key.to_string())?;
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<GuraType, SerializeError> {
+        Ok(GuraType::Object(self.values))
+    }
+}
+
+/// Collects the content of a tuple or struct variant
+pub struct SerializeVariant<T> {
+    variant: &'static str,
+    content: T,
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeArray> {
+    type Ok = GuraType;
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializeError> {
+        let variant = self.variant;
+        self.content
+            .push(value)
+            .map_err(|error| error.in_segment(variant))
+    }
+
+    fn end(self) -> Result<GuraType, SerializeError> {
+        single_key_object(self.variant, GuraType::Array(self.content.values))
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeObject> {
+    type Ok = GuraType;
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerializeError> {
+        let variant = self.variant;
+        ser::SerializeStruct::serialize_field(&mut self.content, key, value)
+            .map_err(|error| error.in_segment(variant))
+    }
+
+    fn end(self) -> Result<GuraType, SerializeError> {
+        single_key_object(self.variant, GuraType::Object(self.content.values))
+    }
+}
+
+/// Serializes the keys of maps, which must be strings, chars or integers
+struct KeySerializer;
+
+impl KeySerializer {
+    fn unsupported(kind: &str) -> SerializeError {
+        SerializeError::new(format!(
+            "map keys must be strings, chars or integers, found {}",
+            kind
+        ))
+    }
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = SerializeError;
+    type SerializeSeq = Impossible<String, SerializeError>;
+    type SerializeTuple = Impossible<String, SerializeError>;
+    type SerializeTupleStruct = Impossible<String, SerializeError>;
+    type SerializeTupleVariant = Impossible<String, SerializeError>;
+    type SerializeMap = Impossible<String, SerializeError>;
+    type SerializeStruct = Impossible<String, SerializeError>;
+    type SerializeStructVariant = Impossible<String, SerializeError>;
+
+    fn serialize_bool(self, _value: bool) -> Result<String, SerializeError> {
+        Err(KeySerializer::unsupported("bool"))
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<String, SerializeError> {
+        check_key(value.to_string())
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<String, SerializeError> {
+        check_key(value.to_string())
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<String, SerializeError> {
+        check_key(value.to_string())
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<String, SerializeError> {
+        check_key(value.to_string())
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<String, SerializeError> {
+        check_key(value.to_string())
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<String, SerializeError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<String, SerializeError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<String, SerializeError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<String, SerializeError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<String, SerializeError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_f32(self, _value: f32) -> Result<String, SerializeError> {
+        Err(KeySerializer::unsupported("float"))
+    }
+
+    fn serialize_f64(self, _value: f64) -> Result<String, SerializeError> {
+        Err(KeySerializer::unsupported("float"))
+    }
+
+    fn serialize_char(self, value: char) -> Result<String, SerializeError> {
+        check_key(value.to_string())
+    }
+
+    fn serialize_str(self, value: &str) -> Result<String, SerializeError> {
+        check_key(value.to_string())
+    }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<String, SerializeError> {
+        Err(KeySerializer::unsupported("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<String, SerializeError> {
+        Err(KeySerializer::unsupported("none"))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, SerializeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, SerializeError> {
+        Err(KeySerializer::unsupported("unit"))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, SerializeError> {
+        Err(KeySerializer::unsupported("unit struct"))
+    }
+
+    /// Unit variants are strings
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, SerializeError> {
+        check_key(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, SerializeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, SerializeError> {
+        Err(KeySerializer::unsupported("enum variant"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, SerializeError> {
+        Err(KeySerializer::unsupported("sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, SerializeError> {
+        Err(KeySerializer::unsupported("tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, SerializeError> {
+        Err(KeySerializer::unsupported("tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, SerializeError> {
+        Err(KeySerializer::unsupported("enum variant"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, SerializeError> {
+        Err(KeySerializer::unsupported("map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, SerializeError> {
+        Err(KeySerializer::unsupported("struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, SerializeError> {
+        Err(KeySerializer::unsupported("enum variant"))
+    }
+}
@@ -0,0 +1,200 @@
+//! Reference counted representation of Gura values, for documents whose subtrees are handed
+//! to many consumers (e.g. every subsystem of a service gets its own section).
+use crate::{
+    parser::{GuraType, Kind},
+    path::{Path, Segment},
+};
+use indexmap::IndexMap;
+use std::{fmt, ops::Index, sync::Arc};
+
+/// A Gura value whose strings, arrays and objects are stored behind an `Arc`, so cloning it
+/// (or any of its subtrees) is cheap regardless of its size. It is immutable: modifications
+/// must be done on a `GuraType` (see `to_gura_type`). Create it with `GuraType::shared`.
+///
+/// # Examples
+///
+/// ```
+/// use gura::{parse, shared::SharedGuraType};
+///
+/// let config = parse("database:\n    host: \"localhost\"\n    port: 5432").unwrap().shared();
+///
+/// // Every subsystem gets a handle to the same subtree, without deep cloning it
+/// let database: SharedGuraType = config.get("database").unwrap();
+/// assert_eq!(database["port"], 5432);
+/// assert!(database.ptr_eq(&config.get("database").unwrap()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum SharedGuraType {
+    Null,
+    Bool(bool),
+    String(Arc<str>),
+    Integer(isize),
+    BigInteger(i128),
+    Float(f64),
+    Array(Arc<[SharedGuraType]>),
+    Object(Arc<IndexMap<String, SharedGuraType>>),
+    /// Custom scalar with its tag and value (see `GuraType::Extension`)
+    Extension(Arc<str>, Arc<SharedGuraType>),
+}
+
+impl SharedGuraType {
+    /// Gets a cheap clone of the value of a key. Returns `None` if the value is not an object
+    /// or the key does not exist.
+    pub fn get(&self, key: &str) -> Option<SharedGuraType> {
+        match self {
+            SharedGuraType::Object(values) => values.get(key).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Like `get` but following a path of keys separated by dots, as in `GuraType::get_path`.
+    pub fn get_path(&self, path: &str) -> Option<SharedGuraType> {
+        let path = path.parse::<Path>().ok()?;
+        path.segments()
+            .iter()
+            .try_fold(self, |current, segment| match (current, segment) {
+                (SharedGuraType::Object(values), Segment::Key(key)) => values.get(key),
+                (SharedGuraType::Array(values), _) => {
+                    segment.index().and_then(|index| values.get(index))
+                }
+                _ => None,
+            })
+            .cloned()
+    }
+
+    /// Gets the kind of the value.
+    pub fn kind(&self) -> Kind {
+        match self {
+            SharedGuraType::Null => Kind::Null,
+            SharedGuraType::Bool(_) => Kind::Bool,
+            SharedGuraType::String(_) => Kind::String,
+            SharedGuraType::Integer(_) => Kind::Integer,
+            SharedGuraType::BigInteger(_) => Kind::BigInteger,
+            SharedGuraType::Float(_) => Kind::Float,
+            SharedGuraType::Array(_) => Kind::Array,
+            SharedGuraType::Object(_) => Kind::Object,
+            SharedGuraType::Extension(..) => Kind::Extension,
+        }
+    }
+
+    /// Checks if both values point to the same shared data. Always `false` for values that
+    /// are not strings, arrays or objects.
+    pub fn ptr_eq(&self, other: &SharedGuraType) -> bool {
+        match (self, other) {
+            (SharedGuraType::String(a), SharedGuraType::String(b)) => Arc::ptr_eq(a, b),
+            (SharedGuraType::Array(a), SharedGuraType::Array(b)) => Arc::ptr_eq(a, b),
+            (SharedGuraType::Object(a), SharedGuraType::Object(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
+    /// Converts the value back into a `GuraType`, deep cloning it.
+    pub fn to_gura_type(&self) -> GuraType {
+        match self {
+            SharedGuraType::Null => GuraType::Null,
+            SharedGuraType::Bool(value) => GuraType::Bool(*value),
+            SharedGuraType::String(value) => GuraType::String(value.to_string()),
+            SharedGuraType::Integer(value) => GuraType::Integer(*value),
+            SharedGuraType::BigInteger(value) => GuraType::BigInteger(*value),
+            SharedGuraType::Float(value) => GuraType::Float(*value),
+            SharedGuraType::Array(values) => {
+                GuraType::Array(values.iter().map(SharedGuraType::to_gura_type).collect())
+            }
+            SharedGuraType::Object(values) => GuraType::Object(
+                values
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.to_gura_type()))
+                    .collect(),
+            ),
+            SharedGuraType::Extension(tag, value) => {
+                GuraType::Extension(tag.to_string(), Box::new(value.to_gura_type()))
+            }
+        }
+    }
+}
+
+impl From<&GuraType> for SharedGuraType {
+    /// Internal variants (e.g. `GuraType::Comment`) are converted to `Null`.
+    fn from(value: &GuraType) -> Self {
+        match value {
+            GuraType::Bool(value) => SharedGuraType::Bool(*value),
+            GuraType::String(value) => SharedGuraType::String(Arc::from(value.as_str())),
+            GuraType::Integer(value) => SharedGuraType::Integer(*value),
+            GuraType::BigInteger(value) => SharedGuraType::BigInteger(*value),
+            GuraType::Float(value) => SharedGuraType::Float(*value),
+            GuraType::Array(values) => {
+                SharedGuraType::Array(values.iter().map(SharedGuraType::from).collect())
+            }
+            GuraType::Object(values) => SharedGuraType::Object(Arc::new(
+                values
+                    .iter()
+                    .map(|(key, value)| (key.clone(), SharedGuraType::from(value)))
+                    .collect(),
+            )),
+            GuraType::Extension(tag, value) => SharedGuraType::Extension(
+                Arc::from(tag.as_str()),
+                Arc::new(SharedGuraType::from(&**value)),
+            ),
+            _ => SharedGuraType::Null,
+        }
+    }
+}
+
+impl<T> Index<T> for SharedGuraType
+where
+    T: AsRef<str>,
+{
+    type Output = SharedGuraType;
+
+    fn index(&self, index: T) -> &SharedGuraType {
+        match self {
+            SharedGuraType::Object(object) => &object[index.as_ref()],
+            _ => panic!("Using index in an non object type. Check if the Gura object contains the key first"),
+        }
+    }
+}
+
+impl PartialEq<GuraType> for SharedGuraType {
+    fn eq(&self, other: &GuraType) -> bool {
+        match (self, other) {
+            (SharedGuraType::Array(values), GuraType::Array(others)) => {
+                values.len() == others.len() && values.iter().zip(others).all(|(a, b)| a == b)
+            }
+            (SharedGuraType::Object(values), GuraType::Object(others)) => {
+                values.len() == others.len()
+                    && values.iter().all(
+                        |(key, value)| matches!(others.get(key), Some(other) if value == other),
+                    )
+            }
+            (SharedGuraType::String(value), GuraType::String(other)) => **value == **other,
+            (SharedGuraType::Null, GuraType::Null) => true,
+            (SharedGuraType::Bool(value), GuraType::Bool(other)) => value == other,
+            (SharedGuraType::Integer(value), GuraType::Integer(other)) => value == other,
+            (SharedGuraType::BigInteger(value), GuraType::BigInteger(other)) => value == other,
+            (SharedGuraType::Float(value), GuraType::Float(other)) => value == other,
+            (SharedGuraType::Extension(tag, value), GuraType::Extension(other_tag, other)) => {
+                **tag == **other_tag && **value == **other
+            }
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq<isize> for SharedGuraType {
+    fn eq(&self, other: &isize) -> bool {
+        matches!(self, SharedGuraType::Integer(value) if value == other)
+    }
+}
+
+impl PartialEq<&str> for SharedGuraType {
+    fn eq(&self, other: &&str) -> bool {
+        matches!(self, SharedGuraType::String(value) if &**value == *other)
+    }
+}
+
+/// Same output as `GuraType`'s `Display`
+impl fmt::Display for SharedGuraType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_gura_type(), f)
+    }
+}
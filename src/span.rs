@@ -0,0 +1,408 @@
+use crate::{errors::TypeError, parser::GuraType};
+use indexmap::IndexMap;
+use std::path::PathBuf;
+
+/// Location of a piece of text in a Gura document. `start` and `end` are indexes of grapheme
+/// clusters, like `GuraError`'s `pos`. The same positions are also available as byte offsets
+/// (to slice the source `&str`) and UTF-16 code unit offsets (as used by LSP).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// Position of the first char
+    pub start: usize,
+    /// Position after the last char
+    pub end: usize,
+    /// Line of the first char
+    pub line: usize,
+    /// Byte offset of the first char
+    pub byte_start: usize,
+    /// Byte offset after the last char
+    pub byte_end: usize,
+    /// UTF-16 code unit offset of the first char
+    pub utf16_start: usize,
+    /// UTF-16 code unit offset after the last char
+    pub utf16_end: usize,
+}
+
+/// Comments and blank lines around a pair, so refactoring tools (e.g. to rename a key or move
+/// a section) can carry its documentation along with it. See `SpannedDocument::trivia`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trivia {
+    /// Number of blank lines before the pair (and its leading comments)
+    pub blank_lines_before: usize,
+    /// Comments in the lines right before the pair, with their `#` and without indentation
+    pub leading_comments: Vec<String>,
+    /// Span of the leading comments, from the `#` of the first one to the end of the last one
+    pub leading_span: Option<Span>,
+    /// Comment at the end of the line of the pair (for objects, the line of the key), with its
+    /// `#`
+    pub trailing_comment: Option<String>,
+}
+
+/// Definition of a variable (`$name: value`) found in spanned mode.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct VariableDefinition {
+    pub(crate) name: String,
+    pub(crate) value: GuraType,
+    /// Span of the name, including the `$`
+    pub(crate) span: Span,
+}
+
+/// Usage of a variable (`$name`) as a value or inside a basic string found in spanned mode.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct VariableReference {
+    pub(crate) name: String,
+    /// Resolved value of the variable
+    pub(crate) value: GuraType,
+    /// The variable is not defined in the text nor in its imported files
+    pub(crate) from_environment: bool,
+    /// Span of the name, including the `$`
+    pub(crate) span: Span,
+}
+
+/// Where the value of a variable comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableSource {
+    /// Defined in the document, with the span of the name in its definition. `None` if it was
+    /// defined in an imported file
+    Document(Option<Span>),
+    /// Read from an environment variable
+    Environment,
+}
+
+/// Usage of a variable with its resolved value (see `SpannedDocument::variable_usages`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableUsage {
+    pub name: String,
+    /// Span of the name, including the `$`
+    pub span: Span,
+    /// Value of the variable. Inside basic strings, numbers are inserted as text and other
+    /// values (except strings) are ignored
+    pub value: GuraType,
+    pub source: VariableSource,
+}
+
+/// Variable defined in the document with the locations of its usages (see
+/// `SpannedDocument::variables`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variable {
+    pub name: String,
+    pub value: GuraType,
+    /// Span of the name in the definition, including the `$`
+    pub span: Span,
+    /// Spans of the names in the usages (as values or inside basic strings), in the order they
+    /// appear in the text
+    pub references: Vec<Span>,
+}
+
+/// Import sentence (`import "file.ura"`) found in spanned mode.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ImportReference {
+    pub(crate) path: String,
+    /// Span of the quoted path
+    pub(crate) span: Span,
+}
+
+/// Element of the source text that refers to another location (see `SpannedDocument::reference_at`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reference {
+    /// Name of a variable, either in its definition or in a usage (as a value or inside a
+    /// basic string)
+    Variable {
+        name: String,
+        /// Span of the name, including the `$`
+        span: Span,
+        /// Span of the name in the definition. `None` if the variable is not defined in the
+        /// text (e.g. environment variables)
+        definition: Option<Span>,
+    },
+    /// Path of an import sentence
+    Import {
+        /// Span of the quoted path
+        span: Span,
+        /// Path of the imported file, as it is resolved by the parser
+        path: PathBuf,
+    },
+}
+
+/// Locations recorded while parsing in spanned mode.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SpanRecords {
+    /// Spans of the keys indexed by path (keys separated by dots, as in `GuraType::get_path`)
+    pub(crate) key_spans: IndexMap<String, Span>,
+    /// Spans of the whole pairs (key and value) indexed by path
+    pub(crate) pair_spans: IndexMap<String, Span>,
+    /// Variables defined in the text by name
+    pub(crate) variables: IndexMap<String, VariableDefinition>,
+    /// Variable usages indexed by their start position (rules can be matched more than once)
+    pub(crate) references: IndexMap<usize, VariableReference>,
+    /// Import sentences of the text
+    pub(crate) imports: Vec<ImportReference>,
+    /// Comments and blank lines of the pairs indexed by path
+    pub(crate) trivia: IndexMap<String, Trivia>,
+}
+
+/// A parsed Gura document with the location of its keys in the source text.
+/// Generated by `parse_spanned`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedDocument {
+    /// Parsed value, the same as the one returned by `parse`
+    pub value: GuraType,
+    pub(crate) records: SpanRecords,
+}
+
+impl SpannedDocument {
+    /// Gets the span of the key located at `path`.
+    ///
+    /// Returns `None` if the path does not exist or the key was defined in an imported file.
+    pub fn span(&self, path: &str) -> Option<Span> {
+        self.records.key_spans.get(path).copied()
+    }
+
+    /// Gets the span of the whole pair (from the start of the key to the end of the value)
+    /// located at `path`. For objects, the pair ends with its last child.
+    ///
+    /// Returns `None` if the path does not exist or the key was defined in an imported file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::parse_spanned;
+    ///
+    /// let text = "server:\n    port: 8080\n\n";
+    /// let document = parse_spanned(text).unwrap();
+    /// let span = document.pair_span("server").unwrap();
+    /// assert_eq!(&text[span.byte_start..span.byte_end], "server:\n    port: 8080");
+    /// ```
+    pub fn pair_span(&self, path: &str) -> Option<Span> {
+        self.records.pair_spans.get(path).copied()
+    }
+
+    /// Gets the value located at `path` with one of the `expect_*` methods of `GuraType` (or any
+    /// other function returning a `TypeError`), filling in the span of its key in the error.
+    ///
+    /// Returns `None` if the path does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{parse_spanned, GuraType};
+    ///
+    /// let document = parse_spanned("name: \"app\"\nport: \"8080\"").unwrap();
+    /// assert_eq!(document.expect("name", GuraType::expect_str), Some(Ok("app")));
+    ///
+    /// let error = document.expect("port", GuraType::expect_bool).unwrap().unwrap_err();
+    /// assert_eq!(error.span, document.span("port"));
+    /// assert_eq!(error.to_string(), "expected bool, found string (line 2)");
+    /// ```
+    pub fn expect<'a, T>(
+        &'a self,
+        path: &str,
+        expect: impl FnOnce(&'a GuraType) -> Result<T, TypeError>,
+    ) -> Option<Result<T, TypeError>> {
+        let value = self.value.get_path(path)?;
+        Some(expect(value).map_err(|mut error| {
+            error.span = self.span(path);
+            error
+        }))
+    }
+
+    /// Gets the comments and blank lines placed around the pair located at `path`.
+    ///
+    /// Returns `None` if the path does not exist or the key was defined in an imported file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::parse_spanned;
+    ///
+    /// let text = "name: \"app\"\n\n# Port to listen to\nport: 8080 # Default one";
+    /// let document = parse_spanned(text).unwrap();
+    /// let trivia = document.trivia("port").unwrap();
+    /// assert_eq!(trivia.blank_lines_before, 1);
+    /// assert_eq!(trivia.leading_comments, vec!["# Port to listen to"]);
+    /// assert_eq!(trivia.trailing_comment.as_deref(), Some("# Default one"));
+    /// ```
+    pub fn trivia(&self, path: &str) -> Option<&Trivia> {
+        self.records.trivia.get(path)
+    }
+
+    /// Gets an iterator over the path of every key of the document (nested ones included)
+    /// with its span, in the order they are defined. Keys defined in imported files are
+    /// not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{parse_spanned, span::Span};
+    ///
+    /// let document = parse_spanned("server:\n    prot: 8080").unwrap();
+    /// let keys: Vec<(&str, Span)> = document.keys_with_spans().collect();
+    ///
+    /// assert_eq!(keys[1].0, "server.prot");
+    /// assert_eq!((keys[1].1.start, keys[1].1.end, keys[1].1.line), (12, 16, 2));
+    /// ```
+    pub fn keys_with_spans(&self) -> impl Iterator<Item = (&str, Span)> + '_ {
+        self.records
+            .key_spans
+            .iter()
+            .map(|(path, span)| (path.as_str(), *span))
+    }
+
+    /// Gets every usage of a variable (as a value or inside a basic string) with its value and
+    /// where it was defined, in the order they appear in the text. Useful to explain where the
+    /// values of a document come from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{parse_spanned, span::VariableSource, GuraType};
+    ///
+    /// std::env::set_var("GURA_USAGES_USER", "admin");
+    /// let text = "$port: 8080\nserver:\n    port: $port\n    user: \"$GURA_USAGES_USER\"";
+    /// let document = parse_spanned(text).unwrap();
+    /// let usages = document.variable_usages();
+    ///
+    /// assert_eq!(usages[0].value, GuraType::Integer(8080));
+    /// match usages[0].source {
+    ///     VariableSource::Document(definition) => assert_eq!(definition.unwrap().line, 1),
+    ///     VariableSource::Environment => panic!("Expected a variable of the document"),
+    /// }
+    /// assert_eq!(usages[1].name, "GURA_USAGES_USER");
+    /// assert_eq!(usages[1].source, VariableSource::Environment);
+    /// ```
+    pub fn variable_usages(&self) -> Vec<VariableUsage> {
+        let mut usages: Vec<VariableUsage> = self
+            .records
+            .references
+            .values()
+            .map(|reference| VariableUsage {
+                name: reference.name.clone(),
+                span: reference.span,
+                value: reference.value.clone(),
+                source: if reference.from_environment {
+                    VariableSource::Environment
+                } else {
+                    VariableSource::Document(
+                        self.records
+                            .variables
+                            .get(&reference.name)
+                            .map(|definition| definition.span),
+                    )
+                },
+            })
+            .collect();
+        usages.sort_by_key(|usage| usage.span.start);
+        usages
+    }
+
+    /// Gets every variable defined in the document (not in its imported files), in the order
+    /// they are defined, with its value and the spans of its definition and usages. Useful for
+    /// linters and editors (e.g. to find references or rename a variable).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{parse_spanned, GuraType};
+    ///
+    /// let text = "$port: 8080\nserver:\n    port: $port\n    url: \"localhost:$port\"";
+    /// let document = parse_spanned(text).unwrap();
+    /// let variables = document.variables();
+    ///
+    /// assert_eq!(variables[0].name, "port");
+    /// assert_eq!(variables[0].value, GuraType::Integer(8080));
+    /// assert_eq!(&text[variables[0].span.byte_start..variables[0].span.byte_end], "$port");
+    /// assert_eq!(variables[0].references.len(), 2);
+    /// ```
+    pub fn variables(&self) -> Vec<Variable> {
+        self.records
+            .variables
+            .keys()
+            .filter_map(|name| self.variable(name))
+            .collect()
+    }
+
+    /// Gets the variable called `name` defined in the document (see `variables`).
+    ///
+    /// Returns `None` if it is not defined in the text (e.g. it was defined in an imported
+    /// file or it is an environment variable).
+    pub fn variable(&self, name: &str) -> Option<Variable> {
+        self.records.variables.get(name).map(|definition| Variable {
+            name: definition.name.clone(),
+            value: definition.value.clone(),
+            span: definition.span,
+            references: self.variable_references(name),
+        })
+    }
+
+    /// Gets the spans of every usage of the variable called `name` (as a value or inside a
+    /// basic string), in the order they appear in the text. Unlike `variable`, it also works
+    /// with variables defined in imported files and environment variables.
+    pub fn variable_references(&self, name: &str) -> Vec<Span> {
+        let mut references: Vec<Span> = self
+            .records
+            .references
+            .values()
+            .filter(|reference| reference.name == name)
+            .map(|reference| reference.span)
+            .collect();
+        references.sort_by_key(|span| span.start);
+        references
+    }
+
+    /// Gets the variable or import path placed at a byte offset of the source text. Useful
+    /// to implement editor navigation (e.g. go to definition).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gura::{parse_spanned, span::Reference};
+    ///
+    /// let text = "$port: 8080\nserver:\n    port: $port";
+    /// let document = parse_spanned(text).unwrap();
+    ///
+    /// match document.reference_at(text.rfind("$port").unwrap()) {
+    ///     Some(Reference::Variable { name, definition, .. }) => {
+    ///         assert_eq!(name, "port");
+    ///         assert_eq!(definition.unwrap().byte_start, 0);
+    ///     }
+    ///     _ => panic!("Expected a variable"),
+    /// }
+    /// assert_eq!(document.reference_at(text.find("server").unwrap()), None);
+    /// ```
+    pub fn reference_at(&self, byte_offset: usize) -> Option<Reference> {
+        let contains = |span: &Span| span.byte_start <= byte_offset && byte_offset < span.byte_end;
+
+        let variable = self
+            .records
+            .variables
+            .values()
+            .map(|definition| (&definition.name, &definition.span))
+            .chain(
+                self.records
+                    .references
+                    .values()
+                    .map(|reference| (&reference.name, &reference.span)),
+            )
+            .find(|(_, span)| contains(span));
+        if let Some((name, span)) = variable {
+            return Some(Reference::Variable {
+                name: name.clone(),
+                span: *span,
+                definition: self
+                    .records
+                    .variables
+                    .get(name)
+                    .map(|definition| definition.span),
+            });
+        }
+
+        self.records
+            .imports
+            .iter()
+            .find(|import| contains(&import.span))
+            .map(|import| Reference::Import {
+                span: import.span,
+                path: PathBuf::from(&import.path),
+            })
+    }
+}
@@ -0,0 +1,236 @@
+//! Versions of the [Gura specification](https://gura.netlify.app/docs/spec) implemented by the
+//! parser and the behaviors it supports beyond them, so tools can check their compatibility.
+//!
+//! # Examples
+//!
+//! ```
+//! use gura::{
+//!     parser::ParserOptions,
+//!     spec::{Extension, SpecVersion},
+//!     SPEC_VERSION,
+//! };
+//!
+//! assert_eq!(SPEC_VERSION, "1.0.0");
+//!
+//! let options = ParserOptions::default().spec_version(SpecVersion::V1_0_0);
+//! let compliance = options.compliance();
+//! assert_eq!(compliance.spec_version, SpecVersion::V1_0_0);
+//! assert!(compliance.has_extension(Extension::BigIntegers));
+//! assert!(!compliance.has_extension(Extension::LineContent));
+//! ```
+use std::fmt;
+
+/// Latest version of the Gura specification supported
+pub const SPEC_VERSION: &str = "1.0.0";
+
+/// Versions of the Gura specification that the parser can target
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum SpecVersion {
+    #[default]
+    V1_0_0,
+}
+
+impl SpecVersion {
+    /// Latest version supported (the one of `SPEC_VERSION`)
+    pub const LATEST: SpecVersion = SpecVersion::V1_0_0;
+
+    /// Gets the version number (e.g. `"1.0.0"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SpecVersion::V1_0_0 => "1.0.0",
+        }
+    }
+
+    /// Gets a supported version from its number. Missing minor and patch numbers are
+    /// considered 0 (e.g. `"1"` is `1.0.0`). Returns `None` for unsupported versions.
+    pub fn from_version(version: &str) -> Option<SpecVersion> {
+        let mut numbers = version.trim().splitn(3, '.').map(str::parse::<u32>);
+        let mut next = || numbers.next().unwrap_or(Ok(0)).ok();
+        match (next()?, next()?, next()?) {
+            (1, 0, 0) => Some(SpecVersion::V1_0_0),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SpecVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Behaviors of the parser not required by the specification
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Extension {
+    /// Integers that do not fit in 64 bits are parsed as `GuraType::BigInteger` (up to 128
+    /// bits) instead of failing
+    BigIntegers,
+    /// Errors include the text of the offending line (see
+    /// `ParserOptions::capture_line_content`)
+    LineContent,
+    /// Array elements separated by new lines without a comma are accepted (see
+    /// `ParserOptions::lenient_arrays`)
+    LenientArrays,
+    /// Indentation levels of a number of spaces other than 4 (see
+    /// `ParserOptions::indent_unit`)
+    IndentUnit,
+    /// The new line before the closing delimiter of multiline strings is removed (see
+    /// `ParserOptions::trim_final_newline`)
+    TrimFinalNewline,
+    /// Variables inside basic strings are not replaced by their values (see
+    /// `ParserOptions::raw_strings`)
+    RawStrings,
+    /// Custom scalars (e.g. `@duration 5s`) are parsed by registered recognizers (see
+    /// `ParserOptions::scalar_extensions`)
+    ScalarExtensions,
+    /// Files are imported only once, however many times they are imported (see
+    /// `ParserOptions::deduplicate_imports`)
+    DeduplicatedImports,
+    /// The variables `$__file__` and `$__dir__` are defined in files (see
+    /// `ParserOptions::builtin_variables`)
+    BuiltinVariables,
+    /// The variables `$__now_iso__`, `$__now_unix__` and `$__today__` are defined with the
+    /// current time (see `ParserOptions::time_variables`)
+    TimeVariables,
+    /// `\r\n` new lines of multiline strings are read as `\n` (see
+    /// `ParserOptions::normalize_newlines`)
+    NormalizedNewlines,
+    /// `\xHH` escape sequences of basic strings are read as code points (see
+    /// `ParserOptions::hex_escapes`)
+    HexEscapes,
+}
+
+impl Extension {
+    /// Gets the option of `ParserOptions` that enables (or, for big integers, disables) the
+    /// extension.
+    pub fn option(&self) -> &'static str {
+        match self {
+            Extension::BigIntegers => "conformance_strict",
+            Extension::LineContent => "capture_line_content",
+            Extension::LenientArrays => "lenient_arrays",
+            Extension::IndentUnit => "indent_unit",
+            Extension::TrimFinalNewline => "trim_final_newline",
+            Extension::RawStrings => "raw_strings",
+            Extension::ScalarExtensions => "scalar_extensions",
+            Extension::DeduplicatedImports => "deduplicate_imports",
+            Extension::BuiltinVariables => "builtin_variables",
+            Extension::TimeVariables => "time_variables",
+            Extension::NormalizedNewlines => "normalize_newlines",
+            Extension::HexEscapes => "hex_escapes",
+        }
+    }
+
+    /// Describes how the extension deviates from the specification.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Extension::BigIntegers => "integers that do not fit in 64 bits are accepted",
+            Extension::LineContent => "errors include the text of the offending line",
+            Extension::LenientArrays => {
+                "array elements separated by new lines without a comma are accepted"
+            }
+            Extension::IndentUnit => "indentation levels are not of 4 spaces",
+            Extension::TrimFinalNewline => {
+                "the new line before the closing delimiter of multiline strings is removed"
+            }
+            Extension::RawStrings => "variables inside basic strings are not replaced",
+            Extension::ScalarExtensions => "custom scalars (e.g. `@duration 5s`) are accepted",
+            Extension::DeduplicatedImports => "files can be imported more than once",
+            Extension::BuiltinVariables => "the variables `$__file__` and `$__dir__` are defined",
+            Extension::TimeVariables => "variables with the current time are defined",
+            Extension::NormalizedNewlines => {
+                "new lines of multiline strings written as \\r\\n are read as \\n"
+            }
+            Extension::HexEscapes => "escape sequences \\xHH are read as code points",
+        }
+    }
+}
+
+/// Report of what a parser configuration supports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComplianceLevel {
+    /// Version of the specification targeted
+    pub spec_version: SpecVersion,
+    /// Enabled behaviors beyond the specification
+    pub extensions: Vec<Extension>,
+    /// Optional features of the crate compiled in (e.g. `"serde"`)
+    pub features: Vec<&'static str>,
+}
+
+impl ComplianceLevel {
+    /// Checks if an extension is enabled.
+    pub fn has_extension(&self, extension: Extension) -> bool {
+        self.extensions.contains(&extension)
+    }
+
+    /// Checks if an optional feature of the crate was compiled in.
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.contains(&feature)
+    }
+}
+
+/// Behaviors of a parser configuration that deviate from the specification (see
+/// `ParserOptions::explain`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConformanceReport {
+    /// Version of the specification targeted
+    pub spec_version: SpecVersion,
+    /// Enabled behaviors beyond the specification
+    pub deviations: Vec<Extension>,
+}
+
+impl ConformanceReport {
+    /// Checks if only the behavior of the specification is enabled.
+    pub fn is_conformant(&self) -> bool {
+        self.deviations.is_empty()
+    }
+}
+
+/// Shows the version of the specification followed by a line for every deviation with its
+/// option (e.g. `- raw_strings: variables inside basic strings are not replaced`).
+impl fmt::Display for ConformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_conformant() {
+            return write!(f, "Conforms to Gura {}", self.spec_version);
+        }
+        write!(f, "Deviates from Gura {}:", self.spec_version)?;
+        for deviation in &self.deviations {
+            write!(f, "\n- {}: {}", deviation.option(), deviation.description())?;
+        }
+        Ok(())
+    }
+}
+
+/// Optional features of the crate, as declared in its manifest, and whether they were compiled
+/// in
+const FEATURES: [(&str, bool); 14] = [
+    ("arena", cfg!(feature = "arena")),
+    ("cli", cfg!(feature = "cli")),
+    ("color", cfg!(feature = "color")),
+    ("compliance", cfg!(feature = "compliance")),
+    ("determinism", cfg!(feature = "determinism")),
+    ("ffi", cfg!(feature = "ffi")),
+    ("lsp", cfg!(feature = "lsp")),
+    ("mmap", cfg!(feature = "mmap")),
+    ("python", cfg!(feature = "python")),
+    ("schemars", cfg!(feature = "schemars")),
+    ("serde", cfg!(feature = "serde")),
+    ("stats", cfg!(feature = "stats")),
+    ("trace-parser", cfg!(feature = "trace-parser")),
+    ("tracing", cfg!(feature = "tracing")),
+];
+
+/// Gets the names of all the optional features of the crate, compiled in or not.
+pub fn available_features() -> Vec<&'static str> {
+    FEATURES.iter().map(|(name, _)| *name).collect()
+}
+
+/// Gets the optional features of the crate that were compiled in.
+pub(crate) fn enabled_features() -> Vec<&'static str> {
+    FEATURES
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| *name)
+        .collect()
+}
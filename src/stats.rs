@@ -0,0 +1,66 @@
+//! Timings and counters of the phases of a parsing (see `parse_with_stats`), to diagnose slow
+//! configuration loads and to guide optimizations of the parser.
+//!
+//! Requires the `stats` feature, which adds the bookkeeping to the parser.
+//!
+//! # Examples
+//!
+//! ```
+//! use gura::{parse_with_stats, parser::ParserOptions};
+//!
+//! let (parsed, stats) = parse_with_stats("server:\n    port: 8080", &ParserOptions::default()).unwrap();
+//! assert_eq!(parsed["server"]["port"], 8080);
+//! assert_eq!(stats.graphemes, 22);
+//! assert_eq!(stats.imported_files, 0);
+//! println!("{}", stats);
+//! ```
+use std::{fmt, time::Duration};
+
+/// Timings and counters of a parsing. The phases do not overlap, so their timings add up to
+/// the whole parsing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseStats {
+    /// Time spent splitting texts (the parsed one and the imported ones) into grapheme clusters
+    pub segmentation: Duration,
+    /// Time spent reading imported files and matching import sentences, without their
+    /// segmentation
+    pub imports: Duration,
+    /// Time spent matching the rules that build the values
+    pub matching: Duration,
+    /// Number of grapheme clusters allocated by the segmentation. Texts are segmented again
+    /// when their imports are inlined, so it can be larger than the text
+    pub graphemes: usize,
+    /// Number of imported files
+    pub imported_files: usize,
+    /// Number of rules tried (including the ones that did not match), which measures the
+    /// backtracking of the parser
+    pub rule_attempts: usize,
+}
+
+impl ParseStats {
+    /// Gets the time of the whole parsing.
+    pub fn total(&self) -> Duration {
+        self.segmentation + self.imports + self.matching
+    }
+
+    /// Adds the counters of a nested parsing (e.g. of an imported file).
+    pub(crate) fn add(&mut self, other: &ParseStats) {
+        self.segmentation += other.segmentation;
+        self.imports += other.imports;
+        self.matching += other.matching;
+        self.graphemes += other.graphemes;
+        self.rule_attempts += other.rule_attempts;
+    }
+}
+
+impl fmt::Display for ParseStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "segmentation: {:?}", self.segmentation)?;
+        writeln!(f, "imports: {:?}", self.imports)?;
+        writeln!(f, "matching: {:?}", self.matching)?;
+        writeln!(f, "total: {:?}", self.total())?;
+        writeln!(f, "graphemes: {}", self.graphemes)?;
+        writeln!(f, "imported files: {}", self.imported_files)?;
+        write!(f, "rule attempts: {}", self.rule_attempts)
+    }
+}
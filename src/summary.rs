@@ -0,0 +1,120 @@
+use crate::parser::{GuraType, Kind};
+use indexmap::IndexMap;
+use std::fmt;
+
+/// Maximum number of distinct example values stored for every path
+const MAX_EXAMPLES: usize = 3;
+
+/// Statistics of the values found at a path of a document
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathSummary {
+    /// Number of values found at the path. Paths inside arrays can have many values
+    pub count: usize,
+    /// Number of values of every kind, in the order they were found
+    pub kinds: IndexMap<Kind, usize>,
+    /// Some distinct primitive values found at the path
+    pub examples: Vec<GuraType>,
+}
+
+/// Structural summary of a document (see `GuraType::summary`). Paths are keys separated by
+/// dots, where the elements of arrays are aggregated with `[]` (e.g. `hosts[].name`). The
+/// root value has the empty path.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Summary {
+    paths: IndexMap<String, PathSummary>,
+}
+
+impl Summary {
+    /// Generates the summary of a value.
+    pub(crate) fn new(value: &GuraType) -> Self {
+        let mut summary = Summary::default();
+        summary.add("", value);
+        summary
+    }
+
+    /// Gets the statistics of a path.
+    pub fn get(&self, path: &str) -> Option<&PathSummary> {
+        self.paths.get(path)
+    }
+
+    /// Gets an iterator over all the paths with their statistics, depth first.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &PathSummary)> {
+        self.paths
+            .iter()
+            .map(|(path, summary)| (path.as_str(), summary))
+    }
+
+    fn add(&mut self, path: &str, value: &GuraType) {
+        let summary = self
+            .paths
+            .entry(path.to_string())
+            .or_insert_with(|| PathSummary {
+                count: 0,
+                kinds: IndexMap::new(),
+                examples: Vec::new(),
+            });
+        summary.count += 1;
+        *summary.kinds.entry(value.kind()).or_insert(0) += 1;
+
+        match value {
+            GuraType::Object(values) => {
+                for (key, child) in values {
+                    let child_path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    self.add(&child_path, child);
+                }
+            }
+            GuraType::Array(values) => {
+                let items_path = format!("{}[]", path);
+                for item in values {
+                    self.add(&items_path, item);
+                }
+            }
+            _ => {
+                if summary.examples.len() < MAX_EXAMPLES && !summary.examples.contains(value) {
+                    summary.examples.push(value.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Shows a line for every path with its count, kinds and examples (e.g.
+/// `hosts[].port: 3 values (integer: 2, null: 1), e.g. 80, 443`).
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, (path, summary)) in self.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+
+            let kinds = summary
+                .kinds
+                .iter()
+                .map(|(kind, count)| format!("{}: {}", kind, count))
+                .collect::<Vec<String>>()
+                .join(", ");
+            let label = if path.is_empty() { "<root>" } else { path };
+            let plural = if summary.count == 1 { "" } else { "s" };
+            write!(
+                f,
+                "{}: {} value{} ({})",
+                label, summary.count, plural, kinds
+            )?;
+
+            if !summary.examples.is_empty() {
+                let examples = summary
+                    .examples
+                    .iter()
+                    .map(|example| example.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, ", e.g. {}", examples)?;
+            }
+        }
+        Ok(())
+    }
+}
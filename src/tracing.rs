@@ -0,0 +1,60 @@
+//! Support for [`valuable`](https://docs.rs/valuable), so Gura values can be recorded as
+//! structured fields by `tracing` (with `tracing::field::valuable`) and other structured
+//! logging libraries, without converting them to JSON.
+//!
+//! Objects are exposed as maps with string keys, arrays as lists, `null` as the unit value
+//! and the rest of values as their primitive types.
+//!
+//! Requires the `tracing` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use gura::{object, GuraType};
+//! use valuable::{Valuable, Value};
+//!
+//! let config = object! { port: 8080 };
+//! assert!(matches!(config.as_value(), Value::Mappable(_)));
+//! assert!(matches!(config["port"].as_value(), Value::Isize(8080)));
+//! ```
+use crate::parser::GuraType;
+use valuable::{Mappable, Valuable, Value, Visit};
+
+impl Valuable for GuraType {
+    fn as_value(&self) -> Value<'_> {
+        match self {
+            GuraType::Object(_) => Value::Mappable(self),
+            GuraType::Array(values) => Value::Listable(values),
+            GuraType::String(value) => Value::String(value),
+            GuraType::Integer(value) => Value::Isize(*value),
+            GuraType::BigInteger(value) => Value::I128(*value),
+            GuraType::Float(value) => Value::F64(*value),
+            GuraType::Bool(value) => Value::Bool(*value),
+            GuraType::Extension(_, value) => value.as_value(),
+            _ => Value::Unit,
+        }
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        match self {
+            GuraType::Object(values) => {
+                for (key, value) in values {
+                    visit.visit_entry(key.as_value(), value.as_value());
+                }
+            }
+            GuraType::Array(values) => values.visit(visit),
+            _ => visit.visit_value(self.as_value()),
+        }
+    }
+}
+
+/// Only objects have entries
+impl Mappable for GuraType {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match self {
+            GuraType::Object(values) => values.len(),
+            _ => 0,
+        };
+        (len, Some(len))
+    }
+}
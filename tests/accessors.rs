@@ -0,0 +1,528 @@
+use gura::{
+    errors::{ArrayConversionError, Error, ExtractError, IntegerConversionError, TypeError},
+    from_str_at, gura_get, object, parse, parse_spanned, GuraType, Kind,
+};
+use std::convert::TryFrom;
+
+fn get_parsed() -> GuraType {
+    parse(
+        r##"title: "Gura"
+port: 8080
+big: 99999999999999999999999
+ratio: 0.5
+enabled: true
+nothing: null
+hosts: ["alpha", "omega"]
+services:
+    nginx:
+        port: 80"##,
+    )
+    .unwrap()
+}
+
+#[test]
+/// Tests kinds and type names of every value type
+fn test_kind() {
+    let parsed = get_parsed();
+    assert_eq!(parsed.kind(), Kind::Object);
+    assert_eq!(parsed["title"].kind(), Kind::String);
+    assert_eq!(parsed["port"].kind(), Kind::Integer);
+    assert_eq!(parsed["big"].kind(), Kind::BigInteger);
+    assert_eq!(parsed["ratio"].kind(), Kind::Float);
+    assert_eq!(parsed["enabled"].kind(), Kind::Bool);
+    assert_eq!(parsed["nothing"].kind(), Kind::Null);
+    assert_eq!(parsed["hosts"].kind(), Kind::Array);
+    assert_eq!(parsed["services"].type_name(), "object");
+    assert_eq!(GuraType::Comment.kind(), Kind::Internal);
+    assert_eq!(object! {}.type_name(), "object");
+    assert_eq!(Kind::BigInteger.to_string(), "big integer");
+}
+
+#[test]
+/// Tests typed extraction of values
+fn test_expect() {
+    let parsed = get_parsed();
+    assert_eq!(parsed["title"].expect_str(), Ok("Gura"));
+    assert_eq!(parsed["port"].expect_i64(), Ok(8080));
+    assert_eq!(parsed["ratio"].expect_f64(), Ok(0.5));
+    assert_eq!(parsed["enabled"].expect_bool(), Ok(true));
+    assert_eq!(parsed["hosts"].expect_array().unwrap().len(), 2);
+    assert!(parsed["services"]
+        .expect_object()
+        .unwrap()
+        .contains_key("nginx"));
+}
+
+#[test]
+/// Tests errors of typed extraction
+fn test_expect_errors() {
+    let parsed = get_parsed();
+    assert_eq!(
+        parsed["title"].expect_i64(),
+        Err(IntegerConversionError::WrongType(TypeError {
+            expected: Kind::Integer,
+            found: Kind::String,
+            span: None,
+        }))
+    );
+    assert_eq!(
+        parsed["big"].expect_i64(),
+        Err(IntegerConversionError::OutOfRange {
+            target: "i64",
+            value: 99999999999999999999999
+        })
+    );
+    assert_eq!(
+        parsed["big"].expect_i64().unwrap_err().to_string(),
+        "integer 99999999999999999999999 is out of range for i64"
+    );
+    assert_eq!(
+        parsed["port"].expect_f64().unwrap_err().found,
+        Kind::Integer
+    );
+    assert_eq!(
+        parsed["nothing"].expect_object().unwrap_err().expected,
+        Kind::Object
+    );
+}
+
+#[test]
+/// Tests that typed extraction from spanned documents reports the position of the key
+fn test_expect_spanned() {
+    let document =
+        parse_spanned("title: \"Gura\"\nservices:\n    nginx:\n        port: \"80\"").unwrap();
+    assert_eq!(
+        document.expect("title", GuraType::expect_str),
+        Some(Ok("Gura"))
+    );
+    assert_eq!(document.expect("missing", GuraType::expect_str), None);
+
+    let error = document
+        .expect("services.nginx.port", GuraType::expect_f64)
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TypeError {
+            expected: Kind::Float,
+            found: Kind::String,
+            span: document.span("services.nginx.port"),
+        }
+    );
+    assert_eq!(error.span.unwrap().line, 4);
+    assert_eq!(error.to_string(), "expected float, found string (line 4)");
+}
+
+#[test]
+/// Tests lookups by key and by path
+fn test_get_path() {
+    let parsed = get_parsed();
+    assert_eq!(*parsed.get("port").unwrap(), 8080);
+    assert!(parsed.get("missing").is_none());
+    assert!(parsed["port"].get("port").is_none());
+    assert_eq!(*parsed.get_path("services.nginx.port").unwrap(), 80);
+    assert_eq!(*parsed.get_path("hosts.1").unwrap(), "omega");
+    assert!(parsed.get_path("hosts.2").is_none());
+    assert!(parsed.get_path("hosts.first").is_none());
+    assert!(parsed.get_path("services.apache.port").is_none());
+}
+
+#[test]
+/// Tests conversions to primitive types
+fn test_try_from() {
+    let parsed = get_parsed();
+    assert_eq!(u16::try_from(&parsed["port"]), Ok(8080));
+    assert_eq!(
+        u8::try_from(&parsed["port"]).unwrap_err().to_string(),
+        "integer 8080 is out of range for u8"
+    );
+    assert_eq!(
+        u64::try_from(&parsed["title"]).unwrap_err().to_string(),
+        "expected integer, found string"
+    );
+    assert_eq!(i128::try_from(&parsed["big"]), Ok(99999999999999999999999));
+    assert_eq!(<&str>::try_from(&parsed["title"]), Ok("Gura"));
+    assert_eq!(String::try_from(&parsed["title"]), Ok(String::from("Gura")));
+    assert_eq!(bool::try_from(&parsed["enabled"]), Ok(true));
+    assert!(f64::try_from(&parsed["title"]).is_err());
+}
+
+#[test]
+/// Tests getters with default values
+fn test_get_or() {
+    let parsed = get_parsed();
+    assert_eq!(parsed.get_or("port", 1), 8080);
+    assert_eq!(parsed.get_or("timeout", 30u64), 30);
+    assert_eq!(parsed.get_or("port", "wrong type"), "wrong type");
+    assert_eq!(parsed.get_path_or("services.nginx.port", 8080u16), 80);
+    assert_eq!(
+        parsed.get_path_or("services.nginx.host", "localhost"),
+        "localhost"
+    );
+    assert_eq!(parsed.get_path_or("ratio", 1.0), 0.5);
+}
+
+#[test]
+/// Tests retaining and filtering keys of objects
+fn test_retain() {
+    let mut parsed = get_parsed();
+    parsed
+        .retain(|_, value| value.kind() != Kind::Object)
+        .unwrap();
+    assert!(!parsed.contains_key("services"));
+    parsed
+        .filter_keys(|key| key != "title" && key != "big")
+        .unwrap();
+    let keys: Vec<&String> = parsed.iter().unwrap().map(|(key, _)| key).collect();
+    assert_eq!(keys, ["port", "ratio", "enabled", "nothing", "hosts"]);
+    assert!(parsed["port"].clone().filter_keys(|_| true).is_err());
+}
+
+#[test]
+/// Tests keys renaming
+fn test_rename_key() {
+    let mut parsed = object! {a: 1, b: 2, c: 3};
+    assert_eq!(parsed.rename_key("b", "renamed"), Ok(true));
+    assert_eq!(parsed, object! {a: 1, renamed: 2, c: 3});
+    let keys: Vec<&String> = parsed.iter().unwrap().map(|(key, _)| key).collect();
+    assert_eq!(keys, ["a", "renamed", "c"]);
+    assert_eq!(parsed.rename_key("b", "d"), Ok(false));
+    assert!(parsed.rename_key("a", "c").is_err());
+    assert_eq!(parsed.rename_key("a", "a"), Ok(true));
+}
+
+#[test]
+/// Tests conversion of arrays into Vecs
+fn test_to_vec_of() {
+    let parsed = get_parsed();
+    assert_eq!(
+        parsed["hosts"].to_vec_of::<&str>(),
+        Ok(vec!["alpha", "omega"])
+    );
+    assert_eq!(
+        parsed["hosts"].to_vec_of::<i64>(),
+        Err(ArrayConversionError::Element {
+            index: 0,
+            error: IntegerConversionError::WrongType(TypeError {
+                expected: Kind::Integer,
+                found: Kind::String,
+                span: None,
+            })
+        })
+    );
+    assert_eq!(
+        parsed["title"]
+            .to_vec_of::<String>()
+            .unwrap_err()
+            .to_string(),
+        "expected array, found string"
+    );
+    assert_eq!(
+        parse("a: [1, 2, true]").unwrap()["a"]
+            .to_vec_of::<u8>()
+            .unwrap_err()
+            .to_string(),
+        "invalid element at index 2: expected integer, found bool"
+    );
+}
+
+#[test]
+/// Tests extraction of subtrees from Gura texts
+fn test_from_str_at() {
+    let text = "services:\n    nginx:\n        port: 80\n        hosts: [\"a\", \"b\"]";
+    assert_eq!(from_str_at::<u16, _>(text, "services.nginx.port"), Ok(80));
+    assert_eq!(
+        from_str_at::<String, _>(text, "services.nginx.hosts.1"),
+        Ok(String::from("b"))
+    );
+    assert_eq!(
+        from_str_at::<u16, _>(text, "services.nginx.host"),
+        Err(ExtractError::PathNotFound(String::from(
+            "services.nginx.host"
+        )))
+    );
+    assert_eq!(
+        from_str_at::<bool, _>(text, "services.nginx.port")
+            .unwrap_err()
+            .to_string(),
+        "Invalid value at \"services.nginx.port\": expected bool, found integer"
+    );
+    match from_str_at::<u16, _>("port: $undefined", "port") {
+        Err(ExtractError::Parse(error)) => assert_eq!(error.kind, Error::VariableNotDefinedError),
+        _ => panic!("Expected a parsing error"),
+    }
+}
+
+#[test]
+/// Tests the path accessor macro
+fn test_gura_get() {
+    let parsed =
+        parse("services:\n    nginx:\n        port: 80\n        hosts: [\"a\", \"b\"]").unwrap();
+    assert_eq!(gura_get!(parsed, services.nginx.port as u16), Ok(80));
+    assert_eq!(gura_get!(&parsed, services.nginx.hosts.1 as &str), Ok("b"));
+    assert_eq!(
+        gura_get!(parsed, services.nginx.host as u16),
+        Err(ExtractError::PathNotFound(String::from(
+            "services.nginx.host"
+        )))
+    );
+    match gura_get!(parsed, services.nginx.port as u8) {
+        Ok(port) => assert_eq!(port, 80),
+        Err(error) => panic!("Unexpected error {}", error),
+    }
+    assert_eq!(
+        gura_get!(parsed, services.nginx.hosts as u16).unwrap_err(),
+        ExtractError::Conversion {
+            path: String::from("services.nginx.hosts"),
+            error: IntegerConversionError::WrongType(TypeError {
+                expected: Kind::Integer,
+                found: Kind::Array,
+                span: None,
+            })
+        }
+    );
+
+    assert_eq!(
+        gura_get!(parsed, services.nginx.port),
+        Some(&GuraType::Integer(80))
+    );
+    assert_eq!(gura_get!(parsed, services.apache), None);
+}
+
+#[test]
+/// Tests key lookup using normalized keys
+fn test_get_normalized() {
+    let parsed =
+        parse("http_server:\n    Max_Connections: 10\n    max_connections: 20\nhosts: [\"alpha\"]")
+            .unwrap();
+    assert_eq!(
+        *parsed
+            .get_normalized("HTTP-SERVER")
+            .unwrap()
+            .get_normalized("max-connections")
+            .unwrap(),
+        10
+    );
+    // Exact matches take precedence
+    assert_eq!(
+        *parsed
+            .get_path_normalized("http-server.max_connections")
+            .unwrap(),
+        20
+    );
+    assert_eq!(*parsed.get_path_normalized("Hosts.0").unwrap(), "alpha");
+    assert!(parsed.get_path_normalized("http_server.timeout").is_none());
+    assert!(parsed.get_path_normalized("hosts.0.name").is_none());
+}
+
+#[test]
+/// Tests structural summaries of arrays of objects
+fn test_summary() {
+    let parsed = parse(
+        "hosts: [\n    host:\n        port: 80\n    ,\n    host:\n        port: 443\n    ,\n    host:\n        port: null\n]\nname: \"generated\"",
+    )
+    .unwrap();
+    let summary = parsed.summary();
+
+    let paths: Vec<&str> = summary.iter().map(|(path, _)| path).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "",
+            "hosts",
+            "hosts[]",
+            "hosts[].host",
+            "hosts[].host.port",
+            "name"
+        ]
+    );
+
+    let ports = summary.get("hosts[].host.port").unwrap();
+    assert_eq!(ports.count, 3);
+    assert_eq!(
+        ports.kinds.iter().collect::<Vec<_>>(),
+        vec![(&Kind::Integer, &2), (&Kind::Null, &1)]
+    );
+    assert_eq!(
+        ports.examples,
+        vec![
+            GuraType::Integer(80),
+            GuraType::Integer(443),
+            GuraType::Null
+        ]
+    );
+    assert!(summary.get("hosts[]").unwrap().examples.is_empty());
+    assert!(summary.get("hosts.0").is_none());
+
+    let text = summary.to_string();
+    assert_eq!(text.lines().next().unwrap(), "<root>: 1 value (object: 1)");
+    assert!(
+        text.contains("\nhosts[].host.port: 3 values (integer: 2, null: 1), e.g. 80, 443, null\n")
+    );
+}
+
+#[test]
+/// Tests size and complexity metrics of nested documents
+fn test_metrics() {
+    let parsed = parse(
+        "hosts: [\n    host:\n        name: \"alpha\"\n    ,\n    [\"beta\", \"gamma\"]\n]\nname: \"generated\"\nempty: empty",
+    )
+    .unwrap();
+    let metrics = parsed.metrics();
+
+    // Root, hosts, both elements, host, name, two strings, name and empty
+    assert_eq!(metrics.nodes, 10);
+    // hosts -> [] -> host -> name
+    assert_eq!(metrics.max_depth, 4);
+    assert_eq!(metrics.keys, 5);
+    assert_eq!(
+        metrics.section_keys.iter().collect::<Vec<_>>(),
+        vec![
+            (&String::from("hosts"), &2),
+            (&String::from("name"), &0),
+            (&String::from("empty"), &0)
+        ]
+    );
+    assert_eq!(metrics.string_bytes, 5 + 4 + 5 + 9);
+
+    let metrics = GuraType::Integer(1).metrics();
+    assert_eq!((metrics.nodes, metrics.max_depth, metrics.keys), (1, 0, 0));
+    assert!(metrics.section_keys.is_empty());
+}
+
+#[test]
+/// Tests checked and lossy numeric conversions
+fn test_numeric_conversions() {
+    assert_eq!(GuraType::Integer(-1).to_u8(), None);
+    assert_eq!(GuraType::Integer(255).to_u8(), Some(255));
+    assert_eq!(GuraType::BigInteger(i128::MAX).to_i128(), Some(i128::MAX));
+    assert_eq!(GuraType::Float(1.0).to_i32(), None);
+    assert_eq!(GuraType::String(String::from("1")).as_f64_lossy(), None);
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+/// Tests conversions of integers that only fit in an `isize` of 64 bits
+fn test_wide_numeric_conversions() {
+    let big = GuraType::Integer(isize::MAX);
+    assert_eq!(big.to_i64(), Some(i64::MAX));
+    assert_eq!(big.to_i32(), None);
+
+    assert_eq!(
+        GuraType::Integer(1 << 53).to_f64(),
+        Some(9007199254740992.0)
+    );
+    assert_eq!(GuraType::Integer((1 << 53) + 1).to_f64(), None);
+    assert_eq!(
+        GuraType::Integer((1 << 53) + 1).as_f64_lossy(),
+        Some(9007199254740992.0)
+    );
+}
+
+#[test]
+/// Tests that comparisons with numbers do not overflow
+fn test_numeric_eq() {
+    #[cfg(target_pointer_width = "64")]
+    {
+        let overflowing = GuraType::Integer((1 << 32) + 5);
+        assert_ne!(overflowing, 5i32);
+        assert_eq!(overflowing, (1i64 << 32) + 5);
+    }
+
+    let big = GuraType::BigInteger(i128::from(i64::MAX) + 1);
+    assert_ne!(big, i64::MIN);
+    assert_eq!(big, i128::from(i64::MAX) + 1);
+    assert_eq!(GuraType::Integer(-3), -3i32);
+}
+
+#[test]
+/// Tests that fingerprints only change when values do
+fn test_fingerprint() {
+    let parsed = get_parsed();
+    assert_eq!(parsed.fingerprint(), get_parsed().fingerprint());
+
+    // Stable across platforms and versions
+    assert_eq!(GuraType::Null.fingerprint(), 0xaf63_e34c_8601_f871);
+
+    let reordered = parse("b: [1, 2]\na:\n    x: 0.0").unwrap();
+    let original = parse("a:\n    x: -0.0\nb: [1, 2]").unwrap();
+    assert_eq!(reordered.fingerprint(), original.fingerprint());
+
+    let distinct = [
+        object! { a: 1 },
+        object! { a: 2 },
+        object! { a: "1" },
+        object! { b: 1 },
+        object! { a: [1, 2] },
+        object! { a: [2, 1] },
+        object! { a: { b: 1 } },
+        object! { ab: "" },
+        object! { a: "b" },
+        GuraType::BigInteger(1),
+        GuraType::Integer(1),
+        GuraType::Float(1.0),
+    ];
+    for (index, value) in distinct.iter().enumerate() {
+        for other in distinct[index + 1..].iter() {
+            assert_ne!(value.fingerprint(), other.fingerprint());
+        }
+    }
+}
+
+#[test]
+/// Tests that the normalized debug representation is sorted and ignores the formatting
+fn test_debug_normalized() {
+    assert_eq!(
+        get_parsed().to_debug_normalized(),
+        "Object{big: Int(99999999999999999999999), enabled: Bool(true), hosts: Array[String(\"alpha\"), String(\"omega\")], nothing: Null, port: Int(8080), ratio: Float(0.5), services: Object{nginx: Object{port: Int(80)}}, title: String(\"Gura\")}"
+    );
+
+    let reordered = parse("b: [\n    1,\n    2\n]\na: \"line\\n\"").unwrap();
+    let compact = parse("a: 'line\n'\nb: [1, 2]").unwrap();
+    assert_eq!(
+        reordered.to_debug_normalized(),
+        compact.to_debug_normalized()
+    );
+    assert_eq!(
+        compact.to_debug_normalized(),
+        "Object{a: String(\"line\\n\"), b: Array[Int(1), Int(2)]}"
+    );
+
+    assert_eq!(
+        GuraType::BigInteger(1).to_debug_normalized(),
+        GuraType::Integer(1).to_debug_normalized()
+    );
+    assert_eq!(
+        object! {"with space": f64::NAN, empty: {}}.to_debug_normalized(),
+        "Object{empty: Object{}, \"with space\": Float(NaN)}"
+    );
+}
+
+#[test]
+/// Tests the shared representation of values
+fn test_shared() {
+    let parsed = get_parsed();
+    let shared = parsed.shared();
+    assert_eq!(shared, parsed);
+    assert_eq!(shared.to_gura_type(), parsed);
+    assert_eq!(shared.to_string(), parsed.to_string());
+
+    // Clones share the same data
+    let copy = shared.clone();
+    assert!(copy.ptr_eq(&shared));
+    assert_eq!(shared["title"], "Gura");
+    assert_eq!(shared.get("port"), Some(shared["port"].clone()));
+    assert_eq!(shared.get("port").unwrap().kind(), Kind::Integer);
+    assert_eq!(shared.get("missing"), None);
+    assert_eq!(shared["port"].get("nested"), None);
+
+    let shared = parse("hosts: [\n    name: \"alpha\",\n    name: \"omega\"\n]")
+        .unwrap()
+        .shared();
+    assert_eq!(shared.get_path("hosts.1.name").unwrap(), "omega");
+    assert!(shared
+        .get("hosts")
+        .unwrap()
+        .ptr_eq(&shared.get("hosts").unwrap()));
+    assert_ne!(shared, object! { hosts: [] });
+}
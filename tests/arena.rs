@@ -0,0 +1,34 @@
+#![cfg(feature = "arena")]
+use gura::{arena::Bump, object, parse, GuraType, Kind};
+
+#[test]
+/// Tests the arena representation of values
+fn test_deep_clone_into() {
+    let parsed = parse(
+        "title: \"Gura\"\nport: 8080\nbig: 99999999999999999999\nratio: 0.5\nenabled: true\nnothing: null\nhosts: [\"alpha\", [\"omega\"]]\nserver:\n    limits:\n        max: 10",
+    )
+    .unwrap();
+    let arena = Bump::new();
+    let value = parsed.deep_clone_into(&arena);
+
+    assert_eq!(value, parsed);
+    assert_eq!(value.to_gura_type(), parsed);
+    assert_eq!(value.to_string(), parsed.to_string());
+    assert_eq!(value["title"], "Gura");
+    assert_eq!(value.get("port").unwrap(), 8080);
+    assert_eq!(value.get("missing"), None);
+    assert_eq!(value.get_path("server.limits.max").unwrap(), 10);
+    assert_eq!(value.get_path("hosts.1.0").unwrap(), "omega");
+    assert_eq!(value.get_path("hosts.2"), None);
+    assert_eq!(value["big"].kind(), Kind::BigInteger);
+    assert_eq!(value["hosts"].kind(), Kind::Array);
+
+    // Values are copies of the arena, not of the parsed document
+    let mut copy = value;
+    copy = copy["server"];
+    assert_eq!(copy, object! { limits: { max: 10 } });
+    assert!(arena.allocated_bytes() > 0);
+
+    let extension = GuraType::Extension(String::from("duration"), Box::new(GuraType::Integer(5)));
+    assert_eq!(extension.deep_clone_into(&arena), extension);
+}
@@ -1,4 +1,8 @@
-use gura::{object, parser::GuraType};
+use gura::{
+    errors::Error,
+    object, parse, parse_with_warnings,
+    parser::{GuraType, ParserOptions},
+};
 mod common;
 
 fn get_expected() -> GuraType {
@@ -101,3 +105,39 @@ fn test_array_in_object() {
             .unwrap();
     assert_eq!(parsed_data, get_expected_inside_object());
 }
+
+#[test]
+/// Tests the error of elements not separated by commas
+fn test_missing_comma() {
+    let error = parse("ports: [80 443]").unwrap_err();
+    assert_eq!(error.kind, Error::ParseError);
+    assert_eq!(error.msg, "Expected \",\" or \"]\" after array element");
+    assert_eq!((error.pos, error.line), (10, 1));
+
+    let error = parse("hosts: [\n    \"a\",\n    \"b\"\n    \"c\"\n]").unwrap_err();
+    assert_eq!((error.pos, error.line), (25, 3));
+    assert_eq!(
+        error.suggestion.as_deref(),
+        Some("Separate the elements of the array with \",\"")
+    );
+}
+
+#[test]
+/// Tests that lenient arrays accept elements separated by new lines with a warning
+fn test_lenient_arrays() {
+    let options = ParserOptions::default().lenient_arrays(true);
+    let (parsed, warnings) = parse_with_warnings(
+        "hosts: [\n    \"a\"\n    # Comment\n    \"b\",\n    [1\n    2]\n]",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(parsed, object! { hosts: ["a", "b", [1, 2]] });
+    let lines: Vec<usize> = warnings.iter().map(|warning| warning.line).collect();
+    assert_eq!(lines, vec![2, 5]);
+
+    // Elements in the same line still need a comma
+    assert!(parse_with_warnings("ports: [80 443]", &options).is_err());
+
+    let (_, warnings) = parse_with_warnings("ports: [80, 443]", &options).unwrap();
+    assert!(warnings.is_empty());
+}
@@ -0,0 +1,63 @@
+use gura::{builder::DocumentBuilder, object, parse, GuraType};
+
+#[test]
+/// Tests that built documents are equal to the ones created with macros
+fn test_builder() {
+    let document = DocumentBuilder::new()
+        .key("title")
+        .string("Gura")
+        .key("version")
+        .float(1.5)
+        .key("debug")
+        .bool(false)
+        .key("owner")
+        .null()
+        .object("server", |server| {
+            server.key("host").string("localhost").key("port").int(8080)
+        })
+        .key("ports")
+        .array(vec![GuraType::Integer(80), GuraType::Integer(443)])
+        .build();
+
+    let expected = object! {
+        title: "Gura",
+        version: 1.5,
+        debug: false,
+        owner: null,
+        server: {
+            host: "localhost",
+            port: 8080
+        },
+        ports: [80, 443]
+    };
+    assert_eq!(document, expected);
+}
+
+#[test]
+/// Tests keys defined at runtime, replacing repeated keys
+fn test_builder_runtime_keys() {
+    let names = ["alpha", "omega", "alpha"];
+    let document = names
+        .iter()
+        .enumerate()
+        .fold(DocumentBuilder::new(), |builder, (index, name)| {
+            builder.key(*name).int(index as isize)
+        })
+        .build();
+
+    assert_eq!(document, parse("alpha: 2\nomega: 1").unwrap());
+}
+
+#[test]
+/// Tests an empty builder and big integers
+fn test_builder_values() {
+    assert_eq!(DocumentBuilder::new().build(), object! {});
+
+    let document = DocumentBuilder::new()
+        .key("big")
+        .big_int(i128::MAX)
+        .pair("nested", object! { a: 1 })
+        .build();
+    assert_eq!(document["big"], GuraType::BigInteger(i128::MAX));
+    assert_eq!(document["nested"]["a"], 1);
+}
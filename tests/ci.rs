@@ -0,0 +1,108 @@
+use gura::{
+    ci::{check_paths, layout_issues, CheckOptions, LayoutIssue},
+    errors::ReadError,
+    lint::LintRule,
+};
+use std::{fs, path::PathBuf};
+
+#[test]
+/// Tests the issues of lines with trailing whitespaces or carriage returns
+fn test_layout_issues() {
+    assert_eq!(layout_issues(""), []);
+    assert_eq!(layout_issues("port: 8080\n"), []);
+    assert_eq!(
+        layout_issues("a: 1\r\nb: 2\t\r\nc: 3"),
+        [
+            LayoutIssue::CarriageReturn(1),
+            LayoutIssue::CarriageReturn(2),
+            LayoutIssue::TrailingWhitespace(2),
+            LayoutIssue::MissingFinalNewline
+        ]
+    );
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "wasi",
+    ignore = "temporary directories are not available in WASI"
+)]
+/// Tests the results of the files matched by several globs, checked in parallel
+fn test_check_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join("services").join("db")).unwrap();
+    fs::create_dir(root.join(".hidden")).unwrap();
+    fs::write(root.join("app.ura"), "$port: 8080\nname: \"app\"\n").unwrap();
+    fs::write(root.join("notes.txt"), "not: gura: at all").unwrap();
+    fs::write(root.join("services").join("web.ura"), "port: 80 \n").unwrap();
+    fs::write(
+        root.join("services").join("db").join("db.ura"),
+        "port: 5432\nport: 5433\n",
+    )
+    .unwrap();
+    fs::write(root.join(".hidden").join("secret.ura"), "invalid: [").unwrap();
+
+    let root_glob = root.to_str().unwrap().replace('\\', "/");
+    let all = format!("{}/**/*.ura", root_glob);
+    let top = format!("{}/*.ura", root_glob);
+    let missing = format!("{}/*.yaml", root_glob);
+    for threads in [None, Some(1), Some(3)] {
+        let options = CheckOptions::default().threads(threads);
+        let report = check_paths(&[&all, &top, &missing], &options);
+
+        let paths: Vec<PathBuf> = report.files.iter().map(|file| file.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [
+                root.join("app.ura"),
+                root.join("services").join("db").join("db.ura"),
+                root.join("services").join("web.ura"),
+            ]
+        );
+        assert_eq!(report.unmatched, [missing.as_str()]);
+        assert!(!report.is_ok());
+
+        let rules: Vec<LintRule> = report.files[0].warnings.iter().map(|w| w.rule).collect();
+        assert_eq!(rules, [LintRule::UnusedVariable]);
+        assert!(matches!(
+            report.files[1].error,
+            Some(ReadError::Parse(ref error)) if error.line == 2
+        ));
+        assert!(report.files[2].error.is_none());
+        assert_eq!(report.files[2].layout, [LayoutIssue::TrailingWhitespace(1)]);
+        assert_eq!(report.failed().count(), 1);
+        assert!(report
+            .to_string()
+            .ends_with("3 files checked, 3 with problems"));
+    }
+
+    // Directories select their Gura files, and checks can be disabled
+    let options = CheckOptions::default().lint(None).layout(false);
+    let services = root.join("services");
+    let report = check_paths(&[services.to_str().unwrap()], &options);
+    assert_eq!(report.files.len(), 2);
+    assert_eq!(report.failed().count(), 1);
+    assert!(report.files.iter().all(|file| file.layout.is_empty()));
+
+    let app = root.join("app.ura");
+    let report = check_paths(&[app.to_str().unwrap()], &options);
+    assert!(report.is_ok());
+}
+
+#[test]
+#[cfg(unix)]
+/// Tests that symbolic links to directories are not followed, so cycles end
+fn test_check_paths_symlink_loop() {
+    let dir = tempfile::tempdir().unwrap();
+    let conf = dir.path().join("conf");
+    fs::create_dir(&conf).unwrap();
+    fs::write(conf.join("app.ura"), "port: 8080\n").unwrap();
+    std::os::unix::fs::symlink(dir.path(), conf.join("loop")).unwrap();
+    std::os::unix::fs::symlink(conf.join("app.ura"), conf.join("linked.ura")).unwrap();
+
+    let glob = format!("{}/**/*.ura", dir.path().display());
+    let report = check_paths(&[&glob], &CheckOptions::default());
+    let paths: Vec<PathBuf> = report.files.iter().map(|file| file.path.clone()).collect();
+    assert_eq!(paths, [conf.join("app.ura"), conf.join("linked.ura")]);
+    assert!(report.is_ok());
+}
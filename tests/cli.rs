@@ -0,0 +1,564 @@
+#![cfg(feature = "cli")]
+use serde_json::json;
+use std::{
+    fs,
+    io::{BufRead, BufReader},
+    process::{Command, Stdio},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+const GURA: &str = env!("CARGO_BIN_EXE_gura");
+
+/// Runs the CLI returning its exit code, standard output and standard error
+fn run(args: &[&str]) -> (i32, String, String) {
+    let output = Command::new(GURA).args(args).output().unwrap();
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+/// Writes a temporary file with the given content
+fn temp_file(content: &str) -> tempfile::NamedTempFile {
+    let file = tempfile::Builder::new().suffix(".ura").tempfile().unwrap();
+    fs::write(file.path(), content).unwrap();
+    file
+}
+
+/// Writes a temporary directory with a `sub/a.ura` file that imports `sub/b.ura` by its name,
+/// so it is only valid if imports are relative to the directory of the file
+fn imports_dir() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("sub")).unwrap();
+    fs::write(
+        dir.path().join("sub").join("a.ura"),
+        "import \"b.ura\"\nname: \"app\"\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("sub").join("b.ura"), "port: 8080\n").unwrap();
+    dir
+}
+
+const CONFIG: &str =
+    "services:\n    nginx:\n        host: \"127.0.0.1\"\n        port: 8080 # Default\n";
+
+#[test]
+/// Tests getting scalars and objects
+fn test_get() {
+    let file = temp_file(CONFIG);
+    let path = file.path().to_str().unwrap();
+    assert_eq!(
+        run(&["get", path, "services.nginx.port"]),
+        (0, String::from("8080\n"), String::new())
+    );
+    assert_eq!(
+        run(&["get", path, "services.nginx.host"]),
+        (0, String::from("127.0.0.1\n"), String::new())
+    );
+    let (code, output, _) = run(&["get", path, "services"]);
+    assert_eq!(code, 0);
+    assert!(output.contains("port: 8080"));
+
+    let (code, _, error) = run(&["get", path, "services.apache"]);
+    assert_eq!(code, 1);
+    assert!(error.contains("services.apache"));
+}
+
+#[test]
+/// Tests setting values keeping comments and formatting
+fn test_set() {
+    let file = temp_file(CONFIG);
+    let path = file.path().to_str().unwrap();
+    assert_eq!(
+        run(&["set", path, "services.nginx.port", "8081"]),
+        (0, String::new(), String::new())
+    );
+    assert_eq!(
+        fs::read_to_string(file.path()).unwrap(),
+        CONFIG.replace("8080", "8081")
+    );
+    assert_eq!(run(&["get", path, "services.nginx.port"]).1, "8081\n");
+
+    // Unquoted words are set as strings
+    run(&["set", path, "services.nginx.host", "localhost"]);
+    assert_eq!(run(&["get", path, "services.nginx.host"]).1, "localhost\n");
+}
+
+#[test]
+/// Tests wrong arguments and unreadable files
+fn test_errors() {
+    assert_eq!(run(&["get"]).0, 2);
+    assert_eq!(run(&["unknown", "a.ura", "b"]).0, 2);
+    let (code, _, error) = run(&["get", "non_existent.ura", "a"]);
+    assert_eq!(code, 1);
+    assert!(error.contains("non_existent.ura"));
+
+    let file = temp_file("a: [1,");
+    let (code, _, _) = run(&["get", file.path().to_str().unwrap(), "a"]);
+    assert_eq!(code, 1);
+}
+
+#[test]
+/// Tests reports of differences and their exit codes
+fn test_diff() {
+    let old = temp_file(CONFIG);
+    let new = temp_file(
+        &CONFIG
+            .replace("8080", "8081")
+            .replace("host: \"127.0.0.1\"", "tls: true"),
+    );
+    let old_path = old.path().to_str().unwrap();
+    let new_path = new.path().to_str().unwrap();
+
+    assert_eq!(
+        run(&["diff", old_path, new_path]),
+        (
+            1,
+            String::from(concat!(
+                "- services.nginx.host: \"127.0.0.1\"\n",
+                "~ services.nginx.port: 8080 -> 8081\n",
+                "+ services.nginx.tls: true\n"
+            )),
+            String::new()
+        )
+    );
+
+    let (code, output, _) = run(&["diff", old_path, new_path, "--json"]);
+    assert_eq!(code, 1);
+    let changes: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(
+        changes[1],
+        serde_json::json!({
+            "change": "modified",
+            "path": "services.nginx.port",
+            "old": 8080,
+            "new": 8081
+        })
+    );
+
+    assert_eq!(
+        run(&["diff", old_path, old_path, "--json"]),
+        (0, String::from("[]\n"), String::new())
+    );
+}
+
+#[test]
+/// Tests lint warnings with their positions and configuration files
+fn test_lint() {
+    let file = temp_file("$unused: 1\nserver:\n    maxConnections: 10\n");
+    let path = file.path().to_str().unwrap();
+    assert_eq!(
+        run(&["lint", path]),
+        (
+            1,
+            format!(
+                "{}:1:1: warning: The variable \"unused\" is never used [unused_variables]\n",
+                path
+            ),
+            String::new()
+        )
+    );
+
+    let config = temp_file("unused_variables: false\nnaming_convention: \"snake_case\"");
+    let (code, output, _) = run(&["lint", path, "--config", config.path().to_str().unwrap()]);
+    assert_eq!(code, 1);
+    assert_eq!(
+        output,
+        format!(
+            "{}:3:5: warning: The key \"server.maxConnections\" does not follow the snake_case convention [naming_convention]\n",
+            path
+        )
+    );
+
+    let config = temp_file("unused_variables: false");
+    assert_eq!(
+        run(&["lint", path, "--config", config.path().to_str().unwrap()]),
+        (0, String::new(), String::new())
+    );
+
+    let config = temp_file("max_nesting: \"2\"");
+    let (code, _, error) = run(&["lint", path, "--config", config.path().to_str().unwrap()]);
+    assert_eq!(code, 1);
+    assert!(error.ends_with("expected a non negative integer or null at max_nesting\n"));
+}
+
+#[test]
+/// Tests annotations of the variables used in every line
+fn test_env() {
+    let file = temp_file("$port: 8080\nserver:\n    port: $port # Default\n    user: \"$CLI_USER_NOT_DEFINED_IN_GURA\"\n");
+    let output = Command::new(GURA)
+        .args(["env", file.path().to_str().unwrap()])
+        .env("CLI_USER_NOT_DEFINED_IN_GURA", "Kiara")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        concat!(
+            "$port: 8080\n",
+            "server:\n",
+            "    port: $port # Default # $port = 8080 (line 1)\n",
+            "    user: \"$CLI_USER_NOT_DEFINED_IN_GURA\" # $CLI_USER_NOT_DEFINED_IN_GURA = \"Kiara\" (environment)\n"
+        )
+    );
+}
+
+#[test]
+/// Tests completing paths and printing the completion scripts
+fn test_completions() {
+    let file = temp_file(CONFIG);
+    let path = file.path().to_str().unwrap();
+    assert_eq!(
+        run(&["complete", path, "services.nginx."]),
+        (
+            0,
+            String::from("services.nginx.host\nservices.nginx.port\n"),
+            String::new()
+        )
+    );
+    assert_eq!(
+        run(&["complete", path, "services.apache."]),
+        (0, String::new(), String::new())
+    );
+
+    for (shell, registration) in [
+        ("bash", "complete -o default -F _gura gura"),
+        ("zsh", "compdef _gura gura"),
+        ("fish", "complete -c gura -f"),
+    ] {
+        let (code, output, _) = run(&["completions", shell]);
+        assert_eq!(code, 0);
+        assert!(output.contains(registration));
+        assert!(output.contains("gura complete"));
+    }
+    assert_eq!(run(&["completions", "powershell"]).0, 2);
+}
+
+/// Runs the CLI with `--format json` returning its exit code and the JSON printed
+fn run_json(args: &[&str]) -> (i32, serde_json::Value) {
+    let mut args = args.to_vec();
+    args.extend(["--format", "json"]);
+    let (code, output, error) = run(&args);
+    assert_eq!(error, "");
+    (code, serde_json::from_str(&output).unwrap())
+}
+
+#[test]
+/// Tests the results of the commands as JSON
+fn test_json_format() {
+    let file = temp_file(CONFIG);
+    let path = file.path().to_str().unwrap();
+    assert_eq!(
+        run_json(&["check", path]),
+        (0, json!({ "file": path, "errors": [] }))
+    );
+    assert_eq!(
+        run_json(&["get", path, "services.nginx"]),
+        (
+            0,
+            json!({ "path": "services.nginx", "value": { "host": "127.0.0.1", "port": 8080 } })
+        )
+    );
+    assert_eq!(
+        run_json(&["complete", path, "services."]),
+        (0, json!(["services.nginx"]))
+    );
+    assert_eq!(
+        run_json(&["set", path, "services.nginx.port", "8081"]),
+        (
+            0,
+            json!({ "file": path, "path": "services.nginx.port", "value": 8081 })
+        )
+    );
+
+    let (code, diff) = run_json(&["diff", path, path]);
+    assert_eq!((code, diff), (0, json!([])));
+
+    let linted = temp_file("port: 80\n$unused: 1\n");
+    assert_eq!(
+        run_json(&["lint", linted.path().to_str().unwrap()]),
+        (
+            1,
+            json!([{
+                "rule": "unused_variables",
+                "message": "The variable \"unused\" is never used",
+                "span": { "line": 2, "column": 1, "byte_start": 9, "byte_end": 16 }
+            }])
+        )
+    );
+
+    let variables = temp_file("$port: 8080\nport: $port\n");
+    let (_, usages) = run_json(&["env", variables.path().to_str().unwrap()]);
+    assert_eq!(usages[0]["name"], "port");
+    assert_eq!(usages[0]["value"], 8080);
+    assert_eq!(usages[0]["source"], "document");
+    assert_eq!(usages[0]["span"]["line"], 2);
+    assert_eq!(usages[0]["definition"]["line"], 1);
+}
+
+#[test]
+/// Tests errors as JSON
+fn test_json_errors() {
+    let file = temp_file("a: 1\na: 2\n");
+    let path = file.path().to_str().unwrap();
+    let error = json!({
+        "code": "DuplicatedKeyError",
+        "message": "The key \"a\" has been already defined",
+        "file": path,
+        "line": 2,
+        "byte_offset": 5,
+        "suggestion": null
+    });
+    assert_eq!(
+        run_json(&["check", path]),
+        (1, json!({ "file": path, "errors": [error] }))
+    );
+    assert_eq!(
+        run_json(&["get", path, "a"]),
+        (1, json!({ "error": error }))
+    );
+
+    let valid = temp_file(CONFIG);
+    let (code, output) = run_json(&["get", valid.path().to_str().unwrap(), "services.apache"]);
+    assert_eq!(code, 1);
+    assert_eq!(output["error"]["code"], "PathNotFoundError");
+    assert_eq!(run_json(&["get"]).0, 2);
+    assert_eq!(run(&["check", "--format", "yaml", path]).0, 2);
+}
+
+#[test]
+/// Tests checking the files of a directory again when they or their imports change
+fn test_check_watch() {
+    let dir = tempfile::tempdir().unwrap();
+    let main_path = dir.path().join("main.ura");
+    let base_path = dir.path().join("sub").join("base.ura");
+    fs::create_dir(dir.path().join("sub")).unwrap();
+    fs::write(&base_path, "name: \"app\"").unwrap();
+    fs::write(&main_path, "import \"sub/base.ura\"\nport: 8080").unwrap();
+    // Symbolic links to directories are not followed, so cycles end
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(dir.path(), dir.path().join("sub").join("loop")).unwrap();
+
+    let mut child = Command::new(GURA)
+        .args(["check", "--watch", dir.path().to_str().unwrap()])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let (sender, lines) = mpsc::channel();
+    thread::spawn(move || {
+        for line in stdout.lines() {
+            sender.send(line.unwrap()).unwrap();
+        }
+    });
+    let next_line = || lines.recv_timeout(Duration::from_secs(10)).unwrap();
+
+    let (main, base) = (main_path.to_string_lossy(), base_path.to_string_lossy());
+    assert_eq!(next_line(), format!("{}: ok", main));
+    assert_eq!(next_line(), format!("{}: ok", base));
+
+    // Files that import the changed one are checked again
+    fs::write(&base_path, "name: \"app\"\nname: 1").unwrap();
+    assert!(next_line().starts_with(&format!("{}:2: The key \"name\"", main)));
+    assert!(next_line().starts_with(&format!("{}:2: The key \"name\"", base)));
+    fs::write(&base_path, "name: \"fixed\"").unwrap();
+    assert_eq!(next_line(), format!("{}: ok", main));
+    assert_eq!(next_line(), format!("{}: ok", base));
+
+    fs::remove_file(&main_path).unwrap();
+    assert_eq!(next_line(), format!("{}: removed", main));
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+/// Tests three-way merges writing the result to our version
+fn test_merge_driver() {
+    let base = temp_file("name: \"app\"\nport: 80\n");
+    let ours = temp_file("name: \"web\" # Renamed\nport: 80\n");
+    let theirs = temp_file("name: \"app\"\nport: 8080\n");
+    let paths = [base.path(), ours.path(), theirs.path()].map(|path| path.to_str().unwrap());
+
+    assert_eq!(
+        run(&["merge-driver", paths[0], paths[1], paths[2]]),
+        (0, String::new(), String::new())
+    );
+    assert_eq!(
+        fs::read_to_string(ours.path()).unwrap(),
+        "name: \"web\" # Renamed\nport: 8080\n"
+    );
+
+    fs::write(theirs.path(), "name: \"api\"\nport: 80\n").unwrap();
+    assert_eq!(
+        run(&[
+            "merge-driver",
+            paths[0],
+            paths[1],
+            paths[2],
+            "--conflict-comments"
+        ]),
+        (
+            1,
+            String::from("CONFLICT name: ours \"web\", theirs \"api\", base \"app\"\n"),
+            String::new()
+        )
+    );
+    assert_eq!(
+        fs::read_to_string(ours.path()).unwrap(),
+        "# CONFLICT name: ours \"web\", theirs \"api\", base \"app\"\nname: \"web\" # Renamed\nport: 8080\n"
+    );
+
+    assert_eq!(
+        run_json(&["merge-driver", paths[0], paths[1], paths[2]]),
+        (
+            1,
+            json!([{ "path": "name", "base": "app", "ours": "web", "theirs": "api" }])
+        )
+    );
+
+    fs::write(theirs.path(), "name: [").unwrap();
+    let (code, _, error) = run(&["merge-driver", paths[0], paths[1], paths[2]]);
+    assert_eq!(code, 1);
+    assert!(error.starts_with(paths[2]));
+
+    fs::write(base.path(), "name: [").unwrap();
+    let (code, _, error) = run(&["merge-driver", paths[0], paths[1], paths[2]]);
+    assert_eq!(code, 1);
+    assert!(error.starts_with(paths[0]));
+}
+
+#[test]
+/// Tests merging versions that import a file of the directory of our version from a different
+/// one
+fn test_merge_driver_relative_imports() {
+    let dir = imports_dir();
+    let sub = dir.path().join("sub");
+    fs::write(sub.join("base.ura"), "import \"b.ura\"\nname: \"app\"\n").unwrap();
+    fs::write(
+        sub.join("theirs.ura"),
+        "import \"b.ura\"\nname: \"app\"\ndebug: true\n",
+    )
+    .unwrap();
+    let paths = [
+        sub.join("base.ura"),
+        sub.join("a.ura"),
+        sub.join("theirs.ura"),
+    ];
+    let paths = paths.each_ref().map(|path| path.to_str().unwrap());
+
+    assert_eq!(
+        run(&["merge-driver", paths[0], paths[1], paths[2]]),
+        (0, String::new(), String::new())
+    );
+    assert_eq!(
+        fs::read_to_string(paths[1]).unwrap(),
+        "import \"b.ura\"\nname: \"app\"\ndebug: true\n"
+    );
+}
+
+#[test]
+/// Tests checking the files of a directory and the ones matched by a glob
+fn test_ci() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("valid.ura"), CONFIG).unwrap();
+    let root = dir.path().to_str().unwrap();
+    let glob = format!("{}/*.ura", root);
+    let (code, output, _) = run(&["ci", &glob]);
+    assert_eq!(
+        (code, output.as_str()),
+        (0, "1 files checked, 0 with problems\n")
+    );
+
+    fs::write(dir.path().join("invalid.ura"), "port: 80\nport: 81 \n").unwrap();
+    let (code, output, _) = run(&["ci", root]);
+    assert_eq!(code, 1);
+    assert!(output.contains("invalid.ura:2: error: "));
+    assert!(output.contains("invalid.ura:2: layout: Trailing whitespace"));
+
+    let (code, output, _) = run(&["ci", &glob, "missing/*.ura", "--format", "json"]);
+    assert_eq!(code, 1);
+    let report: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(report["unmatched"], json!(["missing/*.ura"]));
+    assert_eq!(
+        report["files"][0]["errors"][0]["code"],
+        "DuplicatedKeyError"
+    );
+    assert_eq!(
+        report["files"][0]["layout"],
+        json!([{ "message": "Trailing whitespace", "line": 2 }])
+    );
+    assert_eq!(report["files"][1]["errors"], json!([]));
+}
+
+#[test]
+/// Tests checking a file that imports another one of its directory from a different one
+fn test_check_relative_imports() {
+    let dir = imports_dir();
+    let file = dir.path().join("sub").join("a.ura");
+    let file = file.to_str().unwrap();
+    assert_eq!(run(&["check", file]), (0, String::new(), String::new()));
+}
+
+#[test]
+/// Tests querying and editing a file that imports another one of its directory from a
+/// different one
+fn test_edit_relative_imports() {
+    let dir = imports_dir();
+    let file = dir.path().join("sub").join("a.ura");
+    let file = file.to_str().unwrap();
+    assert_eq!(
+        run(&["get", file, "port"]),
+        (0, String::from("8080\n"), String::new())
+    );
+    assert_eq!(
+        run(&["complete", file, "na"]),
+        (0, String::from("name\n"), String::new())
+    );
+    assert_eq!(run(&["set", file, "name", "web"]).0, 0);
+    assert_eq!(
+        fs::read_to_string(file).unwrap(),
+        "import \"b.ura\"\nname: \"web\"\n"
+    );
+}
+
+#[test]
+/// Tests comparing files that import others of their directories from a different one
+fn test_diff_relative_imports() {
+    let dir = imports_dir();
+    let old = dir.path().join("sub").join("a.ura");
+    let new = dir.path().join("c.ura");
+    fs::write(&new, "import \"sub/b.ura\"\nname: \"web\"\n").unwrap();
+    let (code, output, error) = run(&["diff", old.to_str().unwrap(), new.to_str().unwrap()]);
+    assert_eq!((code, error.as_str()), (1, ""));
+    assert_eq!(output.lines().count(), 1);
+    assert!(output.starts_with("~ name"));
+}
+
+#[test]
+/// Tests linting a file that imports another one of its directory from a different one
+fn test_lint_relative_imports() {
+    let dir = imports_dir();
+    let file = dir.path().join("sub").join("a.ura");
+    let file = file.to_str().unwrap();
+    assert_eq!(run(&["lint", file]), (0, String::new(), String::new()));
+}
+
+#[test]
+/// Tests annotating a file whose variables are defined in an imported file of its directory
+fn test_env_relative_imports() {
+    let dir = imports_dir();
+    fs::write(dir.path().join("sub").join("b.ura"), "$port: 8080\n").unwrap();
+    let file = dir.path().join("sub").join("a.ura");
+    fs::write(&file, "import \"b.ura\"\nport: $port\n").unwrap();
+    assert_eq!(
+        run(&["env", file.to_str().unwrap()]),
+        (
+            0,
+            String::from("import \"b.ura\"\nport: $port # $port = 8080 (imported)\n"),
+            String::new()
+        )
+    );
+}
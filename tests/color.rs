@@ -0,0 +1,35 @@
+#![cfg(feature = "color")]
+use gura::{color::to_colored_string, parse};
+
+#[test]
+/// Tests highlighting of every kind of value
+fn test_colored() {
+    let parsed = parse("title: \"Gura\"\nitems: [1, 2.5, true, null]\nextra: empty").unwrap();
+    let expected = concat!(
+        "\x1b[34mtitle\x1b[0m: \x1b[32m\"Gura\"\x1b[0m\n",
+        "\x1b[34mitems\x1b[0m: [\x1b[33m1\x1b[0m, \x1b[33m2.5\x1b[0m, ",
+        "\x1b[35mtrue\x1b[0m, \x1b[35mnull\x1b[0m]\n",
+        "\x1b[34mextra\x1b[0m: \x1b[35mempty\x1b[0m"
+    );
+    assert_eq!(to_colored_string(&parsed, None), expected);
+}
+
+#[test]
+/// Tests truncation of nested objects, also inside arrays
+fn test_truncated() {
+    let parsed = parse(
+        "server:\n    port: 80\n    tls:\n        enabled: true\nusers: [\n    alice:\n        admin: true\n]",
+    )
+    .unwrap();
+
+    let colored = to_colored_string(&parsed, Some(0));
+    assert_eq!(
+        colored,
+        "\x1b[34mserver\x1b[0m: …\n\x1b[34musers\x1b[0m: [\n    …\n]"
+    );
+
+    let colored = to_colored_string(&parsed, Some(1));
+    assert!(colored.contains("\x1b[34mtls\x1b[0m: …"));
+    assert!(colored.contains("    \x1b[34malice\x1b[0m: …"));
+    assert!(!colored.contains("enabled"));
+}
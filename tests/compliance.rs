@@ -0,0 +1,65 @@
+#![cfg(feature = "compliance")]
+use gura::{
+    compliance::{load_cases, run_suite, Expected, Outcome},
+    parse, GuraType,
+};
+use indexmap::IndexMap;
+
+const SUITE_DIR: &str = "tests/compliance/tests-files";
+
+#[test]
+/// Tests loading of test cases with their expected outcomes
+fn test_load_cases() {
+    let cases = load_cases(SUITE_DIR).unwrap();
+    let names: Vec<&str> = cases.iter().map(|case| case.name.as_str()).collect();
+    assert_eq!(names, vec!["basic", "duplicated_key", "variables"]);
+    assert_eq!(
+        cases[1].expected,
+        Expected::Error {
+            kind: String::from("DuplicatedKeyError"),
+            line: Some(3),
+        }
+    );
+}
+
+#[test]
+/// Tests that the parser passes the suite
+fn test_run_suite() {
+    let report = run_suite(SUITE_DIR, parse).unwrap();
+    assert_eq!(report.failures(), Vec::<(&str, &str)>::new());
+    assert!(report.is_success());
+    assert_eq!(report.passed(), 3);
+}
+
+#[test]
+/// Tests the failures reported for a wrong front-end
+fn test_failures() {
+    let report = run_suite(SUITE_DIR, |text| {
+        let mut parsed = parse(text).unwrap_or_else(|_| GuraType::Object(IndexMap::new()));
+        if let GuraType::Object(values) = &mut parsed {
+            values.shift_remove("title");
+            values.insert(String::from("extra"), GuraType::Bool(true));
+            if let Some(server) = values.get_mut("server") {
+                *server = GuraType::Integer(1);
+            }
+        }
+        Ok(parsed)
+    })
+    .unwrap();
+    assert!(!report.is_success());
+    assert_eq!(
+        report.failures(),
+        vec![
+            ("basic", "missing key title"),
+            (
+                "duplicated_key",
+                "expected DuplicatedKeyError, but the document was parsed"
+            ),
+            ("variables", "expected {\"port\":80}, found 1 at server"),
+        ]
+    );
+    assert_eq!(
+        report.results[0].1,
+        Outcome::Failed(String::from("missing key title"))
+    );
+}
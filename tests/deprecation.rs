@@ -0,0 +1,65 @@
+use gura::{
+    deprecation::{DeprecationMap, DeprecationWarning},
+    parse, parse_spanned,
+};
+
+fn get_deprecations() -> DeprecationMap {
+    let mut deprecations = DeprecationMap::new();
+    deprecations.deprecate("services.nginx.hostname", Some("services.nginx.host"));
+    deprecations.deprecate("debug", None);
+    deprecations.deprecate("not_used", None);
+    deprecations
+}
+
+#[test]
+/// Tests deprecated keys detection
+fn test_deprecations() {
+    let parsed =
+        parse("debug: true\nservices:\n    nginx:\n        hostname: \"localhost\"").unwrap();
+    let warnings = get_deprecations().check_deprecations(&parsed);
+    assert_eq!(
+        warnings,
+        vec![
+            DeprecationWarning {
+                path: String::from("services.nginx.hostname"),
+                replacement: Some(String::from("services.nginx.host")),
+                span: None,
+            },
+            DeprecationWarning {
+                path: String::from("debug"),
+                replacement: None,
+                span: None,
+            }
+        ]
+    );
+    assert_eq!(warnings[1].to_string(), "The key \"debug\" is deprecated");
+}
+
+#[test]
+/// Tests that deprecated keys of spanned documents are reported with their position
+fn test_spanned_deprecations() {
+    let document =
+        parse_spanned("debug: true\nservices:\n    nginx:\n        hostname: \"localhost\"")
+            .unwrap();
+    let warnings = get_deprecations().check_deprecations_spanned(&document);
+    assert_eq!(warnings.len(), 2);
+    assert_eq!(warnings[0].span, document.span("services.nginx.hostname"));
+    assert_eq!(warnings[0].span.unwrap().line, 4);
+    assert_eq!(
+        warnings[0].to_string(),
+        "The key \"services.nginx.hostname\" is deprecated, use \"services.nginx.host\" instead (line 4)"
+    );
+    assert_eq!(
+        warnings[1].to_string(),
+        "The key \"debug\" is deprecated (line 1)"
+    );
+}
+
+#[test]
+/// Tests documents without deprecated keys
+fn test_without_deprecations() {
+    let parsed = parse("services:\n    nginx:\n        host: \"localhost\"").unwrap();
+    assert!(get_deprecations().check_deprecations(&parsed).is_empty());
+    assert!(get_deprecations().is_deprecated("debug"));
+    assert!(!get_deprecations().is_deprecated("services"));
+}
@@ -0,0 +1,16 @@
+#![cfg(feature = "determinism")]
+use gura::determinism::{check, outcome, CASES};
+
+#[test]
+/// Tests that every case of the suite has its expected outcome in this platform
+fn test_suite() {
+    assert_eq!(check(), vec![]);
+    assert!(CASES.iter().any(|case| case.name == "floats"));
+}
+
+#[test]
+/// Tests that outcomes do not depend on the line endings of the text
+fn test_line_endings() {
+    let text = "a: 1\nb:\n    c: [1.5, \"x\"]\n";
+    assert_eq!(outcome(text), outcome(&text.replace('\n', "\r\n")));
+}
@@ -0,0 +1,55 @@
+use gura::{
+    array,
+    diff::{diff, Change},
+    object, GuraType,
+};
+
+#[test]
+/// Tests changes of nested objects, arrays and values of different types
+fn test_diff() {
+    let old = object! {
+        name: "app",
+        server: { host: "localhost", port: 80, tls: { enabled: false } },
+        hosts: ["alpha"]
+    };
+    let new = object! {
+        name: "app",
+        server: { host: "localhost", port: 8080, tls: "disabled" },
+        hosts: ["alpha", "omega"],
+        debug: true
+    };
+    assert_eq!(
+        diff(&old, &new),
+        vec![
+            Change::Modified {
+                path: "server.port".parse().unwrap(),
+                old: GuraType::Integer(80),
+                new: GuraType::Integer(8080),
+            },
+            Change::Modified {
+                path: "server.tls".parse().unwrap(),
+                old: object! { enabled: false },
+                new: GuraType::String(String::from("disabled")),
+            },
+            Change::Modified {
+                path: "hosts".parse().unwrap(),
+                old: array!["alpha"],
+                new: array!["alpha", "omega"],
+            },
+            Change::Added {
+                path: "debug".parse().unwrap(),
+                value: GuraType::Bool(true),
+            },
+        ]
+    );
+    assert_eq!(diff(&new, &old)[3].to_string(), "- debug: true");
+    assert!(diff(&old, &old).is_empty());
+}
+
+#[test]
+/// Tests the path of changes of the root value
+fn test_diff_root() {
+    let changes = diff(&GuraType::Integer(1), &GuraType::Integer(2));
+    assert_eq!(changes[0].path(), "");
+    assert_eq!(changes[0].to_string(), "~ : 1 -> 2");
+}
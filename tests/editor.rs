@@ -0,0 +1,233 @@
+use gura::{
+    array,
+    editor::{DocumentEdit, EditableDocument},
+    errors::EditError,
+    object, GuraType,
+};
+
+#[test]
+/// Tests renaming keys keeping the rest of the text
+fn test_rename_key() {
+    let text =
+        "# Server\nserver:\n    host: \"localhost\" # Address\n    port: 8080\n\nname: \"app\"\n";
+    let mut document = EditableDocument::parse(text).unwrap();
+    document.rename_key("server", "http_server").unwrap();
+    document
+        .rename_key("http_server.port", "listen_port")
+        .unwrap();
+    assert_eq!(
+        document.text(),
+        "# Server\nhttp_server:\n    host: \"localhost\" # Address\n    listen_port: 8080\n\nname: \"app\"\n"
+    );
+    assert_eq!(document.value()["http_server"]["listen_port"], 8080);
+
+    assert_eq!(
+        document.rename_key("name", "http_server"),
+        Err(EditError::DuplicatedKey(String::from("http_server")))
+    );
+    assert_eq!(
+        document.rename_key("name", "app name"),
+        Err(EditError::InvalidKey(String::from("app name")))
+    );
+    assert_eq!(
+        document.rename_key("server", "other"),
+        Err(EditError::PathNotFound(String::from("server")))
+    );
+    // Renaming a key to its own name does nothing
+    document.rename_key("name", "name").unwrap();
+    assert!(document.text().ends_with("name: \"app\"\n"));
+}
+
+#[test]
+/// Tests renaming variables with all their usages
+fn test_rename_variable() {
+    let text = "$host: \"localhost\"\n$port: 8080\nserver:\n    host: $host # $host\n    url: \"http://$host:$port\"\n    literal: '$host'\n";
+    let mut document = EditableDocument::parse(text).unwrap();
+    document.rename_variable("host", "address").unwrap();
+    assert_eq!(
+        document.text(),
+        "$address: \"localhost\"\n$port: 8080\nserver:\n    host: $address # $host\n    url: \"http://$address:$port\"\n    literal: '$host'\n"
+    );
+    assert_eq!(
+        document.value()["server"],
+        object! { host: "localhost", url: "http://localhost:8080", literal: "$host" }
+    );
+
+    assert_eq!(
+        document.rename_variable("address", "port"),
+        Err(EditError::DuplicatedVariable(String::from("port")))
+    );
+    assert_eq!(
+        document.rename_variable("address", "$other"),
+        Err(EditError::InvalidVariable(String::from("$other")))
+    );
+    assert_eq!(
+        document.rename_variable("host", "other"),
+        Err(EditError::VariableNotFound(String::from("host")))
+    );
+}
+
+#[test]
+/// Tests moving pairs with their comments between objects
+fn test_move_key() {
+    let text = "server:\n    # Host to bind\n    host: \"localhost\"\n    motd: \"\"\"\nWelcome\n  home\"\"\"\ndatabase:\n    url: \"postgres://db\"\n    pool:\n        size: 4\ndebug: false";
+    let mut document = EditableDocument::parse(text).unwrap();
+
+    // Nested objects and multiline strings keep their shape
+    document.move_key("database.pool", "server").unwrap();
+    document.move_key("server.motd", "database").unwrap();
+    assert_eq!(
+        document.text(),
+        "server:\n    # Host to bind\n    host: \"localhost\"\n    pool:\n        size: 4\ndatabase:\n    url: \"postgres://db\"\n    motd: \"\"\"\nWelcome\n  home\"\"\"\ndebug: false"
+    );
+
+    document.move_key("server.host", "").unwrap();
+    assert_eq!(
+        document.text(),
+        "server:\n    pool:\n        size: 4\ndatabase:\n    url: \"postgres://db\"\n    motd: \"\"\"\nWelcome\n  home\"\"\"\ndebug: false\n# Host to bind\nhost: \"localhost\"\n"
+    );
+
+    // Objects left without pairs become empty
+    document.move_key("server.pool.size", "database").unwrap();
+    assert!(document
+        .text()
+        .starts_with("server:\n    pool: empty\ndatabase:\n"));
+    assert_eq!(document.value()["database"]["size"], 4);
+
+    // Moving to the current parent does nothing
+    let before = document.text().to_string();
+    document.move_key("debug", "").unwrap();
+    assert_eq!(document.text(), before);
+}
+
+#[test]
+/// Tests the errors of moving pairs
+fn test_move_key_errors() {
+    let text = "server:\n    host: \"localhost\"\n    tls:\n        enabled: true\nhost: \"other\"\nname: \"app\"";
+    let mut document = EditableDocument::parse(text).unwrap();
+    assert_eq!(
+        document.move_key("server.host", ""),
+        Err(EditError::DuplicatedKey(String::from("host")))
+    );
+    assert_eq!(
+        document.move_key("server", "server.tls"),
+        Err(EditError::InvalidParent(String::from("server.tls")))
+    );
+    assert_eq!(
+        document.move_key("name", "host"),
+        Err(EditError::InvalidParent(String::from("host")))
+    );
+    assert_eq!(
+        document.move_key("missing", "server"),
+        Err(EditError::PathNotFound(String::from("missing")))
+    );
+    assert_eq!(document.text(), text);
+}
+
+#[test]
+/// Tests setting values of existing and new keys
+fn test_set_value() {
+    let text = "server:\n    port: 8080 # Default\n    tls:\n        enabled: true\nname: \"app\"";
+    let mut document = EditableDocument::parse(text).unwrap();
+    document
+        .set_value("server.port", &GuraType::Integer(8081))
+        .unwrap();
+    document
+        .set_value("server.tls", &object! { cert: "a.pem", key: "a.key" })
+        .unwrap();
+    document
+        .set_value("server.hosts", &array!["a", "b"])
+        .unwrap();
+    document.set_value("debug", &object! {}).unwrap();
+    assert_eq!(
+        document.text(),
+        "server:\n    port: 8081 # Default\n    tls:\n        cert: \"a.pem\"\n        key: \"a.key\"\n    hosts: [\"a\", \"b\"]\nname: \"app\"\ndebug: empty\n"
+    );
+
+    assert_eq!(
+        document.set_value("name.first", &GuraType::Null),
+        Err(EditError::InvalidParent(String::from("name")))
+    );
+    assert_eq!(
+        document.set_value("server.a-b", &GuraType::Null),
+        Err(EditError::InvalidKey(String::from("a-b")))
+    );
+}
+
+#[test]
+/// Tests deleting pairs with their comments
+fn test_delete_key() {
+    let text = "# Server\nserver:\n    # Port\n    port: 8080\nname: \"app\"\n";
+    let mut document = EditableDocument::parse(text).unwrap();
+    document.delete_key("server.port").unwrap();
+    assert_eq!(document.text(), "# Server\nserver: empty\nname: \"app\"\n");
+    document.delete_key("server").unwrap();
+    assert_eq!(document.text(), "name: \"app\"\n");
+    assert_eq!(
+        document.delete_key("server"),
+        Err(EditError::PathNotFound(String::from("server")))
+    );
+}
+
+#[test]
+/// Tests appending values following the layout of arrays
+fn test_append_value() {
+    let text = "a: []\nb: [1, 2]\nc: [\n    1,\n    2\n]\nd: [\n    1,\n]\nname: \"app\"";
+    let mut document = EditableDocument::parse(text).unwrap();
+    for path in ["a", "b", "c", "d"] {
+        document.append_value(path, &GuraType::Integer(3)).unwrap();
+    }
+    assert_eq!(
+        document.text(),
+        "a: [3]\nb: [1, 2, 3]\nc: [\n    1,\n    2,\n    3\n]\nd: [\n    1,\n    3,\n]\nname: \"app\""
+    );
+    assert_eq!(
+        document.append_value("name", &GuraType::Null),
+        Err(EditError::NotAnArray(String::from("name")))
+    );
+}
+
+#[test]
+/// Tests applying batches of edits atomically
+fn test_apply_edits() {
+    let text = "server:\n    port: 8080\n    hosts: [\"a\"]";
+    let mut document = EditableDocument::parse(text).unwrap();
+    let edits = DocumentEdit::new()
+        .comment("server", "Web server\n\nManaged by the deploy")
+        .set("server.port", GuraType::Integer(8081))
+        .append("server.hosts", GuraType::String(String::from("b")));
+    assert_eq!(
+        document.apply_edits(&edits).unwrap(),
+        "# Web server\n#\n# Managed by the deploy\nserver:\n    port: 8081\n    hosts: [\"a\", \"b\"]"
+    );
+
+    let edits = DocumentEdit::new()
+        .delete("server.port")
+        .append("server.port", GuraType::Null);
+    assert_eq!(
+        document.apply_edits(&edits),
+        Err(EditError::PathNotFound(String::from("server.port")))
+    );
+    assert!(document.text().contains("port: 8081"));
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "wasi",
+    ignore = "temporary directories are not available in WASI"
+)]
+/// Tests editing a text whose imports are relative to a directory
+fn test_parse_in() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("server.ura"), "port: 8080").unwrap();
+    let text = "import \"server.ura\"\nname: \"app\"\n";
+    assert!(EditableDocument::parse(text).is_err());
+
+    let mut document = EditableDocument::parse_in(text, dir.path()).unwrap();
+    assert_eq!(document.value()["port"], 8080);
+    document
+        .set_value("name", &GuraType::String(String::from("web")))
+        .unwrap();
+    assert_eq!(document.text(), "import \"server.ura\"\nname: \"web\"\n");
+    assert_eq!(document.value()["port"], 8080);
+}
@@ -1,4 +1,5 @@
-use gura::errors::Error;
+use gura::{errors::Error, parse_with_options, parser::ParserOptions};
+use std::fs;
 mod common;
 
 const PARENT_FOLDER: &str = "exception_report";
@@ -232,3 +233,90 @@ fn test_duplicated_import_2() {
 fn test_array_issue_12() {
     test_fail("issue_12.ura", Error::InvalidIndentationError, 0, 2);
 }
+
+#[test]
+/// Tests that the content of the line is captured only when enabled
+fn test_line_content() {
+    let content = fs::read_to_string(format!(
+        "tests/{}/tests-files/parsing_error_3.ura",
+        PARENT_FOLDER
+    ))
+    .unwrap();
+
+    let error = parse_with_options(&content, &ParserOptions::default()).unwrap_err();
+    assert_eq!(error.line_content, None);
+
+    let options = ParserOptions::default().capture_line_content(true);
+    let error = parse_with_options(&content, &options).unwrap_err();
+    assert_eq!(
+        error.line_content.as_deref(),
+        Some("    fest: \"Some string\", H   # <---- This \"H\" is a parsing error")
+    );
+    assert!(error
+        .to_string()
+        .ends_with(":\n    fest: \"Some string\", H   # <---- This \"H\" is a parsing error"));
+}
+
+#[test]
+/// Tests the suggestions attached to the most common errors
+fn test_suggestions() {
+    let suggestion = |content: &str| gura::parse(content).unwrap_err().suggestion;
+
+    assert_eq!(
+        suggestion("hosts: [\n    \"a\",\n    \"b\"\n").as_deref(),
+        Some("Close the array with \"]\" and separate its elements with \",\"")
+    );
+    assert_eq!(
+        suggestion("hosts: [1, 2]]").as_deref(),
+        Some("Remove the \"]\", it does not close any array")
+    );
+    assert_eq!(
+        suggestion("a: 1,\nb: 2").as_deref(),
+        Some("Remove the \",\", pairs are separated by new lines")
+    );
+    assert_eq!(
+        suggestion("server:\n    port: 80\n      host: \"a\"").as_deref(),
+        Some("Indent the line with 4 or 8 spaces")
+    );
+    assert_eq!(
+        suggestion("server:\n\tport: 80").as_deref(),
+        Some("Replace every tab with 4 spaces")
+    );
+    assert_eq!(
+        suggestion("hosts: [1, 2] # ports\nb: [1] x").as_deref(),
+        Some("Remove the text after the value or move it to a new line")
+    );
+    assert_eq!(
+        suggestion("port: 80 abc").as_deref(),
+        Some("Remove the text after the value or move it to a new line")
+    );
+    assert_eq!(suggestion("a: {\n}"), None);
+}
+
+#[test]
+/// Tests that unclosed arrays and strings are reported where they were opened
+fn test_unbalanced() {
+    let error = gura::parse(
+        "title: \"Gura\"\nport: 80\nhosts: [\n    \"a\",\n    \"b\"\n\nother: 1\nlast: 2",
+    )
+    .unwrap_err();
+    assert_eq!(error.kind, Error::ParseError);
+    assert_eq!(error.msg, "Unclosed \"[\" opened at line 3");
+    assert_eq!((error.pos, error.line), (30, 3));
+
+    let error = gura::parse("a: [[1, 2], [3]\nb: 'x").unwrap_err();
+    assert_eq!(error.msg, "Unterminated string opened at line 2");
+    assert_eq!(error.suggestion.as_deref(), Some("Close the string with '"));
+
+    let error = gura::parse("a: [1]\nb: 2]").unwrap_err();
+    assert_eq!(error.msg, "Unexpected \"]\" without a matching \"[\"");
+    assert_eq!((error.pos, error.line), (11, 2));
+
+    // Brackets and quotes inside strings and comments are ignored
+    let parsed = gura::parse(
+        "a: \"[\\\"\" # ] '\nb: '['\nc: \"\"\"\n  \"[\" ]\n\"\"\"\nd: '''it's ['''\ne: [\"]\"]",
+    )
+    .unwrap();
+    assert_eq!(parsed["a"], "[\"");
+    assert_eq!(parsed["d"], "it's [");
+}
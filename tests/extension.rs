@@ -0,0 +1,86 @@
+use gura::{
+    dump, extension::ScalarExtensions, parse, parse_with_options, parser::ParserOptions,
+    spec::Extension, GuraType,
+};
+
+/// Gets options with a `@duration` scalar (seconds, as milliseconds) and a `@hex` one
+fn get_options() -> ParserOptions {
+    let mut extensions = ScalarExtensions::new();
+    extensions.register("duration", |text: &str| {
+        let seconds = text
+            .strip_suffix('s')
+            .ok_or("durations must end with \"s\"")?;
+        seconds
+            .parse::<isize>()
+            .map(|seconds| GuraType::Integer(seconds * 1000))
+            .map_err(|error| error.to_string())
+    });
+    extensions.register("hex", |text: &str| {
+        isize::from_str_radix(text, 16)
+            .map(GuraType::Integer)
+            .map_err(|error| error.to_string())
+    });
+    ParserOptions::default().scalar_extensions(extensions)
+}
+
+fn extension(tag: &str, value: GuraType) -> GuraType {
+    GuraType::Extension(tag.to_string(), Box::new(value))
+}
+
+#[test]
+/// Tests custom scalars as values, inside arrays and followed by comments
+fn test_parse() {
+    let text = "timeout: @duration 30s # Half a minute\ncolors: [@hex ff, @hex 10]";
+    let parsed = parse_with_options(text, &get_options()).unwrap();
+    assert_eq!(
+        parsed["timeout"],
+        extension("duration", GuraType::Integer(30000))
+    );
+    assert_eq!(
+        parsed["colors"],
+        GuraType::Array(vec![
+            extension("hex", GuraType::Integer(255)),
+            extension("hex", GuraType::Integer(16)),
+        ])
+    );
+}
+
+#[test]
+/// Tests that custom scalars are not parsed unless enabled
+fn test_disabled() {
+    assert!(parse("timeout: @duration 30s").is_err());
+    assert!(!ParserOptions::default()
+        .compliance()
+        .extensions
+        .contains(&Extension::ScalarExtensions));
+    assert!(get_options()
+        .compliance()
+        .extensions
+        .contains(&Extension::ScalarExtensions));
+}
+
+#[test]
+/// Tests errors of unknown tags and rejected values
+fn test_errors() {
+    let options = get_options();
+    let error = parse_with_options("color: @rgb 255", &options).unwrap_err();
+    assert_eq!(error.msg, "Unknown custom scalar \"@rgb\"");
+    assert_eq!(error.pos, 8);
+
+    let error = parse_with_options("color: @hex zz", &options).unwrap_err();
+    assert_eq!(
+        error.msg,
+        "Invalid @hex value \"zz\": invalid digit found in string"
+    );
+    assert_eq!(error.pos, 12);
+
+    let error = parse_with_options("color: @hex", &options).unwrap_err();
+    assert_eq!(error.msg, "Expected the value of the @hex custom scalar");
+}
+
+#[test]
+/// Tests that dumped custom scalars are parsed back when recognizers accept their value
+fn test_dump() {
+    let parsed = parse_with_options("color: @hex 10", &get_options()).unwrap();
+    assert_eq!(dump(&parsed), "color: @hex 16");
+}
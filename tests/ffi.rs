@@ -0,0 +1,238 @@
+#![cfg(feature = "ffi")]
+use gura::ffi::{
+    gura_dump, gura_error_free, gura_extensions_free, gura_extensions_new,
+    gura_extensions_register, gura_free, gura_get, gura_parse, gura_parse_with_extensions,
+    gura_string_free, GuraErrorInfo, GuraErrorKind, GuraScalar, GuraScalarPlugin,
+    GURA_PLUGIN_ABI_VERSION, GURA_SCALAR_KIND_BOOL,
+};
+use std::{
+    ffi::{CStr, CString},
+    mem::MaybeUninit,
+    os::raw::{c_char, c_void},
+    ptr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Copies and releases a string returned by the bindings
+unsafe fn take_string(text: *mut c_char) -> Option<String> {
+    if text.is_null() {
+        return None;
+    }
+    let copy = CStr::from_ptr(text).to_str().unwrap().to_string();
+    gura_string_free(text);
+    Some(copy)
+}
+
+#[test]
+/// Tests parsing, accessing and dumping documents
+fn test_parse_and_dump() {
+    let text = CString::new("server:\n    host: \"localhost\"\n    port: 8080").unwrap();
+    unsafe {
+        let mut error = MaybeUninit::<GuraErrorInfo>::uninit();
+        let document = gura_parse(text.as_ptr(), error.as_mut_ptr());
+        assert!(!document.is_null());
+        let error = error.assume_init();
+        assert_eq!(error.kind, GuraErrorKind::None);
+        assert!(error.message.is_null());
+
+        let path = CString::new("server.host").unwrap();
+        assert_eq!(
+            take_string(gura_get(document, path.as_ptr())).as_deref(),
+            Some("\"localhost\"")
+        );
+        let path = CString::new("server.user").unwrap();
+        assert_eq!(take_string(gura_get(document, path.as_ptr())), None);
+        assert_eq!(
+            take_string(gura_dump(document)).as_deref(),
+            Some("server:\n    host: \"localhost\"\n    port: 8080")
+        );
+        gura_free(document);
+    }
+}
+
+#[test]
+/// Tests errors of invalid texts and arguments
+fn test_errors() {
+    let text = CString::new("title: \"Gura\"\ntitle: \"Other\"").unwrap();
+    unsafe {
+        let mut error = MaybeUninit::<GuraErrorInfo>::uninit();
+        let document = gura_parse(text.as_ptr(), error.as_mut_ptr());
+        assert!(document.is_null());
+        let mut error = error.assume_init();
+        assert_eq!(error.kind, GuraErrorKind::DuplicatedKeyError);
+        assert_eq!((error.line, error.byte_pos), (2, 14));
+        assert!(CStr::from_ptr(error.message)
+            .to_str()
+            .unwrap()
+            .starts_with("The key \"title\" has been already defined"));
+        gura_error_free(&mut error);
+        assert!(error.message.is_null());
+
+        // Errors are optional
+        assert!(gura_parse(text.as_ptr(), ptr::null_mut()).is_null());
+
+        let mut error = MaybeUninit::<GuraErrorInfo>::uninit();
+        assert!(gura_parse(ptr::null(), error.as_mut_ptr()).is_null());
+        let mut error = error.assume_init();
+        assert_eq!(error.kind, GuraErrorKind::InvalidArgument);
+        gura_error_free(&mut error);
+
+        assert!(gura_dump(ptr::null()).is_null());
+        assert!(gura_get(ptr::null(), ptr::null()).is_null());
+        gura_free(ptr::null_mut());
+    }
+}
+
+/// Recognizer of `@flag on` and `@flag off` scalars
+unsafe extern "C" fn recognize_flag(
+    text: *const c_char,
+    _user_data: *mut c_void,
+    result: *mut GuraScalar,
+) -> bool {
+    let result = &mut *result;
+    match CStr::from_ptr(text).to_str() {
+        Ok("on") | Ok("off") => {
+            result.kind = GURA_SCALAR_KIND_BOOL;
+            result.bool_value = CStr::from_ptr(text).to_bytes() == b"on";
+            true
+        }
+        _ => {
+            result.string = b"expected on or off\0".as_ptr() as *const c_char;
+            false
+        }
+    }
+}
+
+/// Recognizer that accepts any text with a kind of value that does not exist
+unsafe extern "C" fn recognize_unknown_kind(
+    _text: *const c_char,
+    _user_data: *mut c_void,
+    result: *mut GuraScalar,
+) -> bool {
+    (*result).kind = 42;
+    true
+}
+
+/// Counts the releases of the plugin whose user data is the counter
+unsafe extern "C" fn release_counter(user_data: *mut c_void) {
+    (*(user_data as *const AtomicUsize)).fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+/// Tests custom scalars implemented by plugins
+fn test_plugins() {
+    let releases = AtomicUsize::new(0);
+    let tag = CString::new("flag").unwrap();
+    let plugin = GuraScalarPlugin {
+        abi_version: GURA_PLUGIN_ABI_VERSION,
+        tag: tag.as_ptr(),
+        recognize: Some(recognize_flag),
+        user_data: &releases as *const AtomicUsize as *mut c_void,
+        release: Some(release_counter),
+    };
+    unsafe {
+        let extensions = gura_extensions_new();
+        assert_eq!(
+            gura_extensions_register(extensions, &plugin),
+            GuraErrorKind::None
+        );
+
+        let text = CString::new("debug: @flag on").unwrap();
+        let document = gura_parse_with_extensions(text.as_ptr(), extensions, ptr::null_mut());
+        assert!(!document.is_null());
+        let path = CString::new("debug").unwrap();
+        assert_eq!(
+            take_string(gura_get(document, path.as_ptr())).as_deref(),
+            Some("@flag true")
+        );
+        gura_free(document);
+
+        let text = CString::new("debug: @flag maybe").unwrap();
+        let mut error = MaybeUninit::<GuraErrorInfo>::uninit();
+        let document = gura_parse_with_extensions(text.as_ptr(), extensions, error.as_mut_ptr());
+        assert!(document.is_null());
+        let mut error = error.assume_init();
+        assert_eq!(error.kind, GuraErrorKind::ParseError);
+        assert!(CStr::from_ptr(error.message)
+            .to_str()
+            .unwrap()
+            .starts_with("Invalid @flag value \"maybe\": expected on or off"));
+        gura_error_free(&mut error);
+
+        assert_eq!(releases.load(Ordering::SeqCst), 0);
+        gura_extensions_free(extensions);
+        assert_eq!(releases.load(Ordering::SeqCst), 1);
+    }
+}
+
+#[test]
+/// Tests that invalid plugins are rejected and released, except the ones of other ABI
+/// versions, which are not read
+fn test_invalid_plugins() {
+    let releases = AtomicUsize::new(0);
+    let tag = CString::new("flag").unwrap();
+    let mut plugin = GuraScalarPlugin {
+        abi_version: GURA_PLUGIN_ABI_VERSION + 1,
+        tag: tag.as_ptr(),
+        recognize: Some(recognize_flag),
+        user_data: &releases as *const AtomicUsize as *mut c_void,
+        release: Some(release_counter),
+    };
+    unsafe {
+        let extensions = gura_extensions_new();
+        assert_eq!(
+            gura_extensions_register(extensions, &plugin),
+            GuraErrorKind::InvalidArgument
+        );
+        assert_eq!(releases.load(Ordering::SeqCst), 0);
+
+        plugin.abi_version = GURA_PLUGIN_ABI_VERSION;
+        plugin.tag = ptr::null();
+        assert_eq!(
+            gura_extensions_register(extensions, &plugin),
+            GuraErrorKind::InvalidArgument
+        );
+        assert_eq!(releases.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            gura_extensions_register(extensions, ptr::null()),
+            GuraErrorKind::InvalidArgument
+        );
+
+        // Custom scalars are not enabled by an empty set
+        let text = CString::new("debug: @flag on").unwrap();
+        assert!(gura_parse_with_extensions(text.as_ptr(), extensions, ptr::null_mut()).is_null());
+        gura_extensions_free(extensions);
+    }
+}
+
+#[test]
+/// Tests that values of unknown kinds returned by plugins are parsing errors
+fn test_unknown_scalar_kind() {
+    let tag = CString::new("weird").unwrap();
+    let plugin = GuraScalarPlugin {
+        abi_version: GURA_PLUGIN_ABI_VERSION,
+        tag: tag.as_ptr(),
+        recognize: Some(recognize_unknown_kind),
+        user_data: ptr::null_mut(),
+        release: None,
+    };
+    unsafe {
+        let extensions = gura_extensions_new();
+        assert_eq!(
+            gura_extensions_register(extensions, &plugin),
+            GuraErrorKind::None
+        );
+
+        let text = CString::new("value: @weird 1").unwrap();
+        let mut error = MaybeUninit::<GuraErrorInfo>::uninit();
+        let document = gura_parse_with_extensions(text.as_ptr(), extensions, error.as_mut_ptr());
+        assert!(document.is_null());
+        let mut error = error.assume_init();
+        assert_eq!(error.kind, GuraErrorKind::ParseError);
+        assert!(CStr::from_ptr(error.message).to_str().unwrap().starts_with(
+            "Invalid @weird value \"1\": the plugin returned an unknown kind of value (42)"
+        ));
+        gura_error_free(&mut error);
+        gura_extensions_free(extensions);
+    }
+}
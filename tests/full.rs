@@ -1,11 +1,18 @@
 use gura::{
-    errors::Error,
+    errors::{DumpErrorKind, Error},
     object,
-    parser::{dump, parse, GuraType},
+    parser::{dump, dump_truncated, dump_value, parse, try_dump, DumpOptions, GuraType, NanPolicy},
 };
-use std::f64::{INFINITY, NAN, NEG_INFINITY};
+use indexmap::IndexMap;
+use std::convert::TryFrom;
 mod common;
 
+/// Gets 0xDEADBEEF as the parser does, as it only fits in an `isize` of 64 bits
+fn dead_beef() -> GuraType {
+    isize::try_from(0xDEADBEEF_i64).map_or(GuraType::BigInteger(0xDEADBEEF), GuraType::Integer)
+}
+
+#[allow(clippy::approx_constant)]
 fn get_expected() -> GuraType {
     object! {
         a_string: "test string",
@@ -16,9 +23,9 @@ fn get_expected() -> GuraType {
         int5: 1000,
         int6: 5349221,
         int7: 5349221,
-        hex1: 3735928559,
-        hex2: 3735928559,
-        hex3: 3735928559,
+        hex1: (dead_beef()),
+        hex2: (dead_beef()),
+        hex3: (dead_beef()),
         oct1: 342391,
         oct2: 493,
         bin1: 214,
@@ -30,9 +37,9 @@ fn get_expected() -> GuraType {
         flt6: -2E-2,
         flt7: 6.626e-34,
         flt8: 224617.445991228,
-        sf1: INFINITY,
-        sf2: INFINITY,
-        sf3: NEG_INFINITY,
+        sf1: f64::INFINITY,
+        sf2: f64::INFINITY,
+        sf3: f64::NEG_INFINITY,
         null: null,
         empty_single: {},
         bool1: true,
@@ -99,7 +106,7 @@ fn test_parse() {
 fn test_loads_nan() {
     let parsed_data = common::get_file_content_parsed(PARENT_FOLDER, "nan.ura").unwrap();
     for (_, value) in parsed_data.iter().unwrap() {
-        assert_eq!(*value, NAN);
+        assert_eq!(*value, f64::NAN);
     }
 }
 
@@ -161,10 +168,208 @@ fn test_dumps_nan() {
     let string_data_nan = dump(&parsed_data);
     let new_parsed_data = parse(&string_data_nan).unwrap();
     for (_, value) in new_parsed_data.iter().unwrap() {
-        assert_eq!(*value, NAN);
+        assert_eq!(*value, f64::NAN);
     }
 }
 
+#[test]
+/// Tests dumping single values to embed them in other texts
+fn test_dump_value() {
+    assert_eq!(dump_value(&GuraType::Integer(5)), "5");
+    assert_eq!(dump_value(&GuraType::Bool(false)), "false");
+    assert_eq!(
+        dump_value(&GuraType::String("a \"b\"".to_string())),
+        r#""a \"b\"""#
+    );
+    assert_eq!(dump_value(&object! {}), "empty");
+    assert_eq!(dump_value(&object! {a: 1, b: [1, 2]}), "a: 1\nb: [1, 2]\n");
+    assert_eq!(
+        format!(
+            "key: {}",
+            dump_value(&GuraType::Array(vec![GuraType::Null]))
+        ),
+        "key: [null]"
+    );
+}
+
+#[test]
+/// Tests negative zero and NaN dump options
+fn test_try_dump() {
+    let parsed_data = common::get_file_content_parsed(PARENT_FOLDER, "full.ura").unwrap();
+    assert_eq!(
+        try_dump(&parsed_data, &DumpOptions::default()).unwrap(),
+        dump(&parsed_data)
+    );
+
+    let parsed_data = parse("zero: -0.0\nvalues: [1.5, -0.0, nan]").unwrap();
+    assert_eq!(
+        try_dump(&parsed_data, &DumpOptions::default()).unwrap(),
+        "zero: 0.0\nvalues: [1.5, 0.0, nan]"
+    );
+
+    let options = DumpOptions::default().preserve_negative_zero(true);
+    let dumped = try_dump(&parsed_data, &options).unwrap();
+    assert_eq!(dumped, "zero: -0.0\nvalues: [1.5, -0.0, nan]");
+    assert!(parse(&dumped).unwrap()["zero"]
+        .expect_f64()
+        .unwrap()
+        .is_sign_negative());
+
+    let options = DumpOptions::default().nan_policy(NanPolicy::Error);
+    let error = try_dump(&parsed_data, &options).unwrap_err();
+    assert_eq!(error.kind, DumpErrorKind::NaN);
+    assert_eq!(error.path, "values.2");
+    assert_eq!(error.to_string(), "NaN values are not allowed at values.2");
+}
+
+#[test]
+/// Tests that keys which can not be parsed back are rejected with their path
+fn test_try_dump_invalid_keys() {
+    let mut server = IndexMap::new();
+    server.insert(
+        String::from("host name"),
+        GuraType::String(String::from("localhost")),
+    );
+    let mut values = IndexMap::new();
+    values.insert(
+        String::from("servers"),
+        GuraType::Array(vec![object! { port: 80 }, GuraType::Object(server)]),
+    );
+    let object = GuraType::Object(values);
+
+    let error = try_dump(&object, &DumpOptions::default()).unwrap_err();
+    assert_eq!(
+        error.kind,
+        DumpErrorKind::InvalidKey(String::from("host name"))
+    );
+    assert_eq!(error.path, "servers.1.host name");
+    assert_eq!(
+        error.to_string(),
+        "Invalid key \"host name\", keys can only contain ASCII letters, digits and underscores at servers.1.host name"
+    );
+
+    let mut values = IndexMap::new();
+    values.insert(String::from("a.b"), GuraType::Null);
+    assert_eq!(
+        try_dump(&GuraType::Object(values), &DumpOptions::default())
+            .unwrap_err()
+            .path,
+        "a.b"
+    );
+}
+
+#[test]
+/// Tests that internal values are rejected instead of being dumped as empty text
+fn test_try_dump_unsupported_values() {
+    let mut server = IndexMap::new();
+    server.insert(String::from("tls"), GuraType::Comment);
+    let mut values = IndexMap::new();
+    values.insert(String::from("server"), GuraType::Object(server));
+    let error = try_dump(&GuraType::Object(values), &DumpOptions::default()).unwrap_err();
+    assert_eq!(error.kind, DumpErrorKind::UnsupportedValue);
+    assert_eq!(
+        error.to_string(),
+        "Internal values can not be dumped at server.tls"
+    );
+
+    let error = try_dump(&GuraType::UselessLine, &DumpOptions::default()).unwrap_err();
+    assert_eq!(error.path, "");
+    assert_eq!(error.to_string(), "Internal values can not be dumped");
+
+    // The first value that can not be dumped is reported
+    let parsed_data = parse("values: [nan, 1.5]\nother: nan").unwrap();
+    let options = DumpOptions::default().nan_policy(NanPolicy::Error);
+    assert_eq!(
+        try_dump(&parsed_data, &options).unwrap_err().path,
+        "values.0"
+    );
+}
+
+#[test]
+/// Tests factoring repeated values into variables
+fn test_try_dump_variables() {
+    let parsed_data = parse(
+        "primary:\n    host: \"db.local\"\n    port: 5432\nreplica:\n    host: \"db.local\"\n    port: 5433\nbackup:\n    address: \"db.local\"\n    ports: [5432, 5433]",
+    )
+    .unwrap();
+
+    let options = DumpOptions::default().factor_variables(2);
+    let dumped = try_dump(&parsed_data, &options).unwrap();
+    let expected = "$host: \"db.local\"\n$port: 5432\n$port_2: 5433\n\nprimary:\n    host: $host\n    port: $port\nreplica:\n    host: $host\n    port: $port_2\nbackup:\n    address: $host\n    ports: [$port, $port_2]";
+    assert_eq!(dumped, expected);
+    assert_eq!(parse(&dumped).unwrap(), parsed_data);
+
+    // Values not repeated enough are kept in place
+    let options = DumpOptions::default().factor_variables(3);
+    let dumped = try_dump(&parsed_data, &options).unwrap();
+    assert!(dumped.starts_with("$host: \"db.local\"\n\nprimary:\n    host: $host\n    port: 5432"));
+    assert_eq!(parse(&dumped).unwrap(), parsed_data);
+
+    // Values that are not objects are never factored
+    let array = parse("values: [1, 1, 1]").unwrap()["values"].clone();
+    assert_eq!(try_dump(&array, &options).unwrap(), "[1, 1, 1]");
+
+    // Big integers are never factored, as variables can not hold them
+    let parsed_data =
+        parse("a: 99999999999999999999\nb: 99999999999999999999\nc: 1.5\nd: 1.5").unwrap();
+    let options = DumpOptions::default().factor_variables(2);
+    let dumped = try_dump(&parsed_data, &options).unwrap();
+    assert_eq!(
+        dumped,
+        "$c: 1.5\n\na: 99999999999999999999\nb: 99999999999999999999\nc: $c\nd: $c"
+    );
+    assert_eq!(parse(&dumped).unwrap(), parsed_data);
+}
+
+#[test]
+/// Tests dumping with limited depth and array items
+fn test_dump_truncated() {
+    let parsed_data = common::get_file_content_parsed(PARENT_FOLDER, "full.ura").unwrap();
+    assert_eq!(
+        dump_truncated(&parsed_data, usize::MAX, usize::MAX),
+        dump(&parsed_data)
+    );
+
+    let parsed_data = parse(
+        "users: [\n    alice:\n        roles: [\"admin\", \"dev\", \"ops\"]\n    ,\n    bob:\n        roles: empty\n    ,\n    carol:\n        roles: []\n]\nlimits:\n    cpu:\n        max: 4",
+    )
+    .unwrap();
+    assert_eq!(
+        dump_truncated(&parsed_data, 2, 2),
+        "users: [\n    alice:\n        roles: [\"admin\", \"dev\", …],\n    bob:\n        roles: empty,\n    …\n]\nlimits:\n    cpu:\n        max: 4"
+    );
+    assert_eq!(
+        dump_truncated(&parsed_data, 1, 1),
+        "users: [\n    alice: …,\n    …\n]\nlimits:\n    cpu: …"
+    );
+    assert_eq!(dump_truncated(&parsed_data, 0, 0), "users: […]\nlimits: …");
+}
+
+#[test]
+/// Tests compact and alternate (indented) Display formats
+fn test_display() {
+    let parsed_data = parse("a: 1\nb:\n    c: [1, \"two\"]\n    d: empty").unwrap();
+    assert_eq!(
+        format!("{}", parsed_data),
+        "{a: 1, b: {c: [1, \"two\"], d: empty}}"
+    );
+    assert_eq!(
+        format!("{:#}", parsed_data),
+        "a: 1\nb:\n    c: [1, \"two\"]\n    d: empty"
+    );
+    assert_eq!(format!("{:#}", parsed_data), dump(&parsed_data));
+    assert_eq!(format!("{}", parsed_data["a"]), "1");
+}
+
+#[test]
+/// Tests Display of values in their natural form
+fn test_raw_display() {
+    let parsed_data = parse("s: \"Line\\tTab\"\nn: 1.5\na: [\"x\"]").unwrap();
+    assert_eq!(parsed_data["s"].raw_display().to_string(), "Line\tTab");
+    assert_eq!(parsed_data["n"].raw_display().to_string(), "1.5");
+    assert_eq!(parsed_data["a"].raw_display().to_string(), "[\"x\"]");
+}
+
 #[test]
 /// Tests empty Gura documents
 fn test_empty() {
@@ -0,0 +1,101 @@
+use gura::{
+    extension::ScalarExtensions, grammar, parser::ParserOptions, spec::Extension, GuraType,
+};
+use std::collections::HashSet;
+
+/// Gets a set with the recognizer of a custom scalar
+fn scalar_extensions() -> ScalarExtensions {
+    let mut extensions = ScalarExtensions::new();
+    extensions.register("port", |_| Ok(GuraType::Null));
+    extensions
+}
+
+/// Gets the names of the rules referenced by a definition, skipping literals and char classes
+fn references(definition: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut chars = definition.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '"' | '\'' => while chars.next().is_some_and(|other| other != char) {},
+            '[' => while chars.next().is_some_and(|other| other != ']') {},
+            '#' => while chars.next_if(char::is_ascii_alphanumeric).is_some() {},
+            _ if char.is_ascii_alphabetic() => {
+                let mut name = char.to_string();
+                while let Some(other) =
+                    chars.next_if(|other| other.is_ascii_alphanumeric() || *other == '_')
+                {
+                    name.push(other);
+                }
+                names.push(name);
+            }
+            _ => (),
+        }
+    }
+    names
+}
+
+#[test]
+/// Tests that every rule referenced by the grammar is defined once
+fn test_grammar_references() {
+    let all_options = [
+        ParserOptions::default(),
+        ParserOptions::default()
+            .lenient_arrays(true)
+            .indent_unit(2)
+            .scalar_extensions(scalar_extensions()),
+    ];
+    for options in all_options.iter() {
+        let grammar = options.grammar();
+        let names: HashSet<_> = grammar.rules.iter().map(|rule| rule.name).collect();
+        assert_eq!(names.len(), grammar.rules.len());
+        for rule in grammar.rules.iter() {
+            for reference in references(&rule.definition) {
+                assert!(
+                    names.contains(reference.as_str()),
+                    "{} in {}",
+                    reference,
+                    rule.name
+                );
+            }
+        }
+    }
+}
+
+#[test]
+/// Tests the rules changed by extensions
+fn test_grammar_extensions() {
+    let default = grammar();
+    assert!(default.rules.iter().all(|rule| rule.extension.is_none()));
+    assert!(default.rule("scalar_extension").is_none());
+    assert_eq!(default.rule("indent").unwrap().definition, "\"    \"");
+
+    let options = ParserOptions::default().scalar_extensions(scalar_extensions());
+    let extended = options.grammar();
+    assert!(extended
+        .rule("primitive_type")
+        .unwrap()
+        .definition
+        .contains("scalar_extension"));
+    assert_eq!(
+        extended.rule("scalar_extension").unwrap().extension,
+        Some(Extension::ScalarExtensions)
+    );
+
+    // Strict mode disables the extensions
+    assert_eq!(options.conformance_strict().grammar(), default);
+
+    assert!(!default.rule("escape").unwrap().definition.contains("\"x\""));
+    let escape = ParserOptions::default().hex_escapes(true).grammar();
+    let escape = escape.rule("escape").unwrap();
+    assert!(escape.definition.contains("\"x\" hex hex"));
+    assert_eq!(escape.extension, Some(Extension::HexEscapes));
+}
+
+#[test]
+/// Tests the EBNF output
+fn test_grammar_ebnf() {
+    let ebnf = grammar().to_ebnf();
+    assert!(ebnf.starts_with("/* Gura 1.0.0 */\n\n/* A document: "));
+    assert!(ebnf.contains("\n\n/* A boolean value */\nboolean ::= \"true\" | \"false\""));
+    assert_eq!(ebnf.matches(" ::= ").count(), grammar().rules.len());
+}
@@ -0,0 +1,80 @@
+use gura::{highlight, highlight::TokenClass};
+use std::fs;
+
+const PARENT_FOLDER: &str = "highlight";
+
+/// Gets the text and class of every token of a text
+fn get_tokens(text: &str) -> Vec<(&str, TokenClass)> {
+    highlight(text)
+        .into_iter()
+        .map(|(span, class)| (&text[span.byte_start..span.byte_end], class))
+        .collect()
+}
+
+#[test]
+/// Tests classification of every kind of token
+fn test_normal() {
+    let content =
+        fs::read_to_string(format!("tests/{}/tests-files/normal.ura", PARENT_FOLDER)).unwrap();
+    let expected = vec![
+        ("import", TokenClass::Import),
+        ("\"other.ura\"", TokenClass::String),
+        ("# Variables", TokenClass::Comment),
+        ("$host", TokenClass::Variable),
+        ("\"localhost\"", TokenClass::String),
+        ("$port", TokenClass::Variable),
+        ("8080", TokenClass::Number),
+        ("server", TokenClass::Key),
+        ("url", TokenClass::Key),
+        ("\"http://", TokenClass::String),
+        ("$host", TokenClass::Variable),
+        (":", TokenClass::String),
+        ("$port", TokenClass::Variable),
+        ("\"", TokenClass::String),
+        ("path", TokenClass::Key),
+        ("'C:\\$dir'", TokenClass::String),
+        ("port", TokenClass::Key),
+        ("$port", TokenClass::Variable),
+        ("enabled", TokenClass::Key),
+        ("true", TokenClass::Boolean),
+        ("# Inline comment", TokenClass::Comment),
+        ("timeout", TokenClass::Key),
+        ("null", TokenClass::Null),
+        ("extra", TokenClass::Key),
+        ("empty", TokenClass::Null),
+        ("numbers", TokenClass::Key),
+        ("-1", TokenClass::Number),
+        ("+1.5e-3", TokenClass::Number),
+        ("0xDEADbeef", TokenClass::Number),
+        ("1_000", TokenClass::Number),
+        ("-inf", TokenClass::Number),
+        ("nan", TokenClass::Number),
+        ("description", TokenClass::Key),
+        ("\"\"\"Multiline\n\"quoted\" text\"\"\"", TokenClass::String),
+    ];
+    assert_eq!(get_tokens(&content), expected);
+}
+
+#[test]
+/// Tests that invalid and incomplete texts are classified anyway
+fn test_invalid() {
+    assert_eq!(
+        get_tokens("title: \"Unterminated\nimport: 5 and some words"),
+        vec![
+            ("title", TokenClass::Key),
+            ("\"Unterminated", TokenClass::String),
+            ("import", TokenClass::Key),
+            ("5", TokenClass::Number),
+        ]
+    );
+}
+
+#[test]
+/// Tests spans of tokens placed after non ASCII chars
+fn test_spans() {
+    let tokens = highlight("name: \"Café 🎉\"\nport: 80");
+    let (span, class) = tokens[2];
+    assert_eq!(class, TokenClass::Key);
+    assert_eq!((span.start, span.end, span.line), (15, 19, 2));
+    assert_eq!((span.byte_start, span.utf16_start), (19, 16));
+}
@@ -0,0 +1,91 @@
+use gura::import_graph;
+use std::fs;
+
+#[test]
+#[cfg_attr(
+    target_os = "wasi",
+    ignore = "temporary directories are not available in WASI"
+)]
+/// Tests the files, imports and keys of a graph with a file imported twice
+fn test_import_graph() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("services")).unwrap();
+    fs::write(dir.path().join("common.ura"), "timeout: 30").unwrap();
+    fs::write(
+        dir.path().join("services").join("db.ura"),
+        "import \"../common.ura\"\ndb:\n    port: 5432",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("main.ura"),
+        "import \"services/db.ura\"\nname: \"app\"\nserver:\n    port: 8080",
+    )
+    .unwrap();
+
+    let graph = import_graph(dir.path().join("main.ura")).unwrap();
+    let keys: Vec<&Vec<String>> = graph.files.iter().map(|file| &file.keys).collect();
+    assert_eq!(
+        keys,
+        vec![
+            &vec![
+                String::from("name"),
+                String::from("server"),
+                String::from("server.port")
+            ],
+            &vec![String::from("db"), String::from("db.port")],
+            &vec![String::from("timeout")],
+        ]
+    );
+    assert_eq!(graph.edges, vec![(0, 1), (1, 2)]);
+    assert!(graph.files[2].path.ends_with("common.ura"));
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph imports {\n"));
+    assert!(dot.contains("file0 -> file1;\n    file1 -> file2;\n"));
+    assert!(dot.contains("main.ura\\nname\\nserver\"];"));
+
+    let json = graph.to_json();
+    assert!(json.starts_with("{\"files\":[{\"path\":"));
+    assert!(json.contains("\"keys\":[\"timeout\"]"));
+    assert_eq!(json.matches("\"from\":").count(), 2);
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "wasi",
+    ignore = "temporary directories are not available in WASI"
+)]
+/// Tests that files imported by several files appear once
+fn test_shared_imports() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("common.ura"), "$host: \"localhost\"").unwrap();
+    fs::write(dir.path().join("a.ura"), "import \"common.ura\"\na: $host").unwrap();
+    fs::write(
+        dir.path().join("b.ura"),
+        "import \"./common.ura\"\nb: $host",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("main.ura"),
+        "import \"a.ura\"\nimport \"b.ura\"",
+    )
+    .unwrap();
+
+    let graph = import_graph(dir.path().join("main.ura")).unwrap();
+    assert_eq!(graph.files.len(), 4);
+    assert!(graph.files[0].keys.is_empty());
+    assert_eq!(graph.edges, vec![(0, 1), (0, 2), (1, 3), (2, 3)]);
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "wasi",
+    ignore = "temporary directories are not available in WASI"
+)]
+/// Tests errors of missing files
+fn test_import_graph_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("main.ura"), "import \"missing.ura\"").unwrap();
+    assert!(import_graph(dir.path().join("main.ura")).is_err());
+    assert!(import_graph(dir.path().join("other.ura")).is_err());
+}
@@ -1,11 +1,13 @@
 use gura::{
-    errors::Error,
-    object,
-    parser::{parse, GuraType},
+    document::{dump_split, Document, SplitStrategy},
+    errors::{Error, ReadError},
+    object, parse_file, parse_with_options,
+    parser::{parse, GuraType, ParserOptions},
+    resolve_imports,
 };
 use tempfile::NamedTempFile;
 mod common;
-use std::io::Write;
+use std::{fs, io::Write};
 
 fn get_expected() -> GuraType {
     object! {
@@ -37,6 +39,59 @@ fn test_with_variables() {
     assert_eq!(parsed_data, get_expected());
 }
 
+#[test]
+#[cfg_attr(
+    target_os = "wasi",
+    ignore = "temporary directories are not available in WASI"
+)]
+/// Tests replacing import sentences with the content of the files
+fn test_resolve_imports() {
+    for file in ["normal.ura", "with_variable.ura"] {
+        let content =
+            fs::read_to_string(format!("tests/{}/tests-files/{}", PARENT_FOLDER, file)).unwrap();
+        let resolved = resolve_imports(&content, ".").unwrap();
+        assert!(!resolved.text.contains("import "));
+        assert_eq!(parse(&resolved.text).unwrap(), get_expected());
+    }
+
+    // Nested imports are relative to the files that contain them and comments are kept
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("services")).unwrap();
+    fs::write(
+        dir.path().join("common.ura"),
+        "# Common
+timeout: 30",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("services").join("db.ura"),
+        "import \"../common.ura\"\n# Database\ndb_port: 5432\n",
+    )
+    .unwrap();
+    let text = "$name: \"app\"\nimport \"services/db.ura\"\n\nname: $name";
+    let resolved = resolve_imports(text, dir.path()).unwrap();
+    assert_eq!(
+        resolved.text,
+        "$name: \"app\"\n# Common\ntimeout: 30\n# Database\ndb_port: 5432\n\nname: $name"
+    );
+    let db_path = dir.path().join("services").join("db.ura");
+    let sources: Vec<(Option<&std::path::Path>, usize)> = (1..=7)
+        .map(|line| resolved.source_line(line).unwrap())
+        .collect();
+    assert_eq!(sources[0], (None, 1));
+    assert!(sources[1].0.unwrap().ends_with("common.ura"));
+    assert_eq!(sources[2].1, 2);
+    assert_eq!(sources[3], (Some(db_path.as_path()), 2));
+    assert_eq!(sources[4], (Some(db_path.as_path()), 3));
+    assert_eq!(sources[5..], [(None, 3), (None, 4)]);
+    assert_eq!(resolved.source_line(8), None);
+
+    match resolve_imports("import \"invalid_file.ura\"", dir.path()) {
+        Err(ReadError::Parse(error)) => assert_eq!(error.kind, Error::FileNotFoundError),
+        result => panic!("Expected a parsing error, got {:?}", result),
+    }
+}
+
 #[test]
 /// Tests errors importing a non existing file
 fn test_not_found_error() {
@@ -70,6 +125,197 @@ fn test_duplicated_imports() {
 }
 
 #[test]
+/// Tests that the same file is detected however its path is written
+fn test_duplicated_imports_with_other_paths() {
+    let parsed_data = parse(
+        "import \"tests/importing/tests-files/one.ura\"\nimport \"./tests/importing/../importing/tests-files/one.ura\"",
+    );
+    assert_eq!(parsed_data.unwrap_err().kind, Error::DuplicatedImportError);
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "wasi",
+    ignore = "temporary directories are not available in WASI"
+)]
+/// Tests that files imported by several files are imported only once with deduplication
+fn test_deduplicated_imports() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("common.ura"), "$port: 8080\nname: \"app\"").unwrap();
+    fs::write(dir.path().join("copy.ura"), "$port: 8080\nname: \"app\"").unwrap();
+    fs::write(
+        dir.path().join("server.ura"),
+        "import \"common.ura\"\nport: $port",
+    )
+    .unwrap();
+    let text = format!(
+        "import \"{0}/server.ura\"\nimport \"{0}/common.ura\"\nimport \"{0}/copy.ura\"",
+        dir.path().to_str().unwrap()
+    );
+
+    assert_eq!(parse(&text).unwrap_err().kind, Error::DuplicatedImportError);
+
+    let options = ParserOptions::default().deduplicate_imports(true);
+    assert_eq!(
+        parse_with_options(&text, &options).unwrap(),
+        object! {
+            name: "app",
+            port: 8080
+        }
+    );
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "wasi",
+    ignore = "temporary directories are not available in WASI"
+)]
+/// Tests imports written with the separators of another platform
+fn test_mixed_separators() {
+    let parsed_data = parse(
+        "import \"tests\\\\importing\\\\tests-files\\\\one.ura\"\nimport \"tests/importing/tests-files/two.ura\"",
+    )
+    .unwrap();
+    assert_eq!(parsed_data["from_file_one"], 1);
+    assert_eq!(parsed_data["from_file_two"]["year_of_birth"], 1914);
+
+    // Nested imports are relative to the file that imports them
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("nested")).unwrap();
+    fs::write(dir.path().join("nested").join("port.ura"), "port: 8080").unwrap();
+    fs::write(
+        dir.path().join("server.ura"),
+        "import \"nested\\\\port.ura\"",
+    )
+    .unwrap();
+    let server = dir.path().join("server.ura");
+    let parsed_data = parse(&format!("import \"{}\"", server.to_str().unwrap())).unwrap();
+    assert_eq!(parsed_data["port"], 8080);
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "wasi",
+    ignore = "temporary directories are not available in WASI"
+)]
+/// Tests that drive letter and UNC paths are not relative to the file that imports them
+fn test_windows_absolute_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    for path in [
+        "Z:\\\\missing.ura",
+        "Z:/missing.ura",
+        "\\\\\\\\server\\\\missing.ura",
+    ] {
+        fs::write(dir.path().join("main.ura"), format!("import \"{}\"", path)).unwrap();
+        let main = dir.path().join("main.ura");
+        let error = parse(&format!("import \"{}\"", main.to_str().unwrap())).unwrap_err();
+        assert_eq!(error.kind, Error::FileNotFoundError);
+        assert!(!error.msg.contains(dir.path().to_str().unwrap()));
+    }
+}
+
+#[cfg(windows)]
+#[test]
+/// Tests absolute Windows paths written with both separators
+fn test_windows_paths() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    write!(temp_file, "from_temp: true").unwrap();
+    let path = temp_file.path().to_str().unwrap().replace('\\', "/");
+    let parsed_data = parse(&format!("import \"{}\"", path)).unwrap();
+    assert_eq!(parsed_data["from_temp"], true);
+
+    let parsed_data = parse(
+        "import \"tests/importing/tests-files/one.ura\"\nimport \"tests\\\\importing\\\\tests-files\\\\one.ura\"",
+    );
+    assert_eq!(parsed_data.unwrap_err().kind, Error::DuplicatedImportError);
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "wasi",
+    ignore = "temporary directories are not available in WASI"
+)]
+/// Tests parsing of files with imports relative to them
+fn test_parse_file() {
+    let options = ParserOptions::default();
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("nested")).unwrap();
+    fs::write(dir.path().join("nested").join("port.ura"), "port: 8080").unwrap();
+    fs::write(dir.path().join("main.ura"), "import \"nested/port.ura\"").unwrap();
+    let parsed_data = parse_file(dir.path().join("main.ura"), &options).unwrap();
+    assert_eq!(parsed_data["port"], 8080);
+
+    match parse_file(dir.path().join("missing.ura"), &options) {
+        Err(ReadError::Io(_)) => (),
+        _ => panic!("Expected an IO error"),
+    }
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "wasi",
+    ignore = "temporary directories are not available in WASI"
+)]
+/// Tests the paths of built-in variables in parsed and imported files
+fn test_builtin_variables() {
+    let dir = tempfile::tempdir().unwrap();
+    let nested = dir.path().join("nested");
+    fs::create_dir(&nested).unwrap();
+    fs::write(
+        nested.join("tls.ura"),
+        "tls:\n    cert: \"$__dir__/cert.pem\"",
+    )
+    .unwrap();
+    fs::write(
+        nested.join("server.ura"),
+        "import \"tls.ura\"\nserver:\n    file: $__file__",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("main.ura"),
+        "import \"$__dir__/nested/server.ura\"\nmain: $__file__\ndir: $__dir__",
+    )
+    .unwrap();
+
+    let options = ParserOptions::default().builtin_variables(true);
+    let parsed_data = parse_file(dir.path().join("main.ura"), &options).unwrap();
+    assert_eq!(
+        parsed_data["tls"]["cert"],
+        format!("{}/cert.pem", nested.to_str().unwrap())
+    );
+    assert_eq!(
+        parsed_data["server"]["file"],
+        nested.join("server.ura").to_str().unwrap()
+    );
+    assert_eq!(
+        parsed_data["main"],
+        dir.path().join("main.ura").to_str().unwrap()
+    );
+    assert_eq!(parsed_data["dir"], dir.path().to_str().unwrap());
+
+    // Variables of the document take precedence
+    fs::write(
+        dir.path().join("custom.ura"),
+        "$__dir__: \"custom\"\ndir: $__dir__",
+    )
+    .unwrap();
+    let parsed_data = parse_file(dir.path().join("custom.ura"), &options).unwrap();
+    assert_eq!(parsed_data["dir"], "custom");
+
+    // They are only defined in files and if enabled
+    let error = parse_with_options("dir: $__dir__", &options).unwrap_err();
+    assert_eq!(error.kind, Error::VariableNotDefinedError);
+    let error = parse_file(dir.path().join("main.ura"), &ParserOptions::default()).unwrap_err();
+    assert!(
+        matches!(error, ReadError::Parse(error) if error.kind == Error::VariableNotDefinedError)
+    );
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "wasi",
+    ignore = "temporary directories are not available in WASI"
+)]
 /// Tests that absolute paths works as expected
 fn test_with_absolute_paths() {
     let mut temp_file = NamedTempFile::new().unwrap();
@@ -102,3 +348,111 @@ fn test_parse_error_2() {
     let parsed_data = parse("import   \"another_file.ura\"");
     assert_eq!(parsed_data.unwrap_err().kind, Error::ParseError);
 }
+
+#[test]
+/// Tests that documents keep their imports and dump only their local keys
+fn test_document() {
+    let content = fs::read_to_string("tests/importing/tests-files/normal.ura").unwrap();
+    let document = Document::parse(&content).unwrap();
+    assert_eq!(
+        document.imports,
+        vec![
+            "tests/importing/tests-files/one.ura",
+            "tests/importing/tests-files/two.ura"
+        ]
+    );
+    assert_eq!(
+        document.value,
+        object! {
+            from_original_1: [1, 2, 5],
+            from_original_2: false
+        }
+    );
+
+    let dumped = document.dump();
+    assert_eq!(
+        dumped,
+        "import \"tests/importing/tests-files/one.ura\"\nimport \"tests/importing/tests-files/two.ura\"\n\nfrom_original_1: [1, 2, 5]\nfrom_original_2: false"
+    );
+    assert_eq!(parse(&dumped).unwrap(), get_expected());
+
+    let document = Document::new(object! {}).import("one.ura");
+    assert_eq!(document.dump(), "import \"one.ura\"");
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "wasi",
+    ignore = "temporary directories are not available in WASI"
+)]
+/// Tests writing a document into several files and importing them again
+fn test_dump_split() {
+    let content = object! {
+        title: "Gura",
+        server: {
+            host: "localhost",
+            port: 8080
+        },
+        database: {
+            name: "app"
+        },
+        hosts: ["alpha", "omega"]
+    };
+    let dir = tempfile::tempdir().unwrap();
+
+    let files = dump_split(&content, dir.path(), &SplitStrategy::Objects).unwrap();
+    assert_eq!(
+        files,
+        vec![
+            dir.path().join("main.ura"),
+            dir.path().join("server.ura"),
+            dir.path().join("database.ura")
+        ]
+    );
+    assert_eq!(
+        fs::read_to_string(&files[0]).unwrap(),
+        "import \"server.ura\"\nimport \"database.ura\"\n\ntitle: \"Gura\"\nhosts: [\"alpha\", \"omega\"]\n"
+    );
+
+    // Imports of the root file are relative to it
+    let parsed_data = parse(&format!("import \"{}\"", files[0].to_str().unwrap())).unwrap();
+    assert_eq!(parsed_data["server"], content["server"]);
+    assert_eq!(parsed_data["database"], content["database"]);
+    assert_eq!(parsed_data["hosts"], content["hosts"]);
+
+    let strategy = SplitStrategy::Keys(vec![String::from("hosts"), String::from("unknown")]);
+    let files = dump_split(&content, dir.path().join("keys"), &strategy).unwrap();
+    assert_eq!(files.len(), 2);
+    assert_eq!(
+        fs::read_to_string(&files[1]).unwrap(),
+        "hosts: [\"alpha\", \"omega\"]\n"
+    );
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "wasi",
+    ignore = "temporary directories are not available in WASI"
+)]
+/// Tests that a key named like the root file can not be split
+fn test_dump_split_root_key() {
+    let content = object! {
+        main: {
+            port: 8080
+        }
+    };
+    let dir = tempfile::tempdir().unwrap();
+
+    let error =
+        dump_split(&content, dir.path().join("split"), &SplitStrategy::Objects).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(
+        error.to_string(),
+        "Key \"main\" can not be split, its file would be the root main.ura"
+    );
+    assert!(!dir.path().join("split").exists());
+
+    let strategy = SplitStrategy::Keys(vec![String::from("other")]);
+    let files = dump_split(&content, dir.path(), &strategy).unwrap();
+    assert_eq!(files, vec![dir.path().join("main.ura")]);
+}
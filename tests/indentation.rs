@@ -1,4 +1,9 @@
-use gura::errors::Error;
+use gura::{
+    errors::Error,
+    object, parse, parse_with_options,
+    parser::{DumpOptions, GuraType, ParserOptions},
+    try_dump,
+};
 mod common;
 
 const PARENT_FOLDER: &str = "indentation";
@@ -42,3 +47,37 @@ fn test_indentation_with_tabs() {
         Error::InvalidIndentationError
     );
 }
+
+#[test]
+/// Tests parsing and dumping with an indentation unit of 2 spaces
+fn test_indent_unit() {
+    let options = ParserOptions::default().indent_unit(2);
+    let text = "server:\n  host: \"localhost\"\n  ports: [\n    number: 80\n  ]";
+    let parsed = parse_with_options(text, &options).unwrap();
+    assert_eq!(
+        parsed,
+        object! { server: { host: "localhost", ports: [{ number: 80 }] } }
+    );
+    assert_eq!(
+        try_dump(&parsed, &DumpOptions::default().indent_unit(2)).unwrap(),
+        text
+    );
+
+    // Levels are still checked against the unit
+    let error = parse_with_options("server:\n   host: \"localhost\"", &options).unwrap_err();
+    assert_eq!(error.kind, Error::InvalidIndentationError);
+    assert_eq!(error.msg, "Indentation block (3) must be divisible by 2");
+    let error = parse_with_options("server:\n    host: \"localhost\"", &options).unwrap_err();
+    assert_eq!(
+        error.msg,
+        "Difference between different indentation levels must be 2"
+    );
+    let error = parse_with_options("server:\n\thost: \"localhost\"", &options).unwrap_err();
+    assert_eq!(
+        error.suggestion.as_deref(),
+        Some("Replace every tab with 2 spaces")
+    );
+
+    // The specification unit is still the default
+    assert!(parse("server:\n  host: \"localhost\"").is_err());
+}
@@ -0,0 +1,135 @@
+#![cfg(feature = "schemars")]
+use gura::{
+    errors::{TypeError, ValidationError, ValidationErrorKind},
+    parse,
+    schema::Schema,
+    GuraType, Kind,
+};
+use schemars::JsonSchema;
+use std::collections::BTreeMap;
+
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+enum Level {
+    Debug,
+    Info,
+}
+
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    /// Name of the app
+    name: String,
+    #[serde(default = "default_ratio")]
+    ratio: f64,
+    /// Main database
+    database: Database,
+    replica: Option<Database>,
+    hosts: Vec<String>,
+    labels: BTreeMap<String, String>,
+    level: Level,
+    parent: Option<Box<Config>>,
+}
+
+fn default_ratio() -> f64 {
+    0.5
+}
+
+#[test]
+/// Tests the conversion of the shape of a derived schema
+fn test_derived_shape() {
+    let schema = Schema::for_type::<Config>();
+    assert_eq!(schema.get_kind(), Some(Kind::Object));
+
+    let name = &schema.fields()["name"];
+    assert_eq!(name.get_kind(), Some(Kind::String));
+    assert_eq!(name.get_description(), Some("Name of the app"));
+    assert!(name.is_required());
+
+    let ratio = &schema.fields()["ratio"];
+    assert_eq!(ratio.get_kind(), Some(Kind::Float));
+    assert_eq!(ratio.get_default(), Some(&GuraType::Float(0.5)));
+    assert!(!ratio.is_required());
+
+    let database = &schema.fields()["database"];
+    assert_eq!(database.get_description(), Some("Main database"));
+    assert_eq!(database.fields()["port"].get_kind(), Some(Kind::Integer));
+
+    let replica = &schema.fields()["replica"];
+    assert!(replica.is_nullable());
+    assert_eq!(replica.fields().len(), 2);
+
+    let hosts = &schema.fields()["hosts"];
+    assert_eq!(hosts.items().unwrap().get_kind(), Some(Kind::String));
+
+    assert_eq!(schema.fields()["labels"].get_kind(), Some(Kind::Object));
+    assert_eq!(schema.fields()["level"].get_kind(), Some(Kind::String));
+
+    // Recursive definitions stop at the first repetition
+    let parent = &schema.fields()["parent"];
+    assert_eq!(parent.get_kind(), Some(Kind::Object));
+    assert_eq!(parent.fields()["parent"].get_kind(), None);
+}
+
+#[test]
+/// Tests validation of documents with a derived schema
+fn test_derived_validation() {
+    let schema = Schema::for_type::<Config>();
+    let parsed = parse(
+        "name: \"app\"\ndatabase:\n    host: \"localhost\"\n    port: 5432\nreplica: null\nhosts: [\"a\"]\nlabels:\n    zone: \"eu\"\nlevel: \"Info\"",
+    )
+    .unwrap();
+    assert!(schema.validate(&parsed).is_ok());
+
+    // Numbers accept integers, as floats are deserialized from them
+    for ratio in ["1", "99999999999999999999", "0.5"] {
+        let text = format!(
+            "name: \"app\"\nratio: {}\ndatabase:\n    host: \"localhost\"\n    port: 5432\nreplica: null\nhosts: []\nlabels: empty\nlevel: \"Info\"",
+            ratio
+        );
+        assert!(schema.validate(&parse(&text).unwrap()).is_ok());
+    }
+    let text = "name: \"app\"\nratio: \"1\"\ndatabase:\n    host: \"localhost\"\n    port: 5432\nreplica: null\nhosts: []\nlabels: empty\nlevel: \"Info\"";
+    let errors = schema.validate(&parse(text).unwrap()).unwrap_err();
+    assert_eq!(
+        errors[0].to_string(),
+        "expected float, found string at ratio"
+    );
+
+    let parsed = parse(
+        "database:\n    host: \"localhost\"\n    port: \"5432\"\nhosts: [\"a\"]\nlabels:\n    zone: \"eu\"\nlevel: \"Info\"\nlevl: \"Debug\"",
+    )
+    .unwrap();
+    assert_eq!(
+        schema.validate(&parsed).unwrap_err(),
+        vec![
+            ValidationError {
+                path: "name".parse().unwrap(),
+                kind: ValidationErrorKind::MissingKey,
+                span: None,
+            },
+            ValidationError {
+                path: "database.port".parse().unwrap(),
+                kind: ValidationErrorKind::WrongType(TypeError {
+                    expected: Kind::Integer,
+                    found: Kind::String,
+                    span: None,
+                }),
+                span: None,
+            },
+            ValidationError {
+                path: "levl".parse().unwrap(),
+                kind: ValidationErrorKind::UnknownKey,
+                span: None,
+            },
+        ]
+    );
+}
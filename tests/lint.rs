@@ -0,0 +1,115 @@
+use gura::{
+    deprecation::DeprecationMap,
+    lint::{lint, LintConfig, LintRule, NamingConvention},
+    parse, parse_spanned,
+};
+
+const TEXT: &str = "$host: \"localhost\"
+$unused: 1
+legacy_mode: true
+server:
+    host: $host
+    tls:
+        certFile: \"cert.pem\"
+        options:
+            verify: true
+";
+
+/// Gets the rule and line of every warning
+fn found(warnings: &[gura::lint::LintWarning]) -> Vec<(LintRule, usize)> {
+    warnings
+        .iter()
+        .map(|warning| (warning.rule, warning.span.unwrap().line))
+        .collect()
+}
+
+#[test]
+/// Tests every rule, with warnings sorted by position
+fn test_lint() {
+    let document = parse_spanned(TEXT).unwrap();
+    let mut deprecations = DeprecationMap::new();
+    deprecations.deprecate("legacy_mode", None);
+    let config = LintConfig::default()
+        .deprecations(deprecations)
+        .max_nesting(Some(2))
+        .naming_convention(Some(NamingConvention::SnakeCase));
+
+    let warnings = lint(&document, &config);
+    assert_eq!(
+        found(&warnings),
+        vec![
+            (LintRule::UnusedVariable, 2),
+            (LintRule::DeprecatedKey, 3),
+            (LintRule::MaxNesting, 7),
+            (LintRule::NamingConvention, 7),
+            (LintRule::MaxNesting, 8),
+        ]
+    );
+    assert_eq!(
+        warnings[2].to_string(),
+        "The key \"server.tls.certFile\" is nested in more than 2 levels [max_nesting]"
+    );
+
+    let config = LintConfig::default().unused_variables(false);
+    assert!(lint(&document, &config).is_empty());
+}
+
+#[test]
+/// Tests naming conventions
+fn test_naming_conventions() {
+    assert!(NamingConvention::SnakeCase.matches("max_connections_2"));
+    assert!(!NamingConvention::SnakeCase.matches("max__connections"));
+    assert!(!NamingConvention::SnakeCase.matches("_private"));
+    assert!(!NamingConvention::SnakeCase.matches("maxConnections"));
+    assert!(NamingConvention::CamelCase.matches("maxConnections"));
+    assert!(!NamingConvention::CamelCase.matches("MaxConnections"));
+    assert!(!NamingConvention::CamelCase.matches("max_connections"));
+}
+
+#[test]
+/// Tests loading configurations from documents
+fn test_config_from_document() {
+    let config = parse(
+        "unused_variables: false\nmax_nesting: 2\nnaming_convention: \"camelCase\"\ndeprecated_keys: [\"legacy_mode\", [\"server.host\", \"server.address\"]]",
+    )
+    .unwrap();
+    let config = LintConfig::from_document(&config).unwrap();
+    let warnings = lint(&parse_spanned(TEXT).unwrap(), &config);
+    assert_eq!(
+        found(&warnings),
+        vec![
+            (LintRule::DeprecatedKey, 3),
+            (LintRule::NamingConvention, 3),
+            (LintRule::DeprecatedKey, 5),
+            (LintRule::MaxNesting, 7),
+            (LintRule::MaxNesting, 8),
+        ]
+    );
+    assert_eq!(
+        warnings[2].to_string(),
+        "The key \"server.host\" is deprecated, use \"server.address\" instead [deprecated_keys]"
+    );
+
+    let errors = [
+        ("unused: true", "unknown rule at unused"),
+        (
+            "max_nesting: -1",
+            "expected a non negative integer or null at max_nesting",
+        ),
+        (
+            "unused_variables: 1",
+            "expected bool, found integer at unused_variables",
+        ),
+        (
+            "deprecated_keys: [\"a\", [\"b\"]]",
+            "expected a path or an array with a path and its replacement at deprecated_keys.1",
+        ),
+    ];
+    for (text, error) in errors.iter() {
+        let config = parse(text).unwrap();
+        assert_eq!(
+            LintConfig::from_document(&config).unwrap_err().to_string(),
+            *error
+        );
+    }
+}
@@ -0,0 +1,225 @@
+#![cfg(feature = "lsp")]
+use gura::{
+    lsp::{
+        diagnostic, document_symbols, folding_ranges, goto_definition, hover, Definition,
+        DiagnosticSeverity, FoldingRange, LineIndex, Position, Range,
+    },
+    parse, parse_spanned, parse_spanned_in,
+    parser::ParserOptions,
+    platform::FakePlatform,
+    Kind,
+};
+use std::{fs, path::PathBuf};
+
+const PARENT_FOLDER: &str = "lsp";
+
+fn get_content() -> String {
+    fs::read_to_string(format!("tests/{}/tests-files/normal.ura", PARENT_FOLDER)).unwrap()
+}
+
+fn range(start: (usize, usize), end: (usize, usize)) -> Range {
+    Range {
+        start: Position {
+            line: start.0,
+            character: start.1,
+        },
+        end: Position {
+            line: end.0,
+            character: end.1,
+        },
+    }
+}
+
+#[test]
+/// Tests conversions between byte offsets and positions
+fn test_line_index() {
+    let text = "a: \"ñ🎉\"\nb: 1";
+    let line_index = LineIndex::new(text);
+    let b_offset = text.find('b').unwrap();
+    assert_eq!(
+        line_index.position(b_offset),
+        Position {
+            line: 1,
+            character: 0
+        }
+    );
+    // "ñ" takes 1 UTF-16 code unit and "🎉" takes 2
+    let quote_offset = text.rfind('"').unwrap();
+    assert_eq!(
+        line_index.position(quote_offset),
+        Position {
+            line: 0,
+            character: 7
+        }
+    );
+    assert_eq!(
+        line_index.offset(Position {
+            line: 0,
+            character: 7
+        }),
+        Some(quote_offset)
+    );
+    assert_eq!(
+        line_index.offset(Position {
+            line: 1,
+            character: 4
+        }),
+        Some(text.len())
+    );
+    assert_eq!(
+        line_index.offset(Position {
+            line: 1,
+            character: 5
+        }),
+        None
+    );
+}
+
+#[test]
+/// Tests the tree of symbols, including keys inside arrays
+fn test_document_symbols() {
+    let content = get_content();
+    let document = parse_spanned(&content).unwrap();
+    let symbols = document_symbols(&document, &content);
+
+    let names: Vec<&str> = symbols.iter().map(|symbol| symbol.name.as_str()).collect();
+    assert_eq!(names, vec!["services", "hosts"]);
+
+    let services = &symbols[0];
+    assert_eq!(services.kind, Kind::Object);
+    assert_eq!(services.selection_range, range((3, 0), (3, 8)));
+    assert_eq!(services.range, range((3, 0), (6, 30)));
+
+    let nginx = &services.children[0];
+    assert_eq!(nginx.name, "nginx");
+    assert_eq!(nginx.range, range((4, 4), (6, 30)));
+    let children: Vec<(&str, Kind)> = nginx
+        .children
+        .iter()
+        .map(|symbol| (symbol.name.as_str(), symbol.kind))
+        .collect();
+    assert_eq!(
+        children,
+        vec![("port", Kind::Integer), ("url", Kind::String)]
+    );
+
+    let hosts = &symbols[1];
+    assert_eq!(hosts.kind, Kind::Array);
+    assert_eq!(hosts.range, range((8, 0), (14, 1)));
+    let names: Vec<&str> = hosts
+        .children
+        .iter()
+        .map(|symbol| symbol.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["alpha", "omega"]);
+}
+
+#[test]
+/// Tests folding of multiline objects and arrays
+fn test_folding_ranges() {
+    let content = get_content();
+    let document = parse_spanned(&content).unwrap();
+    let ranges: Vec<(usize, usize)> = folding_ranges(&document, &content)
+        .into_iter()
+        .map(
+            |FoldingRange {
+                 start_line,
+                 end_line,
+             }| (start_line, end_line),
+        )
+        .collect();
+    assert_eq!(ranges, vec![(4, 6), (3, 6), (9, 10), (12, 13), (8, 14)]);
+}
+
+#[test]
+/// Tests hover information of variables in definitions, values and strings
+fn test_hover() {
+    let content = get_content();
+    let document = parse_spanned(&content).unwrap();
+    let at = |line, character| hover(&document, &content, Position { line, character });
+
+    // Usage as value
+    let info = at(5, 15).unwrap();
+    assert_eq!(info.name, "default_port");
+    assert_eq!(info.value, 8080);
+    assert_eq!(info.definition, Some(range((1, 0), (1, 13))));
+    assert_eq!(info.range, range((5, 14), (5, 27)));
+
+    // Usage inside a basic string
+    let info = at(6, 21).unwrap();
+    assert_eq!(info.name, "host");
+    assert_eq!(info.value, "localhost");
+    assert_eq!(info.definition, Some(range((0, 0), (0, 5))));
+
+    // Definition
+    assert_eq!(at(1, 3).unwrap().name, "default_port");
+
+    // Not a variable
+    assert!(at(5, 8).is_none());
+    assert!(at(30, 0).is_none());
+}
+
+#[test]
+/// Tests hover information of environment variables, read from the platform of the parser
+fn test_hover_env() {
+    let content = "user: $GURA_FAKE_USER";
+    let options =
+        ParserOptions::default().platform(FakePlatform::new().env("GURA_FAKE_USER", "Kiara"));
+    let document = parse_spanned_in(content, None, &options).unwrap();
+
+    let info = hover(
+        &document,
+        content,
+        Position {
+            line: 0,
+            character: 8,
+        },
+    )
+    .unwrap();
+    assert_eq!(info.name, "GURA_FAKE_USER");
+    assert_eq!(info.value, "Kiara");
+    assert_eq!(info.definition, None);
+}
+
+#[test]
+/// Tests conversion of errors into diagnostics
+fn test_diagnostic() {
+    let content = "server:\n    port: 80\n    port: 81";
+    let error = parse(content).unwrap_err();
+    let diagnostic = diagnostic(&error, content);
+    assert_eq!(diagnostic.severity, DiagnosticSeverity::Error);
+    assert_eq!(diagnostic.code, "DuplicatedKeyError");
+    assert_eq!(
+        diagnostic.message,
+        "The key \"port\" has been already defined"
+    );
+    assert_eq!(diagnostic.range, range((2, 4), (2, 5)));
+}
+
+#[test]
+/// Tests definitions of variables and imported files
+fn test_goto_definition() {
+    let content = get_content();
+    let document = parse_spanned(&content).unwrap();
+    let at = |line, character| goto_definition(&document, &content, Position { line, character });
+
+    assert_eq!(at(5, 20), Some(Definition::Range(range((1, 0), (1, 13)))));
+    assert_eq!(at(6, 21), Some(Definition::Range(range((0, 0), (0, 5)))));
+    assert_eq!(at(5, 8), None);
+
+    let content = "import \"tests/importing/tests-files/one.ura\"\nfrom_original: true";
+    let document = parse_spanned(content).unwrap();
+    assert_eq!(
+        goto_definition(
+            &document,
+            content,
+            Position {
+                line: 0,
+                character: 10
+            }
+        ),
+        Some(Definition::File(PathBuf::from(
+            "tests/importing/tests-files/one.ura"
+        )))
+    );
+}
@@ -0,0 +1,186 @@
+use gura::{
+    errors::{Error, MergeError},
+    merge::{
+        merge, merge_sources, merge_three_way, merge_three_way_in, ArrayMerge, MergeConflict,
+        MergeOptions,
+    },
+    object, parse, GuraType,
+};
+use std::fs;
+
+#[test]
+/// Tests merging of nested objects, arrays and replaced values
+fn test_merge() {
+    let base = object! {
+        name: "app",
+        server: {
+            host: "localhost",
+            port: 80,
+            tls: { enabled: false }
+        },
+        hosts: ["alpha"]
+    };
+    let overlay = object! {
+        server: {
+            port: 8080,
+            tls: "disabled"
+        },
+        hosts: ["omega"],
+        debug: true
+    };
+
+    let merged = merge(&base, &overlay, &MergeOptions::default());
+    let expected = object! {
+        name: "app",
+        server: {
+            host: "localhost",
+            port: 8080,
+            tls: "disabled"
+        },
+        hosts: ["omega"],
+        debug: true
+    };
+    assert_eq!(merged, expected);
+
+    let options = MergeOptions::default().arrays(ArrayMerge::Append);
+    let merged = merge(&base, &overlay, &options);
+    assert_eq!(
+        merged["hosts"],
+        parse("hosts: [\"alpha\", \"omega\"]").unwrap()["hosts"]
+    );
+}
+
+#[test]
+/// Tests merging sources keeping the comments of top-level keys
+fn test_merge_sources() {
+    let base = "# Application name\nname: \"app\"\n\n# Server settings\nserver:\n    # Nested comments are lost\n    port: 80";
+    let production = "$port: 8080\nserver:\n    port: $port\n# Enables debugging\ndebug: false";
+    let local = "# Local overrides\ndebug: true";
+
+    let merged = merge_sources(&[base, production, local], &MergeOptions::default()).unwrap();
+    assert_eq!(
+        merged,
+        "# Application name\nname: \"app\"\n# Server settings\nserver:\n    port: 8080\n# Local overrides\ndebug: true"
+    );
+    assert_eq!(
+        parse(&merged).unwrap(),
+        object! { name: "app", server: { port: 8080 }, debug: true }
+    );
+
+    let options = MergeOptions::default().preserve_comments(false);
+    let merged = merge_sources(&[base, production], &options).unwrap();
+    assert_eq!(
+        merged,
+        "name: \"app\"\nserver:\n    port: 8080\ndebug: false"
+    );
+
+    assert!(merge_sources(&[base, "server: $undefined"], &options).is_err());
+    assert_eq!(merge_sources(&[], &options).unwrap(), "");
+}
+
+#[test]
+/// Tests three-way merges of changes of different keys, keeping the text of our version
+fn test_merge_three_way() {
+    let base =
+        "# Server\nserver:\n    host: \"localhost\"\n    port: 80\ndebug: false\nhosts: [\"a\"]\n";
+    let ours = "# Server\nserver:\n    host: \"0.0.0.0\" # All interfaces\n    port: 80\ndebug: false\nhosts: [\"a\"]\n";
+    let theirs =
+        "server:\n    host: \"localhost\"\n    port: 8080\n    tls: true\nhosts: [\"a\", \"b\"]\n";
+
+    let merged = merge_three_way(base, ours, theirs, &MergeOptions::default()).unwrap();
+    assert!(merged.is_clean());
+    assert_eq!(
+        merged.text,
+        "# Server\nserver:\n    host: \"0.0.0.0\" # All interfaces\n    port: 8080\n    tls: true\nhosts: [\"a\", \"b\"]\n"
+    );
+
+    // Same changes in both versions are not conflicts
+    let merged = merge_three_way(base, theirs, theirs, &MergeOptions::default()).unwrap();
+    assert!(merged.is_clean());
+    assert_eq!(parse(&merged.text).unwrap(), parse(theirs).unwrap());
+}
+
+#[test]
+/// Tests conflicts of three-way merges and their comments
+fn test_merge_three_way_conflicts() {
+    let base = "server:\n    port: 80\nname: \"app\"\n";
+    let ours = "server:\n    port: 8081\n";
+    let theirs = "server:\n    port: 8082\nname: \"web\"\n";
+
+    let merged = merge_three_way(base, ours, theirs, &MergeOptions::default()).unwrap();
+    assert_eq!(
+        merged.conflicts,
+        vec![
+            MergeConflict {
+                path: "server.port".parse().unwrap(),
+                base: Some(GuraType::Integer(80)),
+                ours: Some(GuraType::Integer(8081)),
+                theirs: Some(GuraType::Integer(8082)),
+            },
+            MergeConflict {
+                path: "name".parse().unwrap(),
+                base: Some(GuraType::String(String::from("app"))),
+                ours: None,
+                theirs: Some(GuraType::String(String::from("web"))),
+            },
+        ]
+    );
+    assert_eq!(merged.text, ours);
+
+    let options = MergeOptions::default().conflict_comments(true);
+    let merged = merge_three_way(base, ours, theirs, &options).unwrap();
+    assert_eq!(
+        merged.text,
+        concat!(
+            "# CONFLICT name: ours missing, theirs \"web\", base \"app\"\n",
+            "server:\n",
+            "    # CONFLICT server.port: ours 8081, theirs 8082, base 80\n",
+            "    port: 8081\n"
+        )
+    );
+
+    // Keys added to objects removed by our version can not be written
+    let theirs = "server:\n    port: 80\n    tls: true\nname: \"app\"\n";
+    let merged = merge_three_way(base, "name: \"app\"\n", theirs, &options).unwrap();
+    assert_eq!(merged.conflicts[0].path, "server.tls");
+    assert_eq!(
+        merged.text,
+        "# CONFLICT server.tls: ours missing, theirs true, base missing\nname: \"app\"\n"
+    );
+
+    assert!(matches!(
+        merge_three_way(base, "server: [", theirs, &options),
+        Err(MergeError::Ours(_))
+    ));
+    assert!(matches!(
+        merge_three_way(base, theirs, "server: [", &options),
+        Err(MergeError::Theirs(_))
+    ));
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "wasi",
+    ignore = "temporary directories are not available in WASI"
+)]
+/// Tests three-way merges of versions that import files of a directory
+fn test_merge_three_way_in() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("server.ura"), "port: 80\n").unwrap();
+    let base = "import \"server.ura\"\nname: \"app\"\n";
+    let theirs = "import \"server.ura\"\nname: \"web\"\n";
+
+    let merged = merge_three_way_in(
+        base,
+        base,
+        theirs,
+        Some(dir.path()),
+        &MergeOptions::default(),
+    )
+    .unwrap();
+    assert!(merged.is_clean());
+    assert_eq!(merged.text, theirs);
+
+    let error = merge_three_way(base, base, theirs, &MergeOptions::default()).unwrap_err();
+    assert!(matches!(error, MergeError::Base(ref error) if error.kind == Error::FileNotFoundError));
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "mmap")]
+use gura::{errors::ReadError, object, parse_mmap, GuraType};
+use std::fs;
+
+#[test]
+/// Tests parsing of mapped files
+fn test_parse() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.ura");
+    fs::write(&path, "title: \"Gura\"\nitems: [1, 2]\n").unwrap();
+    assert_eq!(
+        parse_mmap(&path).unwrap(),
+        object! {
+            title: "Gura",
+            items: [1, 2]
+        }
+    );
+
+    let empty = dir.path().join("empty.ura");
+    fs::write(&empty, "").unwrap();
+    assert_eq!(parse_mmap(&empty).unwrap(), object! {});
+}
+
+#[test]
+/// Tests errors of missing, non UTF-8 and invalid files
+fn test_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(matches!(
+        parse_mmap(dir.path().join("missing.ura")),
+        Err(ReadError::Io(_))
+    ));
+
+    let path = dir.path().join("binary.ura");
+    fs::write(&path, b"title: \"\xff\"").unwrap();
+    assert!(matches!(parse_mmap(&path), Err(ReadError::InvalidUtf8(8))));
+
+    let path = dir.path().join("invalid.ura");
+    fs::write(&path, "title: \"Gura\"\ntitle: \"Other\"").unwrap();
+    match parse_mmap(&path) {
+        Err(ReadError::Parse(error)) => assert_eq!(error.line, 2),
+        _ => panic!("Expected a parsing error"),
+    }
+}
+
+#[test]
+/// Tests that imports are relative to the directory of the mapped file
+fn test_relative_imports() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("conf")).unwrap();
+    fs::write(dir.path().join("conf").join("server.ura"), "port: 8080\n").unwrap();
+    let path = dir.path().join("conf").join("app.ura");
+    fs::write(&path, "import \"server.ura\"\nname: \"app\"\n").unwrap();
+    assert_eq!(
+        parse_mmap(&path).unwrap(),
+        object! {
+            port: 8080,
+            name: "app"
+        }
+    );
+}
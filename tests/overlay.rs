@@ -0,0 +1,82 @@
+use gura::{object, overlay::Overlay, parse, GuraType};
+use std::sync::Arc;
+
+fn get_base() -> Arc<GuraType> {
+    Arc::new(
+        parse("name: \"app\"\nserver:\n    host: \"localhost\"\n    port: 80\n    tls:\n        enabled: false\nhosts: [\"alpha\"]")
+            .unwrap(),
+    )
+}
+
+#[test]
+/// Tests reading through modifications without touching the base
+fn test_overlay() {
+    let base = get_base();
+    let mut overlay = Overlay::new(Arc::clone(&base));
+    assert!(overlay.is_empty());
+    assert_eq!(*overlay.get("server.tls.enabled").unwrap(), false);
+    assert_eq!(overlay.materialize(), *base);
+
+    overlay.set("server.tls.enabled", GuraType::Bool(true));
+    overlay.set("server.timeout", GuraType::Integer(30));
+    overlay.set("name.first", GuraType::String(String::from("web")));
+    overlay.set("cache.size", GuraType::Integer(64));
+    overlay.set("cache.policy.kind", GuraType::String(String::from("lru")));
+    overlay.remove("hosts");
+    overlay.remove("missing.key");
+
+    assert!(!overlay.is_empty());
+    assert_eq!(*overlay.get("server.tls.enabled").unwrap(), true);
+    assert_eq!(*overlay.get("server.host").unwrap(), "localhost");
+    assert_eq!(*overlay.get("cache.policy.kind").unwrap(), "lru");
+    assert_eq!(overlay.get("hosts"), None);
+    assert_eq!(overlay.get("hosts.0"), None);
+    assert_eq!(overlay.get("missing"), None);
+
+    let expected = object! {
+        name: {
+            first: "web"
+        },
+        server: {
+            host: "localhost",
+            port: 80,
+            tls: {
+                enabled: true
+            },
+            timeout: 30
+        },
+        cache: {
+            size: 64,
+            policy: {
+                kind: "lru"
+            }
+        }
+    };
+    assert_eq!(overlay.materialize(), expected);
+    assert_eq!(*base, *get_base());
+}
+
+#[test]
+/// Tests removing keys set or modified by the overlay
+fn test_overlay_remove() {
+    let mut overlay = Overlay::new(get_base());
+    overlay.set("server.timeout", GuraType::Integer(30));
+    overlay.remove("server.timeout");
+    overlay.remove("server.tls.enabled");
+    overlay.set("extra.nested.value", GuraType::Null);
+    overlay.remove("extra.nested.value");
+    assert_eq!(*overlay.get("server.tls").unwrap(), object! {});
+    assert_eq!(overlay.get("server.timeout"), None);
+    assert_eq!(overlay.get("server").unwrap()["port"], 80);
+
+    // Removed keys can be set again
+    overlay.remove("name");
+    assert_eq!(overlay.get("name"), None);
+    overlay.set("name.first", GuraType::String(String::from("web")));
+    assert_eq!(*overlay.get("name.first").unwrap(), "web");
+
+    let materialized = overlay.materialize();
+    assert_eq!(materialized["server"]["tls"], object! {});
+    assert_eq!(materialized["extra"], object! { nested: {} });
+    assert_eq!(materialized["name"], object! { first: "web" });
+}
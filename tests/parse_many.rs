@@ -0,0 +1,93 @@
+use gura::{
+    errors::Error, object, parse_many, parse_many_with_scope, parser::VariableScope, GuraType,
+};
+use std::fs;
+
+const PARENT_FOLDER: &str = "parse_many";
+
+/// Reads a file located in tests/parse_many/tests-files/{file_path}
+fn get_file_content(file_path: &str) -> String {
+    fs::read_to_string(format!("tests/{}/tests-files/{}", PARENT_FOLDER, file_path)).unwrap()
+}
+
+#[test]
+/// Tests parsing several sources sharing variables
+fn test_normal() {
+    let base = get_file_content("base.ura");
+    let server = get_file_content("server.ura");
+    let parsed_data = parse_many(&[&base, &server]).unwrap();
+    assert_eq!(
+        parsed_data,
+        object! {
+            name: "app",
+            server: {
+                host: "localhost",
+                port: 80
+            }
+        }
+    );
+}
+
+#[test]
+/// Tests parsing empty lists of sources and sources without keys
+fn test_empty() {
+    assert_eq!(parse_many(&[]).unwrap(), object! {});
+    assert_eq!(parse_many(&["$var: 1", "# Comment"]).unwrap(), object! {});
+}
+
+#[test]
+/// Tests errors when a key is defined in more than one source
+fn test_duplicated_key() {
+    let server = get_file_content("server.ura");
+    let duplicated = get_file_content("duplicated_key.ura");
+    let err = parse_many(&["$host: \"localhost\"", &server, &duplicated]).unwrap_err();
+    assert_eq!(err.kind, Error::DuplicatedKeyError);
+    assert_eq!(err.line, 2);
+    assert_eq!(err.pos, 12);
+}
+
+#[test]
+/// Tests errors when a variable is defined in more than one source
+fn test_duplicated_variable() {
+    let base = get_file_content("base.ura");
+    let duplicated = get_file_content("duplicated_variable.ura");
+    let err = parse_many(&[&base, &duplicated]).unwrap_err();
+    assert_eq!(err.kind, Error::DuplicatedVariableError);
+}
+
+#[test]
+/// Tests that nested keys do not collide with root keys of other sources
+fn test_nested_keys() {
+    let parsed_data = parse_many(&["name: \"app\"", "db:\n    name: \"users\""]).unwrap();
+    assert_eq!(parsed_data["db"]["name"], "users");
+}
+
+#[test]
+/// Tests sources with their own variables
+fn test_isolated() {
+    let base = get_file_content("base.ura");
+    let duplicated = get_file_content("duplicated_variable.ura");
+    let parsed_data =
+        parse_many_with_scope(&[&base, &duplicated], &VariableScope::Isolated).unwrap();
+    assert_eq!(parsed_data, object! {name: "app", other: "127.0.0.1"});
+
+    // Variables of other sources are not visible
+    let server = get_file_content("server.ura");
+    let err = parse_many_with_scope(&[&base, &server], &VariableScope::Isolated).unwrap_err();
+    assert_eq!(err.kind, Error::VariableNotDefinedError);
+}
+
+#[test]
+/// Tests exported variables
+fn test_export() {
+    let base = get_file_content("base.ura");
+    let server = get_file_content("server.ura");
+    let scope = VariableScope::Export(vec![String::from("host")]);
+    let parsed_data = parse_many_with_scope(&[&base, &server], &scope).unwrap();
+    assert_eq!(parsed_data["server"]["host"], "localhost");
+
+    // Exported variables can not be defined again
+    let duplicated = get_file_content("duplicated_variable.ura");
+    let err = parse_many_with_scope(&[&base, &duplicated], &scope).unwrap_err();
+    assert_eq!(err.kind, Error::DuplicatedVariableError);
+}
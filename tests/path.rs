@@ -0,0 +1,103 @@
+use gura::{
+    errors::PathError,
+    object, parse,
+    path::{completions, Path, Segment},
+    GuraType,
+};
+
+#[test]
+/// Tests parsing and displaying paths
+fn test_parse() {
+    let path: Path = "matrix[0][1].name".parse().unwrap();
+    assert_eq!(
+        path.segments(),
+        &[
+            Segment::Key(String::from("matrix")),
+            Segment::Index(0),
+            Segment::Index(1),
+            Segment::Key(String::from("name")),
+        ]
+    );
+    assert_eq!(path.to_string(), "matrix[0][1].name");
+    assert_eq!(path.parent().unwrap(), "matrix[0][1]");
+
+    let root: Path = "".parse().unwrap();
+    assert!(root.is_root());
+    assert_eq!(root, Path::root());
+    assert!(root.parent().is_none());
+    assert_eq!(root.to_string(), "");
+
+    let element: Path = "[2].port".parse().unwrap();
+    assert_eq!(element.segments()[0], Segment::Index(2));
+    assert_eq!(element, Path::root().join_index(2).join_key("port"));
+}
+
+#[test]
+/// Tests errors of invalid paths
+fn test_parse_errors() {
+    let error = |text: &str| text.parse::<Path>().unwrap_err();
+    assert_eq!(
+        error("a..b"),
+        PathError {
+            pos: 2,
+            msg: String::from("Expected a key")
+        }
+    );
+    assert_eq!(error("a.").pos, 2);
+    assert_eq!(
+        error("a[1"),
+        PathError {
+            pos: 1,
+            msg: String::from("Unclosed \"[\"")
+        }
+    );
+    assert_eq!(error("a[x]").to_string(), "Expected a digit at position 2");
+    assert_eq!(error("a[]").msg, "Invalid index");
+    assert_eq!(error("a]").msg, "Unexpected \"]\"");
+    assert_eq!(error("a[0]b").msg, "Expected \".\" or \"[\"");
+}
+
+#[test]
+/// Tests looking values up by path
+fn test_get_at() {
+    let parsed = parse("services:\n    hosts: [\"alpha\", [\"beta\", \"gamma\"]]").unwrap();
+    let path: Path = "services.hosts[1][0]".parse().unwrap();
+    assert_eq!(*parsed.get_at(&path).unwrap(), "beta");
+    assert_eq!(*parsed.get_path("services.hosts[1][1]").unwrap(), "gamma");
+    assert_eq!(*parsed.get_path("services.hosts.0").unwrap(), "alpha");
+    assert!(parsed.get_path("services.hosts[2]").is_none());
+    assert!(parsed.get_path("services[0]").is_none());
+    assert!(parsed.get_path("services.hosts[").is_none());
+    assert_eq!(parsed.get_at(&Path::root()), Some(&parsed));
+
+    let shared = parsed.shared();
+    assert_eq!(shared.get_path("services.hosts[1][0]").unwrap(), "beta");
+
+    let object = object! {"with space": {key: true}};
+    assert_eq!(*object.get_path("with space.key").unwrap(), true);
+}
+
+#[test]
+/// Tests completing partial paths
+fn test_completions() {
+    let parsed =
+        parse("services:\n    nginx:\n        port: 80\n    node: [1, 2]\nname: \"web\"").unwrap();
+    let complete = |partial: &str| -> Vec<String> {
+        completions(&parsed, partial)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    };
+    assert_eq!(complete(""), ["services", "name"]);
+    assert_eq!(complete("services.n"), ["services.nginx", "services.node"]);
+    assert_eq!(complete("services.ng"), ["services.nginx"]);
+    assert_eq!(complete("services.nginx."), ["services.nginx.port"]);
+    assert_eq!(
+        complete("services.node["),
+        ["services.node[0]", "services.node[1]"]
+    );
+    assert_eq!(complete("services.node[1]"), ["services.node[1]"]);
+    assert!(complete("services.apache.").is_empty());
+    assert!(complete("name.").is_empty());
+    assert!(complete("services..").is_empty());
+}
@@ -0,0 +1,110 @@
+use gura::{
+    errors::{Error, ReadError},
+    imports::resolve_imports_with_options,
+    object, parse_file, parse_spanned_in, parse_with_options,
+    parser::ParserOptions,
+    platform::FakePlatform,
+    GuraType,
+};
+use std::path::Path;
+
+#[test]
+/// Tests imports read from a fake filesystem, relative to the importing files
+fn test_imports() {
+    let platform = FakePlatform::new()
+        .file("config/base.ura", "import \"server.ura\"\nname: \"app\"")
+        .file("config/server.ura", "port: 8080");
+    let options = ParserOptions::default().platform(platform);
+    let parsed = parse_with_options("import \"config/base.ura\"\ndebug: true", &options).unwrap();
+    assert_eq!(parsed, object! {port: 8080, name: "app", debug: true});
+
+    let error = parse_with_options("import \"missing.ura\"", &options).unwrap_err();
+    assert_eq!(error.kind, Error::FileNotFoundError);
+
+    let error = parse_with_options(
+        "import \"config/server.ura\"\nimport \"config/server.ura\"",
+        &options,
+    )
+    .unwrap_err();
+    assert_eq!(error.kind, Error::DuplicatedImportError);
+}
+
+#[test]
+/// Tests environment variables read from a fake environment instead of the process one
+fn test_env_vars() {
+    let options =
+        ParserOptions::default().platform(FakePlatform::new().env("GURA_FAKE_USER", "Kiara"));
+    let parsed = parse_with_options(
+        "user: $GURA_FAKE_USER\ngreeting: \"Hi $GURA_FAKE_USER\"",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(parsed, object! {user: "Kiara", greeting: "Hi Kiara"});
+
+    // Variables of the process are not visible
+    let options = ParserOptions::default().platform(FakePlatform::new());
+    let error = parse_with_options("path: $PATH", &options).unwrap_err();
+    assert_eq!(error.kind, Error::VariableNotDefinedError);
+}
+
+#[test]
+/// Tests time variables with a fixed time
+fn test_time() {
+    let options = ParserOptions::default()
+        .time_variables(true)
+        .platform(FakePlatform::new().time(951827100));
+    let parsed = parse_with_options(
+        "iso: $__now_iso__\nunix: $__now_unix__\ntoday: $__today__",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(
+        parsed,
+        object! {iso: "2000-02-29T12:25:00Z", unix: 951827100, today: "2000-02-29"}
+    );
+}
+
+#[test]
+/// Tests that the platform is kept in strict mode and that default options are equal
+fn test_options() {
+    assert_eq!(ParserOptions::default(), ParserOptions::default());
+    assert_ne!(
+        ParserOptions::default(),
+        ParserOptions::default().platform(FakePlatform::new())
+    );
+
+    let options = ParserOptions::default()
+        .conformance_strict()
+        .platform(FakePlatform::new().env("GURA_FAKE_HOST", "localhost"));
+    let parsed = parse_with_options("host: $GURA_FAKE_HOST", &options).unwrap();
+    assert_eq!(parsed["host"], "localhost");
+    assert!(options.compliance().extensions.is_empty());
+}
+
+#[test]
+/// Tests that files given to the entry points that read them, and their imports, come from the
+/// platform
+fn test_files() {
+    let platform = FakePlatform::new()
+        .file(
+            "config/app.ura",
+            "import \"server.ura\"\nuser: $GURA_FAKE_USER",
+        )
+        .file("config/server.ura", "port: 8080")
+        .env("GURA_FAKE_USER", "Kiara");
+    let options = ParserOptions::default().platform(platform);
+
+    let parsed = parse_file("config/app.ura", &options).unwrap();
+    assert_eq!(parsed, object! {port: 8080, user: "Kiara"});
+    match parse_file("config/missing.ura", &options) {
+        Err(ReadError::Io(_)) => (),
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    let text = "import \"server.ura\"\nuser: $GURA_FAKE_USER";
+    let document = parse_spanned_in(text, Some(Path::new("config")), &options).unwrap();
+    assert_eq!(document.value, object! {port: 8080, user: "Kiara"});
+
+    let resolved = resolve_imports_with_options(text, "config", &options).unwrap();
+    assert_eq!(resolved.text, "port: 8080\nuser: $GURA_FAKE_USER");
+}
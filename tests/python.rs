@@ -0,0 +1,88 @@
+#![cfg(feature = "python")]
+use gura::{
+    object,
+    python::{from_python, register, to_python},
+    GuraType,
+};
+use indexmap::IndexMap;
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+
+/// Runs a test with the `gura` module available in the globals
+fn with_module<F: FnOnce(Python<'_>, &Bound<'_, PyDict>)>(test: F) {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let module = PyModule::new(py, "gura").unwrap();
+        register(&module).unwrap();
+        let globals = PyDict::new(py);
+        globals.set_item("gura", module).unwrap();
+        test(py, &globals);
+    });
+}
+
+#[test]
+/// Tests conversions of every kind of value in both directions
+fn test_conversions() {
+    with_module(|py, _| {
+        let mut nested = IndexMap::new();
+        nested.insert(
+            String::from("big"),
+            GuraType::BigInteger(99999999999999999999),
+        );
+        let mut value = object! {
+            title: "Gura",
+            numbers: [1, 2.5, true, null]
+        };
+        if let GuraType::Object(values) = &mut value {
+            values.insert(String::from("nested"), GuraType::Object(nested));
+        }
+        let converted = to_python(py, &value).unwrap();
+        assert_eq!(
+            converted.repr().unwrap().to_string(),
+            "{'title': 'Gura', 'numbers': [1, 2.5, True, None], 'nested': {'big': 99999999999999999999}}"
+        );
+        assert_eq!(from_python(&converted).unwrap(), value);
+    });
+}
+
+#[test]
+/// Tests the functions and exceptions of the module
+fn test_module() {
+    with_module(|py, globals| {
+        py.run(
+            c_str!(
+                r#"
+parsed = gura.parse('$port: 8080\nserver:\n    port: $port\n    hosts: ["a", "b"]')
+assert parsed == {'server': {'port': 8080, 'hosts': ['a', 'b']}}
+assert gura.dump({'tags': ('a', 1)}) == 'tags: ["a", 1]'
+
+try:
+    gura.parse('a: 1\na: 2')
+    assert False
+except gura.DuplicatedKeyError as error:
+    assert isinstance(error, gura.GuraError) and isinstance(error, ValueError)
+
+try:
+    gura.dump({1: 'a'})
+    assert False
+except TypeError as error:
+    assert str(error) == 'Keys of dicts must be strings'
+"#
+            ),
+            Some(globals),
+            None,
+        )
+        .unwrap();
+    });
+
+    with_module(|py, _| {
+        let set = py.eval(c_str!("{1, 2}"), None, None).unwrap();
+        assert_eq!(
+            from_python(&set).unwrap_err().to_string(),
+            "TypeError: Values of type set can not be dumped into Gura"
+        );
+        assert_eq!(
+            from_python(&py.None().into_bound(py)).unwrap(),
+            GuraType::Null
+        );
+    });
+}
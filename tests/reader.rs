@@ -0,0 +1,52 @@
+use gura::{errors::ReadError, object, parse_reader, GuraType};
+use std::io::{self, BufReader, Read};
+
+/// Stream that fails after returning some bytes
+struct FailingStream(bool);
+
+impl Read for FailingStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.0 {
+            return Err(io::Error::new(io::ErrorKind::ConnectionReset, "reset"));
+        }
+        self.0 = true;
+        buf[..6].copy_from_slice(b"title:");
+        Ok(6)
+    }
+}
+
+#[test]
+/// Tests parsing of streams, even through small buffers
+fn test_parse() {
+    let text = "title: \"Gura\"\nitems: [1, 2]\n";
+    let expected = object! {
+        title: "Gura",
+        items: [1, 2]
+    };
+    assert_eq!(parse_reader(text.as_bytes()).unwrap(), expected);
+    assert_eq!(
+        parse_reader(BufReader::with_capacity(4, text.as_bytes())).unwrap(),
+        expected
+    );
+    assert_eq!(parse_reader(io::empty()).unwrap(), object! {});
+}
+
+#[test]
+/// Tests errors of failing, non UTF-8 and invalid streams
+fn test_errors() {
+    match parse_reader(BufReader::new(FailingStream(false))) {
+        Err(ReadError::Io(error)) => assert_eq!(error.kind(), io::ErrorKind::ConnectionReset),
+        _ => panic!("Expected an IO error"),
+    }
+
+    let stream: &[u8] = b"title: \"\xff\"";
+    assert!(matches!(
+        parse_reader(stream),
+        Err(ReadError::InvalidUtf8(8))
+    ));
+
+    match parse_reader("title: \"Gura\"\ntitle: \"Other\"".as_bytes()) {
+        Err(ReadError::Parse(error)) => assert_eq!(error.line, 2),
+        _ => panic!("Expected a parsing error"),
+    }
+}
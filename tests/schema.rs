@@ -0,0 +1,286 @@
+use gura::{
+    errors::{ErrorSet, TypeError, ValidationError, ValidationErrorKind},
+    parse, parse_spanned,
+    schema::Schema,
+    GuraType, Kind,
+};
+
+fn get_schema() -> Schema {
+    Schema::object()
+        .field(
+            "title",
+            Schema::new(Kind::String)
+                .required()
+                .description("Title of the app"),
+        )
+        .field(
+            "services",
+            Schema::object().field(
+                "nginx",
+                Schema::object()
+                    .field("host", Schema::new(Kind::String).required())
+                    .field(
+                        "port",
+                        Schema::new(Kind::Integer).default(GuraType::Integer(80)),
+                    ),
+            ),
+        )
+        .field(
+            "hosts",
+            Schema::array(Schema::new(Kind::String)).description("Allowed | hosts"),
+        )
+}
+
+#[test]
+/// Tests valid documents
+fn test_valid() {
+    let parsed = parse(
+        "title: \"Gura\"\nservices:\n    nginx:\n        host: \"localhost\"\n        port: 99999999999999999999999\nhosts: [\"a\"]",
+    )
+    .unwrap();
+    assert!(get_schema().validate(&parsed).is_ok());
+}
+
+#[test]
+/// Tests that every error is reported with its path
+fn test_invalid() {
+    let parsed = parse("services:\n    nginx:\n        port: \"80\"\nhosts: [\"a\", 5]").unwrap();
+    let errors = get_schema().validate(&parsed).unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            ValidationError {
+                path: "title".parse().unwrap(),
+                kind: ValidationErrorKind::MissingKey,
+                span: None,
+            },
+            ValidationError {
+                path: "services.nginx.host".parse().unwrap(),
+                kind: ValidationErrorKind::MissingKey,
+                span: None,
+            },
+            ValidationError {
+                path: "services.nginx.port".parse().unwrap(),
+                kind: ValidationErrorKind::WrongType(TypeError {
+                    expected: Kind::Integer,
+                    found: Kind::String,
+                    span: None,
+                }),
+                span: None,
+            },
+            ValidationError {
+                path: "hosts[1]".parse().unwrap(),
+                kind: ValidationErrorKind::WrongType(TypeError {
+                    expected: Kind::String,
+                    found: Kind::Integer,
+                    span: None,
+                }),
+                span: None,
+            },
+        ]
+    );
+}
+
+#[test]
+/// Tests Markdown documentation
+fn test_markdown() {
+    let expected = r##"| Key | Type | Required | Default | Description |
+| --- | --- | --- | --- | --- |
+| `title` | string | yes |  | Title of the app |
+| `services` | object | no |  |  |
+| `services.nginx` | object | no |  |  |
+| `services.nginx.host` | string | yes |  |  |
+| `services.nginx.port` | integer | no | `80` |  |
+| `hosts` | array of string | no |  | Allowed \| hosts |
+"##;
+    assert_eq!(get_schema().to_markdown(), expected);
+}
+
+#[test]
+/// Tests HTML documentation
+fn test_html() {
+    let html = get_schema().to_html();
+    assert!(html.starts_with("<table>\n"));
+    assert!(html.contains(
+        "<tr><td><code>services.nginx.port</code></td><td>integer</td><td>no</td><td><code>80</code></td><td></td></tr>"
+    ));
+    assert!(html.ends_with("</tbody>\n</table>\n"));
+}
+
+#[test]
+/// Tests schemas inferred from documents
+fn test_from_document() {
+    let parsed = parse("name: \"app\"\nserver:\n    port: 80\ntags: [\"a\", \"b\"]").unwrap();
+    let schema = Schema::from_document(&parsed);
+    assert!(schema.validate(&parsed).is_ok());
+    assert_eq!(
+        schema.fields()["server"].fields()["port"].get_default(),
+        Some(&GuraType::Integer(80))
+    );
+    assert_eq!(
+        schema.fields()["tags"].items().unwrap().get_kind(),
+        Some(Kind::String)
+    );
+    assert!(schema
+        .to_markdown()
+        .contains("| `tags` | array of string | no | `[\"a\", \"b\"]` |  |"));
+}
+
+#[test]
+/// Tests that the comments of annotated documents are the descriptions of the inferred fields
+fn test_from_spanned_document() {
+    let document = parse_spanned(
+        "# Name of the app\nname: \"app\"\n\n# Server settings\n#\n# Applied on restart\nserver:\n    # Port to listen to\n    port: 80 # Not a description\n    host: \"localhost\"",
+    )
+    .unwrap();
+    let schema = Schema::from_spanned_document(&document);
+    assert!(schema.validate(&document.value).is_ok());
+    assert_eq!(
+        schema.fields()["name"].get_description(),
+        Some("Name of the app")
+    );
+    let server = &schema.fields()["server"];
+    assert_eq!(
+        server.get_description(),
+        Some("Server settings Applied on restart")
+    );
+    assert_eq!(
+        server.fields()["port"].get_description(),
+        Some("Port to listen to")
+    );
+    assert_eq!(server.fields()["host"].get_description(), None);
+    assert!(schema
+        .to_markdown()
+        .contains("| `server.port` | integer | no | `80` | Port to listen to |"));
+}
+
+#[test]
+/// Tests examples generated from schemas
+fn test_generate_example() {
+    let example = get_schema().generate_example();
+    assert_eq!(
+        example,
+        parse(
+            "title: \"\"\nservices:\n    nginx:\n        host: \"\"\n        port: 80\nhosts: []"
+        )
+        .unwrap()
+    );
+    assert!(get_schema().validate(&example).is_ok());
+}
+
+#[test]
+/// Tests example texts with descriptions as comments
+fn test_generate_example_text() {
+    let schema = get_schema().field(
+        "admin",
+        Schema::object()
+            .description("Default admin\nChange it!")
+            .default(parse("user: \"root\"").unwrap()),
+    );
+    let expected = r##"# Title of the app
+title: ""
+services:
+    nginx:
+        host: ""
+        port: 80
+# Allowed | hosts
+hosts: []
+# Default admin
+# Change it!
+admin:
+    user: "root"
+"##;
+    let text = schema.generate_example_text();
+    assert_eq!(text, expected);
+    assert_eq!(parse(&text).unwrap(), schema.generate_example());
+}
+
+#[test]
+/// Tests prompting for missing required keys
+fn test_fill_missing() {
+    let mut parsed = parse("hosts: [\"a\"]").unwrap();
+    let mut prompted = Vec::new();
+    get_schema().fill_missing(&mut parsed, |path, kind| {
+        prompted.push((path.to_string(), kind));
+        if path == "title" {
+            Some(GuraType::String(String::from("Prompted")))
+        } else {
+            None
+        }
+    });
+
+    // As "services" is not required, "services.nginx.host" is not prompted
+    assert_eq!(prompted, vec![(String::from("title"), Some(Kind::String))]);
+    assert_eq!(parsed["title"], "Prompted");
+    assert!(get_schema().validate(&parsed).is_ok());
+}
+
+#[test]
+/// Tests detection of unknown keys
+fn test_deny_unknown_keys() {
+    let schema = Schema::object()
+        .field(
+            "server",
+            Schema::object()
+                .field("port", Schema::new(Kind::Integer))
+                .deny_unknown_keys(),
+        )
+        .field("debug", Schema::new(Kind::Bool));
+    let content = "server:\n    prot: 8080\n    port: 80\nverbose: true";
+
+    // Root object allows unknown keys
+    let errors = schema.validate(&parse(content).unwrap()).unwrap_err();
+    assert_eq!(
+        errors,
+        vec![ValidationError {
+            path: "server.prot".parse().unwrap(),
+            kind: ValidationErrorKind::UnknownKey,
+            span: None,
+        }]
+    );
+
+    let errors = schema
+        .validate_spanned(&parse_spanned(content).unwrap())
+        .unwrap_err();
+    let span = errors[0].span.unwrap();
+    assert_eq!((span.start, span.end, span.line), (12, 16, 2));
+    assert_eq!(errors[0].to_string(), "unknown key at server.prot (line 2)");
+}
+
+#[test]
+/// Tests that nullable schemas accept null values
+fn test_nullable() {
+    let schema = Schema::object().field("owner", Schema::new(Kind::String).nullable());
+    assert!(schema.validate(&parse("owner: null").unwrap()).is_ok());
+    assert!(schema.validate(&parse("owner: \"me\"").unwrap()).is_ok());
+    assert!(schema.validate(&parse("owner: 5").unwrap()).is_err());
+    assert!(Schema::new(Kind::String).validate(&GuraType::Null).is_err());
+}
+
+#[test]
+/// Tests collecting errors of different sources in a set
+fn test_error_set() {
+    let mut errors = ErrorSet::from(
+        get_schema()
+            .validate_spanned(&parse_spanned("hosts: [\"a\", 1]").unwrap())
+            .unwrap_err(),
+    );
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[1].path().unwrap(), "hosts[1]");
+    assert_eq!(errors[1].line(), None);
+
+    errors.push(parse("title: $undefined").unwrap_err());
+    assert_eq!(errors[2].path(), None);
+    assert_eq!(errors[2].line(), Some(1));
+    assert_eq!(
+        errors.to_string(),
+        "3 errors found:\n\
+        - missing required key at title\n\
+        - expected string, found integer at hosts[1]\n\
+        - Variable \"undefined\" is not defined in Gura nor as environment variable at line 1 (text position = 7)"
+    );
+
+    let single: ErrorSet = errors.into_iter().take(1).collect();
+    assert_eq!(single.to_string(), "missing required key at title");
+    assert!(ErrorSet::new().is_empty());
+}
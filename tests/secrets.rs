@@ -0,0 +1,151 @@
+use gura::{
+    errors::SecretError,
+    object, parse,
+    secrets::{encrypt_secrets, resolve_secrets, SecretOptions},
+    GuraType,
+};
+
+/// Fake cipher that uppercases the plaintext
+fn encrypt(text: &str) -> Result<String, String> {
+    Ok(text.to_uppercase())
+}
+
+/// Inverse of `encrypt`, which fails with texts that it can not produce
+fn decrypt(text: &str) -> Result<String, String> {
+    if text.chars().any(char::is_lowercase) {
+        Err(String::from("invalid ciphertext"))
+    } else {
+        Ok(text.to_lowercase())
+    }
+}
+
+fn get_object() -> GuraType {
+    object! {
+        db: { user: "admin", password: "secret", port: 5432 },
+        tokens: ["abc", "def"],
+        name: "app"
+    }
+}
+
+#[test]
+/// Tests encrypting whole objects and arrays and decrypting them back
+fn test_encrypt_and_resolve() {
+    let options = SecretOptions::default();
+    let paths = ["db".parse().unwrap(), "tokens[1]".parse().unwrap()];
+    let encrypted = encrypt_secrets(&get_object(), &paths, &encrypt, &options).unwrap();
+    assert_eq!(
+        encrypted,
+        object! {
+            db: { user: "ENC[ADMIN]", password: "ENC[SECRET]", port: 5432 },
+            tokens: ["abc", "ENC[DEF]"],
+            name: "app"
+        }
+    );
+
+    // Encrypting again does not change the secrets
+    let paths = ["tokens".parse().unwrap()];
+    let twice = encrypt_secrets(&encrypted, &paths, &encrypt, &options).unwrap();
+    let expected = object! { tokens: ["ENC[ABC]", "ENC[DEF]"] };
+    assert_eq!(twice["tokens"], expected["tokens"]);
+
+    assert_eq!(
+        resolve_secrets(&twice, &decrypt, &options).unwrap(),
+        get_object()
+    );
+}
+
+#[test]
+/// Tests custom envelopes
+fn test_envelope() {
+    let options = SecretOptions::default().envelope("vault:", "");
+    assert_eq!(options.ciphertext("vault:ABC"), Some("ABC"));
+    assert_eq!(options.ciphertext("ENC[ABC]"), None);
+    assert_eq!(options.wrap("ABC"), "vault:ABC");
+
+    let parsed = parse("password: \"vault:SECRET\"\nother: \"ENC[SECRET]\"").unwrap();
+    let resolved = resolve_secrets(&parsed, &decrypt, &options).unwrap();
+    assert_eq!(
+        resolved,
+        object! { password: "secret", other: "ENC[SECRET]" }
+    );
+}
+
+#[test]
+/// Tests the errors of the hooks and of missing paths
+fn test_secret_errors() {
+    let options = SecretOptions::default();
+    let parsed = parse("db:\n    password: \"ENC[secret]\"").unwrap();
+    let error = resolve_secrets(&parsed, &decrypt, &options).unwrap_err();
+    assert_eq!(error.path, "db.password");
+    assert_eq!(
+        error.to_string(),
+        "Could not decrypt: invalid ciphertext at db.password"
+    );
+
+    let paths = ["db.host".parse().unwrap()];
+    let error = encrypt_secrets(&get_object(), &paths, &encrypt, &options).unwrap_err();
+    assert_eq!(
+        error,
+        SecretError {
+            path: "db.host".parse().unwrap(),
+            msg: String::from("Path not found")
+        }
+    );
+
+    let fail = |_: &str| Err::<String, _>(String::from("no key"));
+    let paths = ["name".parse().unwrap()];
+    let error = encrypt_secrets(&get_object(), &paths, &fail, &options).unwrap_err();
+    assert_eq!(error.to_string(), "Could not encrypt: no key at name");
+}
+
+#[test]
+/// Tests selecting elements of arrays by their index as a key
+fn test_index_keys() {
+    let options = SecretOptions::default();
+    let paths = ["tokens.0".parse().unwrap()];
+    let encrypted = encrypt_secrets(&get_object(), &paths, &encrypt, &options).unwrap();
+    let expected = object! { tokens: ["ENC[ABC]", "def"] };
+    assert_eq!(encrypted["tokens"], expected["tokens"]);
+
+    let paths = ["tokens.2".parse().unwrap()];
+    let error = encrypt_secrets(&get_object(), &paths, &encrypt, &options).unwrap_err();
+    assert_eq!(error.to_string(), "Path not found at tokens.2");
+}
+
+#[test]
+/// Tests that selected values which are not strings, objects or arrays are rejected
+fn test_non_string_secrets() {
+    let options = SecretOptions::default();
+    let paths = ["db.port".parse().unwrap()];
+    let error = encrypt_secrets(&get_object(), &paths, &encrypt, &options).unwrap_err();
+    assert_eq!(
+        error,
+        SecretError {
+            path: "db.port".parse().unwrap(),
+            msg: String::from("Only strings can be encrypted, found integer")
+        }
+    );
+
+    let value = object! { flags: [true, "on"] };
+    let paths = ["flags[0]".parse().unwrap()];
+    let error = encrypt_secrets(&value, &paths, &encrypt, &options).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Only strings can be encrypted, found bool at flags[0]"
+    );
+}
+
+#[test]
+/// Tests that plaintexts looking like an envelope are taken as encrypted secrets
+fn test_plaintext_like_envelope() {
+    let options = SecretOptions::default();
+    let value = object! { password: "ENC[abc]" };
+    let paths = ["password".parse().unwrap()];
+    let encrypted = encrypt_secrets(&value, &paths, &encrypt, &options).unwrap();
+    assert_eq!(encrypted, value);
+
+    // A different envelope encrypts them
+    let options = options.envelope("vault:", "");
+    let encrypted = encrypt_secrets(&value, &paths, &encrypt, &options).unwrap();
+    assert_eq!(encrypted, object! { password: "vault:ENC[ABC]" });
+}
@@ -0,0 +1,493 @@
+#![cfg(feature = "serde")]
+use gura::{
+    de::{from_str, from_str_with_schema},
+    errors::{DeserializeError, ErrorItem, SerializeError},
+    schema::Schema,
+    ser::{to_string, to_value},
+    GuraType, Kind,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Parses a Gura text and its equivalent JSON checking that both give the same value.
+fn assert_like_json<T>(gura_text: &str, json_text: &str) -> T
+where
+    T: for<'de> Deserialize<'de> + PartialEq + std::fmt::Debug,
+{
+    let from_gura: T = from_str(gura_text).unwrap();
+    let from_json: T = serde_json::from_str(json_text).unwrap();
+    assert_eq!(from_gura, from_json);
+    from_gura
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(tag = "type")]
+enum InternallyTagged {
+    Memory,
+    File { path: String },
+    Redis { host: String, port: u16 },
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(tag = "type", content = "options")]
+enum AdjacentlyTagged {
+    Memory,
+    File(String),
+    Redis { host: String, port: u16 },
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+enum Untagged {
+    Port(u16),
+    Address { host: String, port: u16 },
+    Hosts(Vec<String>),
+    Name(String),
+    Nothing,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+enum ExternallyTagged {
+    Memory,
+    File(String),
+    Pair(String, u16),
+    Redis { host: String, port: u16 },
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Backends<T> {
+    backends: Vec<T>,
+}
+
+#[test]
+/// Tests enums with a tag key inside their objects
+fn test_internally_tagged_enums() {
+    let parsed: Backends<InternallyTagged> = assert_like_json(
+        r#"backends: [
+    type: "Memory",
+    type: "File"
+    path: "/tmp/cache",
+    type: "Redis"
+    host: "localhost"
+    port: 6379
+]"#,
+        r#"{"backends": [
+    {"type": "Memory"},
+    {"type": "File", "path": "/tmp/cache"},
+    {"type": "Redis", "host": "localhost", "port": 6379}
+]}"#,
+    );
+    assert_eq!(parsed.backends[0], InternallyTagged::Memory);
+
+    let error = from_str::<InternallyTagged>("type: \"Disk\"").unwrap_err();
+    assert!(error.to_string().starts_with("unknown variant `Disk`"));
+    assert!(from_str::<InternallyTagged>("path: \"/tmp\"").is_err());
+}
+
+#[test]
+/// Tests enums with tag and content keys
+fn test_adjacently_tagged_enums() {
+    assert_like_json::<Backends<AdjacentlyTagged>>(
+        r#"backends: [
+    type: "Memory",
+    type: "File"
+    options: "/tmp/cache",
+    type: "Redis"
+    options:
+        host: "localhost"
+        port: 6379
+]"#,
+        r#"{"backends": [
+    {"type": "Memory"},
+    {"type": "File", "options": "/tmp/cache"},
+    {"type": "Redis", "options": {"host": "localhost", "port": 6379}}
+]}"#,
+    );
+}
+
+#[test]
+/// Tests enums matched by the shape of their values
+fn test_untagged_enums() {
+    let parsed: Backends<Untagged> = assert_like_json(
+        r#"backends: [
+    8080,
+    host: "localhost"
+    port: 6379,
+    ["alpha", "omega"],
+    "memory",
+    null
+]"#,
+        r#"{"backends": [
+    8080,
+    {"host": "localhost", "port": 6379},
+    ["alpha", "omega"],
+    "memory",
+    null
+]}"#,
+    );
+    assert_eq!(parsed.backends[0], Untagged::Port(8080));
+    assert_eq!(parsed.backends[4], Untagged::Nothing);
+
+    let error = from_str::<Backends<Untagged>>("backends: [true]").unwrap_err();
+    assert_eq!(
+        error,
+        DeserializeError::Invalid {
+            path: String::from("backends.0"),
+            message: String::from("data did not match any variant of untagged enum Untagged"),
+        }
+    );
+}
+
+#[test]
+/// Tests enums represented as strings or objects with a single key
+fn test_externally_tagged_enums() {
+    assert_like_json::<Backends<ExternallyTagged>>(
+        r#"backends: [
+    "Memory",
+    File: "/tmp/cache",
+    Pair: ["localhost", 80],
+    Redis:
+        host: "localhost"
+        port: 6379
+]"#,
+        r#"{"backends": [
+    "Memory",
+    {"File": "/tmp/cache"},
+    {"Pair": ["localhost", 80]},
+    {"Redis": {"host": "localhost", "port": 6379}}
+]}"#,
+    );
+
+    let error = from_str::<Backends<ExternallyTagged>>(
+        "backends: [\n    Redis:\n        host: \"localhost\"\n        port: \"6379\"\n]",
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid type: string \"6379\", expected u16 at backends.0.Redis.port"
+    );
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Connection {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Service {
+    name: String,
+    #[serde(flatten)]
+    connection: Connection,
+    #[serde(flatten)]
+    labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Limits {
+    #[serde(default)]
+    retries: u8,
+    #[serde(default = "default_timeout")]
+    timeout: f64,
+    #[serde(default)]
+    tags: Vec<String>,
+    owner: Option<String>,
+}
+
+fn default_timeout() -> f64 {
+    2.5
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Strict {
+    host: String,
+    #[serde(default)]
+    port: u16,
+    #[serde(rename = "max-connections", alias = "max_connections", default)]
+    max_connections: u32,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct StrictSection {
+    server: Strict,
+}
+
+#[test]
+/// Tests fields flattened from other structs and maps
+fn test_flatten() {
+    let parsed: Service = assert_like_json(
+        "name: \"cache\"\nhost: \"localhost\"\nport: 6379\nzone: \"eu\"\ntier: \"gold\"",
+        r#"{"name": "cache", "host": "localhost", "port": 6379, "zone": "eu", "tier": "gold"}"#,
+    );
+    assert_eq!(parsed.connection.port, 6379);
+    assert_eq!(parsed.labels.len(), 2);
+
+    let error = from_str::<Service>("name: \"cache\"\nhost: \"localhost\"").unwrap_err();
+    assert_eq!(error.to_string(), "missing field `port`");
+}
+
+#[test]
+/// Tests default values of missing fields
+fn test_default() {
+    let parsed: Limits = assert_like_json("retries: 3", r#"{"retries": 3}"#);
+    assert_eq!(
+        parsed,
+        Limits {
+            retries: 3,
+            timeout: 2.5,
+            tags: Vec::new(),
+            owner: None,
+        }
+    );
+
+    let parsed: Limits = assert_like_json(
+        "timeout: 10\ntags: [\"a\"]\nowner: null",
+        r#"{"timeout": 10, "tags": ["a"], "owner": null}"#,
+    );
+    assert_eq!(parsed.timeout, 10.0);
+    assert_eq!(parsed.retries, 0);
+
+    // An empty document has all the defaults
+    let parsed: Limits = assert_like_json("", "{}");
+    assert_eq!(parsed.tags, Vec::<String>::new());
+}
+
+#[test]
+/// Tests rejection of unknown keys
+fn test_deny_unknown_fields() {
+    assert_like_json::<Strict>(
+        "host: \"localhost\"\nmax_connections: 10",
+        r#"{"host": "localhost", "max_connections": 10}"#,
+    );
+    assert_like_json::<Strict>(
+        "host: \"localhost\"\nport: 80",
+        r#"{"host": "localhost", "port": 80}"#,
+    );
+
+    let json_error =
+        serde_json::from_str::<StrictSection>(r#"{"server": {"host": "localhost", "prot": 80}}"#)
+            .unwrap_err();
+    let error =
+        from_str::<StrictSection>("server:\n    host: \"localhost\"\n    prot: 80").unwrap_err();
+    match error {
+        DeserializeError::Invalid { path, message } => {
+            assert_eq!(path, "server.prot");
+            assert!(json_error.to_string().starts_with(&message));
+            assert_eq!(
+                message,
+                "unknown field `prot`, expected one of `host`, `port`, `max-connections`, `max_connections`"
+            );
+        }
+        error => panic!("Unexpected error {}", error),
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Marker;
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Encoded {
+    marker: Marker,
+    #[serde(with = "serde_bytes")]
+    key: Vec<u8>,
+    initial: char,
+    ratio: f32,
+    big: u64,
+}
+
+/// Serializes a value checking that its Gura document is read back as the same value.
+fn assert_round_trip<T>(value: &T) -> String
+where
+    T: for<'de> Deserialize<'de> + Serialize + PartialEq + std::fmt::Debug,
+{
+    let text = to_string(value).unwrap();
+    assert_eq!(&from_str::<T>(&text).unwrap(), value);
+    text
+}
+
+#[test]
+/// Tests that enum variants are serialized like serde_json does
+fn test_serialize_enums() {
+    let backends = Backends {
+        backends: vec![
+            ExternallyTagged::Memory,
+            ExternallyTagged::File(String::from("/tmp/cache")),
+            ExternallyTagged::Pair(String::from("localhost"), 80),
+            ExternallyTagged::Redis {
+                host: String::from("localhost"),
+                port: 6379,
+            },
+        ],
+    };
+    assert_eq!(
+        assert_round_trip(&backends),
+        r#"backends: [
+    "Memory",
+    File: "/tmp/cache",
+    Pair: ["localhost", 80],
+    Redis:
+        host: "localhost"
+        port: 6379
+]"#
+    );
+
+    let json: serde_json::Value = serde_json::to_value(&backends).unwrap();
+    let from_json: Backends<ExternallyTagged> =
+        gura::de::from_value(&to_value(&json).unwrap()).unwrap();
+    assert_eq!(from_json, backends);
+}
+
+#[test]
+/// Tests unit structs, bytes, chars and numbers
+fn test_serialize_special_values() {
+    let encoded = Encoded {
+        marker: Marker,
+        key: b"gura!".to_vec(),
+        initial: 'g',
+        ratio: 1.1,
+        big: u64::MAX,
+    };
+    assert_eq!(
+        assert_round_trip(&encoded),
+        "marker: null\nkey: \"Z3VyYSE=\"\ninitial: \"g\"\nratio: 1.1\nbig: 18446744073709551615"
+    );
+    assert_eq!(
+        to_value(&serde_bytes::Bytes::new(b"ab")).unwrap(),
+        GuraType::String(String::from("YWI="))
+    );
+
+    let error =
+        from_str::<Encoded>("marker: null\nkey: \"Z3V?\"\ninitial: \"g\"\nratio: 1.1\nbig: 1")
+            .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid value: string \"Z3V?\", expected a base64 string at key"
+    );
+}
+
+#[test]
+/// Tests map keys that are not strings
+fn test_serialize_map_keys() {
+    let mut ports = BTreeMap::new();
+    ports.insert(80, "http");
+    ports.insert(443, "https");
+    assert_eq!(to_string(&ports).unwrap(), "80: \"http\"\n443: \"https\"");
+
+    let mut flags = BTreeMap::new();
+    flags.insert(true, 1);
+    assert_eq!(
+        to_string(&flags).unwrap_err().to_string(),
+        "map keys must be strings, chars or integers, found bool"
+    );
+
+    let mut labels = BTreeMap::new();
+    labels.insert("my-label", 1);
+    let error = to_string(&Backends {
+        backends: vec![labels],
+    })
+    .unwrap_err();
+    assert_eq!(
+        error,
+        SerializeError {
+            path: String::from("backends.0"),
+            message: String::from(
+                "invalid key \"my-label\", keys can only contain ASCII letters, digits and underscores"
+            ),
+        }
+    );
+
+    assert_eq!(
+        to_string(&vec![1, 2]).unwrap_err().to_string(),
+        "the root value must be an object, found array"
+    );
+}
+
+#[test]
+/// Tests that maps with integer keys round-trip through the serializer and the deserializer
+fn test_deserialize_map_keys() {
+    let mut ports: BTreeMap<u32, String> = BTreeMap::new();
+    ports.insert(80, String::from("http"));
+    ports.insert(443, String::from("https"));
+    let text = to_string(&ports).unwrap();
+    assert_eq!(from_str::<BTreeMap<u32, String>>(&text).unwrap(), ports);
+
+    let chars: BTreeMap<char, u8> = from_str("a: 1\nb: 2").unwrap();
+    assert_eq!(chars.keys().collect::<String>(), "ab");
+
+    let error = from_str::<BTreeMap<u8, String>>("http: \"80\"").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid type: string \"http\", expected u8 at http"
+    );
+    let error = from_str::<BTreeMap<u8, String>>("443: \"https\"").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid type: string \"443\", expected u8 at 443"
+    );
+}
+
+#[test]
+/// Tests conversion of Gura documents to JSON through the deserializer
+fn test_transcode() {
+    let text = "name: \"cache\"\nenabled: true\nlimits:\n    ratio: 0.5\n    big: 99999999999999999999\nports: [80, 443]\nowner: null";
+    let expected = r#"{"name":"cache","enabled":true,"limits":{"ratio":0.5,"big":99999999999999999999},"ports":[80,443],"owner":null}"#;
+
+    let parsed = gura::parse(text).unwrap();
+    let mut json = Vec::new();
+    serde_transcode::transcode(
+        gura::de::Deserializer::new(&parsed),
+        &mut serde_json::Serializer::new(&mut json),
+    )
+    .unwrap();
+    assert_eq!(String::from_utf8(json).unwrap(), expected);
+
+    let mut json = Vec::new();
+    gura::de::transcode(text, &mut serde_json::Serializer::new(&mut json)).unwrap();
+    assert_eq!(String::from_utf8(json).unwrap(), expected);
+    assert_eq!(serde_json::to_string(&parsed).unwrap(), expected);
+
+    let error = gura::de::transcode(
+        "name: \"cache",
+        &mut serde_json::Serializer::new(Vec::new()),
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("line 1"));
+}
+
+#[test]
+/// Tests deserialization after validation, reporting every error found
+fn test_from_str_with_schema() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Server {
+        host: String,
+        port: u8,
+    }
+
+    let schema = Schema::object()
+        .field("host", Schema::new(Kind::String).required())
+        .field("port", Schema::new(Kind::Integer).required());
+
+    let errors = from_str_with_schema::<Server>("host: \"a", &schema).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], ErrorItem::Parse(_)));
+
+    let errors = from_str_with_schema::<Server>("host: 127", &schema).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].path().unwrap(), "host");
+    assert_eq!(errors[0].line(), Some(1));
+    assert_eq!(errors[1].path().unwrap(), "port");
+
+    // Valid documents can still not match the Rust type
+    let errors = from_str_with_schema::<Server>("host: \"a\"\nport: 8080", &schema).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], ErrorItem::Deserialize { .. }));
+
+    assert_eq!(
+        from_str_with_schema::<Server>("host: \"a\"\nport: 80", &schema).unwrap(),
+        Server {
+            host: "a".to_string(),
+            port: 80
+        }
+    );
+}
@@ -0,0 +1,274 @@
+use gura::{
+    parse, parse_spanned,
+    span::{Reference, Span, Trivia, VariableSource},
+    GuraType,
+};
+use std::fs;
+use std::path::PathBuf;
+
+const PARENT_FOLDER: &str = "spans";
+
+/// Generates the span of an ASCII text, where all the offsets are the same
+fn ascii_span(start: usize, end: usize, line: usize) -> Span {
+    Span {
+        start,
+        end,
+        line,
+        byte_start: start,
+        byte_end: end,
+        utf16_start: start,
+        utf16_end: end,
+    }
+}
+
+#[test]
+/// Tests spans of root, nested and in-array keys
+fn test_keys_with_spans() {
+    let content =
+        fs::read_to_string(format!("tests/{}/tests-files/normal.ura", PARENT_FOLDER)).unwrap();
+    let document = parse_spanned(&content).unwrap();
+    assert_eq!(document.value, parse(&content).unwrap());
+
+    let keys: Vec<(&str, Span)> = document.keys_with_spans().collect();
+    let expected = vec![
+        ("services", ascii_span(11, 19, 2)),
+        ("services.nginx", ascii_span(25, 30, 3)),
+        ("services.nginx.host", ascii_span(40, 44, 4)),
+        ("services.nginx.prot", ascii_span(66, 70, 5)),
+        ("tango_singers", ascii_span(76, 89, 7)),
+        ("tango_singers.0.user1", ascii_span(97, 102, 8)),
+        ("tango_singers.0.user1.name", ascii_span(112, 116, 9)),
+    ];
+    assert_eq!(keys, expected);
+
+    // Spans point to the keys in the original text
+    for (path, span) in keys {
+        let key = path.rsplit('.').next().unwrap();
+        assert_eq!(&content[span.byte_start..span.byte_end], key);
+    }
+}
+
+#[test]
+/// Tests spans when the document imports other files
+fn test_with_imports() {
+    let content = fs::read_to_string("tests/importing/tests-files/normal.ura").unwrap();
+    let document = parse_spanned(&content).unwrap();
+
+    // Keys from imported files do not have spans
+    assert!(document.value.contains_key("from_file_one"));
+    assert_eq!(document.span("from_file_one"), None);
+    assert_eq!(
+        document.span("from_original_1"),
+        Some(ascii_span(91, 106, 4))
+    );
+    assert_eq!(
+        document.span("from_original_2"),
+        Some(ascii_span(118, 133, 5))
+    );
+}
+
+#[test]
+/// Tests that spans are not generated for missing keys
+fn test_missing() {
+    let document = parse_spanned("a: 1").unwrap();
+    assert_eq!(document.span("b"), None);
+    assert_eq!(document.keys_with_spans().count(), 1);
+}
+
+#[test]
+/// Tests byte and UTF-16 offsets of spans in non ASCII texts
+fn test_offsets() {
+    let content = "title: \"Café 🎉\"\nname: 1";
+    let document = parse_spanned(content).unwrap();
+    let span = document.span("name").unwrap();
+    assert_eq!((span.start, span.end, span.line), (16, 20, 2));
+    assert_eq!((span.byte_start, span.byte_end), (20, 24));
+    assert_eq!((span.utf16_start, span.utf16_end), (17, 21));
+    assert_eq!(&content[span.byte_start..span.byte_end], "name");
+}
+
+#[test]
+/// Tests byte and UTF-16 offsets of errors in non ASCII texts
+fn test_error_offsets() {
+    let content = "title: \"🎉\"\nname: 1 2";
+    let error = parse(content).unwrap_err();
+    assert_eq!(error.pos, 19);
+    assert_eq!(error.byte_pos, 22);
+    assert_eq!(error.utf16_pos, 20);
+    assert_eq!(&content[error.byte_pos..], "2");
+}
+
+#[test]
+/// Tests references of variables and imports
+fn test_reference_at() {
+    let content = fs::read_to_string("tests/importing/tests-files/normal.ura").unwrap();
+    let document = parse_spanned(&content).unwrap();
+    let second_import = content
+        .find("\"tests/importing/tests-files/two.ura\"")
+        .unwrap();
+    assert_eq!(
+        document.reference_at(second_import + 5),
+        Some(Reference::Import {
+            span: ascii_span(second_import, second_import + 37, 2),
+            path: PathBuf::from("tests/importing/tests-files/two.ura"),
+        })
+    );
+    assert_eq!(document.reference_at(0), None);
+
+    let content = "$name: \"Gura\"\ntitle: \"Hi $name\"\nuser: $USER_NOT_DEFINED_IN_GURA";
+    std::env::set_var("USER_NOT_DEFINED_IN_GURA", "Kiara");
+    let document = parse_spanned(content).unwrap();
+    let definition = Some(ascii_span(0, 5, 1));
+    assert_eq!(
+        document.reference_at(1),
+        Some(Reference::Variable {
+            name: String::from("name"),
+            span: ascii_span(0, 5, 1),
+            definition,
+        })
+    );
+    assert_eq!(
+        document.reference_at(content.find("$name\"").unwrap()),
+        Some(Reference::Variable {
+            name: String::from("name"),
+            span: ascii_span(25, 30, 2),
+            definition,
+        })
+    );
+    assert_eq!(
+        document.reference_at(content.len() - 1),
+        Some(Reference::Variable {
+            name: String::from("USER_NOT_DEFINED_IN_GURA"),
+            span: ascii_span(38, 63, 3),
+            definition: None,
+        })
+    );
+}
+
+#[test]
+/// Tests the values and sources of variables used in the document
+fn test_variable_usages() {
+    let content =
+        fs::read_to_string(format!("tests/{}/tests-files/usages.ura", PARENT_FOLDER)).unwrap();
+    std::env::set_var("USAGES_USER_NOT_DEFINED_IN_GURA", "Kiara");
+    let document = parse_spanned(&content).unwrap();
+    let usages = document.variable_usages();
+    let usages: Vec<(&str, usize, &GuraType, VariableSource)> = usages
+        .iter()
+        .map(|usage| {
+            (
+                usage.name.as_str(),
+                usage.span.line,
+                &usage.value,
+                usage.source,
+            )
+        })
+        .collect();
+
+    let host = GuraType::String(String::from("localhost"));
+    let host_source = VariableSource::Document(Some(ascii_span(49, 54, 2)));
+    assert_eq!(
+        usages,
+        vec![
+            ("host", 5, &host, host_source),
+            ("host", 6, &host, host_source),
+            (
+                "port",
+                6,
+                &GuraType::Integer(8080),
+                VariableSource::Document(None)
+            ),
+            (
+                "USAGES_USER_NOT_DEFINED_IN_GURA",
+                6,
+                &GuraType::String(String::from("Kiara")),
+                VariableSource::Environment
+            ),
+        ]
+    );
+}
+
+#[test]
+/// Tests the definitions of variables with their references
+fn test_variables() {
+    let content =
+        fs::read_to_string(format!("tests/{}/tests-files/usages.ura", PARENT_FOLDER)).unwrap();
+    std::env::set_var("USAGES_USER_NOT_DEFINED_IN_GURA", "Kiara");
+    let document = parse_spanned(&content).unwrap();
+
+    let variables = document.variables();
+    assert_eq!(variables.len(), 1);
+    let host = &variables[0];
+    assert_eq!(host.name, "host");
+    assert_eq!(host.value, GuraType::String(String::from("localhost")));
+    assert_eq!(host.span, ascii_span(49, 54, 2));
+    let lines: Vec<usize> = host.references.iter().map(|span| span.line).collect();
+    assert_eq!(lines, vec![5, 6]);
+    for span in host.references.iter() {
+        assert_eq!(&content[span.byte_start..span.byte_end], "$host");
+    }
+    assert_eq!(document.variable("host").as_ref(), Some(host));
+
+    // Variables not defined in the text have references but no definition
+    assert_eq!(document.variable("port"), None);
+    assert_eq!(document.variable_references("port").len(), 1);
+    assert_eq!(
+        document
+            .variable_references("USAGES_USER_NOT_DEFINED_IN_GURA")
+            .len(),
+        1
+    );
+    assert!(document.variable_references("undefined").is_empty());
+}
+
+#[test]
+/// Tests the comments and blank lines around pairs
+fn test_trivia() {
+    let content =
+        fs::read_to_string(format!("tests/{}/tests-files/trivia.ura", PARENT_FOLDER)).unwrap();
+    let document = parse_spanned(&content).unwrap();
+
+    let trivia = document.trivia("name").unwrap();
+    assert_eq!(trivia.leading_comments, vec!["# Name of the service"]);
+    assert_eq!(trivia.leading_span, Some(ascii_span(0, 21, 1)));
+    assert_eq!(trivia.blank_lines_before, 0);
+
+    let trivia = document.trivia("server").unwrap();
+    assert_eq!(trivia.blank_lines_before, 2);
+    assert_eq!(
+        trivia.leading_comments,
+        vec!["# Server settings", "# (see the docs)"]
+    );
+    let span = trivia.leading_span.unwrap();
+    assert_eq!(
+        &content[span.byte_start..span.byte_end],
+        "# Server settings\n# (see the docs)"
+    );
+    assert_eq!(span.line, 5);
+    assert_eq!(trivia.trailing_comment.as_deref(), Some("# Main one"));
+
+    let trivia = document.trivia("server.host").unwrap();
+    assert_eq!(trivia.leading_comments, vec!["# Host to bind"]);
+    assert_eq!(trivia.trailing_comment, None);
+
+    assert_eq!(
+        document.trivia("server.port"),
+        Some(&Trivia {
+            blank_lines_before: 1,
+            trailing_comment: Some(String::from("# Default port")),
+            ..Trivia::default()
+        })
+    );
+
+    // Comments separated by blank lines and lines of strings are not attached
+    let trivia = document.trivia("server.motd").unwrap();
+    assert!(trivia.leading_comments.is_empty());
+    assert_eq!(
+        trivia.trailing_comment.as_deref(),
+        Some("# After the string")
+    );
+    let trivia = document.trivia("debug").unwrap();
+    assert_eq!(trivia.blank_lines_before, 1);
+    assert!(trivia.leading_comments.is_empty());
+    assert_eq!(document.trivia("missing"), None);
+}
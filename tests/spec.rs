@@ -0,0 +1,173 @@
+use gura::{
+    errors::Error,
+    extension::ScalarExtensions,
+    parse_with_options,
+    parser::ParserOptions,
+    spec::{available_features, Extension, SpecVersion},
+    GuraType, SPEC_VERSION,
+};
+
+#[test]
+/// Tests the supported versions of the specification
+fn test_spec_version() {
+    assert_eq!(SpecVersion::LATEST.as_str(), SPEC_VERSION);
+    assert_eq!(SpecVersion::default(), SpecVersion::LATEST);
+    assert_eq!(SpecVersion::V1_0_0.to_string(), "1.0.0");
+
+    assert_eq!(
+        SpecVersion::from_version("1.0.0"),
+        Some(SpecVersion::V1_0_0)
+    );
+    assert_eq!(SpecVersion::from_version("1"), Some(SpecVersion::V1_0_0));
+    assert_eq!(SpecVersion::from_version("1.0"), Some(SpecVersion::V1_0_0));
+    assert_eq!(SpecVersion::from_version("2.0.0"), None);
+    assert_eq!(SpecVersion::from_version("1.x"), None);
+}
+
+#[test]
+/// Tests that the reported features are the ones declared in the manifest
+fn test_features() {
+    let manifest = include_str!("../Cargo.toml");
+    let mut declared: Vec<&str> = manifest
+        .split("\n[features]\n")
+        .nth(1)
+        .unwrap()
+        .lines()
+        .take_while(|line| !line.starts_with('['))
+        .filter_map(|line| line.split_once(" = "))
+        .map(|(name, _)| name)
+        .collect();
+    declared.sort_unstable();
+    assert_eq!(available_features(), declared);
+
+    let compliance = ParserOptions::default().compliance();
+    assert!(compliance
+        .features
+        .iter()
+        .all(|feature| available_features().contains(feature)));
+    assert_eq!(compliance.has_feature("mmap"), cfg!(feature = "mmap"));
+    assert_eq!(compliance.has_feature("cli"), cfg!(feature = "cli"));
+}
+
+#[test]
+/// Tests the compliance report of parser options
+fn test_compliance() {
+    let compliance = ParserOptions::default().compliance();
+    assert_eq!(compliance.spec_version, SpecVersion::LATEST);
+    assert_eq!(compliance.extensions, vec![Extension::BigIntegers]);
+    assert_eq!(compliance.has_feature("serde"), cfg!(feature = "serde"));
+
+    let compliance = ParserOptions::default()
+        .capture_line_content(true)
+        .spec_version(SpecVersion::V1_0_0)
+        .compliance();
+    assert!(compliance.has_extension(Extension::LineContent));
+
+    let compliance = ParserOptions::default().indent_unit(2).compliance();
+    assert!(compliance.has_extension(Extension::IndentUnit));
+    let compliance = ParserOptions::default().indent_unit(4).compliance();
+    assert!(!compliance.has_extension(Extension::IndentUnit));
+
+    let compliance = ParserOptions::default().hex_escapes(true).compliance();
+    assert!(compliance.has_extension(Extension::HexEscapes));
+    assert_eq!(
+        ParserOptions::default().hex_escapes(true).explain().to_string(),
+        "Deviates from Gura 1.0.0:\n- conformance_strict: integers that do not fit in 64 bits are accepted\n- hex_escapes: escape sequences \\xHH are read as code points"
+    );
+}
+
+#[test]
+/// Tests that strict mode ignores every extension, whenever they are enabled
+fn test_conformance_strict() {
+    let options = ParserOptions::default()
+        .raw_strings(true)
+        .conformance_strict()
+        .lenient_arrays(true)
+        .indent_unit(2);
+    let compliance = options.compliance();
+    assert!(compliance.extensions.is_empty());
+    assert!(options.explain().is_conformant());
+    assert_eq!(options.explain().to_string(), "Conforms to Gura 1.0.0");
+
+    let parsed = parse_with_options("$host: \"localhost\"\nurl: \"$host:80\"", &options);
+    assert_eq!(parsed.unwrap()["url"], "localhost:80");
+    let error = parse_with_options("big: 99999999999999999999", &options).unwrap_err();
+    assert_eq!(error.kind, Error::ParseError);
+    let error = parse_with_options("big: 0xFFFFFFFFFFFFFFFFF", &options).unwrap_err();
+    assert_eq!(error.kind, Error::ParseError);
+    let error = parse_with_options("server:\n  port: 8080", &options).unwrap_err();
+    assert_eq!(error.kind, Error::InvalidIndentationError);
+}
+
+#[test]
+/// Tests that strict mode disables every extension, all of them listed by `explain`
+fn test_conformance_strict_all_extensions() {
+    let mut scalar_extensions = ScalarExtensions::new();
+    scalar_extensions.register("port", |_| Ok(GuraType::Null));
+    let options = ParserOptions::default()
+        .capture_line_content(true)
+        .lenient_arrays(true)
+        .indent_unit(2)
+        .trim_final_newline(true)
+        .raw_strings(true)
+        .scalar_extensions(scalar_extensions)
+        .deduplicate_imports(true)
+        .builtin_variables(true)
+        .time_variables(true)
+        .normalize_newlines(true)
+        .hex_escapes(true);
+    let report = options.explain();
+    assert_eq!(
+        report.deviations,
+        vec![
+            Extension::BigIntegers,
+            Extension::LineContent,
+            Extension::LenientArrays,
+            Extension::IndentUnit,
+            Extension::TrimFinalNewline,
+            Extension::RawStrings,
+            Extension::ScalarExtensions,
+            Extension::DeduplicatedImports,
+            Extension::BuiltinVariables,
+            Extension::TimeVariables,
+            Extension::NormalizedNewlines,
+            Extension::HexEscapes,
+        ]
+    );
+    for deviation in &report.deviations {
+        let line = format!("- {}: {}", deviation.option(), deviation.description());
+        assert!(report.to_string().contains(&line));
+    }
+
+    let options = options.conformance_strict();
+    assert!(options.explain().is_conformant());
+    let text = "a: \"\\x41 $__now_unix__\"\nb: '''\r\nx\r\n'''\nc: [\n    1\n    2\n]";
+    let error = parse_with_options(text, &options).unwrap_err();
+    assert_eq!(error.kind, Error::VariableNotDefinedError);
+    let parsed = parse_with_options(&text.replace(" $__now_unix__", ""), &options);
+    assert_eq!(parsed.unwrap_err().kind, Error::ParseError);
+    let parsed = parse_with_options("a: \"\\x41\"\nb: '''\r\nx\r\n'''", &options).unwrap();
+    assert_eq!(parsed["a"], "\\x41");
+    assert_eq!(parsed["b"], "x\r\n");
+}
+
+#[test]
+/// Tests the report of the options that deviate from the specification
+fn test_explain() {
+    let report = ParserOptions::default()
+        .raw_strings(true)
+        .indent_unit(2)
+        .explain();
+    assert_eq!(
+        report.deviations,
+        vec![
+            Extension::BigIntegers,
+            Extension::IndentUnit,
+            Extension::RawStrings
+        ]
+    );
+    assert_eq!(
+        report.to_string(),
+        "Deviates from Gura 1.0.0:\n- conformance_strict: integers that do not fit in 64 bits are accepted\n- indent_unit: indentation levels are not of 4 spaces\n- raw_strings: variables inside basic strings are not replaced"
+    );
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "stats")]
+use gura::{parse, parse_with_stats, parser::ParserOptions};
+use std::fs;
+
+#[test]
+/// Tests the counters of a parsing with imports
+fn test_parse_with_stats() {
+    let dir = tempfile::tempdir().unwrap();
+    let imported = dir.path().join("base.ura");
+    fs::write(&imported, "name: \"app\"").unwrap();
+    let text = format!("import \"{}\"\nport: 8080", imported.display());
+
+    let (parsed, stats) = parse_with_stats(&text, &ParserOptions::default()).unwrap();
+    assert_eq!(parsed, parse(&text).unwrap());
+    assert_eq!(stats.imported_files, 1);
+    // The text, the imported file and the text with the import inlined
+    assert!(stats.graphemes > text.len() + "name: \"app\"".len());
+    assert!(stats.rule_attempts > 0);
+    assert_eq!(
+        stats.total(),
+        stats.segmentation + stats.imports + stats.matching
+    );
+    assert!(stats.to_string().contains("imported files: 1"));
+
+    // Errors are reported as in the other parsing functions
+    let error = parse_with_stats("port: $undefined", &ParserOptions::default()).unwrap_err();
+    assert_eq!(error, parse("port: $undefined").unwrap_err());
+}
@@ -1,9 +1,9 @@
 use gura::{
     errors::Error,
-    object,
-    parser::{parse, GuraType},
+    object, parse_with_options,
+    parser::{parse, GuraType, ParserOptions},
 };
-use std::env;
+use std::{env, fs};
 mod common;
 
 const ESCAPED_VALUE: &str = "$name is cool";
@@ -18,17 +18,15 @@ fn get_expected_basic() -> GuraType {
     }
 }
 
-// Multiline char depends on platform (Windows handles \r\n, Unix \n, etc.)
+// Fixtures are checked out with \n in every platform (see .gitattributes)
 const MULTILINE_VALUE: &str = "Roses are red\nViolets are blue";
-const MULTILINE_VALUE_LINUX: &str = "Roses are red\nViolets are blue";
-const MULTILINE_VALUE_WINDOWS: &str = "Roses are red\r\nViolets are blue";
 const MULTILINE_VALUE_WITHOUT_NEWLINE: &str = "The quick brown fox jumps over the lazy dog.";
 fn get_expected_multiline_basic() -> GuraType {
     object! {
-        str: if cfg!(windows) { MULTILINE_VALUE_WINDOWS } else { MULTILINE_VALUE_LINUX },
-        str_2: if cfg!(windows) { MULTILINE_VALUE_WINDOWS } else { MULTILINE_VALUE_LINUX },
+        str: MULTILINE_VALUE,
+        str_2: MULTILINE_VALUE,
         str_3: MULTILINE_VALUE,
-        with_var: if cfg!(windows) { MULTILINE_VALUE_WINDOWS } else { MULTILINE_VALUE_LINUX },
+        with_var: MULTILINE_VALUE,
         with_env_var: MULTILINE_VALUE,
         str_with_backslash: MULTILINE_VALUE_WITHOUT_NEWLINE,
         str_with_backslash_2: MULTILINE_VALUE_WITHOUT_NEWLINE,
@@ -50,12 +48,12 @@ fn get_expected_literal() -> GuraType {
     }
 }
 
-const LINES_LINUX: &str = "The first newline is\ntrimmed in raw strings.\n   All other whitespace\n   is preserved.\n";
-const LINES_WINDOWS: &str = "The first newline is\r\ntrimmed in raw strings.\r\n   All other whitespace\r\n   is preserved.\r\n";
+const LINES: &str =
+    "The first newline is\ntrimmed in raw strings.\n   All other whitespace\n   is preserved.\n";
 
 fn get_expected_multiline_literal() -> GuraType {
     object! {
-        lines: if cfg!(windows) { LINES_WINDOWS } else { LINES_LINUX },
+        lines: LINES,
         regex2: "I [dw]on't need \\d{2} apples",
         with_var: "$no_parsed variable!",
         escaped_var: ESCAPED_VALUE
@@ -121,3 +119,139 @@ fn test_invalid_escape_sentence() {
         }
     );
 }
+
+#[test]
+/// Tests escape sequences of code points
+fn test_code_point_escapes() {
+    let parsed_data = parse(r#"foo: "\u00e9\U0001F600""#).unwrap();
+    assert_eq!(parsed_data, object! { foo: "é😀" });
+
+    let error = parse("foo: \"\\u12\"\nbar: 2").unwrap_err();
+    assert_eq!(error.kind, Error::ParseError);
+    assert_eq!(
+        error.msg,
+        "Invalid escape sequence \"\\u12\", expected 4 hexadecimal digits"
+    );
+    assert_eq!((error.pos, error.line), (6, 1));
+
+    let error = parse(r#"foo: "\uD800""#).unwrap_err();
+    assert_eq!(error.msg, "Invalid code point \"\\uD800\"");
+
+    // Literal strings have no escape sequences
+    assert_eq!(parse(r"foo: '\u12'").unwrap(), object! { foo: "\\u12" });
+}
+
+#[test]
+/// Tests the escape sequences of 8 bits code points, only read if they are enabled
+fn test_hex_escapes() {
+    // By default they are kept as they are written, like any unknown escape sequence
+    let parsed_data = parse("foo: \"\\x41\"\npath: \"C:\\xfiles\"").unwrap();
+    assert_eq!(parsed_data, object! { foo: "\\x41", path: "C:\\xfiles" });
+
+    let options = ParserOptions::default().hex_escapes(true);
+    let parsed_data = parse_with_options(r#"foo: "\x41\u00e9""#, &options).unwrap();
+    assert_eq!(parsed_data, object! { foo: "Aé" });
+
+    let error = parse_with_options("foo: \"\"\"\n\\x4\"\"\"", &options).unwrap_err();
+    assert_eq!(
+        error.msg,
+        "Invalid escape sequence \"\\x4\", expected 2 hexadecimal digits"
+    );
+    assert_eq!((error.pos, error.line), (9, 2));
+    assert!(parse_with_options(r#"path: "C:\xfiles""#, &options).is_err());
+
+    // Strict mode only accepts the escape sequences of the specification
+    let options = options.conformance_strict();
+    let parsed_data = parse_with_options(r#"foo: "\x41""#, &options).unwrap();
+    assert_eq!(parsed_data, object! { foo: "\\x41" });
+}
+
+#[test]
+/// Tests escaped delimiters inside multiline basic strings
+fn test_escaped_delimiters() {
+    let parsed_data = parse("foo: \"\"\"a \\\"\"\" b \\\"\\\"\\\"\"\"\"\nbar: 1").unwrap();
+    assert_eq!(parsed_data, object! { foo: "a \"\"\" b \"\"\"", bar: 1 });
+}
+
+#[test]
+/// Tests line continuations and final new lines of multiline strings
+fn test_multiline_trimming() {
+    let parsed_data =
+        common::get_file_content_parsed(PARENT_FOLDER, "multiline_trimming.ura").unwrap();
+    assert_eq!(
+        parsed_data,
+        object! {
+            continuation: MULTILINE_VALUE_WITHOUT_NEWLINE,
+            escaped_backslash: "C:\\\n    Users",
+            not_continuation: "a \\ b",
+            final_newline: "Roses are red\nViolets are blue\n",
+            escaped_final_newline: "Roses are red\n",
+            literal_final_newline: "Roses are red\n"
+        }
+    );
+
+    let content = fs::read_to_string(format!(
+        "tests/{}/tests-files/multiline_trimming.ura",
+        PARENT_FOLDER
+    ))
+    .unwrap();
+    let options = ParserOptions::default().trim_final_newline(true);
+    let parsed_data = parse_with_options(&content, &options).unwrap();
+    assert_eq!(
+        parsed_data["final_newline"],
+        "Roses are red\nViolets are blue"
+    );
+    assert_eq!(parsed_data["escaped_final_newline"], "Roses are red\n");
+    assert_eq!(parsed_data["literal_final_newline"], "Roses are red");
+
+    // New lines inside strings are counted
+    let error = parse(&format!("{}\nafter: $undefined_var", content)).unwrap_err();
+    assert_eq!(error.line, 23);
+    let error = parse("text: \"\"\"a \\\n   b\\\n\"\"\"\nafter: [1").unwrap_err();
+    assert_eq!(error.line, 4);
+}
+
+#[test]
+/// Tests reading \r\n new lines of multiline strings as \n
+fn test_normalize_newlines() {
+    let content = fs::read_to_string(format!(
+        "tests/{}/tests-files/multiline_literal.ura",
+        PARENT_FOLDER
+    ))
+    .unwrap()
+    .replace('\n', "\r\n");
+    let options = ParserOptions::default().normalize_newlines(true);
+    assert_eq!(
+        parse_with_options(&content, &options).unwrap(),
+        get_expected_multiline_literal()
+    );
+    assert_ne!(parse(&content).unwrap(), get_expected_multiline_literal());
+
+    // Escape sequences and single line strings are preserved
+    let parsed_data = parse_with_options(
+        "escaped: \"\"\"a\\r\\nb\"\"\"\r\nliteral: '''a\r\nb'''",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(parsed_data, object! { escaped: "a\r\nb", literal: "a\nb" });
+}
+
+#[test]
+/// Tests keeping the variables of basic strings without replacing them
+fn test_raw_strings() {
+    let options = ParserOptions::default().raw_strings(true);
+    let parsed_data = parse_with_options(
+        "$name: \"Gura\"\ntitle: \"$name is cool\"\nescaped: \"\\$name\\t\"\nmultiline: \"\"\"$undefined\"\"\"\nvalue: $name",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(
+        parsed_data,
+        object! {
+            title: "$name is cool",
+            escaped: "$name\t",
+            multiline: "$undefined",
+            value: "Gura"
+        }
+    );
+}
@@ -0,0 +1,76 @@
+use gura::{
+    errors::GuraError, extension::ScalarExtensions, parse, parse_with_options,
+    parser::ParserOptions, shared::SharedGuraType, GuraType,
+};
+use std::{sync::Arc, thread};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+/// Tests that the main types can be shared across threads
+fn test_send_sync() {
+    assert_send_sync::<GuraType>();
+    assert_send_sync::<GuraError>();
+    assert_send_sync::<ParserOptions>();
+    assert_send_sync::<SharedGuraType>();
+    assert_send_sync::<gura::document::Document>();
+    assert_send_sync::<gura::span::SpannedDocument>();
+}
+
+#[test]
+#[cfg_attr(target_os = "wasi", ignore = "threads are not available in WASI")]
+/// Tests reading the same parsed document from many threads
+fn test_shared_document() {
+    let parsed = Arc::new(parse("server:\n    host: \"localhost\"\n    port: 8080").unwrap());
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let parsed = Arc::clone(&parsed);
+            thread::spawn(move || parsed["server"]["port"].clone())
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), 8080);
+    }
+}
+
+#[test]
+#[cfg_attr(target_os = "wasi", ignore = "threads are not available in WASI")]
+/// Tests parsing concurrently with the same options (and their scalar extensions)
+fn test_shared_options() {
+    let mut extensions = ScalarExtensions::new();
+    extensions.register("port", |value: &str| {
+        value
+            .parse::<isize>()
+            .map(GuraType::Integer)
+            .map_err(|error| error.to_string())
+    });
+    let options = Arc::new(ParserOptions::default().scalar_extensions(extensions));
+    let handles: Vec<_> = (0..4)
+        .map(|index| {
+            let options = Arc::clone(&options);
+            thread::spawn(move || {
+                parse_with_options(&format!("port: @port {}", 8000 + index), &options)
+            })
+        })
+        .collect();
+    for (index, handle) in handles.into_iter().enumerate() {
+        let parsed = handle.join().unwrap().unwrap();
+        assert_eq!(
+            parsed["port"],
+            GuraType::Extension(
+                String::from("port"),
+                Box::new(GuraType::Integer(8000 + index as isize))
+            )
+        );
+    }
+}
+
+#[test]
+#[cfg_attr(target_os = "wasi", ignore = "threads are not available in WASI")]
+/// Tests sending errors to another thread
+fn test_send_error() {
+    let error = thread::spawn(|| parse("key: $undefined").unwrap_err())
+        .join()
+        .unwrap();
+    assert_eq!(error.line, 1);
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "trace-parser")]
+use gura::{parse, parse_with_options, parser::ParserOptions, platform::FakePlatform};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::sync::Mutex;
+
+/// Records the messages of the parser
+struct Recorder {
+    messages: Mutex<Vec<String>>,
+}
+
+impl Log for Recorder {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target() == "gura::parser" && metadata.level() == Level::Trace
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.messages
+                .lock()
+                .unwrap()
+                .push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static RECORDER: Recorder = Recorder {
+    messages: Mutex::new(Vec::new()),
+};
+
+/// Gets the messages recorded while running `function`
+fn traces<F: FnOnce()>(function: F) -> Vec<String> {
+    RECORDER.messages.lock().unwrap().clear();
+    function();
+    std::mem::take(&mut *RECORDER.messages.lock().unwrap())
+}
+
+#[test]
+/// Tests the traces of rules, backtracking and imports. A single test, as the logger is global
+fn test_trace_parser() {
+    log::set_logger(&RECORDER).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    let messages = traces(|| {
+        parse("a: true").unwrap();
+    });
+    assert!(messages.contains(&"pair at line 1 (position 0): \"a: true\"".to_string()));
+    assert!(messages.contains(&"boolean matched up to position 7".to_string()));
+    assert!(messages
+        .iter()
+        .any(|message| message.starts_with("null failed (")
+            && message.ends_with("backtracking to line 1 (position 3)")));
+
+    let messages = traces(|| {
+        parse("a: $undefined").unwrap_err();
+    });
+    assert!(messages
+        .iter()
+        .any(|message| message.starts_with("variable_value raised VariableNotDefinedError: ")));
+
+    let options = ParserOptions::default()
+        .platform(FakePlatform::new().file("a.ura", "a: 1"))
+        .deduplicate_imports(true);
+    let messages = traces(|| {
+        parse_with_options("import \"a.ura\"\nimport \"a.ura\"\nb: 2", &options).unwrap();
+    });
+    assert!(messages.contains(&"importing \"a.ura\" from \"a.ura\"".to_string()));
+    assert!(messages.contains(&"import of \"a.ura\" skipped, already imported".to_string()));
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "tracing")]
+use gura::parse;
+use valuable::{Valuable, Value, Visit};
+
+/// Writes the visited values in a JSON like format, with the type of numbers
+struct Printer(String);
+
+impl Visit for Printer {
+    fn visit_value(&mut self, value: Value<'_>) {
+        match value {
+            Value::Mappable(map) => {
+                self.0.push('{');
+                map.visit(self);
+                self.0.push('}');
+            }
+            Value::Listable(list) => {
+                self.0.push('[');
+                list.visit(self);
+                self.0.push(']');
+            }
+            Value::String(value) => self.0.push_str(&format!("{:?} ", value)),
+            Value::Isize(value) => self.0.push_str(&format!("isize({}) ", value)),
+            Value::I128(value) => self.0.push_str(&format!("i128({}) ", value)),
+            Value::F64(value) => self.0.push_str(&format!("f64({}) ", value)),
+            Value::Bool(value) => self.0.push_str(&format!("{} ", value)),
+            Value::Unit => self.0.push_str("null "),
+            value => panic!("unexpected value {:?}", value),
+        }
+    }
+
+    fn visit_entry(&mut self, key: Value<'_>, value: Value<'_>) {
+        self.visit_value(key);
+        self.0.push_str(": ");
+        self.visit_value(value);
+    }
+}
+
+#[test]
+/// Tests visiting all kinds of values
+fn test_valuable() {
+    let parsed = parse(
+        "title: \"Gura\"\nport: 8080\nbig: 99999999999999999999\nratio: 0.5\ndebug: true\nowner: null\nhosts: [\"alpha\"]\nserver:\n    tls: false",
+    )
+    .unwrap();
+
+    let mut printer = Printer(String::new());
+    printer.visit_value(parsed.as_value());
+    assert_eq!(
+        printer.0,
+        "{\"title\" : \"Gura\" \"port\" : isize(8080) \"big\" : i128(99999999999999999999) \"ratio\" : f64(0.5) \"debug\" : true \"owner\" : null \"hosts\" : [\"alpha\" ]\"server\" : {\"tls\" : false }}"
+    );
+}
@@ -1,9 +1,12 @@
 use gura::{
     errors::Error,
-    object,
-    parser::{parse, GuraType},
+    object, parse_with_options,
+    parser::{parse, GuraType, ParserOptions},
+};
+use std::{
+    env,
+    time::{SystemTime, UNIX_EPOCH},
 };
-use std::env;
 mod common;
 
 fn get_expected() -> GuraType {
@@ -95,3 +98,78 @@ fn test_invalid_variable_5() {
         common::get_file_content_parsed(PARENT_FOLDER, "invalid_variable_with_object.ura");
     assert_eq!(parsed_data.unwrap_err().kind, Error::ParseError);
 }
+
+#[test]
+/// Tests time variables, which share the same time in a text
+fn test_time_variables() {
+    let seconds = || {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as isize
+    };
+    let options = ParserOptions::default().time_variables(true);
+    let before = seconds();
+    let parsed = parse_with_options(
+        "iso: $__now_iso__\nunix: $__now_unix__\ntoday: \"Generated on $__today__\"",
+        &options,
+    )
+    .unwrap();
+    let after = seconds();
+
+    let unix = match parsed["unix"] {
+        GuraType::Integer(unix) => unix,
+        _ => panic!("Expected an integer"),
+    };
+    assert!(before <= unix && unix <= after);
+
+    let iso = match &parsed["iso"] {
+        GuraType::String(iso) => iso.clone(),
+        _ => panic!("Expected a string"),
+    };
+    let time_of_day = unix % 86400;
+    assert_eq!(iso.len(), 20);
+    assert_eq!(
+        &iso[10..],
+        format!(
+            "T{:02}:{:02}:{:02}Z",
+            time_of_day / 3600,
+            time_of_day % 3600 / 60,
+            time_of_day % 60
+        )
+    );
+    assert_eq!(parsed["today"], format!("Generated on {}", &iso[..10]));
+
+    // Disabled by default
+    let error = parse("iso: $__now_iso__").unwrap_err();
+    assert_eq!(error.kind, Error::VariableNotDefinedError);
+}
+
+#[test]
+/// Tests the dates of time variables, which must be valid in the proleptic Gregorian calendar
+fn test_time_variables_date() {
+    let options = ParserOptions::default().time_variables(true);
+    let parsed = parse_with_options("unix: $__now_unix__\ntoday: $__today__", &options).unwrap();
+    let (unix, today) = match (&parsed["unix"], &parsed["today"]) {
+        (GuraType::Integer(unix), GuraType::String(today)) => (*unix, today.clone()),
+        _ => panic!("Expected an integer and a string"),
+    };
+
+    // Counts the days from the Unix epoch to the date
+    let parts: Vec<isize> = today.split('-').map(|part| part.parse().unwrap()).collect();
+    let is_leap = |year: isize| (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let mut days: isize = (1970..parts[0])
+        .map(|year| if is_leap(year) { 366 } else { 365 })
+        .sum();
+    let month_days = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    for month in 1..parts[1] {
+        days += month_days[(month - 1) as usize]
+            + if month == 2 && is_leap(parts[0]) {
+                1
+            } else {
+                0
+            };
+    }
+    days += parts[2] - 1;
+    assert_eq!(days, unix / 86400);
+}